@@ -36,6 +36,12 @@ pub async fn main() -> Result<()> {
                 Sub::TxVoteProposal(TxVoteProposal(args)) => {
                     tx::submit_vote_proposal(ctx, args).await;
                 }
+                Sub::TxVotePassThrough(TxVotePassThrough(args)) => {
+                    tx::submit_vote_pass_through(ctx, args).await;
+                }
+                Sub::TxPgfFunding(TxPgfFunding(args)) => {
+                    tx::submit_pgf_funding(ctx, args).await;
+                }
                 Sub::TxRevealPk(TxRevealPk(args)) => {
                     tx::submit_reveal_pk(ctx, args).await;
                 }
@@ -82,6 +88,9 @@ pub async fn main() -> Result<()> {
                 Sub::QueryRawBytes(QueryRawBytes(args)) => {
                     rpc::query_raw_bytes(ctx, args).await;
                 }
+                Sub::QueryChanges(QueryChanges(args)) => {
+                    rpc::query_changes(ctx, args).await;
+                }
 
                 Sub::QueryProposal(QueryProposal(args)) => {
                     rpc::query_proposal(ctx, args).await;
@@ -89,9 +98,21 @@ pub async fn main() -> Result<()> {
                 Sub::QueryProposalResult(QueryProposalResult(args)) => {
                     rpc::query_proposal_result(ctx, args).await;
                 }
+                Sub::QueryVoteHistory(QueryVoteHistory(args)) => {
+                    rpc::query_vote_history(ctx, args).await;
+                }
                 Sub::QueryProtocolParameters(QueryProtocolParameters(args)) => {
                     rpc::query_protocol_parameters(ctx, args).await;
                 }
+                Sub::QueryPgf(QueryPgf(args)) => {
+                    rpc::query_pgf(ctx, args).await;
+                }
+                Sub::QueryTreasury(QueryTreasury(args)) => {
+                    rpc::query_treasury(ctx, args).await;
+                }
+                Sub::Watch(Watch(args)) => {
+                    rpc::watch_events(ctx, args).await;
+                }
             }
         }
         cli::NamadaClient::WithoutContext(cmd, global_args) => match cmd {
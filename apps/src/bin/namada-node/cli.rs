@@ -37,6 +37,15 @@ pub fn main() -> Result<()> {
                 ledger::reset(ctx.config.ledger)
                     .wrap_err("Failed to reset Namada node")?;
             }
+            cmds::Ledger::Export(cmds::LedgerExport(args)) => {
+                let wasm_dir = ctx.wasm_dir();
+                ledger::export(ctx.config.ledger, wasm_dir, args)
+                    .wrap_err("Failed to export the ledger state")?;
+            }
+            cmds::Ledger::CheckDb(cmds::LedgerCheckDb(args)) => {
+                ledger::check_db(ctx.config.ledger, args)
+                    .wrap_err("Failed to check the ledger DB")?;
+            }
         },
         cmds::NamadaNode::Config(sub) => match sub {
             cmds::Config::Gen(cmds::ConfigGen) => {
@@ -160,6 +160,8 @@ pub mod cmds {
                 // Proposal transactions
                 .subcommand(TxInitProposal::def().display_order(1))
                 .subcommand(TxVoteProposal::def().display_order(1))
+                .subcommand(TxVotePassThrough::def().display_order(1))
+                .subcommand(TxPgfFunding::def().display_order(1))
                 // PoS transactions
                 .subcommand(TxInitValidator::def().display_order(2))
                 .subcommand(Bond::def().display_order(2))
@@ -176,9 +178,14 @@ pub mod cmds {
                 .subcommand(QuerySlashes::def().display_order(3))
                 .subcommand(QueryResult::def().display_order(3))
                 .subcommand(QueryRawBytes::def().display_order(3))
+                .subcommand(QueryChanges::def().display_order(3))
                 .subcommand(QueryProposal::def().display_order(3))
                 .subcommand(QueryProposalResult::def().display_order(3))
+                .subcommand(QueryVoteHistory::def().display_order(3))
                 .subcommand(QueryProtocolParameters::def().display_order(3))
+                .subcommand(QueryPgf::def().display_order(3))
+                .subcommand(QueryTreasury::def().display_order(3))
+                .subcommand(Watch::def().display_order(3))
                 // Utils
                 .subcommand(Utils::def().display_order(5))
         }
@@ -197,6 +204,9 @@ pub mod cmds {
                 Self::parse_with_ctx(matches, TxInitProposal);
             let tx_vote_proposal =
                 Self::parse_with_ctx(matches, TxVoteProposal);
+            let tx_vote_pass_through =
+                Self::parse_with_ctx(matches, TxVotePassThrough);
+            let tx_pgf_funding = Self::parse_with_ctx(matches, TxPgfFunding);
             let bond = Self::parse_with_ctx(matches, Bond);
             let unbond = Self::parse_with_ctx(matches, Unbond);
             let withdraw = Self::parse_with_ctx(matches, Withdraw);
@@ -212,11 +222,17 @@ pub mod cmds {
             let query_slashes = Self::parse_with_ctx(matches, QuerySlashes);
             let query_result = Self::parse_with_ctx(matches, QueryResult);
             let query_raw_bytes = Self::parse_with_ctx(matches, QueryRawBytes);
+            let query_changes = Self::parse_with_ctx(matches, QueryChanges);
             let query_proposal = Self::parse_with_ctx(matches, QueryProposal);
             let query_proposal_result =
                 Self::parse_with_ctx(matches, QueryProposalResult);
+            let query_vote_history =
+                Self::parse_with_ctx(matches, QueryVoteHistory);
             let query_protocol_parameters =
                 Self::parse_with_ctx(matches, QueryProtocolParameters);
+            let query_pgf = Self::parse_with_ctx(matches, QueryPgf);
+            let query_treasury = Self::parse_with_ctx(matches, QueryTreasury);
+            let watch = Self::parse_with_ctx(matches, Watch);
             let utils = SubCmd::parse(matches).map(Self::WithoutContext);
             tx_custom
                 .or(tx_transfer)
@@ -226,6 +242,8 @@ pub mod cmds {
                 .or(tx_reveal_pk)
                 .or(tx_init_proposal)
                 .or(tx_vote_proposal)
+                .or(tx_vote_pass_through)
+                .or(tx_pgf_funding)
                 .or(tx_init_validator)
                 .or(bond)
                 .or(unbond)
@@ -240,9 +258,14 @@ pub mod cmds {
                 .or(query_slashes)
                 .or(query_result)
                 .or(query_raw_bytes)
+                .or(query_changes)
                 .or(query_proposal)
                 .or(query_proposal_result)
+                .or(query_vote_history)
                 .or(query_protocol_parameters)
+                .or(query_pgf)
+                .or(query_treasury)
+                .or(watch)
                 .or(utils)
         }
     }
@@ -288,6 +311,8 @@ pub mod cmds {
         TxInitValidator(TxInitValidator),
         TxInitProposal(TxInitProposal),
         TxVoteProposal(TxVoteProposal),
+        TxVotePassThrough(TxVotePassThrough),
+        TxPgfFunding(TxPgfFunding),
         TxRevealPk(TxRevealPk),
         Bond(Bond),
         Unbond(Unbond),
@@ -302,9 +327,14 @@ pub mod cmds {
         QueryCommissionRate(QueryCommissionRate),
         QuerySlashes(QuerySlashes),
         QueryRawBytes(QueryRawBytes),
+        QueryChanges(QueryChanges),
         QueryProposal(QueryProposal),
         QueryProposalResult(QueryProposalResult),
+        QueryVoteHistory(QueryVoteHistory),
         QueryProtocolParameters(QueryProtocolParameters),
+        QueryPgf(QueryPgf),
+        QueryTreasury(QueryTreasury),
+        Watch(Watch),
     }
 
     #[allow(clippy::large_enum_variant)]
@@ -755,6 +785,8 @@ pub mod cmds {
     pub enum Ledger {
         Run(LedgerRun),
         Reset(LedgerReset),
+        Export(LedgerExport),
+        CheckDb(LedgerCheckDb),
     }
 
     impl SubCmd for Ledger {
@@ -764,7 +796,11 @@ pub mod cmds {
             matches.subcommand_matches(Self::CMD).and_then(|matches| {
                 let run = SubCmd::parse(matches).map(Self::Run);
                 let reset = SubCmd::parse(matches).map(Self::Reset);
+                let export = SubCmd::parse(matches).map(Self::Export);
+                let check_db = SubCmd::parse(matches).map(Self::CheckDb);
                 run.or(reset)
+                    .or(export)
+                    .or(check_db)
                     // The `run` command is the default if no sub-command given
                     .or(Some(Self::Run(LedgerRun(args::LedgerRun(None)))))
             })
@@ -778,6 +814,8 @@ pub mod cmds {
                 )
                 .subcommand(LedgerRun::def())
                 .subcommand(LedgerReset::def())
+                .subcommand(LedgerExport::def())
+                .subcommand(LedgerCheckDb::def())
         }
     }
 
@@ -818,6 +856,51 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct LedgerExport(pub args::LedgerExport);
+
+    impl SubCmd for LedgerExport {
+        const CMD: &'static str = "export";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::LedgerExport::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Export the ledger's state at the given height into a \
+                     genesis configuration file for a new chain.",
+                )
+                .add_args::<args::LedgerExport>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct LedgerCheckDb(pub args::LedgerCheckDb);
+
+    impl SubCmd for LedgerCheckDb {
+        const CMD: &'static str = "check-db";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::LedgerCheckDb::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Check the integrity of the ledger's DB. The merkle tree \
+                     of the last committed block is rebuilt from the stored \
+                     values and compared with the stored tree.",
+                )
+                .add_args::<args::LedgerCheckDb>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub enum Config {
         Gen(ConfigGen),
@@ -919,6 +1002,82 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct QueryVoteHistory(pub args::QueryVoteHistory);
+
+    impl SubCmd for QueryVoteHistory {
+        const CMD: &'static str = "query-vote-history";
+
+        fn parse(matches: &ArgMatches) -> Option<Self>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                QueryVoteHistory(args::QueryVoteHistory::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Query all the votes cast on a proposal, including the \
+                     votes that were changed.",
+                )
+                .add_args::<args::QueryVoteHistory>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct QueryPgf(pub args::QueryPgf);
+
+    impl SubCmd for QueryPgf {
+        const CMD: &'static str = "query-pgf";
+
+        fn parse(matches: &ArgMatches) -> Option<Self>
+        where
+            Self: Sized,
+        {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| QueryPgf(args::QueryPgf::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Query the PGF council, the continuous funding streams \
+                     and the pending retroactive payments.",
+                )
+                .add_args::<args::QueryPgf>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct QueryTreasury(pub args::QueryTreasury);
+
+    impl SubCmd for QueryTreasury {
+        const CMD: &'static str = "query-treasury";
+
+        fn parse(matches: &ArgMatches) -> Option<Self>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                QueryTreasury(args::QueryTreasury::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Query the governance treasury with its balance, the \
+                     vested spends that are still to be paid and its \
+                     history.",
+                )
+                .add_args::<args::QueryTreasury>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct QueryProtocolParameters(pub args::QueryProtocolParameters);
 
@@ -943,6 +1102,28 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct Watch(pub args::Watch);
+
+    impl SubCmd for Watch {
+        const CMD: &'static str = "watch";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Watch(args::Watch::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Watch the events of the ledger that match a query via \
+                     the node's event subscriptions.",
+                )
+                .add_args::<args::Watch>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct TxCustom(pub args::TxCustom);
 
@@ -1316,6 +1497,28 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct QueryChanges(pub args::QueryChanges);
+
+    impl SubCmd for QueryChanges {
+        const CMD: &'static str = "query-changes";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| QueryChanges(args::QueryChanges::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Query the storage keys modified or deleted at a given \
+                     block height with their old and new raw values",
+                )
+                .add_args::<args::QueryChanges>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct TxInitProposal(pub args::InitProposal);
 
@@ -1360,6 +1563,57 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct TxVotePassThrough(pub args::VotePassThrough);
+
+    impl SubCmd for TxVotePassThrough {
+        const CMD: &'static str = "vote-pass-through";
+
+        fn parse(matches: &ArgMatches) -> Option<Self>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                TxVotePassThrough(args::VotePassThrough::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Opt a validator in or out of passing through the \
+                     majority vote of its delegators on proposals.",
+                )
+                .add_args::<args::VotePassThrough>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct TxPgfFunding(pub args::PgfFunding);
+
+    impl SubCmd for TxPgfFunding {
+        const CMD: &'static str = "pgf-funding";
+
+        fn parse(matches: &ArgMatches) -> Option<Self>
+        where
+            Self: Sized,
+        {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| TxPgfFunding(args::PgfFunding::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Set a continuous funding stream or approve a \
+                     retroactive payment of the public goods funding as the \
+                     PGF council.",
+                )
+                .add_args::<args::PgfFunding>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct TxRevealPk(pub args::RevealPk);
 
@@ -1522,7 +1776,7 @@ pub mod args {
     use namada::types::governance::ProposalVote;
     use namada::types::key::*;
     use namada::types::masp::MaspValue;
-    use namada::types::storage::{self, Epoch};
+    use namada::types::storage::{self, BlockHeight, Epoch};
     use namada::types::time::DateTimeUtc;
     use namada::types::token;
     use namada::types::transaction::GasLimit;
@@ -1531,10 +1785,11 @@ pub mod args {
     use super::context::*;
     use super::utils::*;
     use super::{ArgGroup, ArgMatches};
+    use crate::client::light_client::TrustRoot;
     use crate::client::types::{ParsedTxArgs, ParsedTxTransferArgs};
     use crate::config;
     use crate::config::TendermintMode;
-    use crate::facade::tendermint::Timeout;
+    use crate::facade::tendermint::{Hash as TendermintHash, Timeout};
     use crate::facade::tendermint_config::net::Address as TendermintAddress;
 
     const ADDRESS: Arg<WalletAddress> = arg("address");
@@ -1551,6 +1806,8 @@ pub mod args {
             Err(_) => config::DEFAULT_BASE_DIR.into(),
         }),
     );
+    const BLOCK_HEIGHT: Arg<u64> = arg("height");
+    const BLOCK_HEIGHT_OPT: ArgOpt<u64> = BLOCK_HEIGHT.opt();
     const BROADCAST_ONLY: ArgFlag = flag("broadcast-only");
     const CHAIN_ID: Arg<ChainId> = arg("chain-id");
     const CHAIN_ID_OPT: ArgOpt<ChainId> = CHAIN_ID.opt();
@@ -1563,12 +1820,16 @@ pub mod args {
         "consensus-timeout-commit",
         DefaultFn(|| Timeout::from_str("1s").unwrap()),
     );
+    const CONTENT_SOURCE: ArgOpt<String> = arg_opt("content");
+    const COUNCIL: Arg<WalletAddress> = arg("council");
     const DATA_PATH_OPT: ArgOpt<PathBuf> = arg_opt("data-path");
     const DATA_PATH: Arg<PathBuf> = arg("data-path");
     const DECRYPT: ArgFlag = flag("decrypt");
+    const DISABLE: ArgFlag = flag("disable");
     const DONT_ARCHIVE: ArgFlag = flag("dont-archive");
     const DRY_RUN_TX: ArgFlag = flag("dry-run");
     const EPOCH: ArgOpt<Epoch> = arg_opt("epoch");
+    const EVENT_QUERY: ArgOpt<String> = arg_opt("query");
     const FORCE: ArgFlag = flag("force");
     const DONT_PREFETCH_WASM: ArgFlag = flag("dont-prefetch-wasm");
     const GAS_AMOUNT: ArgDefault<token::Amount> =
@@ -1597,6 +1858,7 @@ pub mod args {
     const NET_ADDRESS: Arg<SocketAddr> = arg("net-address");
     const NAMADA_START_TIME: ArgOpt<DateTimeUtc> = arg_opt("time");
     const NO_CONVERSIONS: ArgFlag = flag("no-conversions");
+    const OUTPUT_PATH_OPT: ArgOpt<PathBuf> = arg_opt("output");
     const OWNER: ArgOpt<WalletAddress> = arg_opt("owner");
     const PIN: ArgFlag = flag("pin");
     const PORT_ID: ArgDefault<PortId> = arg_default(
@@ -1614,6 +1876,9 @@ pub mod args {
     const RAW_ADDRESS_OPT: ArgOpt<Address> = RAW_ADDRESS.opt();
     const RAW_PUBLIC_KEY_OPT: ArgOpt<common::PublicKey> = arg_opt("public-key");
     const RECEIVER: Arg<String> = arg("receiver");
+    const RECIPIENT: Arg<WalletAddress> = arg("recipient");
+    const REPAIR: ArgFlag = flag("repair");
+    const RETRO: ArgFlag = flag("retro");
     const SCHEME: ArgDefault<SchemeType> =
         arg_default("scheme", DefaultFn(|| SchemeType::Ed25519));
     const SIGNER: ArgOpt<WalletAddress> = arg_opt("signer");
@@ -1622,13 +1887,20 @@ pub mod args {
     const SOURCE: Arg<WalletAddress> = arg("source");
     const SOURCE_OPT: ArgOpt<WalletAddress> = SOURCE.opt();
     const STORAGE_KEY: Arg<storage::Key> = arg("storage-key");
+    const STORAGE_KEY_PREFIX_OPT: ArgOpt<storage::Key> = arg_opt("prefix");
     const SUB_PREFIX: ArgOpt<String> = arg_opt("sub-prefix");
+    const SUBSCRIPTION_ADDRESS: ArgDefault<SocketAddr> = arg_default(
+        "subscription-address",
+        DefaultFn(|| SocketAddr::from_str("127.0.0.1:26659").unwrap()),
+    );
     const TIMEOUT_HEIGHT: ArgOpt<u64> = arg_opt("timeout-height");
     const TIMEOUT_SEC_OFFSET: ArgOpt<u64> = arg_opt("timeout-sec-offset");
     const TOKEN_OPT: ArgOpt<WalletAddress> = TOKEN.opt();
     const TOKEN: Arg<WalletAddress> = arg("token");
     const TRANSFER_SOURCE: Arg<WalletTransferSource> = arg("source");
     const TRANSFER_TARGET: Arg<WalletTransferTarget> = arg("target");
+    const TRUST_HASH: ArgOpt<TendermintHash> = arg_opt("trust-hash");
+    const TRUST_HEIGHT: ArgOpt<u64> = arg_opt("trust-height");
     const TX_HASH: Arg<String> = arg("tx-hash");
     const UNSAFE_DONT_ENCRYPT: ArgFlag = flag("unsafe-dont-encrypt");
     const UNSAFE_SHOW_SECRET: ArgFlag = flag("unsafe-show-secret");
@@ -1710,6 +1982,57 @@ pub mod args {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct LedgerExport {
+        /// The height of the exported state. Defaults to the last committed
+        /// height.
+        pub height: Option<BlockHeight>,
+        /// Path of the output genesis configuration file
+        pub output: Option<PathBuf>,
+    }
+
+    impl Args for LedgerExport {
+        fn parse(matches: &ArgMatches) -> Self {
+            let height = BLOCK_HEIGHT_OPT.parse(matches).map(BlockHeight);
+            let output = OUTPUT_PATH_OPT.parse(matches);
+            Self { height, output }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(BLOCK_HEIGHT_OPT.def().about(
+                "The block height of the state to export. Defaults to the \
+                 last committed height.",
+            ))
+            .arg(OUTPUT_PATH_OPT.def().about(
+                "The path of the output genesis configuration file. A file \
+                 with a `.json` extension is written as JSON, otherwise as \
+                 TOML. Defaults to \
+                 `{base-dir}/{chain-id}-export-{height}.toml`.",
+            ))
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct LedgerCheckDb {
+        /// Replace the stored merkle tree with the one rebuilt from the
+        /// stored values
+        pub repair: bool,
+    }
+
+    impl Args for LedgerCheckDb {
+        fn parse(matches: &ArgMatches) -> Self {
+            let repair = REPAIR.parse(matches);
+            Self { repair }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(REPAIR.def().about(
+                "Replace the stored merkle tree of the last committed block \
+                 with the tree rebuilt from the stored values.",
+            ))
+        }
+    }
+
     /// Transaction associated results arguments
     #[derive(Clone, Debug)]
     pub struct QueryResult {
@@ -2242,9 +2565,10 @@ pub mod args {
                         ]),
                 )
                 .arg(
-                    PROPOSAL_VOTE
-                        .def()
-                        .about("The vote for the proposal. Either yay or nay."),
+                    PROPOSAL_VOTE.def().about(
+                        "The vote for the proposal. Either yay, nay, \
+                         abstain or nay-with-veto.",
+                    ),
                 )
                 .arg(
                     PROPOSAL_OFFLINE
@@ -2264,6 +2588,88 @@ pub mod args {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct VotePassThrough {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// The validator, which signs the transaction
+        pub validator: WalletAddress,
+        /// Opt out of passing through the votes of the delegators
+        pub disable: bool,
+    }
+
+    impl Args for VotePassThrough {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let validator = VALIDATOR.parse(matches);
+            let disable = DISABLE.parse(matches);
+            Self {
+                tx,
+                validator,
+                disable,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(VALIDATOR.def().about("The validator's address."))
+                .arg(DISABLE.def().about(
+                    "Stop passing through the majority vote of the \
+                     delegators.",
+                ))
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct PgfFunding {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// The PGF council, which signs the transaction
+        pub council: WalletAddress,
+        /// The recipient of the funding
+        pub recipient: WalletAddress,
+        /// The amount paid every epoch or once
+        pub amount: token::Amount,
+        /// Approve a retroactive payment instead of setting a stream
+        pub retro: bool,
+    }
+
+    impl Args for PgfFunding {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let council = COUNCIL.parse(matches);
+            let recipient = RECIPIENT.parse(matches);
+            let amount = AMOUNT.parse(matches);
+            let retro = RETRO.parse(matches);
+            Self {
+                tx,
+                council,
+                recipient,
+                amount,
+                retro,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(COUNCIL.def().about("The address of the PGF council."))
+                .arg(RECIPIENT.def().about("The recipient of the funding."))
+                .arg(
+                    AMOUNT.def().about(
+                        "The amount of NAM paid to the recipient every \
+                         epoch, or once with --retro. A zero amount removes \
+                         the stream or cancels the pending payment.",
+                    ),
+                )
+                .arg(
+                    RETRO.def().about(
+                        "Approve a retroactive payment instead of setting a \
+                         continuous funding stream.",
+                    ),
+                )
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct RevealPk {
         /// Common tx arguments
@@ -2292,19 +2698,31 @@ pub mod args {
         pub query: Query,
         /// Proposal id
         pub proposal_id: Option<u64>,
+        /// A local path or URL to fetch the content of a proposal from
+        pub content: Option<String>,
     }
 
     impl Args for QueryProposal {
         fn parse(matches: &ArgMatches) -> Self {
             let query = Query::parse(matches);
             let proposal_id = PROPOSAL_ID_OPT.parse(matches);
+            let content = CONTENT_SOURCE.parse(matches);
 
-            Self { query, proposal_id }
+            Self {
+                query,
+                proposal_id,
+                content,
+            }
         }
 
         fn def(app: App) -> App {
             app.add_args::<Tx>()
                 .arg(PROPOSAL_ID_OPT.def().about("The proposal identifier."))
+                .arg(CONTENT_SOURCE.def().about(
+                    "A local path or URL to fetch the content of a proposal \
+                     that only has its hash on chain from. Defaults to the \
+                     URL stored with the hash.",
+                ))
         }
     }
 
@@ -2359,6 +2777,64 @@ pub mod args {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct QueryVoteHistory {
+        /// Common query args
+        pub query: Query,
+        /// Proposal id
+        pub proposal_id: u64,
+    }
+
+    impl Args for QueryVoteHistory {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+            let proposal_id = PROPOSAL_ID.parse(matches);
+
+            Self { query, proposal_id }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query>()
+                .arg(PROPOSAL_ID.def().about("The proposal identifier."))
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct QueryPgf {
+        /// Common query args
+        pub query: Query,
+    }
+
+    impl Args for QueryPgf {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+
+            Self { query }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct QueryTreasury {
+        /// Common query args
+        pub query: Query,
+    }
+
+    impl Args for QueryTreasury {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+
+            Self { query }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct QueryProtocolParameters {
         /// Common query args
@@ -2377,6 +2853,40 @@ pub mod args {
         }
     }
 
+    /// Watch events arguments
+    #[derive(Clone, Debug)]
+    pub struct Watch {
+        /// The address of the node's event subscriptions
+        pub subscription_address: SocketAddr,
+        /// The query the events must match, all the events when not set
+        pub query: Option<String>,
+    }
+
+    impl Args for Watch {
+        fn parse(matches: &ArgMatches) -> Self {
+            let subscription_address = SUBSCRIPTION_ADDRESS.parse(matches);
+            let query = EVENT_QUERY.parse(matches);
+            Self {
+                subscription_address,
+                query,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(SUBSCRIPTION_ADDRESS.def().about(
+                "Address of the node's event subscriptions as \
+                 \"{host}:{port}\".",
+            ))
+            .arg(EVENT_QUERY.def().about(
+                "A Tendermint query that the events must match, e.g. \
+                 \"transfer.source='atest1...' AND transfer.amount>10\". \
+                 The keys are the events' types joined with their \
+                 attributes by a dot. When not set, all the events are \
+                 watched.",
+            ))
+        }
+    }
+
     /// Withdraw arguments
     #[derive(Clone, Debug)]
     pub struct Withdraw {
@@ -2729,6 +3239,39 @@ pub mod args {
                 .arg(STORAGE_KEY.def().about("Storage key"))
         }
     }
+
+    /// Query the storage changes at a given block height
+    #[derive(Clone, Debug)]
+    pub struct QueryChanges {
+        /// The block height whose changes to query
+        pub height: BlockHeight,
+        /// Only query the changes of keys matching this prefix
+        pub prefix: Option<storage::Key>,
+        /// Common query args
+        pub query: Query,
+    }
+
+    impl Args for QueryChanges {
+        fn parse(matches: &ArgMatches) -> Self {
+            let height = BlockHeight(BLOCK_HEIGHT.parse(matches));
+            let prefix = STORAGE_KEY_PREFIX_OPT.parse(matches);
+            let query = Query::parse(matches);
+            Self {
+                height,
+                prefix,
+                query,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query>()
+                .arg(BLOCK_HEIGHT.def().about("The block height."))
+                .arg(STORAGE_KEY_PREFIX_OPT.def().about(
+                    "Only show the changes of the storage keys matching this \
+                     prefix.",
+                ))
+        }
+    }
     /// Common transaction arguments
     #[derive(Clone, Debug)]
     pub struct Tx {
@@ -2860,16 +3403,52 @@ pub mod args {
     pub struct Query {
         /// The address of the ledger node as host:port
         pub ledger_address: TendermintAddress,
+        /// The trusted header from which the responses are verified, if any
+        pub trust_root: Option<TrustRoot>,
     }
 
     impl Args for Query {
         fn def(app: App) -> App {
             app.arg(LEDGER_ADDRESS_DEFAULT.def().about(LEDGER_ADDRESS_ABOUT))
+                .arg(
+                    TRUST_HEIGHT
+                        .def()
+                        .about(
+                            "The height of a trusted block header. When set \
+                             together with the trusted header hash, the \
+                             headers up to the latest block are verified with \
+                             a light client and the supported queries are \
+                             checked against the verified app hash.",
+                        )
+                        .requires(TRUST_HASH.name),
+                )
+                .arg(
+                    TRUST_HASH
+                        .def()
+                        .about(
+                            "The hash of the trusted block header at the \
+                             trusted height.",
+                        )
+                        .requires(TRUST_HEIGHT.name),
+                )
         }
 
         fn parse(matches: &ArgMatches) -> Self {
             let ledger_address = LEDGER_ADDRESS_DEFAULT.parse(matches);
-            Self { ledger_address }
+            let trust_root = TRUST_HEIGHT
+                .parse(matches)
+                .zip(TRUST_HASH.parse(matches))
+                .map(|(height, hash)| TrustRoot {
+                    height: height.try_into().unwrap_or_else(|err| {
+                        eprintln!("Invalid trusted height {height}: {err}");
+                        safe_exit(1)
+                    }),
+                    hash,
+                });
+            Self {
+                ledger_address,
+                trust_root,
+            }
         }
     }
 
@@ -0,0 +1,271 @@
+//! A light client that verifies the block headers of the chain starting from a
+//! header trusted by the user. The app hash of a verified header is then used
+//! to check the proofs of the storage queries, so that the client doesn't have
+//! to trust the ledger node that answers them.
+
+use std::convert::TryFrom;
+use std::time::Duration;
+
+use namada::ledger::storage::merkle_tree::{self, MerkleRoot};
+use namada::ledger::storage::Sha256Hasher;
+use namada::types::storage::{BlockHeight, Key};
+use thiserror::Error;
+
+use crate::facade::tendermint::block::Height;
+use crate::facade::tendermint::merkle::proof::Proof;
+use crate::facade::tendermint::{validator, AppHash, Hash, Time};
+use crate::facade::tendermint_light_client_verifier::options::Options;
+use crate::facade::tendermint_light_client_verifier::types::{
+    LightBlock, PeerId, TrustThreshold,
+};
+use crate::facade::tendermint_light_client_verifier::{
+    ProdVerifier, Verdict, Verifier,
+};
+use crate::facade::tendermint_rpc::{self, Client, HttpClient, Paging};
+
+/// The period of time during which a verified header can be trusted. It must
+/// be shorter than the period during which the validators can be slashed for
+/// the blocks they have signed.
+pub const TRUSTING_PERIOD: Duration = Duration::from_secs(14 * 24 * 60 * 60);
+
+/// The tolerated difference between the clock of the client and the time in
+/// the verified headers
+pub const CLOCK_DRIFT: Duration = Duration::from_secs(10);
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("RPC error: {0}")]
+    Rpc(#[from] tendermint_rpc::Error),
+    #[error(
+        "The header at the trusted height {height} has the hash {actual}, \
+         expected {expected}"
+    )]
+    TrustRootMismatch {
+        height: Height,
+        expected: Hash,
+        actual: Hash,
+    },
+    #[error(
+        "The validator sets of the block at the height {0} don't match its \
+         header"
+    )]
+    InvalidValidators(Height),
+    #[error(
+        "Cannot verify the height {target} that is lower than the trusted \
+         height {trusted}"
+    )]
+    PastHeight { target: Height, trusted: Height },
+    #[error("The block at the height {0} failed the verification: {1}")]
+    Verification(Height, String),
+    #[error("The app hash in the header at the height {0} can't be queried")]
+    NoQueryableState(Height),
+    #[error("Invalid proof: {0}")]
+    InvalidProof(#[from] merkle_tree::Error),
+    #[error("Missing a proof of the value of the key {0}")]
+    MissingProof(Key),
+    #[error("The proofs don't match the verified app hash")]
+    AppHashMismatch,
+}
+
+/// Result for functions that may fail
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// A block header trusted by the user, e.g. obtained from a trusted source or
+/// from a previously verified query
+#[derive(Clone, Debug)]
+pub struct TrustRoot {
+    /// The height of the trusted header
+    pub height: Height,
+    /// The hash of the trusted header
+    pub hash: Hash,
+}
+
+/// The state of the ledger at a height whose app hash has been verified
+#[derive(Clone, Debug)]
+pub struct VerifiedState {
+    /// The height of the state to query. Its app hash is in the header of the
+    /// next block.
+    pub height: BlockHeight,
+    /// The verified app hash of the state
+    pub app_hash: AppHash,
+}
+
+impl VerifiedState {
+    /// Verify the proofs of the given storage values against the app hash. The
+    /// absence of a key, i.e. a `None` value, has to be proven too.
+    pub fn verify_values<'a>(
+        &self,
+        proof: Option<&Proof>,
+        values: impl IntoIterator<Item = (&'a Key, Option<&'a [u8]>)>,
+    ) -> Result<()> {
+        let proofs = match proof {
+            Some(proof) => merkle_tree::Proof::from_tm_proof(proof)?,
+            None => vec![],
+        };
+        for (key, value) in values {
+            let proof = proofs
+                .iter()
+                .find(|proof| &proof.key == key)
+                .ok_or_else(|| Error::MissingProof(key.clone()))?;
+            self.verify_root(&proof.verify::<Sha256Hasher>(value)?)?;
+        }
+        Ok(())
+    }
+
+    /// Check that the root of verified proofs matches the app hash
+    pub fn verify_root(&self, root: &MerkleRoot) -> Result<()> {
+        if root.0 != self.app_hash.value() {
+            return Err(Error::AppHashMismatch);
+        }
+        Ok(())
+    }
+}
+
+/// A light client that keeps track of the latest verified light block
+pub struct LightClient {
+    client: HttpClient,
+    provider: PeerId,
+    options: Options,
+    trusted: LightBlock,
+}
+
+impl LightClient {
+    /// Initialize the light client with the light block of the trust root.
+    /// The trust root must be within the [`TRUSTING_PERIOD`] to be able to
+    /// verify any later headers.
+    pub async fn new(
+        client: HttpClient,
+        trust_root: &TrustRoot,
+    ) -> Result<Self> {
+        let provider = client.status().await?.node_info.id;
+        let trusted =
+            fetch_light_block(&client, provider, trust_root.height).await?;
+        let actual = trusted.signed_header.header.hash();
+        if actual != trust_root.hash {
+            return Err(Error::TrustRootMismatch {
+                height: trust_root.height,
+                expected: trust_root.hash,
+                actual,
+            });
+        }
+        let options = Options {
+            trust_threshold: TrustThreshold::ONE_THIRD,
+            trusting_period: TRUSTING_PERIOD,
+            clock_drift: CLOCK_DRIFT,
+        };
+        Ok(Self {
+            client,
+            provider,
+            options,
+            trusted,
+        })
+    }
+
+    /// The latest verified light block
+    pub fn trusted(&self) -> &LightBlock {
+        &self.trusted
+    }
+
+    /// Verify the light block at the given height from the latest verified
+    /// one. When the validators of the trusted block don't have enough voting
+    /// power in the target block, the blocks in between are verified first by
+    /// bisection.
+    pub async fn verify_to(&mut self, target: Height) -> Result<&LightBlock> {
+        let trusted = self.trusted.height();
+        if target < trusted {
+            return Err(Error::PastHeight { target, trusted });
+        }
+        let verifier = ProdVerifier::default();
+        // The blocks that still have to be verified, the last one first
+        let mut pending: Vec<LightBlock> = vec![];
+        while self.trusted.height() < target {
+            let untrusted = match pending.pop() {
+                Some(block) => block,
+                None => {
+                    fetch_light_block(&self.client, self.provider, target)
+                        .await?
+                }
+            };
+            let verdict = verifier.verify(
+                untrusted.as_untrusted_state(),
+                self.trusted.as_trusted_state(),
+                &self.options,
+                Time::now(),
+            );
+            match verdict {
+                Verdict::Success => self.trusted = untrusted,
+                Verdict::NotEnoughTrust(_) => {
+                    // Adjacent blocks are always either verified or invalid,
+                    // so the pivot is strictly in between
+                    let pivot = (self.trusted.height().value()
+                        + untrusted.height().value())
+                        / 2;
+                    let pivot = Height::try_from(pivot)
+                        .expect("The pivot height must be valid");
+                    let pivot =
+                        fetch_light_block(&self.client, self.provider, pivot)
+                            .await?;
+                    pending.push(untrusted);
+                    pending.push(pivot);
+                }
+                Verdict::Invalid(detail) => {
+                    return Err(Error::Verification(
+                        untrusted.height(),
+                        detail.to_string(),
+                    ));
+                }
+            }
+        }
+        Ok(&self.trusted)
+    }
+
+    /// Verify the latest block and get the state of the ledger whose app hash
+    /// is in its header
+    pub async fn verify_latest_state(&mut self) -> Result<VerifiedState> {
+        let latest = self.client.status().await?.sync_info.latest_block_height;
+        let header = &self.verify_to(latest).await?.signed_header.header;
+        // The app hash in the header of the first block is the hash of the
+        // genesis state, which isn't stored at any height
+        let height = header
+            .height
+            .value()
+            .checked_sub(1)
+            .filter(|height| *height > 0)
+            .ok_or(Error::NoQueryableState(header.height))?;
+        Ok(VerifiedState {
+            height: BlockHeight(height),
+            app_hash: header.app_hash.clone(),
+        })
+    }
+}
+
+/// Fetch the light block at the given height and check that its validator sets
+/// match the hashes in its header
+async fn fetch_light_block(
+    client: &HttpClient,
+    provider: PeerId,
+    height: Height,
+) -> Result<LightBlock> {
+    let signed_header = client.commit(height).await?.signed_header;
+    let validators = fetch_validators(client, height).await?;
+    let next_validators = fetch_validators(client, height.increment()).await?;
+    let block =
+        LightBlock::new(signed_header, validators, next_validators, provider);
+    let header = &block.signed_header.header;
+    if block.validators.hash() != header.validators_hash
+        || block.next_validators.hash() != header.next_validators_hash
+    {
+        return Err(Error::InvalidValidators(height));
+    }
+    Ok(block)
+}
+
+/// Fetch the validator set of the block at the given height
+async fn fetch_validators(
+    client: &HttpClient,
+    height: Height,
+) -> Result<validator::Set> {
+    let response = client.validators(height, Paging::All).await?;
+    Ok(validator::Set::without_proposer(response.validators))
+}
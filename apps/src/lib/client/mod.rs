@@ -1,3 +1,4 @@
+pub mod light_client;
 pub mod rpc;
 pub mod signing;
 pub mod tendermint_rpc_types;
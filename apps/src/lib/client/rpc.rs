@@ -12,9 +12,12 @@ use std::str::FromStr;
 use async_std::fs;
 use async_std::path::PathBuf;
 use async_std::prelude::*;
+use async_tungstenite::tokio::connect_async;
+use async_tungstenite::tungstenite::Message as WsMessage;
 use borsh::{BorshDeserialize, BorshSerialize};
 use data_encoding::HEXLOWER;
 use eyre::{eyre, Context as EyreContext};
+use futures::SinkExt;
 use itertools::Itertools;
 use masp_primitives::asset_type::AssetType;
 use masp_primitives::merkle_tree::MerklePath;
@@ -24,22 +27,27 @@ use masp_primitives::transaction::components::Amount;
 use masp_primitives::zip32::ExtendedFullViewingKey;
 #[cfg(not(feature = "mainnet"))]
 use namada::core::ledger::testnet_pow;
+use namada::ledger::events::subscription::{ClientMessage, ServerMessage};
 use namada::ledger::events::Event;
 use namada::ledger::governance::parameters::GovParams;
 use namada::ledger::governance::storage as gov_storage;
-use namada::ledger::native_vp::governance::utils::Votes;
+use namada::ledger::native_vp::governance::utils::{self, TallyParams};
 use namada::ledger::parameters::{storage as param_storage, EpochDuration};
 use namada::ledger::pos::types::{decimal_mult_u64, WeightedValidator};
 use namada::ledger::pos::{
-    self, is_validator_slashes_key, BondId, Bonds, PosParams, Slash, Unbonds,
+    self, is_validator_slashes_key, BondId, Bonds, Slash, Unbonds,
 };
-use namada::ledger::queries::{self, RPC};
-use namada::ledger::storage::ConversionState;
+use namada::ledger::queries::proven::Proven;
+use namada::ledger::queries::{
+    self, BatchRequest, IndexedTransfer, MAX_TRANSFERS_PER_PAGE, RPC,
+};
+use namada::ledger::storage::{current_epoch_key, ConversionState};
 use namada::proto::{SignedTxData, Tx};
 use namada::types::address::{masp, tokens, Address};
 use namada::types::governance::{
-    OfflineProposal, OfflineVote, ProposalResult, ProposalVote, TallyResult,
-    VotePower,
+    OfflineProposal, OfflineVote, PgfCouncil, ProposalContent, ProposalInfo,
+    ProposalResult, ProposalStatus, ProposalVotes, TreasuryRecord, VotePower,
+    WeightedVote,
 };
 use namada::types::hash::Hash;
 use namada::types::key::*;
@@ -57,6 +65,7 @@ use rust_decimal::Decimal;
 use tokio::time::{Duration, Instant};
 
 use crate::cli::{self, args, Context};
+use crate::client::light_client::{self, LightClient, VerifiedState};
 use crate::client::tendermint_rpc_types::TxResponse;
 use crate::client::tx::{
     Conversions, PinnedBalanceError, TransactionDelta, TransferDelta,
@@ -175,7 +184,11 @@ pub async fn query_tx_deltas(
     let _ = ctx.shielded.save();
     // Required for filtering out rejected transactions from Tendermint
     // responses
-    let block_results = query_results(args::Query { ledger_address }).await;
+    let block_results = query_results(args::Query {
+        ledger_address,
+        trust_root: None,
+    })
+    .await;
     let mut transfers = ctx.shielded.get_tx_deltas().clone();
     // Construct the set of addresses relevant to user's query
     let relevant_addrs = match &query_owner {
@@ -434,6 +447,34 @@ pub async fn query_raw_bytes(_ctx: Context, args: args::QueryRawBytes) {
     }
 }
 
+/// Query the storage changes at a given block height
+pub async fn query_changes(_ctx: Context, args: args::QueryChanges) {
+    let client = HttpClient::new(args.query.ledger_address).unwrap();
+    let changes = match &args.prefix {
+        Some(prefix) => unwrap_client_response(
+            RPC.shell()
+                .storage_prefix_changes(&client, &args.height, prefix)
+                .await,
+        ),
+        None => unwrap_client_response(
+            RPC.shell().storage_changes(&client, &args.height).await,
+        ),
+    };
+    if changes.is_empty() {
+        println!("No changes found at height {}", args.height);
+        return;
+    }
+    let fmt_value = |value: &Option<Vec<u8>>| match value {
+        Some(value) => format!("0x{}", HEXLOWER.encode(value)),
+        None => "none".to_owned(),
+    };
+    for change in changes {
+        println!("{}:", change.key);
+        println!("  old: {}", fmt_value(&change.old_value));
+        println!("  new: {}", fmt_value(&change.new_value));
+    }
+}
+
 /// Query token balance(s)
 pub async fn query_balance(mut ctx: Context, args: args::QueryBalance) {
     // Query the balances of shielded or transparent account types depending on
@@ -464,7 +505,8 @@ pub async fn query_transparent_balance(
     ctx: &mut Context,
     args: args::QueryBalance,
 ) {
-    let client = HttpClient::new(args.query.ledger_address).unwrap();
+    let client = HttpClient::new(args.query.ledger_address.clone()).unwrap();
+    let verified = query_verified_state(&client, &args.query).await;
     let tokens = address::tokens();
     match (args.token, args.owner) {
         (Some(token), Some(owner)) => {
@@ -486,7 +528,13 @@ pub async fn query_transparent_balance(
                 .get(&token)
                 .map(|c| Cow::Borrowed(*c))
                 .unwrap_or_else(|| Cow::Owned(token.to_string()));
-            match query_storage_value::<token::Amount>(&client, &key).await {
+            match query_storage_value_verified::<token::Amount>(
+                &client,
+                &key,
+                verified.as_ref(),
+            )
+            .await
+            {
                 Some(balance) => match &args.sub_prefix {
                     Some(sub_prefix) => {
                         println!(
@@ -501,13 +549,18 @@ pub async fn query_transparent_balance(
                 }
             }
         }
-        (None, Some(owner)) => {
+        (None, Some(owner)) if verified.is_some() => {
+            // The balances of all the tokens can't be proven in a single
+            // query, so they are verified token by token
             let owner = ctx.get_cached(&owner);
             for (token, _) in tokens {
                 let prefix = token.to_db_key().into();
-                let balances =
-                    query_storage_prefix::<token::Amount>(&client, &prefix)
-                        .await;
+                let balances = query_storage_prefix_verified::<token::Amount>(
+                    &client,
+                    &prefix,
+                    verified.as_ref(),
+                )
+                .await;
                 if let Some(balances) = balances {
                     print_balances(
                         ctx,
@@ -518,11 +571,42 @@ pub async fn query_transparent_balance(
                 }
             }
         }
+        (None, Some(owner)) => {
+            let owner = ctx.get_cached(&owner).address().unwrap();
+            let balances = unwrap_client_response(
+                RPC.vp().token().balances(&client, &owner).await,
+            );
+            if balances.is_empty() {
+                println!("No balances owned by {}", lookup_alias(ctx, &owner));
+            }
+            for (token, token_balances) in &balances
+                .into_iter()
+                .group_by(|balance| balance.token.clone())
+            {
+                let balances = token_balances.map(|balance| {
+                    let key = match &balance.sub_prefix {
+                        Some(sub_prefix) => token::multitoken_balance_key(
+                            &token::multitoken_balance_prefix(
+                                &token, sub_prefix,
+                            ),
+                            &owner,
+                        ),
+                        None => token::balance_key(&token, &owner),
+                    };
+                    (key, balance.amount)
+                });
+                print_balances(ctx, balances, &token, Some(&owner));
+            }
+        }
         (Some(token), None) => {
             let token = ctx.get(&token);
             let prefix = token.to_db_key().into();
-            let balances =
-                query_storage_prefix::<token::Amount>(&client, &prefix).await;
+            let balances = query_storage_prefix_verified::<token::Amount>(
+                &client,
+                &prefix,
+                verified.as_ref(),
+            )
+            .await;
             if let Some(balances) = balances {
                 print_balances(ctx, balances, &token, None);
             }
@@ -530,8 +614,12 @@ pub async fn query_transparent_balance(
         (None, None) => {
             for (token, _) in tokens {
                 let key = token::balance_prefix(&token);
-                let balances =
-                    query_storage_prefix::<token::Amount>(&client, &key).await;
+                let balances = query_storage_prefix_verified::<token::Amount>(
+                    &client,
+                    &key,
+                    verified.as_ref(),
+                )
+                .await;
                 if let Some(balances) = balances {
                     print_balances(ctx, balances, &token, None);
                 }
@@ -746,48 +834,60 @@ fn print_balances(
 pub async fn query_proposal(_ctx: Context, args: args::QueryProposal) {
     async fn print_proposal(
         client: &HttpClient,
-        id: u64,
-        current_epoch: Epoch,
+        verified: Option<&VerifiedState>,
+        proposal: &ProposalInfo,
         details: bool,
-    ) -> Option<()> {
-        let author_key = gov_storage::get_author_key(id);
-        let start_epoch_key = gov_storage::get_voting_start_epoch_key(id);
-        let end_epoch_key = gov_storage::get_voting_end_epoch_key(id);
-
-        let author =
-            query_storage_value::<Address>(client, &author_key).await?;
-        let start_epoch =
-            query_storage_value::<Epoch>(client, &start_epoch_key).await?;
-        let end_epoch =
-            query_storage_value::<Epoch>(client, &end_epoch_key).await?;
-
+        content_source: Option<&str>,
+    ) {
+        println!("Proposal: {}", proposal.id);
+        println!("{:4}Author: {}", "", proposal.author);
+        println!("{:4}Type: {}", "", proposal.r#type);
+        if proposal.expedited {
+            println!("{:4}Expedited: yes", "");
+        }
         if details {
-            let content_key = gov_storage::get_content_key(id);
-            let grace_epoch_key = gov_storage::get_grace_epoch_key(id);
-            let content = query_storage_value::<HashMap<String, String>>(
-                client,
-                &content_key,
-            )
-            .await?;
-            let grace_epoch =
-                query_storage_value::<Epoch>(client, &grace_epoch_key).await?;
-
-            println!("Proposal: {}", id);
-            println!("{:4}Author: {}", "", author);
-            println!("{:4}Content:", "");
-            for (key, value) in &content {
-                println!("{:8}{}: {}", "", key, value);
-            }
-            println!("{:4}Start Epoch: {}", "", start_epoch);
-            println!("{:4}End Epoch: {}", "", end_epoch);
-            println!("{:4}Grace Epoch: {}", "", grace_epoch);
-            if start_epoch > current_epoch {
-                println!("{:4}Status: pending", "");
-            } else if start_epoch <= current_epoch && current_epoch <= end_epoch
-            {
-                let votes = get_proposal_votes(client, start_epoch, id).await;
+            match &proposal.content {
+                ProposalContent::Full(content) => {
+                    print_content(content);
+                }
+                ProposalContent::Hash { hash, url } => {
+                    println!("{:4}Content hash: {}", "", hash);
+                    println!("{:4}Content URL: {}", "", url);
+                    let source = content_source.unwrap_or(url);
+                    match fetch_proposal_content(source).await {
+                        Ok(content) if proposal.content.matches(&content) => {
+                            print_content(&content);
+                        }
+                        Ok(_) => eprintln!(
+                            "Warning: The content from {} doesn't match the \
+                             proposal content hash.",
+                            source
+                        ),
+                        Err(err) => eprintln!(
+                            "Couldn't fetch the proposal content from {}: {}",
+                            source, err
+                        ),
+                    }
+                }
+            }
+        }
+        println!("{:4}Start Epoch: {}", "", proposal.voting_start_epoch);
+        println!("{:4}End Epoch: {}", "", proposal.voting_end_epoch);
+        if !details {
+            println!("{:4}Status: {}", "", proposal.status);
+            return;
+        }
+        println!("{:4}Grace Epoch: {}", "", proposal.grace_epoch);
+        match proposal.status {
+            ProposalStatus::Pending => {
+                println!("{:4}Status: {}", "", proposal.status);
+            }
+            ProposalStatus::Voting => {
+                if verified.is_some() {
+                    eprintln!("Warning: The proposal votes are not verified.");
+                }
                 let partial_proposal_result =
-                    compute_tally(client, start_epoch, votes).await;
+                    query_proposal_tally(client, proposal.id).await;
                 println!(
                     "{:4}Yay votes: {}",
                     "", partial_proposal_result.total_yay_power
@@ -796,62 +896,175 @@ pub async fn query_proposal(_ctx: Context, args: args::QueryProposal) {
                     "{:4}Nay votes: {}",
                     "", partial_proposal_result.total_nay_power
                 );
-                println!("{:4}Status: on-going", "");
-            } else {
-                let votes = get_proposal_votes(client, start_epoch, id).await;
+                println!(
+                    "{:4}Abstain votes: {}",
+                    "", partial_proposal_result.total_abstain_power
+                );
+                println!(
+                    "{:4}Nay with veto votes: {}",
+                    "", partial_proposal_result.total_veto_power
+                );
+                println!("{:4}Status: {}", "", proposal.status);
+            }
+            ProposalStatus::Ended
+            | ProposalStatus::Passed
+            | ProposalStatus::Rejected
+            | ProposalStatus::Executed
+            | ProposalStatus::ExecutionFailed => {
+                if verified.is_some() {
+                    eprintln!("Warning: The proposal votes are not verified.");
+                }
                 let proposal_result =
-                    compute_tally(client, start_epoch, votes).await;
-                println!("{:4}Status: done", "");
+                    query_proposal_tally(client, proposal.id).await;
+                println!("{:4}Status: {}", "", proposal.status);
                 println!("{:4}Result: {}", "", proposal_result);
             }
-        } else {
-            println!("Proposal: {}", id);
-            println!("{:4}Author: {}", "", author);
-            println!("{:4}Start Epoch: {}", "", start_epoch);
-            println!("{:4}End Epoch: {}", "", end_epoch);
-            if start_epoch > current_epoch {
-                println!("{:4}Status: pending", "");
-            } else if start_epoch <= current_epoch && current_epoch <= end_epoch
-            {
-                println!("{:4}Status: on-going", "");
-            } else {
-                println!("{:4}Status: done", "");
-            }
         }
+    }
 
-        Some(())
+    fn print_content(content: &BTreeMap<String, String>) {
+        println!("{:4}Content:", "");
+        for (key, value) in content {
+            println!("{:8}{}: {}", "", key, value);
+        }
     }
 
     let client = HttpClient::new(args.query.ledger_address.clone()).unwrap();
-    let current_epoch = query_epoch(args.query.clone()).await;
+    let verified = query_verified_state(&client, &args.query).await;
     match args.proposal_id {
         Some(id) => {
-            if print_proposal(&client, id, current_epoch, true)
-                .await
-                .is_none()
-            {
-                eprintln!("No valid proposal was found with id {}", id)
+            match query_proposal_by_id(&client, verified.as_ref(), id).await {
+                Some(proposal) => {
+                    print_proposal(
+                        &client,
+                        verified.as_ref(),
+                        &proposal,
+                        true,
+                        args.content.as_deref(),
+                    )
+                    .await
+                }
+                None => {
+                    eprintln!("No valid proposal was found with id {}", id)
+                }
             }
         }
         None => {
-            let last_proposal_id_key = gov_storage::get_counter_key();
-            let last_proposal_id =
-                query_storage_value::<u64>(&client, &last_proposal_id_key)
-                    .await
-                    .unwrap();
-
-            for id in 0..last_proposal_id {
-                if print_proposal(&client, id, current_epoch, false)
-                    .await
-                    .is_none()
-                {
-                    eprintln!("No valid proposal was found with id {}", id)
-                };
+            let mut offset = 0;
+            loop {
+                let page =
+                    query_proposals_page(&client, verified.as_ref(), offset)
+                        .await;
+                if page.is_empty() {
+                    break;
+                }
+                for proposal in &page {
+                    print_proposal(
+                        &client,
+                        verified.as_ref(),
+                        proposal,
+                        false,
+                        None,
+                    )
+                    .await;
+                }
+                offset += page.len() as u64;
             }
         }
     }
 }
 
+/// Fetch the content of a proposal that only has its hash on chain from a
+/// local path or a HTTP(S) URL. The content is a JSON object of its fields.
+async fn fetch_proposal_content(
+    source: &str,
+) -> Result<BTreeMap<String, String>, String> {
+    let bytes =
+        if source.starts_with("http://") || source.starts_with("https://") {
+            let response = reqwest::get(source)
+                .await
+                .and_then(|response| response.error_for_status())
+                .map_err(|err| err.to_string())?;
+            response
+                .bytes()
+                .await
+                .map_err(|err| err.to_string())?
+                .to_vec()
+        } else {
+            fs::read(source).await.map_err(|err| err.to_string())?
+        };
+    serde_json::from_slice(&bytes).map_err(|err| err.to_string())
+}
+
+/// Query a proposal by its ID. In the verified mode, i.e. with a `verified`
+/// state, the proposal is queried at the height of the state with proofs
+/// that are checked against the verified app hash.
+async fn query_proposal_by_id(
+    client: &HttpClient,
+    verified: Option<&VerifiedState>,
+    id: u64,
+) -> Option<ProposalInfo> {
+    let governance = RPC.vp().governance();
+    match verified {
+        Some(verified) => {
+            let proven = unwrap_client_response(
+                governance
+                    .proposal_with_proof(client, Some(verified.height), &id)
+                    .await,
+            );
+            check_proven(proven, verified)
+        }
+        None => unwrap_client_response(governance.proposal(client, &id).await),
+    }
+}
+
+/// Query a page of all the proposals, starting from the given number of
+/// proposals to skip. The page is empty when there are no more proposals. In
+/// the verified mode, the page is checked like in [`query_proposal_by_id`].
+async fn query_proposals_page(
+    client: &HttpClient,
+    verified: Option<&VerifiedState>,
+    offset: u64,
+) -> Vec<ProposalInfo> {
+    /// The number of proposals to request in a single page
+    const PAGE_SIZE: u64 = 100;
+
+    let governance = RPC.vp().governance();
+    match verified {
+        Some(verified) => {
+            let proven = unwrap_client_response(
+                governance
+                    .proposals_with_proof(
+                        client,
+                        Some(verified.height),
+                        &None,
+                        &offset,
+                        &PAGE_SIZE,
+                    )
+                    .await,
+            );
+            check_proven(proven, verified)
+        }
+        None => unwrap_client_response(
+            governance
+                .proposals(client, &None, &offset, &PAGE_SIZE)
+                .await,
+        ),
+    }
+}
+
+/// Query the tally of a proposal. It's the live tally until the end of the
+/// voting period and the final one after that.
+async fn query_proposal_tally(client: &HttpClient, id: u64) -> ProposalResult {
+    unwrap_client_response(
+        RPC.vp().governance().proposal_tally(client, &id).await,
+    )
+    .unwrap_or_else(|| {
+        eprintln!("No valid proposal was found with id {}", id);
+        cli::safe_exit(1)
+    })
+}
+
 /// Get the component of the given amount corresponding to the given token
 pub fn value_by_address(
     amt: &masp_primitives::transaction::components::Amount,
@@ -1167,27 +1380,23 @@ pub async fn query_proposal_result(
     args: args::QueryProposalResult,
 ) {
     let client = HttpClient::new(args.query.ledger_address.clone()).unwrap();
-    let current_epoch = query_epoch(args.query.clone()).await;
 
     match args.proposal_id {
         Some(id) => {
-            let end_epoch_key = gov_storage::get_voting_end_epoch_key(id);
-            let end_epoch =
-                query_storage_value::<Epoch>(&client, &end_epoch_key).await;
-
-            match end_epoch {
-                Some(end_epoch) => {
-                    if current_epoch > end_epoch {
-                        let votes =
-                            get_proposal_votes(&client, end_epoch, id).await;
-                        let proposal_result =
-                            compute_tally(&client, end_epoch, votes).await;
-                        println!("Proposal: {}", id);
-                        println!("{:4}Result: {}", "", proposal_result);
-                    } else {
-                        eprintln!("Proposal is still in progress.");
-                        cli::safe_exit(1)
-                    }
+            let proposal = unwrap_client_response(
+                RPC.vp().governance().proposal(&client, &id).await,
+            );
+
+            match proposal {
+                Some(proposal) if proposal.status.is_voting_over() => {
+                    let proposal_result =
+                        query_proposal_tally(&client, id).await;
+                    println!("Proposal: {}", id);
+                    println!("{:4}Result: {}", "", proposal_result);
+                }
+                Some(_) => {
+                    eprintln!("Proposal is still in progress.");
+                    cli::safe_exit(1)
                 }
                 None => {
                     eprintln!("Error while retriving proposal.");
@@ -1339,9 +1548,13 @@ pub async fn query_protocol_parameters(
     println!("{:4}Transactions whitelist: {:?}", "", tx_whitelist);
 
     println!("PoS parameters");
+    let epoch = unwrap_client_response(RPC.shell().epoch(&client).await);
     let key = pos::params_key();
-    let pos_params = query_storage_value::<PosParams>(&client, &key)
+    let pos_params = query_storage_value_bytes(&client, &key, None, false)
         .await
+        .0
+        .and_then(|bytes| pos::decode_params(bytes).ok())
+        .and_then(|params| params.get(epoch).cloned())
         .expect("Parameter should be definied.");
     println!(
         "{:4}Block proposer reward: {}",
@@ -1368,10 +1581,137 @@ pub async fn query_protocol_parameters(
     println!("{:4}Votes per token: {}", "", pos_params.tm_votes_per_token);
 }
 
+/// Query the public goods funding
+pub async fn query_pgf(ctx: Context, args: args::QueryPgf) {
+    let client = HttpClient::new(args.query.ledger_address).unwrap();
+    let pgf = RPC.vp().pgf();
+
+    match get_pgf_council(&client).await {
+        Some(council) => println!(
+            "PGF council: {} with a spending cap of {} per epoch",
+            lookup_alias(&ctx, &council.address),
+            council.spending_cap
+        ),
+        None => println!("No PGF council has been elected"),
+    }
+
+    let streams = unwrap_client_response(pgf.streams(&client).await);
+    if streams.is_empty() {
+        println!("No continuous funding streams");
+    } else {
+        println!("Continuous funding streams (per epoch):");
+        for (recipient, amount) in streams {
+            println!("{:4}{}: {}", "", lookup_alias(&ctx, &recipient), amount);
+        }
+    }
+
+    let retro_payments =
+        unwrap_client_response(pgf.retro_payments(&client).await);
+    if retro_payments.is_empty() {
+        println!("No pending retroactive payments");
+    } else {
+        println!("Pending retroactive payments:");
+        for (recipient, amount) in retro_payments {
+            println!("{:4}{}: {}", "", lookup_alias(&ctx, &recipient), amount);
+        }
+    }
+}
+
+/// Query the governance treasury
+pub async fn query_treasury(ctx: Context, args: args::QueryTreasury) {
+    let client = HttpClient::new(args.query.ledger_address).unwrap();
+    let treasury =
+        unwrap_client_response(RPC.vp().governance().treasury(&client).await);
+
+    println!("Treasury balance: {}", treasury.balance);
+
+    if treasury.vesting.is_empty() {
+        println!("No vested spends");
+    } else {
+        println!("Vested spends:");
+        for vesting in treasury.vesting {
+            println!(
+                "{:4}Proposal {}: {} {} to {} over {} epochs",
+                "",
+                vesting.proposal_id,
+                vesting.remaining_amount,
+                lookup_alias(&ctx, &vesting.token),
+                lookup_alias(&ctx, &vesting.recipient),
+                vesting.remaining_epochs
+            );
+        }
+    }
+
+    if treasury.history.is_empty() {
+        println!("No history");
+    } else {
+        println!("History:");
+        for record in treasury.history {
+            match record {
+                TreasuryRecord::Deposit {
+                    proposal_id,
+                    epoch,
+                    amount,
+                } => println!(
+                    "{:4}Epoch {}: deposit of {} forfeited by proposal {}",
+                    "", epoch, amount, proposal_id
+                ),
+                TreasuryRecord::Payment(payment) => println!(
+                    "{:4}Epoch {}: payment of {} {} to {} for proposal {}",
+                    "",
+                    payment.epoch,
+                    payment.amount,
+                    lookup_alias(&ctx, &payment.token),
+                    lookup_alias(&ctx, &payment.recipient),
+                    payment.proposal_id
+                ),
+            }
+        }
+    }
+}
+
+/// Query all the votes cast on a proposal
+pub async fn query_vote_history(ctx: Context, args: args::QueryVoteHistory) {
+    let client = HttpClient::new(args.query.ledger_address).unwrap();
+    let history = unwrap_client_response(
+        RPC.vp()
+            .governance()
+            .vote_history(&client, &args.proposal_id)
+            .await,
+    );
+
+    if history.is_empty() {
+        println!("No votes on proposal {}", args.proposal_id);
+        return;
+    }
+    println!("Votes on proposal {}:", args.proposal_id);
+    for record in history {
+        if record.voter == record.delegation {
+            println!(
+                "{:4}Height {}: {} voted {}",
+                "",
+                record.height,
+                lookup_alias(&ctx, &record.voter),
+                record.vote
+            );
+        } else {
+            println!(
+                "{:4}Height {}: {} voted {} for the delegation to {}",
+                "",
+                record.height,
+                lookup_alias(&ctx, &record.voter),
+                record.vote,
+                lookup_alias(&ctx, &record.delegation)
+            );
+        }
+    }
+}
+
 /// Query PoS bond(s)
 pub async fn query_bonds(ctx: Context, args: args::QueryBonds) {
-    let epoch = query_epoch(args.query.clone()).await;
-    let client = HttpClient::new(args.query.ledger_address).unwrap();
+    let client = HttpClient::new(args.query.ledger_address.clone()).unwrap();
+    let verified = query_verified_state(&client, &args.query).await;
+    let epoch = query_epoch_verified(args.query, verified.as_ref()).await;
     match (args.owner, args.validator) {
         (Some(owner), Some(validator)) => {
             let source = ctx.get(&owner);
@@ -1379,19 +1719,30 @@ pub async fn query_bonds(ctx: Context, args: args::QueryBonds) {
             // Find owner's delegations to the given validator
             let bond_id = pos::BondId { source, validator };
             let bond_key = pos::bond_key(&bond_id);
-            let bonds =
-                query_storage_value::<pos::Bonds>(&client, &bond_key).await;
+            let bonds = query_storage_value_verified::<pos::Bonds>(
+                &client,
+                &bond_key,
+                verified.as_ref(),
+            )
+            .await;
             // Find owner's unbonded delegations from the given
             // validator
             let unbond_key = pos::unbond_key(&bond_id);
-            let unbonds =
-                query_storage_value::<pos::Unbonds>(&client, &unbond_key).await;
+            let unbonds = query_storage_value_verified::<pos::Unbonds>(
+                &client,
+                &unbond_key,
+                verified.as_ref(),
+            )
+            .await;
             // Find validator's slashes, if any
             let slashes_key = pos::validator_slashes_key(&bond_id.validator);
-            let slashes =
-                query_storage_value::<pos::Slashes>(&client, &slashes_key)
-                    .await
-                    .unwrap_or_default();
+            let slashes = query_storage_value_verified::<pos::Slashes>(
+                &client,
+                &slashes_key,
+                verified.as_ref(),
+            )
+            .await
+            .unwrap_or_default();
 
             let stdout = io::stdout();
             let mut w = stdout.lock();
@@ -1438,18 +1789,29 @@ pub async fn query_bonds(ctx: Context, args: args::QueryBonds) {
                 validator,
             };
             let bond_key = pos::bond_key(&bond_id);
-            let bonds =
-                query_storage_value::<pos::Bonds>(&client, &bond_key).await;
+            let bonds = query_storage_value_verified::<pos::Bonds>(
+                &client,
+                &bond_key,
+                verified.as_ref(),
+            )
+            .await;
             // Find validator's unbonded self-bonds
             let unbond_key = pos::unbond_key(&bond_id);
-            let unbonds =
-                query_storage_value::<pos::Unbonds>(&client, &unbond_key).await;
+            let unbonds = query_storage_value_verified::<pos::Unbonds>(
+                &client,
+                &unbond_key,
+                verified.as_ref(),
+            )
+            .await;
             // Find validator's slashes, if any
             let slashes_key = pos::validator_slashes_key(&bond_id.validator);
-            let slashes =
-                query_storage_value::<pos::Slashes>(&client, &slashes_key)
-                    .await
-                    .unwrap_or_default();
+            let slashes = query_storage_value_verified::<pos::Slashes>(
+                &client,
+                &slashes_key,
+                verified.as_ref(),
+            )
+            .await
+            .unwrap_or_default();
 
             let stdout = io::stdout();
             let mut w = stdout.lock();
@@ -1481,14 +1843,32 @@ pub async fn query_bonds(ctx: Context, args: args::QueryBonds) {
             let owner = ctx.get(&owner);
             // Find owner's bonds to any validator
             let bonds_prefix = pos::bonds_for_source_prefix(&owner);
-            let bonds =
-                query_storage_prefix::<pos::Bonds>(&client, &bonds_prefix)
-                    .await;
+            let bonds = query_storage_prefix_verified::<pos::Bonds>(
+                &client,
+                &bonds_prefix,
+                verified.as_ref(),
+            )
+            .await
+            .map(|values| values.collect::<Vec<_>>());
             // Find owner's unbonds to any validator
             let unbonds_prefix = pos::unbonds_for_source_prefix(&owner);
-            let unbonds =
-                query_storage_prefix::<pos::Unbonds>(&client, &unbonds_prefix)
-                    .await;
+            let unbonds = query_storage_prefix_verified::<pos::Unbonds>(
+                &client,
+                &unbonds_prefix,
+                verified.as_ref(),
+            )
+            .await
+            .map(|values| values.collect::<Vec<_>>());
+            // Find the slashes of all the validators in a single batch
+            let validators_slashes = query_validators_slashes(
+                &client,
+                bonds_validators(
+                    bonds.iter().flatten(),
+                    unbonds.iter().flatten(),
+                ),
+                verified.as_ref(),
+            )
+            .await;
 
             let mut total: token::Amount = 0.into();
             let mut total_active: token::Amount = 0.into();
@@ -1498,14 +1878,10 @@ pub async fn query_bonds(ctx: Context, args: args::QueryBonds) {
                     match pos::is_bond_key(&key) {
                         Some(pos::BondId { source, validator }) => {
                             // Find validator's slashes, if any
-                            let slashes_key =
-                                pos::validator_slashes_key(&validator);
-                            let slashes = query_storage_value::<pos::Slashes>(
-                                &client,
-                                &slashes_key,
-                            )
-                            .await
-                            .unwrap_or_default();
+                            let slashes = validators_slashes
+                                .get(&validator)
+                                .cloned()
+                                .unwrap_or_default();
 
                             let stdout = io::stdout();
                             let mut w = stdout.lock();
@@ -1549,14 +1925,10 @@ pub async fn query_bonds(ctx: Context, args: args::QueryBonds) {
                     match pos::is_unbond_key(&key) {
                         Some(pos::BondId { source, validator }) => {
                             // Find validator's slashes, if any
-                            let slashes_key =
-                                pos::validator_slashes_key(&validator);
-                            let slashes = query_storage_value::<pos::Slashes>(
-                                &client,
-                                &slashes_key,
-                            )
-                            .await
-                            .unwrap_or_default();
+                            let slashes = validators_slashes
+                                .get(&validator)
+                                .cloned()
+                                .unwrap_or_default();
 
                             let stdout = io::stdout();
                             let mut w = stdout.lock();
@@ -1597,14 +1969,32 @@ pub async fn query_bonds(ctx: Context, args: args::QueryBonds) {
         (None, None) => {
             // Find all the bonds
             let bonds_prefix = pos::bonds_prefix();
-            let bonds =
-                query_storage_prefix::<pos::Bonds>(&client, &bonds_prefix)
-                    .await;
+            let bonds = query_storage_prefix_verified::<pos::Bonds>(
+                &client,
+                &bonds_prefix,
+                verified.as_ref(),
+            )
+            .await
+            .map(|values| values.collect::<Vec<_>>());
             // Find all the unbonds
             let unbonds_prefix = pos::unbonds_prefix();
-            let unbonds =
-                query_storage_prefix::<pos::Unbonds>(&client, &unbonds_prefix)
-                    .await;
+            let unbonds = query_storage_prefix_verified::<pos::Unbonds>(
+                &client,
+                &unbonds_prefix,
+                verified.as_ref(),
+            )
+            .await
+            .map(|values| values.collect::<Vec<_>>());
+            // Find the slashes of all the validators in a single batch
+            let validators_slashes = query_validators_slashes(
+                &client,
+                bonds_validators(
+                    bonds.iter().flatten(),
+                    unbonds.iter().flatten(),
+                ),
+                verified.as_ref(),
+            )
+            .await;
 
             let mut total: token::Amount = 0.into();
             let mut total_active: token::Amount = 0.into();
@@ -1613,14 +2003,10 @@ pub async fn query_bonds(ctx: Context, args: args::QueryBonds) {
                     match pos::is_bond_key(&key) {
                         Some(pos::BondId { source, validator }) => {
                             // Find validator's slashes, if any
-                            let slashes_key =
-                                pos::validator_slashes_key(&validator);
-                            let slashes = query_storage_value::<pos::Slashes>(
-                                &client,
-                                &slashes_key,
-                            )
-                            .await
-                            .unwrap_or_default();
+                            let slashes = validators_slashes
+                                .get(&validator)
+                                .cloned()
+                                .unwrap_or_default();
 
                             let stdout = io::stdout();
                             let mut w = stdout.lock();
@@ -1664,14 +2050,10 @@ pub async fn query_bonds(ctx: Context, args: args::QueryBonds) {
                     match pos::is_unbond_key(&key) {
                         Some(pos::BondId { source, validator }) => {
                             // Find validator's slashes, if any
-                            let slashes_key =
-                                pos::validator_slashes_key(&validator);
-                            let slashes = query_storage_value::<pos::Slashes>(
-                                &client,
-                                &slashes_key,
-                            )
-                            .await
-                            .unwrap_or_default();
+                            let slashes = validators_slashes
+                                .get(&validator)
+                                .cloned()
+                                .unwrap_or_default();
 
                             let stdout = io::stdout();
                             let mut w = stdout.lock();
@@ -1715,34 +2097,87 @@ pub async fn query_bonds(ctx: Context, args: args::QueryBonds) {
     }
 }
 
+/// Find the validators of the given bonds and unbonds
+fn bonds_validators<'a>(
+    bonds: impl Iterator<Item = &'a (Key, pos::Bonds)>,
+    unbonds: impl Iterator<Item = &'a (Key, pos::Unbonds)>,
+) -> HashSet<Address> {
+    bonds
+        .filter_map(|(key, _bonds)| pos::is_bond_key(key))
+        .chain(unbonds.filter_map(|(key, _unbonds)| pos::is_unbond_key(key)))
+        .map(|BondId { validator, .. }| validator)
+        .collect()
+}
+
+/// Query the slashes of the given validators in a single batch of requests
+async fn query_validators_slashes(
+    client: &HttpClient,
+    validators: HashSet<Address>,
+    verified: Option<&VerifiedState>,
+) -> HashMap<Address, pos::Slashes> {
+    let validators: Vec<Address> = validators.into_iter().collect();
+    let keys: Vec<Key> =
+        validators.iter().map(pos::validator_slashes_key).collect();
+    let slashes =
+        query_storage_values_verified::<pos::Slashes>(client, &keys, verified)
+            .await;
+    validators
+        .into_iter()
+        .zip(slashes)
+        .map(|(validator, slashes)| (validator, slashes.unwrap_or_default()))
+        .collect()
+}
+
 /// Query PoS bonded stake
 pub async fn query_bonded_stake(ctx: Context, args: args::QueryBondedStake) {
-    let epoch = match args.epoch {
-        Some(epoch) => epoch,
-        None => query_epoch(args.query.clone()).await,
-    };
     let client = HttpClient::new(args.query.ledger_address).unwrap();
+    let validator = args.validator.map(|validator| ctx.get(&validator));
 
-    // Find the validator set
+    // Find the epoch, the validator set, the total bonded stake and the
+    // bonded stake of the given validator, if any, in a single batch
     let validator_set_key = pos::validator_set_key();
-    let validator_sets =
-        query_storage_value::<pos::ValidatorSets>(&client, &validator_set_key)
-            .await
+    let total_deltas_key = pos::total_deltas_key();
+    let mut requests = vec![
+        BatchRequest::new(RPC.shell().epoch_path()),
+        BatchRequest::new(RPC.shell().storage_value_path(&validator_set_key)),
+        BatchRequest::new(RPC.shell().storage_value_path(&total_deltas_key)),
+    ];
+    if let Some(validator) = &validator {
+        let validator_deltas_key = pos::validator_deltas_key(validator);
+        requests.push(BatchRequest::new(
+            RPC.shell().storage_value_path(&validator_deltas_key),
+        ));
+    }
+    let mut responses = unwrap_client_response(
+        queries::Client::batch_request(&client, &requests, None).await,
+    )
+    .into_iter()
+    .map(|response| unwrap_client_response(response).data);
+
+    let last_epoch: Epoch = decode_batch_value(responses.next().unwrap())
+        .expect("The epoch should always be set");
+    let epoch = match args.epoch {
+        Some(epoch) => epoch,
+        None => {
+            println!("Last committed epoch: {}", last_epoch);
+            last_epoch
+        }
+    };
+    let validator_sets: pos::ValidatorSets =
+        decode_batch_value(responses.next().unwrap())
             .expect("Validator set should always be set");
     let validator_set = validator_sets
         .get(epoch)
         .expect("Validator set should be always set in the current epoch");
+    let total_deltas: pos::TotalDeltas =
+        decode_batch_value(responses.next().unwrap())
+            .expect("Total bonded stake should always be set");
 
-    match args.validator {
+    match validator {
         Some(validator) => {
-            let validator = ctx.get(&validator);
             // Find bonded stake for the given validator
-            let validator_deltas_key = pos::validator_deltas_key(&validator);
-            let validator_deltas = query_storage_value::<pos::ValidatorDeltas>(
-                &client,
-                &validator_deltas_key,
-            )
-            .await;
+            let validator_deltas: Option<pos::ValidatorDeltas> =
+                decode_batch_value(responses.next().unwrap());
             match validator_deltas.and_then(|data| data.get(epoch)) {
                 Some(val_stake) => {
                     let bonded_stake: u64 = val_stake.try_into().expect(
@@ -1800,11 +2235,6 @@ pub async fn query_bonded_stake(ctx: Context, args: args::QueryBondedStake) {
             }
         }
     }
-    let total_deltas_key = pos::total_deltas_key();
-    let total_deltas =
-        query_storage_value::<pos::TotalDeltas>(&client, &total_deltas_key)
-            .await
-            .expect("Total bonded stake should always be set");
     let total_bonded_stake = total_deltas
         .get(epoch)
         .expect("Total bonded stake should be always set in the current epoch");
@@ -1958,8 +2388,9 @@ pub async fn get_public_key(
     ledger_address: TendermintAddress,
 ) -> Option<common::PublicKey> {
     let client = HttpClient::new(ledger_address).unwrap();
-    let key = pk_key(address);
-    query_storage_value(&client, &key).await
+    unwrap_client_response(
+        RPC.vp().account().public_key(&client, address).await,
+    )
 }
 
 /// Check if the given address is a known validator.
@@ -2005,14 +2436,9 @@ pub async fn known_address(
     ledger_address: TendermintAddress,
 ) -> bool {
     let client = HttpClient::new(ledger_address).unwrap();
-    match address {
-        Address::Established(_) => {
-            // Established account exists if it has a VP
-            let key = storage::Key::validity_predicate(address);
-            query_has_storage_key(&client, &key).await
-        }
-        Address::Implicit(_) | Address::Internal(_) => true,
-    }
+    unwrap_client_response(
+        RPC.vp().account().account_exists(&client, address).await,
+    )
 }
 
 #[cfg(not(feature = "mainnet"))]
@@ -2244,6 +2670,33 @@ pub async fn query_conversions(ctx: Context, args: args::QueryConversions) {
     }
 }
 
+/// Query all the accepted shielded transfers starting from the given index, a
+/// page at a time
+pub async fn query_shielded_transfers(
+    client: &HttpClient,
+    from: u64,
+) -> Vec<IndexedTransfer> {
+    let mut transfers = vec![];
+    let mut from = from;
+    loop {
+        let page = unwrap_client_response(
+            RPC.shell()
+                .masp()
+                .transfers(client, &from, &MAX_TRANSFERS_PER_PAGE)
+                .await,
+        );
+        if page.transfers.is_empty() {
+            break;
+        }
+        from += page.transfers.len() as u64;
+        transfers.extend(page.transfers);
+        if from >= page.head {
+            break;
+        }
+    }
+    transfers
+}
+
 /// Query a conversion.
 pub async fn query_conversion(
     client: HttpClient,
@@ -2273,8 +2726,12 @@ where
     let maybe_unit = T::try_from_slice(&[]);
     if let Ok(unit) = maybe_unit {
         return if unwrap_client_response(
-            RPC.shell().storage_has_key(client, key).await,
-        ) {
+            RPC.shell()
+                .storage_has_key(client, None, None, false, key)
+                .await,
+        )
+        .data
+        {
             Some(unit)
         } else {
             None
@@ -2317,6 +2774,134 @@ pub async fn query_storage_value_bytes(
     }
 }
 
+/// Query a storage value and decode it with [`BorshDeserialize`]. In the
+/// verified mode, i.e. with a `verified` state, the value is read at the height
+/// of the state and its proof is checked against the verified app hash.
+/// Otherwise, this is the same as [`query_storage_value`].
+pub async fn query_storage_value_verified<T>(
+    client: &HttpClient,
+    key: &storage::Key,
+    verified: Option<&VerifiedState>,
+) -> Option<T>
+where
+    T: BorshDeserialize,
+{
+    match verified {
+        Some(verified) => query_storage_values_verified(
+            client,
+            std::slice::from_ref(key),
+            Some(verified),
+        )
+        .await
+        .pop()
+        .flatten(),
+        None => query_storage_value(client, key).await,
+    }
+}
+
+/// Query the storage values of the given keys in a single batch of requests
+/// and decode them with [`BorshDeserialize`]. In the verified mode, i.e. with
+/// a `verified` state, the values are read at the height of the state and
+/// their proofs are checked against the verified app hash. The values are in
+/// the order of the keys.
+pub async fn query_storage_values_verified<T>(
+    client: &HttpClient,
+    keys: &[storage::Key],
+    verified: Option<&VerifiedState>,
+) -> Vec<Option<T>>
+where
+    T: BorshDeserialize,
+{
+    let height = verified.map(|verified| verified.height);
+    let requests: Vec<BatchRequest> = keys
+        .iter()
+        .map(|key| BatchRequest {
+            prove: verified.is_some(),
+            ..BatchRequest::new(RPC.shell().storage_value_path(key))
+        })
+        .collect();
+    let responses = unwrap_client_response(
+        queries::Client::batch_request(client, &requests, height).await,
+    );
+    let mut values = Vec::with_capacity(keys.len());
+    let mut failed_proofs = vec![];
+    for (key, response) in keys.iter().zip(responses) {
+        let value = match response {
+            Ok(response) => {
+                let value =
+                    (!response.data.is_empty()).then_some(response.data);
+                if let Some(verified) = verified {
+                    if let Err(err) = verified.verify_values(
+                        response.proof.as_ref(),
+                        [(key, value.as_deref())],
+                    ) {
+                        eprintln!(
+                            "Failed to verify the value of the key {key}: \
+                             {err}"
+                        );
+                        cli::safe_exit(1)
+                    }
+                }
+                value
+            }
+            Err(err) if verified.is_some() => {
+                failed_proofs.push((key, err));
+                None
+            }
+            Err(err) => {
+                eprintln!("Error in the query {}", err);
+                cli::safe_exit(1)
+            }
+        };
+        values.push(value);
+    }
+    // The ledger can only prove the absence of the keys in the IBC sub-tree,
+    // so the requests with proofs fail for the other absent keys
+    if !failed_proofs.is_empty() {
+        let requests: Vec<BatchRequest> = failed_proofs
+            .iter()
+            .map(|(key, _err)| {
+                BatchRequest::new(RPC.shell().storage_value_path(key))
+            })
+            .collect();
+        let responses = unwrap_client_response(
+            queries::Client::batch_request(client, &requests, height).await,
+        );
+        for ((key, err), response) in failed_proofs.into_iter().zip(responses) {
+            match response {
+                Ok(response) if response.data.is_empty() => eprintln!(
+                    "Warning: The absence of the key {key} cannot be proven."
+                ),
+                _ => {
+                    eprintln!(
+                        "Failed to query the proof of the key {key}: {err}"
+                    );
+                    cli::safe_exit(1)
+                }
+            }
+        }
+    }
+    values
+        .into_iter()
+        .map(|value| value.and_then(decode_batch_value))
+        .collect()
+}
+
+/// Decode a storage value from the response to a request in a batch, which is
+/// empty if the value is not present
+fn decode_batch_value<T>(data: Vec<u8>) -> Option<T>
+where
+    T: BorshDeserialize,
+{
+    if data.is_empty() {
+        return None;
+    }
+    Some(T::try_from_slice(&data[..]).unwrap_or_else(|err| {
+        eprintln!("Error decoding the value: {}", err);
+        cli::safe_exit(1)
+    }))
+}
+
 /// Query a range of storage values with a matching prefix and decode them with
 /// [`BorshDeserialize`]. Returns an iterator of the storage keys paired with
 /// their associated values.
@@ -2327,11 +2912,41 @@ pub async fn query_storage_prefix<T>(
 where
     T: BorshDeserialize,
 {
+    query_storage_prefix_verified(client, key, None).await
+}
+
+/// Query a range of storage values with a matching prefix and decode them with
+/// [`BorshDeserialize`]. In the verified mode, i.e. with a `verified` state,
+/// the values are read at the height of the state and their proofs are checked
+/// against the verified app hash. Note that only the returned values can be
+/// proven, not that there are no other values with the prefix.
+pub async fn query_storage_prefix_verified<T>(
+    client: &HttpClient,
+    key: &storage::Key,
+    verified: Option<&VerifiedState>,
+) -> Option<impl Iterator<Item = (storage::Key, T)>>
+where
+    T: BorshDeserialize,
+{
+    let height = verified.map(|verified| verified.height);
+    let prove = verified.is_some();
     let values = unwrap_client_response(
         RPC.shell()
-            .storage_prefix(client, None, None, false, key)
+            .storage_prefix(client, None, height, prove, key)
             .await,
     );
+    if let Some(verified) = verified {
+        let proven_values = values
+            .data
+            .iter()
+            .map(|PrefixValue { key, value }| (key, Some(&value[..])));
+        if let Err(err) =
+            verified.verify_values(values.proof.as_ref(), proven_values)
+        {
+            eprintln!("Failed to verify the values with prefix {key}: {err}");
+            cli::safe_exit(1)
+        }
+    }
     let decode =
         |PrefixValue { key, value }: PrefixValue| match T::try_from_slice(
             &value[..],
@@ -2352,12 +2967,96 @@ where
     }
 }
 
+/// In the verified mode, i.e. when the query arguments have a trust root,
+/// verify the latest block header with a light client and return the state of
+/// the ledger whose app hash is in the header
+pub async fn query_verified_state(
+    client: &HttpClient,
+    args: &args::Query,
+) -> Option<VerifiedState> {
+    let trust_root = args.trust_root.as_ref()?;
+    let mut light_client = LightClient::new(client.clone(), trust_root)
+        .await
+        .unwrap_or_else(|err| {
+            eprintln!("Failed to initialize the light client: {err}");
+            cli::safe_exit(1)
+        });
+    let verified =
+        light_client
+            .verify_latest_state()
+            .await
+            .unwrap_or_else(|err| {
+                eprintln!("Failed to verify the latest block header: {err}");
+                cli::safe_exit(1)
+            });
+    let header = &light_client.trusted().signed_header.header;
+    println!(
+        "Verified the block header at height {} with hash {}. The queried \
+         values are verified at height {}.",
+        header.height,
+        header.hash(),
+        verified.height
+    );
+    Some(verified)
+}
+
+/// Query the epoch of the last committed block. In the verified mode, i.e.
+/// with a `verified` state, the epoch of the state is queried with a proof
+/// that is checked against the verified app hash.
+pub async fn query_epoch_verified(
+    args: args::Query,
+    verified: Option<&VerifiedState>,
+) -> Epoch {
+    let verified = match verified {
+        Some(verified) => verified,
+        None => return query_epoch(args).await,
+    };
+    let client = HttpClient::new(args.ledger_address).unwrap();
+    let proven = unwrap_client_response(
+        RPC.shell()
+            .block_epoch_with_proof(&client, Some(verified.height))
+            .await,
+    );
+    let result = match &proven.root {
+        Some(root) => verified.verify_root(root),
+        None => Err(light_client::Error::MissingProof(current_epoch_key())),
+    };
+    if let Err(err) = result {
+        eprintln!("Failed to verify the epoch: {err}");
+        cli::safe_exit(1)
+    }
+    println!("Verified epoch: {}", proven.data);
+    proven.data
+}
+
+/// Check the root of the proofs in the response of a query with proofs
+/// against the verified app hash and get the proven data. The reads of the
+/// keys that are not present and whose absence can't be proven are reported
+/// with a warning.
+fn check_proven<T>(proven: Proven<T>, verified: &VerifiedState) -> T {
+    if let Some(root) = &proven.root {
+        if let Err(err) = verified.verify_root(root) {
+            eprintln!("Failed to verify the query response: {err}");
+            cli::safe_exit(1)
+        }
+    }
+    for key in &proven.unproven {
+        eprintln!("Warning: The absence of the key {key} is not proven.");
+    }
+    proven.data
+}
+
 /// Query to check if the given storage key exists.
 pub async fn query_has_storage_key(
     client: &HttpClient,
     key: &storage::Key,
 ) -> bool {
-    unwrap_client_response(RPC.shell().storage_has_key(client, key).await)
+    unwrap_client_response(
+        RPC.shell()
+            .storage_has_key(client, None, None, false, key)
+            .await,
+    )
+    .data
 }
 
 /// Represents a query for an event pertaining to the specified transaction
@@ -2543,87 +3242,91 @@ pub async fn query_result(_ctx: Context, args: args::QueryResult) {
     }
 }
 
-pub async fn get_proposal_votes(
-    client: &HttpClient,
-    epoch: Epoch,
-    proposal_id: u64,
-) -> Votes {
-    let validators = get_all_validators(client, epoch).await;
-
-    let vote_prefix_key =
-        gov_storage::get_proposal_vote_prefix_key(proposal_id);
-    let vote_iter =
-        query_storage_prefix::<ProposalVote>(client, &vote_prefix_key).await;
-
-    let mut yay_validators: HashMap<Address, VotePower> = HashMap::new();
-    let mut yay_delegators: HashMap<Address, HashMap<Address, VotePower>> =
-        HashMap::new();
-    let mut nay_delegators: HashMap<Address, HashMap<Address, VotePower>> =
-        HashMap::new();
-
-    if let Some(vote_iter) = vote_iter {
-        for (key, vote) in vote_iter {
-            let voter_address = gov_storage::get_voter_address(&key)
-                .expect("Vote key should contain the voting address.")
-                .clone();
-            if vote.is_yay() && validators.contains(&voter_address) {
-                let amount: VotePower =
-                    get_validator_stake(client, epoch, &voter_address)
-                        .await
-                        .into();
-                yay_validators.insert(voter_address, amount);
-            } else if !validators.contains(&voter_address) {
-                let validator_address =
-                    gov_storage::get_vote_delegation_address(&key)
-                        .expect(
-                            "Vote key should contain the delegation address.",
-                        )
-                        .clone();
-                let delegator_token_amount = get_bond_amount_at(
-                    client,
-                    &voter_address,
-                    &validator_address,
-                    epoch,
-                )
-                .await;
-                if let Some(amount) = delegator_token_amount {
-                    if vote.is_yay() {
-                        let entry =
-                            yay_delegators.entry(voter_address).or_default();
-                        entry
-                            .insert(validator_address, VotePower::from(amount));
-                    } else {
-                        let entry =
-                            nay_delegators.entry(voter_address).or_default();
-                        entry
-                            .insert(validator_address, VotePower::from(amount));
-                    }
+/// Subscribe to the events of the ledger that match the query and print them
+/// as they're emitted, until the node closes the connection.
+pub async fn watch_events(_ctx: Context, args: args::Watch) {
+    let url = format!("ws://{}", args.subscription_address);
+    let (mut ws, _) = match connect_async(url.as_str()).await {
+        Ok(connected) => connected,
+        Err(err) => {
+            eprintln!(
+                "Failed to connect to the event subscriptions at {}: {}",
+                url, err
+            );
+            cli::safe_exit(1)
+        }
+    };
+    let query = args.query.unwrap_or_default();
+    let subscribe = ClientMessage::Subscribe {
+        id: 0,
+        query: query.clone(),
+    };
+    let subscribe = serde_json::to_string(&subscribe)
+        .expect("Encoding a client message shouldn't fail");
+    if let Err(err) = ws.send(WsMessage::Text(subscribe)).await {
+        eprintln!("Failed to subscribe to the events: {}", err);
+        cli::safe_exit(1)
+    }
+    while let Some(msg) = ws.next().await {
+        let text = match msg {
+            Ok(WsMessage::Text(text)) => text,
+            Ok(WsMessage::Close(_)) => break,
+            Ok(_) => continue,
+            Err(err) => {
+                eprintln!("The event subscription has failed: {}", err);
+                cli::safe_exit(1)
+            }
+        };
+        match serde_json::from_str(&text) {
+            Ok(ServerMessage::Subscribed { .. }) => {
+                if query.is_empty() {
+                    println!("Watching all the events...");
+                } else {
+                    println!("Watching the events matching {}...", query);
                 }
             }
+            Ok(ServerMessage::Event { height, event, .. }) => {
+                println!(
+                    "Block {}: {}",
+                    height,
+                    serde_json::to_string(&event).unwrap()
+                );
+            }
+            Ok(ServerMessage::Error {
+                id: Some(_),
+                message,
+            }) => {
+                // The subscription has been refused
+                eprintln!("{}", message);
+                cli::safe_exit(1)
+            }
+            Ok(ServerMessage::Error { id: None, message }) => {
+                eprintln!("{}", message);
+            }
+            Ok(ServerMessage::Unsubscribed { .. }) => break,
+            Err(err) => {
+                eprintln!("Couldn't decode a message of the node: {}", err);
+            }
         }
     }
-
-    Votes {
-        yay_validators,
-        yay_delegators,
-        nay_delegators,
-    }
+    println!("The node has closed the event subscription.");
 }
 
 pub async fn get_proposal_offline_votes(
     client: &HttpClient,
     proposal: OfflineProposal,
     files: HashSet<PathBuf>,
-) -> Votes {
+) -> ProposalVotes {
     let validators = get_all_validators(client, proposal.tally_epoch).await;
 
     let proposal_hash = proposal.compute_hash();
 
-    let mut yay_validators: HashMap<Address, VotePower> = HashMap::new();
-    let mut yay_delegators: HashMap<Address, HashMap<Address, VotePower>> =
-        HashMap::new();
-    let mut nay_delegators: HashMap<Address, HashMap<Address, VotePower>> =
-        HashMap::new();
+    let mut votes = ProposalVotes {
+        epoch: proposal.tally_epoch,
+        validators: BTreeMap::new(),
+        delegators: BTreeMap::new(),
+        pass_through: BTreeMap::new(),
+    };
 
     for path in files {
         let file = File::open(&path).expect("Proposal file must exist.");
@@ -2641,17 +3344,21 @@ pub async fn get_proposal_offline_votes(
             continue;
         }
 
-        if proposal_vote.vote.is_yay()
-            && validators.contains(&proposal_vote.address)
-        {
-            let amount: VotePower = get_validator_stake(
+        if validators.contains(&proposal_vote.address) {
+            let power: VotePower = get_validator_stake(
                 client,
                 proposal.tally_epoch,
                 &proposal_vote.address,
             )
             .await
             .into();
-            yay_validators.insert(proposal_vote.address, amount);
+            votes.validators.insert(
+                proposal_vote.address,
+                WeightedVote {
+                    vote: proposal_vote.vote,
+                    power,
+                },
+            );
         } else if is_delegator_at(
             client,
             &proposal_vote.address,
@@ -2710,140 +3417,56 @@ pub async fn get_proposal_offline_votes(
                         pos::get_validator_address_from_bond(&key).expect(
                             "Delegation key should contain validator address.",
                         );
-                    if proposal_vote.vote.is_yay() {
-                        let entry = yay_delegators
-                            .entry(proposal_vote.address.clone())
-                            .or_default();
-                        entry.insert(
+                    votes
+                        .delegators
+                        .entry(proposal_vote.address.clone())
+                        .or_default()
+                        .insert(
                             validator_address,
-                            VotePower::from(delegated_amount),
+                            WeightedVote {
+                                vote: proposal_vote.vote.clone(),
+                                power: VotePower::from(delegated_amount),
+                            },
                         );
-                    } else {
-                        let entry = nay_delegators
-                            .entry(proposal_vote.address.clone())
-                            .or_default();
-                        entry.insert(
-                            validator_address,
-                            VotePower::from(delegated_amount),
-                        );
-                    }
                 }
             }
         }
     }
 
-    Votes {
-        yay_validators,
-        yay_delegators,
-        nay_delegators,
+    // The validators that pass through the majority vote of their delegators
+    let pass_through_prefix = gov_storage::get_vote_pass_through_prefix();
+    let pass_through_iter =
+        query_storage_prefix::<bool>(client, &pass_through_prefix).await;
+    for (key, _) in pass_through_iter.into_iter().flatten() {
+        let validator = match gov_storage::is_vote_pass_through_key(&key) {
+            Some(validator) if validators.contains(validator) => validator,
+            _ => continue,
+        };
+        let power: VotePower =
+            get_validator_stake(client, proposal.tally_epoch, validator)
+                .await
+                .into();
+        votes.pass_through.insert(validator.clone(), power);
     }
+
+    votes
 }
 
-// Compute the result of a proposal
+// Compute the result of a proposal with the same tally as the ledger
 pub async fn compute_tally(
     client: &HttpClient,
     epoch: Epoch,
-    votes: Votes,
+    votes: ProposalVotes,
 ) -> ProposalResult {
     let total_staked_tokens: VotePower =
         get_total_staked_tokens(client, epoch).await.into();
+    let params = get_governance_parameters(client).await;
 
-    let Votes {
-        yay_validators,
-        yay_delegators,
-        nay_delegators,
-    } = votes;
-
-    let mut total_yay_staked_tokens = VotePower::from(0_u64);
-    for (_, amount) in yay_validators.clone().into_iter() {
-        total_yay_staked_tokens += amount;
-    }
-
-    // YAY: Add delegator amount whose validator didn't vote / voted nay
-    for (_, vote_map) in yay_delegators.iter() {
-        for (validator_address, vote_power) in vote_map.iter() {
-            if !yay_validators.contains_key(validator_address) {
-                total_yay_staked_tokens += vote_power;
-            }
-        }
-    }
-
-    // NAY: Remove delegator amount whose validator validator vote yay
-    for (_, vote_map) in nay_delegators.iter() {
-        for (validator_address, vote_power) in vote_map.iter() {
-            if yay_validators.contains_key(validator_address) {
-                total_yay_staked_tokens -= vote_power;
-            }
-        }
-    }
-
-    if total_yay_staked_tokens >= (total_staked_tokens / 3) * 2 {
-        ProposalResult {
-            result: TallyResult::Passed,
-            total_voting_power: total_staked_tokens,
-            total_yay_power: total_yay_staked_tokens,
-            total_nay_power: 0,
-        }
-    } else {
-        ProposalResult {
-            result: TallyResult::Rejected,
-            total_voting_power: total_staked_tokens,
-            total_yay_power: total_yay_staked_tokens,
-            total_nay_power: 0,
-        }
-    }
-}
-
-pub async fn get_bond_amount_at(
-    client: &HttpClient,
-    delegator: &Address,
-    validator: &Address,
-    epoch: Epoch,
-) -> Option<token::Amount> {
-    let slashes_key = pos::validator_slashes_key(validator);
-    let slashes = query_storage_value::<pos::Slashes>(client, &slashes_key)
-        .await
-        .unwrap_or_default();
-    let bond_key = pos::bond_key(&BondId {
-        source: delegator.clone(),
-        validator: validator.clone(),
-    });
-    let epoched_bonds = query_storage_value::<Bonds>(client, &bond_key).await;
-    match epoched_bonds {
-        Some(epoched_bonds) => {
-            let mut delegated_amount: token::Amount = 0.into();
-            for bond in epoched_bonds.iter() {
-                let mut to_deduct = bond.neg_deltas;
-                for (epoch_start, &(mut delta)) in
-                    bond.pos_deltas.iter().sorted()
-                {
-                    // deduct bond's neg_deltas
-                    if to_deduct > delta {
-                        to_deduct -= delta;
-                        // If the whole bond was deducted, continue to
-                        // the next one
-                        continue;
-                    } else {
-                        delta -= to_deduct;
-                        to_deduct = token::Amount::default();
-                    }
-
-                    delta = apply_slashes(
-                        &slashes,
-                        delta,
-                        *epoch_start,
-                        None,
-                        None,
-                    );
-                    if epoch >= *epoch_start {
-                        delegated_amount += delta;
-                    }
-                }
-            }
-            Some(delegated_amount)
-        }
-        None => None,
-    }
+    utils::compute_tally(
+        votes,
+        total_staked_tokens,
+        &TallyParams::new(&params, false),
+    )
 }
 
 pub async fn get_all_validators(
@@ -2888,45 +3511,11 @@ pub async fn get_delegators_delegation(
 }
 
 pub async fn get_governance_parameters(client: &HttpClient) -> GovParams {
-    use namada::types::token::Amount;
-    let key = gov_storage::get_max_proposal_code_size_key();
-    let max_proposal_code_size = query_storage_value::<u64>(client, &key)
-        .await
-        .expect("Parameter should be definied.");
-
-    let key = gov_storage::get_max_proposal_content_key();
-    let max_proposal_content_size = query_storage_value::<u64>(client, &key)
-        .await
-        .expect("Parameter should be definied.");
-
-    let key = gov_storage::get_min_proposal_fund_key();
-    let min_proposal_fund = query_storage_value::<Amount>(client, &key)
-        .await
-        .expect("Parameter should be definied.");
-
-    let key = gov_storage::get_min_proposal_grace_epoch_key();
-    let min_proposal_grace_epochs = query_storage_value::<u64>(client, &key)
-        .await
-        .expect("Parameter should be definied.");
-
-    let key = gov_storage::get_min_proposal_period_key();
-    let min_proposal_period = query_storage_value::<u64>(client, &key)
-        .await
-        .expect("Parameter should be definied.");
-
-    let key = gov_storage::get_max_proposal_period_key();
-    let max_proposal_period = query_storage_value::<u64>(client, &key)
-        .await
-        .expect("Parameter should be definied.");
+    unwrap_client_response(RPC.vp().governance().parameters(client).await)
+}
 
-    GovParams {
-        min_proposal_fund: u64::from(min_proposal_fund),
-        max_proposal_code_size,
-        min_proposal_period,
-        max_proposal_period,
-        max_proposal_content_size,
-        min_proposal_grace_epochs,
-    }
+pub async fn get_pgf_council(client: &HttpClient) -> Option<PgfCouncil> {
+    unwrap_client_response(RPC.vp().pgf().council(client).await)
 }
 
 /// Try to find an alias for a given address from the wallet. If not found,
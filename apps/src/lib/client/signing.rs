@@ -156,6 +156,7 @@ pub async fn sign_tx(
 
     let epoch = rpc::query_epoch(args::Query {
         ledger_address: args.ledger_address.clone(),
+        trust_root: None,
     })
     .await;
     let broadcast_data = if args.dry_run {
@@ -42,7 +42,7 @@ use namada::ledger::pos::{BondId, Bonds, CommissionRates, Unbonds};
 use namada::proto::Tx;
 use namada::types::address::{masp, masp_tx_key, Address};
 use namada::types::governance::{
-    OfflineProposal, OfflineVote, Proposal, ProposalVote,
+    validate_content, OfflineProposal, OfflineVote, Proposal, ProposalError,
 };
 use namada::types::key::*;
 use namada::types::masp::{PaymentAddress, TransferTarget};
@@ -50,12 +50,11 @@ use namada::types::storage::{
     BlockHeight, Epoch, Key, KeySeg, TxIndex, RESERVED_ADDRESS_PREFIX,
 };
 use namada::types::time::DateTimeUtc;
-use namada::types::token::{
-    Transfer, HEAD_TX_KEY, PIN_KEY_PREFIX, TX_KEY_PREFIX,
-};
+use namada::types::token::{Transfer, PIN_KEY_PREFIX, TX_KEY_PREFIX};
 use namada::types::transaction::governance::{
-    InitProposalData, VoteProposalData,
+    InitProposalData, VotePassThroughData, VoteProposalData,
 };
+use namada::types::transaction::pgf::PgfFundingAction;
 use namada::types::transaction::{pos, InitAccount, InitValidator, UpdateVp};
 use namada::types::{storage, token};
 use namada::{ledger, vm};
@@ -82,6 +81,8 @@ const TX_INIT_ACCOUNT_WASM: &str = "tx_init_account.wasm";
 const TX_INIT_VALIDATOR_WASM: &str = "tx_init_validator.wasm";
 const TX_INIT_PROPOSAL: &str = "tx_init_proposal.wasm";
 const TX_VOTE_PROPOSAL: &str = "tx_vote_proposal.wasm";
+const TX_VOTE_PASS_THROUGH: &str = "tx_vote_pass_through.wasm";
+const TX_PGF_FUNDING: &str = "tx_pgf_funding.wasm";
 const TX_REVEAL_PK: &str = "tx_reveal_pk.wasm";
 const TX_UPDATE_VP_WASM: &str = "tx_update_vp.wasm";
 const TX_TRANSFER_WASM: &str = "tx_transfer.wasm";
@@ -703,43 +704,24 @@ impl ShieldedContext {
 
     /// Obtain a chronologically-ordered list of all accepted shielded
     /// transactions from the ledger. The ledger conceptually stores
-    /// transactions as a vector. More concretely, the HEAD_TX_KEY location
-    /// stores the index of the last accepted transaction and each transaction
-    /// is stored at a key derived from its index.
+    /// transactions as a vector. The transactions following the given index
+    /// are requested a page at a time from the MASP sync queries.
     pub async fn fetch_shielded_transfers(
         ledger_address: &TendermintAddress,
         last_txidx: u64,
     ) -> BTreeMap<(BlockHeight, TxIndex), (Epoch, Transfer)> {
         let client = HttpClient::new(ledger_address.clone()).unwrap();
-        // The address of the MASP account
-        let masp_addr = masp();
-        // Construct the key where last transaction pointer is stored
-        let head_tx_key = Key::from(masp_addr.to_db_key())
-            .push(&HEAD_TX_KEY.to_owned())
-            .expect("Cannot obtain a storage key");
-        // Query for the index of the last accepted transaction
-        let head_txidx = query_storage_value::<u64>(&client, &head_tx_key)
-            .await
-            .unwrap_or(0);
-        let mut shielded_txs = BTreeMap::new();
         // Fetch all the transactions we do not have yet
-        for i in last_txidx..head_txidx {
-            // Construct the key for where the current transaction is stored
-            let current_tx_key = Key::from(masp_addr.to_db_key())
-                .push(&(TX_KEY_PREFIX.to_owned() + &i.to_string()))
-                .expect("Cannot obtain a storage key");
-            // Obtain the current transaction
-            let (tx_epoch, tx_height, tx_index, current_tx) =
-                query_storage_value::<(Epoch, BlockHeight, TxIndex, Transfer)>(
-                    &client,
-                    &current_tx_key,
+        rpc::query_shielded_transfers(&client, last_txidx)
+            .await
+            .into_iter()
+            .map(|transfer| {
+                (
+                    (transfer.height, transfer.tx_index),
+                    (transfer.epoch, transfer.transfer),
                 )
-                .await
-                .unwrap();
-            // Collect the current transaction
-            shielded_txs.insert((tx_height, tx_index), (tx_epoch, current_tx));
-        }
-        shielded_txs
+            })
+            .collect()
     }
 
     /// Applies the given transaction to the supplied context. More precisely,
@@ -1820,45 +1802,52 @@ pub async fn submit_init_proposal(mut ctx: Context, args: args::InitProposal) {
     let governance_parameters = rpc::get_governance_parameters(&client).await;
     let current_epoch = rpc::query_epoch(args::Query {
         ledger_address: args.tx.ledger_address.clone(),
+        trust_root: None,
     })
     .await;
+    // An expedited proposal has its own min. period and funds
+    let (min_proposal_period, min_proposal_fund) = if proposal.expedited {
+        (
+            governance_parameters.min_expedited_proposal_period,
+            governance_parameters.min_expedited_proposal_fund,
+        )
+    } else {
+        (
+            governance_parameters.min_proposal_period,
+            governance_parameters.min_proposal_fund,
+        )
+    };
 
     if proposal.voting_start_epoch <= current_epoch
-        || proposal.voting_start_epoch.0
-            % governance_parameters.min_proposal_period
-            != 0
+        || proposal.voting_start_epoch.0 % min_proposal_period != 0
     {
         println!("{}", proposal.voting_start_epoch <= current_epoch);
         println!(
             "{}",
-            proposal.voting_start_epoch.0
-                % governance_parameters.min_proposal_period
-                == 0
+            proposal.voting_start_epoch.0 % min_proposal_period == 0
         );
         eprintln!(
             "Invalid proposal start epoch: {} must be greater than current \
              epoch {} and a multiple of {}",
-            proposal.voting_start_epoch,
-            current_epoch,
-            governance_parameters.min_proposal_period
+            proposal.voting_start_epoch, current_epoch, min_proposal_period
         );
         if !args.tx.force {
             safe_exit(1)
         }
     } else if proposal.voting_end_epoch <= proposal.voting_start_epoch
         || proposal.voting_end_epoch.0 - proposal.voting_start_epoch.0
-            < governance_parameters.min_proposal_period
+            < min_proposal_period
         || proposal.voting_end_epoch.0 - proposal.voting_start_epoch.0
             > governance_parameters.max_proposal_period
-        || proposal.voting_end_epoch.0 % 3 != 0
+        || proposal.voting_end_epoch.0 % min_proposal_period != 0
     {
         eprintln!(
             "Invalid proposal end epoch: difference between proposal start \
              and end epoch must be at least {} and at max {} and end epoch \
              must be a multiple of {}",
-            governance_parameters.min_proposal_period,
+            min_proposal_period,
             governance_parameters.max_proposal_period,
-            governance_parameters.min_proposal_period
+            min_proposal_period
         );
         if !args.tx.force {
             safe_exit(1)
@@ -1876,6 +1865,23 @@ pub async fn submit_init_proposal(mut ctx: Context, args: args::InitProposal) {
             safe_exit(1)
         }
     }
+    // The content must follow the schema even when only its hash is
+    // published on chain
+    let content_validation =
+        validate_content(&proposal.content).and_then(|()| {
+            match proposal.content_url.as_deref() {
+                Some(url) if url.trim().is_empty() => {
+                    Err(ProposalError::MissingContentUrl)
+                }
+                _ => Ok(()),
+            }
+        });
+    if let Err(err) = content_validation {
+        eprintln!("Invalid proposal content: {}", err);
+        if !args.tx.force {
+            safe_exit(1)
+        }
+    }
 
     if args.offline {
         let signer = ctx.get(&signer);
@@ -1907,11 +1913,15 @@ pub async fn submit_init_proposal(mut ctx: Context, args: args::InitProposal) {
         }
     } else {
         let tx_data: Result<InitProposalData, _> = proposal.clone().try_into();
-        let init_proposal_data = if let Ok(data) = tx_data {
-            data
-        } else {
-            eprintln!("Invalid data for init proposal transaction.");
-            safe_exit(1)
+        let init_proposal_data = match tx_data {
+            Ok(data) => data,
+            Err(err) => {
+                eprintln!(
+                    "Invalid data for init proposal transaction: {}",
+                    err
+                );
+                safe_exit(1)
+            }
         };
 
         let balance = rpc::get_token_balance(
@@ -1921,9 +1931,7 @@ pub async fn submit_init_proposal(mut ctx: Context, args: args::InitProposal) {
         )
         .await
         .unwrap_or_default();
-        if balance
-            < token::Amount::from(governance_parameters.min_proposal_fund)
-        {
+        if balance < token::Amount::from(min_proposal_fund) {
             eprintln!(
                 "Address {} doesn't have enough funds.",
                 &proposal.author
@@ -2017,6 +2025,7 @@ pub async fn submit_vote_proposal(mut ctx: Context, args: args::VoteProposal) {
         let client = HttpClient::new(args.tx.ledger_address.clone()).unwrap();
         let current_epoch = rpc::query_epoch(args::Query {
             ledger_address: args.tx.ledger_address.clone(),
+            trust_root: None,
         })
         .await;
 
@@ -2043,33 +2052,30 @@ pub async fn submit_vote_proposal(mut ctx: Context, args: args::VoteProposal) {
                         safe_exit(1)
                     }
                 }
-                let mut delegations =
-                    rpc::get_delegators_delegation(&client, &voter_address)
-                        .await;
+                // A vote may be changed until the end of the voting period
+                let proposal_end_epoch_key =
+                    gov_storage::get_voting_end_epoch_key(proposal_id);
+                let proposal_end_epoch = rpc::query_storage_value::<Epoch>(
+                    &client,
+                    &proposal_end_epoch_key,
+                )
+                .await;
+                if let Some(end_epoch) = proposal_end_epoch {
+                    if current_epoch > end_epoch {
+                        eprintln!(
+                            "Current epoch {} is greater than proposal end \
+                             epoch {}",
+                            current_epoch, end_epoch
+                        );
 
-                // Optimize by quering if a vote from a validator
-                // is equal to ours. If so, we can avoid voting, but ONLY if we
-                // are  voting in the last third of the voting
-                // window, otherwise there's  the risk of the
-                // validator changing his vote and, effectively, invalidating
-                // the delgator's vote
-                if !args.tx.force
-                    && is_safe_voting_window(
-                        args.tx.ledger_address.clone(),
-                        &client,
-                        proposal_id,
-                        epoch,
-                    )
-                    .await
-                {
-                    delegations = filter_delegations(
-                        &client,
-                        delegations,
-                        proposal_id,
-                        &args.vote,
-                    )
-                    .await;
+                        if !args.tx.force {
+                            safe_exit(1)
+                        }
+                    }
                 }
+                let delegations =
+                    rpc::get_delegators_delegation(&client, &voter_address)
+                        .await;
 
                 let tx_data = VoteProposalData {
                     id: proposal_id,
@@ -2107,6 +2113,91 @@ pub async fn submit_vote_proposal(mut ctx: Context, args: args::VoteProposal) {
     }
 }
 
+pub async fn submit_vote_pass_through(
+    ctx: Context,
+    args: args::VotePassThrough,
+) {
+    let validator = ctx.get(&args.validator);
+    // Check that the validator address exists on chain
+    let is_validator =
+        rpc::is_validator(&validator, args.tx.ledger_address.clone()).await;
+    if !is_validator {
+        eprintln!(
+            "The address {} doesn't belong to any known validator account.",
+            validator
+        );
+        if !args.tx.force {
+            safe_exit(1)
+        }
+    }
+
+    let data = VotePassThroughData {
+        validator,
+        pass_through: !args.disable,
+    };
+    let data = data.try_to_vec().expect("Encoding tx data shouldn't fail");
+    let tx_code = ctx.read_wasm(TX_VOTE_PASS_THROUGH);
+    let tx = Tx::new(tx_code, Some(data));
+    process_tx(
+        ctx,
+        &args.tx,
+        tx,
+        TxSigningKey::WalletAddress(args.validator),
+        #[cfg(not(feature = "mainnet"))]
+        false,
+    )
+    .await;
+}
+
+pub async fn submit_pgf_funding(ctx: Context, args: args::PgfFunding) {
+    let client = HttpClient::new(args.tx.ledger_address.clone()).unwrap();
+    let council = ctx.get(&args.council);
+    // Check that the address is the current PGF council
+    match rpc::get_pgf_council(&client).await {
+        Some(current) if current.address == council => {}
+        Some(current) => {
+            eprintln!(
+                "The address {} is not the PGF council {}.",
+                council, current.address
+            );
+            if !args.tx.force {
+                safe_exit(1)
+            }
+        }
+        None => {
+            eprintln!("No PGF council has been elected.");
+            if !args.tx.force {
+                safe_exit(1)
+            }
+        }
+    }
+
+    let recipient = ctx.get(&args.recipient);
+    let data = if args.retro {
+        PgfFundingAction::RetroPayment {
+            recipient,
+            amount: args.amount,
+        }
+    } else {
+        PgfFundingAction::SetStream {
+            recipient,
+            amount: args.amount,
+        }
+    };
+    let data = data.try_to_vec().expect("Encoding tx data shouldn't fail");
+    let tx_code = ctx.read_wasm(TX_PGF_FUNDING);
+    let tx = Tx::new(tx_code, Some(data));
+    process_tx(
+        ctx,
+        &args.tx,
+        tx,
+        TxSigningKey::WalletAddress(args.council),
+        #[cfg(not(feature = "mainnet"))]
+        false,
+    )
+    .await;
+}
+
 pub async fn submit_reveal_pk(mut ctx: Context, args: args::RevealPk) {
     let args::RevealPk {
         tx: args,
@@ -2168,6 +2259,7 @@ pub async fn submit_reveal_pk_aux(
     };
     let epoch = rpc::query_epoch(args::Query {
         ledger_address: args.ledger_address.clone(),
+        trust_root: None,
     })
     .await;
     let to_broadcast = if args.dry_run {
@@ -2224,75 +2316,6 @@ pub async fn submit_reveal_pk_aux(
     }
 }
 
-/// Check if current epoch is in the last third of the voting period of the
-/// proposal. This ensures that it is safe to optimize the vote writing to
-/// storage.
-async fn is_safe_voting_window(
-    ledger_address: TendermintAddress,
-    client: &HttpClient,
-    proposal_id: u64,
-    proposal_start_epoch: Epoch,
-) -> bool {
-    let current_epoch = rpc::query_epoch(args::Query { ledger_address }).await;
-
-    let proposal_end_epoch_key =
-        gov_storage::get_voting_end_epoch_key(proposal_id);
-    let proposal_end_epoch =
-        rpc::query_storage_value::<Epoch>(client, &proposal_end_epoch_key)
-            .await;
-
-    match proposal_end_epoch {
-        Some(proposal_end_epoch) => {
-            !namada::ledger::native_vp::governance::utils::is_valid_validator_voting_period(
-                current_epoch,
-                proposal_start_epoch,
-                proposal_end_epoch,
-            )
-        }
-        None => {
-            eprintln!("Proposal end epoch is not in the storage.");
-            safe_exit(1)
-        }
-    }
-}
-
-/// Removes validators whose vote corresponds to that of the delegator (needless
-/// vote)
-async fn filter_delegations(
-    client: &HttpClient,
-    delegations: HashSet<Address>,
-    proposal_id: u64,
-    delegator_vote: &ProposalVote,
-) -> HashSet<Address> {
-    // Filter delegations by their validator's vote concurrently
-    let delegations = futures::future::join_all(
-        delegations
-            .into_iter()
-            // we cannot use `filter/filter_map` directly because we want to
-            // return a future
-            .map(|validator_address| async {
-                let vote_key = gov_storage::get_vote_proposal_key(
-                    proposal_id,
-                    validator_address.to_owned(),
-                    validator_address.to_owned(),
-                );
-
-                if let Some(validator_vote) =
-                    rpc::query_storage_value::<ProposalVote>(client, &vote_key)
-                        .await
-                {
-                    if &validator_vote == delegator_vote {
-                        return None;
-                    }
-                }
-                Some(validator_address)
-            }),
-    )
-    .await;
-    // Take out the `None`s
-    delegations.into_iter().flatten().collect()
-}
-
 pub async fn submit_bond(ctx: Context, args: args::Bond) {
     let validator = ctx.get(&args.validator);
     // Check that the validator address exists on chain
@@ -2445,6 +2468,7 @@ pub async fn submit_unbond(ctx: Context, args: args::Unbond) {
 pub async fn submit_withdraw(ctx: Context, args: args::Withdraw) {
     let epoch = rpc::query_epoch(args::Query {
         ledger_address: args.tx.ledger_address.clone(),
+        trust_root: None,
     })
     .await;
 
@@ -2523,6 +2547,7 @@ pub async fn submit_validator_commission_change(
 ) {
     let epoch = rpc::query_epoch(args::Query {
         ledger_address: args.tx.ledger_address.clone(),
+        trust_root: None,
     })
     .await;
 
@@ -89,6 +89,10 @@ impl ShieldedTransferContext for Context {
     }
 
     async fn query_epoch(&self, ledger_address: TendermintAddress) -> Epoch {
-        rpc::query_epoch(args::Query { ledger_address }).await
+        rpc::query_epoch(args::Query {
+            ledger_address,
+            trust_root: None,
+        })
+        .await
     }
 }
@@ -411,7 +411,17 @@ pub fn init_network(
         // Find the sha256 from checksums.json
         let name = format!("{}.wasm", name);
         // Full name in format `{name}.{sha256}.wasm`
-        let full_name = checksums.0.get(&name).unwrap();
+        let full_name = match checksums.0.get(&name) {
+            Some(full_name) => full_name,
+            // WASMs that are not built from this repository (e.g. VPs
+            // carried over from an exported chain state) must have their
+            // sha256 set in the genesis config
+            None if config.sha256.is_some() => return,
+            None => {
+                eprintln!("Missing checksum for {} in checksums file", name);
+                cli::safe_exit(1)
+            }
+        };
         let hash = full_name
             .split_once('.')
             .unwrap()
@@ -474,8 +484,17 @@ pub fn init_network(
         .expect("Validator address must be valid");
         persistent_peers.push(peer);
 
-        // Generate account and reward addresses
-        let address = address::gen_established_address("validator account");
+        // Generate account and reward addresses, unless pre-defined (e.g. in
+        // a genesis config exported from an existing chain)
+        let address = match &config.address {
+            Some(address) => {
+                address::Address::decode(address).unwrap_or_else(|err| {
+                    eprintln!("Invalid validator {name} address: {}", err);
+                    cli::safe_exit(1)
+                })
+            }
+            None => address::gen_established_address("validator account"),
+        };
         config.address = Some(address.to_string());
 
         // Generate the consensus, account and reward keys, unless they're
@@ -759,6 +778,11 @@ pub fn init_network(
                 .rpc_address
                 .set_port(first_port + 1);
             config.ledger.shell.ledger_address.set_port(first_port + 2);
+            if let Some(address) =
+                config.ledger.shell.events_subscription_address.as_mut()
+            {
+                address.set_port(first_port + 3);
+            }
             // Validator node should turned off peer exchange reactor
             config.ledger.tendermint.p2p_pex = false;
 
@@ -1,6 +1,6 @@
 //! The parameters used for the chain's genesis
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 #[cfg(not(feature = "dev"))]
 use std::path::Path;
 
@@ -10,13 +10,16 @@ use derivative::Derivative;
 use namada::core::ledger::testnet_pow;
 use namada::ledger::governance::parameters::GovParams;
 use namada::ledger::parameters::EpochDuration;
+use namada::ledger::pos::types::BondId;
 use namada::ledger::pos::{GenesisValidator, PosParams};
 use namada::types::address::Address;
 #[cfg(not(feature = "dev"))]
 use namada::types::chain::ChainId;
 use namada::types::chain::ProposalBytes;
+use namada::types::governance::{ProposalStatus, ProposalType, ProposalVote};
 use namada::types::key::dkg_session_keys::DkgPublicKey;
 use namada::types::key::*;
+use namada::types::storage::{BlockHeight, Epoch};
 use namada::types::time::{DateTimeUtc, DurationSecs};
 use namada::types::{storage, token};
 use rust_decimal::Decimal;
@@ -24,7 +27,7 @@ use rust_decimal::Decimal;
 /// Genesis configuration file format
 pub mod genesis_config {
     use std::array::TryFromSliceError;
-    use std::collections::HashMap;
+    use std::collections::{BTreeMap, HashMap};
     use std::convert::TryInto;
     use std::path::Path;
     use std::str::FromStr;
@@ -35,11 +38,16 @@ pub mod genesis_config {
     use namada::core::ledger::testnet_pow;
     use namada::ledger::governance::parameters::GovParams;
     use namada::ledger::parameters::EpochDuration;
+    use namada::ledger::pos::types::BondId;
     use namada::ledger::pos::{GenesisValidator, PosParams};
     use namada::types::address::Address;
     use namada::types::chain::ProposalBytes;
+    use namada::types::governance::{
+        ProposalStatus, ProposalType, ProposalVote,
+    };
     use namada::types::key::dkg_session_keys::DkgPublicKey;
     use namada::types::key::*;
+    use namada::types::storage::{BlockHeight, Epoch};
     use namada::types::time::Rfc3339String;
     use namada::types::{storage, token};
     use rust_decimal::Decimal;
@@ -47,8 +55,8 @@ pub mod genesis_config {
     use thiserror::Error;
 
     use super::{
-        EstablishedAccount, Genesis, ImplicitAccount, Parameters, TokenAccount,
-        Validator,
+        EstablishedAccount, ExportedProposal, ExportedState, Genesis,
+        ImplicitAccount, Parameters, TokenAccount, Validator,
     };
     use crate::cli;
 
@@ -137,6 +145,9 @@ pub mod genesis_config {
         pub gov_params: GovernanceParamsConfig,
         // Wasm definitions
         pub wasm: HashMap<String, WasmConfig>,
+        // State exported from an existing chain (`namadan ledger export`)
+        // to be carried over into the new chain
+        pub state: Option<ExportedStateConfig>,
     }
 
     #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -159,6 +170,27 @@ pub mod genesis_config {
         // Minimum number of epoch between end and grace epoch
         // XXX: u64 doesn't work with toml-rs!
         pub min_proposal_grace_epochs: u64,
+        // Minimum share of the total voting power that must vote on a
+        // proposal, including the abstain votes
+        pub quorum: Decimal,
+        // Minimum share of the yay and nay votes that must be yay for a
+        // proposal to pass
+        pub pass_threshold: Decimal,
+        // Minimum share of all the votes that must be nay with veto for a
+        // proposal to be vetoed
+        pub veto_threshold: Decimal,
+        // Burn the funds of the proposals that miss the quorum instead of
+        // sending them to the slash fund
+        pub burn_missed_quorum_deposit: bool,
+        // Minimum amount of locked funds for an expedited proposal
+        // XXX: u64 doesn't work with toml-rs!
+        pub min_expedited_proposal_fund: u64,
+        // Minimum expedited proposal period length in epochs
+        // XXX: u64 doesn't work with toml-rs!
+        pub min_expedited_proposal_period: u64,
+        // Minimum share of the yay and nay votes that must be yay for an
+        // expedited proposal to pass
+        pub expedited_pass_threshold: Decimal,
     }
 
     /// Validator pre-genesis configuration can be created with client utils
@@ -309,10 +341,102 @@ pub mod genesis_config {
 
     #[derive(Clone, Debug, Deserialize, Serialize)]
     pub struct WasmConfig {
-        filename: String,
+        pub filename: String,
         pub sha256: Option<HexString>,
     }
 
+    #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+    pub struct ExportedStateConfig {
+        // Height of the block at which the state was exported.
+        // XXX: u64 doesn't work with toml-rs!
+        pub height: u64,
+        // Epoch of the block at which the state was exported. The epochs of
+        // the unbonds and proposals are shifted relative to it on import.
+        // XXX: u64 doesn't work with toml-rs!
+        pub epoch: u64,
+        // The ID that will be assigned to the next governance proposal.
+        // XXX: u64 doesn't work with toml-rs!
+        pub proposal_counter: u64,
+        // Bonds, including validators' self-bonds
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub bonds: Vec<ExportedBondConfig>,
+        // Unbonds that haven't been withdrawn yet
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub unbonds: Vec<ExportedUnbondConfig>,
+        // Governance proposals
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub proposals: Vec<ExportedProposalConfig>,
+        // Exact balances of all the accounts, keyed by the token address and
+        // then by the owner address. These take precedence over any balances
+        // set in the accounts' configs.
+        pub balances: HashMap<String, HashMap<String, token::Amount>>,
+    }
+
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct ExportedBondConfig {
+        // Address of the bond's owner
+        pub source: String,
+        // Address of the validator
+        pub validator: String,
+        // Bonded amount, including bonds that weren't active yet at export
+        pub amount: token::Amount,
+    }
+
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct ExportedUnbondConfig {
+        // Address of the unbond's owner
+        pub source: String,
+        // Address of the validator
+        pub validator: String,
+        // Unbonded amount
+        pub amount: token::Amount,
+        // Epoch from which the amount can be withdrawn.
+        // XXX: u64 doesn't work with toml-rs!
+        pub withdraw_epoch: u64,
+    }
+
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct ExportedProposalConfig {
+        // XXX: u64 doesn't work with toml-rs!
+        pub id: u64,
+        // Raw proposal content
+        pub content: HexString,
+        // Address of the proposal's author
+        pub author: String,
+        // XXX: u64 doesn't work with toml-rs!
+        pub voting_start_epoch: u64,
+        // XXX: u64 doesn't work with toml-rs!
+        pub voting_end_epoch: u64,
+        // XXX: u64 doesn't work with toml-rs!
+        pub grace_epoch: u64,
+        // Funds locked by the proposal
+        pub funds: token::Amount,
+        // Raw proposal WASM code, if any
+        pub proposal_code: Option<HexString>,
+        // Type of the proposal, if it's not the default type
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub proposal_type: Option<ProposalType>,
+        // Is the proposal expedited?
+        #[serde(default)]
+        pub expedited: bool,
+        // Status of the proposal, if it's stored
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub status: Option<ProposalStatus>,
+        // Votes cast on the proposal
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub votes: Vec<ExportedVoteConfig>,
+    }
+
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct ExportedVoteConfig {
+        // Address of the voter
+        pub voter: String,
+        // Address of the validator or delegator on behalf of whom the vote
+        // was cast
+        pub delegator: String,
+        pub vote: ProposalVote,
+    }
+
     fn load_validator(
         config: &ValidatorConfig,
         wasm: &HashMap<String, WasmConfig>,
@@ -508,6 +632,95 @@ pub mod genesis_config {
         }
     }
 
+    fn load_state(config: &ExportedStateConfig) -> ExportedState {
+        let parse_address = |address: &str| {
+            Address::decode(address).unwrap_or_else(|err| {
+                eprintln!(
+                    "Invalid address {} in exported state: {}",
+                    address, err
+                );
+                cli::safe_exit(1)
+            })
+        };
+        let height = BlockHeight(config.height);
+        let epoch = Epoch(config.epoch);
+        let balances = config
+            .balances
+            .iter()
+            .flat_map(|(token, balances)| {
+                let token = parse_address(token);
+                balances.iter().map(move |(owner, amount)| {
+                    ((token.clone(), parse_address(owner)), *amount)
+                })
+            })
+            .collect();
+        let mut bonds: BTreeMap<BondId, token::Amount> = BTreeMap::new();
+        for bond in &config.bonds {
+            let bond_id = BondId {
+                source: parse_address(&bond.source),
+                validator: parse_address(&bond.validator),
+            };
+            *bonds.entry(bond_id).or_default() += bond.amount;
+        }
+        let mut unbonds: BTreeMap<(BondId, Epoch), token::Amount> =
+            BTreeMap::new();
+        for unbond in &config.unbonds {
+            let bond_id = BondId {
+                source: parse_address(&unbond.source),
+                validator: parse_address(&unbond.validator),
+            };
+            *unbonds
+                .entry((bond_id, Epoch(unbond.withdraw_epoch)))
+                .or_default() += unbond.amount;
+        }
+        let mut proposals: Vec<ExportedProposal> = config
+            .proposals
+            .iter()
+            .map(|proposal| ExportedProposal {
+                id: proposal.id,
+                content: proposal.content.to_bytes().unwrap(),
+                author: parse_address(&proposal.author),
+                voting_start_epoch: Epoch(proposal.voting_start_epoch),
+                voting_end_epoch: Epoch(proposal.voting_end_epoch),
+                grace_epoch: Epoch(proposal.grace_epoch),
+                funds: proposal.funds,
+                proposal_code: proposal
+                    .proposal_code
+                    .as_ref()
+                    .map(|code| code.to_bytes().unwrap()),
+                proposal_type: proposal
+                    .proposal_type
+                    .clone()
+                    .unwrap_or_default(),
+                expedited: proposal.expedited,
+                status: proposal.status,
+                votes: proposal
+                    .votes
+                    .iter()
+                    .map(|vote| {
+                        (
+                            (
+                                parse_address(&vote.voter),
+                                parse_address(&vote.delegator),
+                            ),
+                            vote.vote.clone(),
+                        )
+                    })
+                    .collect(),
+            })
+            .collect();
+        proposals.sort_by_key(|proposal| proposal.id);
+        ExportedState {
+            height,
+            epoch,
+            proposal_counter: config.proposal_counter,
+            balances,
+            bonds,
+            unbonds,
+            proposals,
+        }
+    }
+
     fn load_implicit(config: &ImplicitAccountConfig) -> ImplicitAccount {
         ImplicitAccount {
             public_key: config
@@ -535,6 +748,7 @@ pub mod genesis_config {
             pos_params,
             gov_params,
             wasm,
+            state,
         } = config;
 
         let native_token = Address::decode(
@@ -550,10 +764,25 @@ pub mod genesis_config {
         )
         .expect("Invalid address");
 
-        let validators: HashMap<String, Validator> = validator
+        let state = state.as_ref().map(load_state);
+        let mut validators: HashMap<String, Validator> = validator
             .iter()
             .map(|(name, cfg)| (name.clone(), load_validator(cfg, &wasm)))
             .collect();
+        if let Some(state) = &state {
+            // The validators' stake is given by the exported bonds
+            for validator in validators.values_mut() {
+                validator.pos_data.tokens = state
+                    .bonds
+                    .iter()
+                    .filter(|(bond_id, _)| {
+                        bond_id.validator == validator.pos_data.address
+                    })
+                    .fold(token::Amount::default(), |sum, (_, amount)| {
+                        sum + *amount
+                    });
+            }
+        }
         let established_accounts: HashMap<String, EstablishedAccount> =
             established
                 .unwrap_or_default()
@@ -626,6 +855,13 @@ pub mod genesis_config {
             max_proposal_content_size,
             min_proposal_grace_epochs,
             max_proposal_period,
+            quorum,
+            pass_threshold,
+            veto_threshold,
+            burn_missed_quorum_deposit,
+            min_expedited_proposal_fund,
+            min_expedited_proposal_period,
+            expedited_pass_threshold,
         } = gov_params;
         let gov_params = GovParams {
             min_proposal_fund,
@@ -634,7 +870,25 @@ pub mod genesis_config {
             max_proposal_content_size,
             min_proposal_grace_epochs,
             max_proposal_period,
+            quorum,
+            pass_threshold,
+            veto_threshold,
+            burn_missed_quorum_deposit,
+            min_expedited_proposal_fund,
+            min_expedited_proposal_period,
+            expedited_pass_threshold,
         };
+        let errors = gov_params.validate();
+        if !errors.is_empty() {
+            panic!(
+                "Invalid governance parameters: {}",
+                errors
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
 
         let PosParamsConfig {
             max_validator_slots,
@@ -675,11 +929,14 @@ pub mod genesis_config {
             parameters,
             pos_params,
             gov_params,
+            state,
         };
         genesis.init();
         genesis
     }
 
+    /// Open a genesis config file. Files with a `.json` extension are parsed
+    /// as JSON, any other files as TOML.
     pub fn open_genesis_config(
         path: impl AsRef<Path>,
     ) -> color_eyre::eyre::Result<GenesisConfig> {
@@ -690,12 +947,23 @@ pub mod genesis_config {
                     path.as_ref().to_string_lossy()
                 )
             })?;
-        toml::from_str(&config_file).wrap_err_with(|| {
-            format!(
-                "couldn't parse TOML from {}",
-                path.as_ref().to_string_lossy()
-            )
-        })
+        if path.as_ref().extension().and_then(|ext| ext.to_str())
+            == Some("json")
+        {
+            serde_json::from_str(&config_file).wrap_err_with(|| {
+                format!(
+                    "couldn't parse JSON from {}",
+                    path.as_ref().to_string_lossy()
+                )
+            })
+        } else {
+            toml::from_str(&config_file).wrap_err_with(|| {
+                format!(
+                    "couldn't parse TOML from {}",
+                    path.as_ref().to_string_lossy()
+                )
+            })
+        }
     }
 
     pub fn write_genesis_config(
@@ -727,6 +995,8 @@ pub struct Genesis {
     pub parameters: Parameters,
     pub pos_params: PosParams,
     pub gov_params: GovParams,
+    /// State exported from an existing chain, if any
+    pub state: Option<ExportedState>,
 }
 
 impl Genesis {
@@ -822,6 +1092,56 @@ pub struct ImplicitAccount {
     pub public_key: common::PublicKey,
 }
 
+/// State exported from an existing chain to be imported at genesis. The
+/// balances are applied last, so that they take precedence over the balances
+/// of the genesis accounts and the tokens bonded in PoS.
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct ExportedState {
+    /// Height of the block at which the state was exported
+    pub height: BlockHeight,
+    /// Epoch of the block at which the state was exported
+    pub epoch: Epoch,
+    /// The ID that will be assigned to the next governance proposal
+    pub proposal_counter: u64,
+    /// Balances keyed by the token and owner addresses
+    pub balances: BTreeMap<(Address, Address), token::Amount>,
+    /// Bonded amounts, including validators' self-bonds
+    pub bonds: BTreeMap<BondId, token::Amount>,
+    /// Unbonded amounts keyed by their withdrawable epoch
+    pub unbonds: BTreeMap<(BondId, Epoch), token::Amount>,
+    /// Governance proposals, sorted by their ID
+    pub proposals: Vec<ExportedProposal>,
+}
+
+/// A governance proposal exported from an existing chain
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct ExportedProposal {
+    /// Proposal ID
+    pub id: u64,
+    /// Raw proposal content
+    pub content: Vec<u8>,
+    /// Address of the author
+    pub author: Address,
+    /// Epoch from which voting is allowed
+    pub voting_start_epoch: Epoch,
+    /// Epoch until which voting is allowed
+    pub voting_end_epoch: Epoch,
+    /// Epoch at which the proposal is executed
+    pub grace_epoch: Epoch,
+    /// Funds locked by the proposal
+    pub funds: token::Amount,
+    /// Raw proposal WASM code, if any
+    pub proposal_code: Option<Vec<u8>>,
+    /// Type of the proposal, without its code
+    pub proposal_type: ProposalType,
+    /// Is the proposal expedited?
+    pub expedited: bool,
+    /// Status of the proposal, if it's stored
+    pub status: Option<ProposalStatus>,
+    /// Votes keyed by the voter and delegator addresses
+    pub votes: BTreeMap<(Address, Address), ProposalVote>,
+}
+
 /// Protocol parameters. This is almost the same as
 /// `ledger::parameters::Parameters`, but instead of having the `implicit_vp`
 /// WASM code bytes, it only has the name and sha as the actual code is loaded
@@ -1005,6 +1325,7 @@ pub fn genesis() -> Genesis {
         faucet_pow_difficulty: None,
         #[cfg(not(feature = "mainnet"))]
         faucet_withdrawal_limit: None,
+        state: None,
     }
 }
 
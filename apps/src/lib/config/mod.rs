@@ -91,6 +91,24 @@ pub struct Shell {
     /// When set, will limit the how many block heights in the past can the
     /// storage be queried for reading values.
     pub storage_read_past_height_limit: Option<u64>,
+    /// When set, a state-sync snapshot of the storage will be taken every
+    /// this many blocks.
+    pub snapshot_interval: Option<u64>,
+    /// Maximum number of the most recent state-sync snapshots to keep on
+    /// disk. When not set, defaults to 2.
+    pub snapshots_to_keep: Option<u64>,
+    /// When set, the persistent event store only keeps the events of this
+    /// many most recent blocks. When not set, all the events are kept.
+    pub events_retention_blocks: Option<u64>,
+    /// The address of the WebSocket service of event subscriptions. When
+    /// not set, the service is disabled.
+    pub events_subscription_address: Option<SocketAddr>,
+    /// Maximum number of concurrent connections to the event subscriptions.
+    /// When not set, defaults to 100.
+    pub events_subscription_max_connections: Option<usize>,
+    /// Maximum number of active subscriptions of a single connection to the
+    /// event subscriptions. When not set, defaults to 10.
+    pub events_subscription_max_queries: Option<usize>,
     /// Use the [`Ledger::db_dir()`] method to read the value.
     db_dir: PathBuf,
     /// Use the [`Ledger::tendermint_dir()`] method to read the value.
@@ -140,6 +158,15 @@ impl Ledger {
                 tx_wasm_compilation_cache_bytes: None,
                 // Default corresponds to 1 hour of past blocks at 1 block/sec
                 storage_read_past_height_limit: Some(3600),
+                snapshot_interval: None,
+                snapshots_to_keep: None,
+                events_retention_blocks: None,
+                events_subscription_address: Some(SocketAddr::new(
+                    IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
+                    26659,
+                )),
+                events_subscription_max_connections: None,
+                events_subscription_max_queries: None,
                 db_dir: DB_DIR.into(),
                 tendermint_dir: TENDERMINT_DIR.into(),
             },
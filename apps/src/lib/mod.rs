@@ -23,13 +23,14 @@ pub mod facade {
 
     #[cfg(not(feature = "abcipp"))]
     pub use {
-        tendermint, tendermint_config, tendermint_proto, tendermint_rpc,
-        tower_abci,
+        tendermint, tendermint_config, tendermint_light_client_verifier,
+        tendermint_proto, tendermint_rpc, tower_abci,
     };
     #[cfg(feature = "abcipp")]
     pub use {
         tendermint_abcipp as tendermint,
         tendermint_config_abcipp as tendermint_config,
+        tendermint_light_client_verifier_abcipp as tendermint_light_client_verifier,
         tendermint_proto_abcipp as tendermint_proto,
         tendermint_rpc_abcipp as tendermint_rpc,
         tower_abci_abcipp as tower_abci,
@@ -0,0 +1,288 @@
+//! Integrity check of the ledger's DB.
+//!
+//! A node that crashes during a block commit can leave the DB with subspace
+//! values that don't match the stored merkle tree. The check rebuilds the
+//! merkle tree of the last committed block from the subspace values and
+//! compares its sub-tree roots with the stored ones. It also checks that the
+//! last block's header, epoch and predecessor epochs agree with each other.
+//!
+//! In the repair mode, the stored merkle tree is replaced with the rebuilt
+//! one. The block's metadata cannot be repaired.
+
+use eyre::{eyre, Context};
+use namada::ledger::storage::{
+    DBIter, MerkleTree, Storage, StorageHasher, StoreType, DB,
+};
+use namada::types::address;
+use namada::types::storage::{BlockHeight, Key};
+
+use crate::cli::args;
+use crate::config;
+use crate::node::ledger::storage::PersistentStorage;
+
+/// Check the integrity of the DB of the last committed block and optionally
+/// repair its merkle tree.
+pub fn check_db(
+    config: config::Ledger,
+    args::LedgerCheckDb { repair }: args::LedgerCheckDb,
+) -> eyre::Result<()> {
+    let chain_id = config.chain_id.clone();
+    // The native token is not used by the check
+    let mut storage = PersistentStorage::open(
+        config.shell.db_dir(&chain_id),
+        chain_id,
+        address::nam(),
+        None,
+    );
+    storage
+        .load_last_state()
+        .map_err(|err| eyre!("Cannot load the last state: {}", err))?;
+    if storage.get_state().is_none() {
+        return Err(eyre!("There is no committed block in the DB"));
+    }
+    let height = storage.last_height;
+    println!("Checking the DB at the last committed height {}...", height);
+
+    let (tree, tree_mismatches) = check_merkle_tree(&storage)?;
+    let block_issues = check_block_state(&storage)?;
+
+    for mismatch in &tree_mismatches {
+        println!("{}", mismatch);
+    }
+    for issue in &block_issues {
+        println!("{}", issue);
+    }
+
+    let tree_is_ok = if tree_mismatches.is_empty() {
+        println!("The merkle tree matches the stored values.");
+        true
+    } else if repair {
+        storage
+            .db
+            .write_merkle_tree_stores(height, &tree.stores())
+            .and_then(|()| storage.db.flush(true))
+            .map_err(|err| eyre!("Cannot write the merkle tree: {}", err))?;
+        println!(
+            "The merkle tree at height {} has been rebuilt from the stored \
+             values with the root {}.",
+            height,
+            tree.root()
+        );
+        true
+    } else {
+        println!(
+            "Run the command with `--repair` to rebuild the merkle tree from \
+             the stored values."
+        );
+        false
+    };
+    if !block_issues.is_empty() {
+        println!("The block's metadata cannot be repaired.");
+    }
+
+    if tree_is_ok && block_issues.is_empty() {
+        println!("No issues found.");
+        Ok(())
+    } else {
+        Err(eyre!(
+            "Found {} issue(s) in the DB",
+            if tree_is_ok { 0 } else { tree_mismatches.len() }
+                + block_issues.len()
+        ))
+    }
+}
+
+/// Rebuild the merkle tree of the last committed block from the subspace
+/// values in the DB. Returns the rebuilt tree together with a description of
+/// every sub-tree whose root doesn't match the stored merkle tree.
+fn check_merkle_tree<D, H>(
+    storage: &Storage<D, H>,
+) -> eyre::Result<(MerkleTree<H>, Vec<String>)>
+where
+    D: DB + for<'iter> DBIter<'iter>,
+    H: StorageHasher,
+{
+    let mut subspace = vec![];
+    for (key, value, _gas) in storage.db.iter_prefix(&Key::default()) {
+        let key = Key::parse(key).wrap_err("Invalid storage key in the DB")?;
+        subspace.push((key, value));
+    }
+    let tree = storage
+        .rebuild_merkle_tree(subspace)
+        .map_err(|err| eyre!("Cannot rebuild the merkle tree: {}", err))?;
+
+    let stored_stores = storage.block.tree.stores();
+    let rebuilt_stores = tree.stores();
+    let mismatches = StoreType::iter()
+        .filter_map(|st| {
+            let stored_root = stored_stores.root(st);
+            let rebuilt_root = rebuilt_stores.root(st);
+            (stored_root != rebuilt_root).then(|| {
+                format!(
+                    "The stored root {} of the {} sub-tree doesn't match the \
+                     root {} computed from the stored values.",
+                    stored_root, st, rebuilt_root
+                )
+            })
+        })
+        .collect();
+    Ok((tree, mismatches))
+}
+
+/// Check that the last committed block's header, epoch and predecessor
+/// epochs agree with each other. Returns a description of every issue found.
+fn check_block_state<D, H>(storage: &Storage<D, H>) -> eyre::Result<Vec<String>>
+where
+    D: DB + for<'iter> DBIter<'iter>,
+    H: StorageHasher,
+{
+    let mut issues = vec![];
+    let height = storage.last_height;
+    let epoch = storage.last_epoch;
+
+    match storage.block.pred_epochs.get_epoch(height) {
+        Some(pred_epoch) if pred_epoch == epoch => {}
+        Some(pred_epoch) => issues.push(format!(
+            "The epoch {} of the last block doesn't match the epoch {} of \
+             its height {} in the predecessor epochs.",
+            epoch, pred_epoch, height
+        )),
+        None => issues.push(format!(
+            "The height {} of the last block is not in the predecessor \
+             epochs.",
+            height
+        )),
+    }
+
+    let header = storage
+        .db
+        .read_block_header(height)
+        .map_err(|err| eyre!("Cannot read the block header: {}", err))?;
+    match header {
+        None => issues.push(format!(
+            "The header of the last block at height {} is missing.",
+            height
+        )),
+        Some(header) => {
+            // The epoch is updated at the beginning of every block, so the
+            // last block cannot satisfy the conditions of a new epoch
+            if height >= storage.next_epoch_min_start_height
+                && header.time >= storage.next_epoch_min_start_time
+            {
+                issues.push(format!(
+                    "The last block at height {} with time {} should have \
+                     started a new epoch (the minimum start height is {} and \
+                     time {}).",
+                    height,
+                    header.time.to_rfc3339(),
+                    storage.next_epoch_min_start_height,
+                    storage.next_epoch_min_start_time.to_rfc3339()
+                ));
+            }
+
+            // Without ABCI++, the header's hash is the app hash, which is the
+            // merkle root of the predecessor block
+            #[cfg(not(feature = "abcipp"))]
+            if let Some(pred_height) = height.0.checked_sub(1) {
+                if let Ok(pred_tree) =
+                    storage.read_merkle_tree(BlockHeight(pred_height))
+                {
+                    let pred_root = pred_tree.root();
+                    if header.hash.0.as_slice() != pred_root.0.as_slice() {
+                        issues.push(format!(
+                            "The app hash {} in the header of the last block \
+                             doesn't match the merkle root {} of the block at \
+                             height {}.",
+                            header.hash, pred_root, pred_height
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    Ok(issues)
+}
+
+#[cfg(test)]
+mod test_check_db {
+    use namada::ledger::storage::types;
+    use namada::types::chain::ChainId;
+    use namada::types::storage::BlockHash;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    /// Test that a subspace value written without updating the merkle tree is
+    /// detected and that the tree can be repaired.
+    #[test]
+    fn test_check_and_repair_merkle_tree() {
+        let db_path =
+            TempDir::new().expect("Unable to create a temporary DB directory");
+        let mut storage = PersistentStorage::open(
+            db_path.path(),
+            ChainId::default(),
+            address::nam(),
+            None,
+        );
+        let key = Key::parse("key").unwrap();
+
+        storage
+            .begin_block(BlockHash::default(), BlockHeight(1))
+            .unwrap();
+        storage.write(&key, types::encode(&1_u64)).unwrap();
+        // The epoch data is only added to the tree by the shell, so we
+        // rebuild it with the epoch data here
+        storage.block.tree = storage
+            .rebuild_merkle_tree([(key.clone(), types::encode(&1_u64))])
+            .unwrap();
+        storage.commit().unwrap();
+
+        let (_tree, mismatches) = check_merkle_tree(&storage).unwrap();
+        assert!(mismatches.is_empty(), "Unexpected {:?}", mismatches);
+
+        // Write a value into the subspace without updating the tree, as if
+        // the node crashed before committing the block
+        storage
+            .db
+            .write_subspace_val(BlockHeight(1), &key, types::encode(&2_u64))
+            .unwrap();
+        let (tree, mismatches) = check_merkle_tree(&storage).unwrap();
+        // Both the account sub-tree and the base tree don't match
+        assert_eq!(mismatches.len(), 2, "Unexpected {:?}", mismatches);
+
+        // Repair the tree and check the DB again from the reloaded state
+        storage
+            .db
+            .write_merkle_tree_stores(BlockHeight(1), &tree.stores())
+            .unwrap();
+        storage.load_last_state().unwrap();
+        let (_tree, mismatches) = check_merkle_tree(&storage).unwrap();
+        assert!(mismatches.is_empty(), "Unexpected {:?}", mismatches);
+    }
+
+    /// Test that the last block's epoch is checked against the predecessor
+    /// epochs.
+    #[test]
+    fn test_check_block_epoch() {
+        let db_path =
+            TempDir::new().expect("Unable to create a temporary DB directory");
+        let mut storage = PersistentStorage::open(
+            db_path.path(),
+            ChainId::default(),
+            address::nam(),
+            None,
+        );
+        storage
+            .begin_block(BlockHash::default(), BlockHeight(1))
+            .unwrap();
+        storage.commit().unwrap();
+
+        // There's no header in this block
+        let issues = check_block_state(&storage).unwrap();
+        assert_eq!(issues.len(), 1, "Unexpected {:?}", issues);
+
+        storage.last_epoch = storage.last_epoch.next();
+        let issues = check_block_state(&storage).unwrap();
+        assert_eq!(issues.len(), 2, "Unexpected {:?}", issues);
+    }
+}
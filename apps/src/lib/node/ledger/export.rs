@@ -0,0 +1,872 @@
+//! Export of the ledger's state at a given height into a genesis config that
+//! can be used to start a new chain from it.
+//!
+//! The exported genesis config is based on the genesis config of the current
+//! chain, with the accounts, parameters and validators updated from the
+//! state. The state that can be carried over into the new chain (exact
+//! balances, PoS bonds and unbonds and governance proposals) is written into
+//! its `state` section and it's imported in `init_chain`.
+//!
+//! The IBC state, the MASP state and multitoken balances are not exported.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use borsh::BorshDeserialize;
+use data_encoding::HEXLOWER;
+use eyre::{eyre, Context};
+use namada::ledger::governance::storage as gov_storage;
+use namada::ledger::parameters::{storage as param_storage, EpochDuration};
+use namada::ledger::pos::namada_proof_of_stake::epoched::DynEpochOffset;
+use namada::ledger::pos::types::{
+    decimal_mult_u64, Bonds, CommissionRates, Slashes, Unbonds,
+    ValidatorConsensusKeys,
+};
+use namada::ledger::pos::{self, PosParams};
+use namada::ledger::storage::{DBIter, Storage, StorageHasher, DB};
+use namada::types::address::Address;
+use namada::types::chain::ProposalBytes;
+use namada::types::governance::{ProposalStatus, ProposalType, ProposalVote};
+use namada::types::key::dkg_session_keys::{dkg_pk_key, DkgPublicKey};
+use namada::types::key::{common, is_pk_key, pk_key, protocol_pk_key};
+use namada::types::storage::{BlockHeight, DbKeySeg, Epoch, Key};
+use namada::types::time::DurationSecs;
+use namada::types::token;
+use sha2::{Digest, Sha256};
+
+use crate::cli::args;
+use crate::config;
+use crate::config::genesis::genesis_config::{
+    self, EstablishedAccountConfig, ExportedBondConfig, ExportedProposalConfig,
+    ExportedStateConfig, ExportedUnbondConfig, ExportedVoteConfig,
+    GenesisConfig, HexString, ImplicitAccountConfig, TokenAccountConfig,
+    ValidatorConfig, WasmConfig,
+};
+use crate::config::DEFAULT_WASM_CHECKSUMS_FILE;
+use crate::node::ledger::storage::PersistentStorage;
+
+/// The subspace state of a chain at some height
+type State = BTreeMap<Key, Vec<u8>>;
+
+/// Export the state of the ledger at the given height (defaults to the last
+/// committed height) into a genesis config file.
+pub fn export(
+    config: config::Ledger,
+    wasm_dir: PathBuf,
+    args::LedgerExport { height, output }: args::LedgerExport,
+) -> eyre::Result<()> {
+    let base_dir = config.shell.base_dir.clone();
+    let chain_id = config.chain_id.clone();
+    let genesis_path = base_dir.join(format!("{}.toml", chain_id.as_str()));
+    let genesis_config = genesis_config::open_genesis_config(&genesis_path)?;
+    let native_token = Address::decode(
+        genesis_config
+            .token
+            .get(&genesis_config.native_token)
+            .and_then(|token| token.address.as_ref())
+            .ok_or_else(|| eyre!("Missing native token address"))?,
+    )?;
+
+    let mut storage = PersistentStorage::open(
+        config.shell.db_dir(&chain_id),
+        chain_id.clone(),
+        native_token,
+        None,
+    );
+    storage
+        .load_last_state()
+        .map_err(|err| eyre!("Cannot load the last state: {}", err))?;
+    let height = height.unwrap_or(storage.last_height);
+
+    let output = output.unwrap_or_else(|| {
+        base_dir.join(format!("{}-export-{}.toml", chain_id.as_str(), height))
+    });
+    let output_dir = output.parent().map(Path::to_path_buf).unwrap_or_default();
+
+    let state = read_state_at_height(&storage, height)?;
+    let epoch =
+        storage.block.pred_epochs.get_epoch(height).ok_or_else(|| {
+            eyre!("Unknown epoch of the block height {}", height)
+        })?;
+    let mut wasm = WasmNames::new(&genesis_config, &wasm_dir, output_dir);
+    let exported = export_genesis_config(
+        genesis_config,
+        &state,
+        height,
+        epoch,
+        &mut wasm,
+    )?;
+
+    let is_json =
+        output.extension().and_then(|ext| ext.to_str()) == Some("json");
+    let contents = if is_json {
+        serde_json::to_string_pretty(&exported)?
+    } else {
+        toml::to_string(&exported)?
+    };
+    std::fs::write(&output, contents).wrap_err_with(|| {
+        format!("couldn't write the genesis config to {}", output.display())
+    })?;
+    println!(
+        "The state at height {} (epoch {}) has been exported to {}.",
+        height,
+        epoch,
+        output.display()
+    );
+    Ok(())
+}
+
+/// Read the whole account subspace at the given height. The current state is
+/// rolled back to the given height by applying the diffs of the blocks
+/// committed after it in reverse order.
+fn read_state_at_height<D, H>(
+    storage: &Storage<D, H>,
+    height: BlockHeight,
+) -> eyre::Result<State>
+where
+    D: DB + for<'iter> DBIter<'iter>,
+    H: StorageHasher,
+{
+    if height.0 == 0 || height > storage.last_height {
+        return Err(eyre!(
+            "The height {} is not committed, the last committed height is {}",
+            height,
+            storage.last_height
+        ));
+    }
+    let parse_key =
+        |key: String| Key::parse(key).wrap_err("Invalid storage key in the DB");
+    let mut state = State::new();
+    for (key, value, _gas) in storage.db.iter_prefix(&Key::default()) {
+        state.insert(parse_key(key)?, value);
+    }
+    let mut current = storage.last_height;
+    while current > height {
+        let old_values: HashMap<String, Vec<u8>> = storage
+            .db
+            .iter_old_diffs(current, &Key::default())
+            .map(|(key, value, _gas)| (key, value))
+            .collect();
+        // A key that has a new value, but no old value was created at this
+        // height
+        for (key, _value, _gas) in
+            storage.db.iter_new_diffs(current, &Key::default())
+        {
+            if !old_values.contains_key(&key) {
+                state.remove(&parse_key(key)?);
+            }
+        }
+        for (key, value) in old_values {
+            state.insert(parse_key(key)?, value);
+        }
+        current = BlockHeight(current.0 - 1);
+    }
+    Ok(state)
+}
+
+fn read<T: BorshDeserialize>(
+    state: &State,
+    key: &Key,
+) -> eyre::Result<Option<T>> {
+    state
+        .get(key)
+        .map(|bytes| T::try_from_slice(bytes))
+        .transpose()
+        .wrap_err_with(|| format!("Invalid value of the storage key {}", key))
+}
+
+fn read_required<T: BorshDeserialize>(
+    state: &State,
+    key: &Key,
+) -> eyre::Result<T> {
+    read(state, key)?
+        .ok_or_else(|| eyre!("Missing value of the storage key {}", key))
+}
+
+/// Resolves the names of the WASM codes found in the state. Codes that are
+/// not known from the genesis config or the WASM checksums are written into
+/// the output directory.
+struct WasmNames {
+    /// Names of the known WASMs keyed by their hex encoded sha256 hash
+    names: HashMap<String, (String, WasmConfig)>,
+    /// The WASMs that have to be in the exported genesis config
+    used: HashMap<String, WasmConfig>,
+    output_dir: PathBuf,
+}
+
+impl WasmNames {
+    fn new(
+        genesis_config: &GenesisConfig,
+        wasm_dir: impl AsRef<Path>,
+        output_dir: PathBuf,
+    ) -> Self {
+        let mut names = HashMap::new();
+        let checksums_path =
+            wasm_dir.as_ref().join(DEFAULT_WASM_CHECKSUMS_FILE);
+        if checksums_path.exists() {
+            let checksums = crate::wasm_loader::Checksums::read_checksums_file(
+                checksums_path,
+            );
+            for (file_name, full_name) in checksums.0 {
+                // Full name in format `{name}.{sha256}.wasm`
+                let hash = full_name
+                    .strip_suffix(".wasm")
+                    .and_then(|name| name.rsplit_once('.'))
+                    .map(|(_name, hash)| hash.to_owned());
+                if let (Some(hash), Some(name)) =
+                    (hash, file_name.strip_suffix(".wasm"))
+                {
+                    let config = WasmConfig {
+                        filename: file_name.clone(),
+                        sha256: Some(HexString(hash.clone())),
+                    };
+                    names.insert(hash, (name.to_owned(), config));
+                }
+            }
+        }
+        // The names from the genesis config take precedence
+        for (name, config) in &genesis_config.wasm {
+            if let Some(HexString(hash)) = &config.sha256 {
+                names.insert(hash.clone(), (name.clone(), config.clone()));
+            }
+        }
+        Self {
+            names,
+            used: HashMap::new(),
+            output_dir,
+        }
+    }
+
+    /// Get the name of the given WASM code
+    fn name(&mut self, code: &[u8]) -> eyre::Result<String> {
+        let hash = HEXLOWER.encode(&Sha256::digest(code));
+        let (name, config) = match self.names.get(&hash) {
+            Some(known) => known.clone(),
+            None => {
+                let filename = format!("{}.wasm", hash);
+                let path = self.output_dir.join(&filename);
+                std::fs::write(&path, code).wrap_err_with(|| {
+                    format!("couldn't write WASM code to {}", path.display())
+                })?;
+                println!(
+                    "Unknown WASM code has been written to {}. It must be \
+                     copied into the WASM directory of the new chain.",
+                    path.display()
+                );
+                let config = WasmConfig {
+                    filename,
+                    sha256: Some(HexString(hash.clone())),
+                };
+                self.names
+                    .insert(hash.clone(), (hash.clone(), config.clone()));
+                (hash, config)
+            }
+        };
+        self.used.insert(name.clone(), config);
+        Ok(name)
+    }
+
+    /// Get the name of the VP of the given account
+    fn vp_name(
+        &mut self,
+        state: &State,
+        owner: &Address,
+    ) -> eyre::Result<String> {
+        let code: Vec<u8> = state
+            .get(&Key::validity_predicate(owner))
+            .cloned()
+            .ok_or_else(|| eyre!("Missing validity predicate of {}", owner))?;
+        self.name(&code)
+    }
+}
+
+/// Build a genesis config from the given state based on the genesis config
+/// of the current chain.
+fn export_genesis_config(
+    mut config: GenesisConfig,
+    state: &State,
+    height: BlockHeight,
+    epoch: Epoch,
+    wasm: &mut WasmNames,
+) -> eyre::Result<GenesisConfig> {
+    let params_key = pos::params_key();
+    let epoched_pos_params = state
+        .get(&params_key)
+        .ok_or_else(|| eyre!("Missing value of the storage key {}", params_key))
+        .and_then(|bytes| {
+            pos::decode_params(bytes).wrap_err_with(|| {
+                format!("Invalid value of the storage key {}", params_key)
+            })
+        })?;
+    let pos_params =
+        epoched_pos_params.get(epoch).cloned().ok_or_else(|| {
+            eyre!("Missing PoS parameters of the epoch {}", epoch)
+        })?;
+
+    // Find the addresses of the accounts in the state
+    let mut validators = BTreeSet::new();
+    let mut tokens = BTreeSet::new();
+    let mut established = BTreeSet::new();
+    let mut implicit = BTreeSet::new();
+    for key in state.keys() {
+        if let Some(validator) = pos::is_validator_state_key(key) {
+            validators.insert(validator.clone());
+        } else if token::is_any_token_balance_key(key).is_some() {
+            if let Some(DbKeySeg::AddressSeg(token)) = key.segments.first() {
+                tokens.insert(token.clone());
+            }
+        } else if let Some(address @ Address::Established(_)) =
+            key.is_validity_predicate()
+        {
+            established.insert(address.clone());
+        } else if let Some(address @ Address::Implicit(_)) = is_pk_key(key) {
+            implicit.insert(address.clone());
+        }
+    }
+    let established: BTreeSet<Address> = established
+        .difference(&validators)
+        .filter(|address| !tokens.contains(address))
+        .cloned()
+        .collect();
+
+    // Keep the aliases from the genesis config for known addresses
+    let alias = |aliases: &HashMap<String, Option<String>>,
+                 address: &Address| {
+        let address = address.encode();
+        aliases
+            .iter()
+            .find_map(|(alias, known)| {
+                (known.as_ref() == Some(&address)).then(|| alias.clone())
+            })
+            .unwrap_or(address)
+    };
+
+    let validator_aliases: HashMap<String, Option<String>> = config
+        .validator
+        .iter()
+        .map(|(alias, config)| (alias.clone(), config.address.clone()))
+        .collect();
+    let mut validator_configs = HashMap::new();
+    for address in &validators {
+        let name = alias(&validator_aliases, address);
+        let mut validator = match config.validator.remove(&name) {
+            Some(validator) => validator,
+            None => {
+                println!(
+                    "Validator {} is not in the genesis config, its \
+                     `net_address` must be set before the new chain can be \
+                     initialized.",
+                    address
+                );
+                ValidatorConfig::default()
+            }
+        };
+        let consensus_keys: ValidatorConsensusKeys =
+            read_required(state, &pos::validator_consensus_key_key(address))?;
+        let consensus_key = consensus_keys
+            .get(epoch)
+            .ok_or_else(|| eyre!("Missing consensus key of {}", address))?;
+        let commission_rates: CommissionRates =
+            read_required(state, &pos::validator_commission_rate_key(address))?;
+        let account_key: common::PublicKey =
+            read_required(state, &pk_key(address))?;
+        let protocol_key: common::PublicKey =
+            read_required(state, &protocol_pk_key(address))?;
+        let dkg_key: DkgPublicKey = read_required(state, &dkg_pk_key(address))?;
+        validator.address = Some(address.encode());
+        validator.consensus_public_key =
+            Some(HexString(consensus_key.to_string()));
+        validator.account_public_key = Some(HexString(account_key.to_string()));
+        validator.protocol_public_key =
+            Some(HexString(protocol_key.to_string()));
+        validator.dkg_public_key = Some(HexString(dkg_key.to_string()));
+        // The stake and balances are given by the exported state
+        validator.tokens = None;
+        validator.non_staked_balance = None;
+        validator.commission_rate = commission_rates.get(epoch).copied();
+        validator.max_commission_rate_change = read(
+            state,
+            &pos::validator_max_commission_rate_change_key(address),
+        )?;
+        validator.validator_vp = Some(wasm.vp_name(state, address)?);
+        validator_configs.insert(name, validator);
+    }
+    config.validator = validator_configs;
+
+    let token_aliases: HashMap<String, Option<String>> = config
+        .token
+        .iter()
+        .map(|(alias, config)| (alias.clone(), config.address.clone()))
+        .collect();
+    let mut token_configs = HashMap::new();
+    for address in &tokens {
+        let vp = Some(wasm.vp_name(state, address)?);
+        token_configs.insert(
+            alias(&token_aliases, address),
+            TokenAccountConfig {
+                address: Some(address.encode()),
+                vp,
+                // The balances are given by the exported state
+                balances: None,
+            },
+        );
+    }
+    // The native token must be kept even if nobody holds it
+    if !token_configs.contains_key(&config.native_token) {
+        if let Some(native_token) = config.token.remove(&config.native_token) {
+            token_configs.insert(
+                config.native_token.clone(),
+                TokenAccountConfig {
+                    balances: None,
+                    ..native_token
+                },
+            );
+        }
+    }
+    config.token = token_configs;
+
+    // The faucet's PoW parameters are set from the top-level config fields
+    #[allow(unused_mut)]
+    let mut excluded_keys: Vec<Key> = vec![];
+    #[cfg(not(feature = "mainnet"))]
+    {
+        use namada::core::ledger::testnet_pow;
+        let faucet: Option<Address> =
+            read(state, &param_storage::get_faucet_account_key())?;
+        if let Some(faucet) = &faucet {
+            let difficulty_key = testnet_pow::difficulty_key(faucet);
+            let withdrawal_limit_key =
+                testnet_pow::withdrawal_limit_key(faucet);
+            config.faucet_pow_difficulty = read(state, &difficulty_key)?;
+            config.faucet_withdrawal_limit =
+                read(state, &withdrawal_limit_key)?;
+            excluded_keys.push(difficulty_key);
+            excluded_keys.push(withdrawal_limit_key);
+        }
+    }
+    let established_aliases: HashMap<String, Option<String>> = config
+        .established
+        .iter()
+        .flatten()
+        .map(|(alias, config)| (alias.clone(), config.address.clone()))
+        .collect();
+    let mut established_configs = HashMap::new();
+    for address in &established {
+        let vp = Some(wasm.vp_name(state, address)?);
+        let public_key: Option<common::PublicKey> =
+            read(state, &pk_key(address))?;
+        let address_seg = DbKeySeg::AddressSeg(address.clone());
+        let storage = state
+            .range(Key::from(address_seg.clone())..)
+            .take_while(|(key, _)| key.first() == Some(&address_seg))
+            .filter(|(key, _)| {
+                key.is_validity_predicate().is_none()
+                    && is_pk_key(key).is_none()
+                    && token::is_any_token_balance_key(key).is_none()
+                    && !excluded_keys.contains(key)
+            })
+            .map(|(key, value)| {
+                (key.to_string(), HexString(HEXLOWER.encode(value)))
+            })
+            .collect::<HashMap<_, _>>();
+        established_configs.insert(
+            alias(&established_aliases, address),
+            EstablishedAccountConfig {
+                address: Some(address.encode()),
+                vp,
+                public_key: public_key
+                    .map(|public_key| HexString(public_key.to_string())),
+                storage: (!storage.is_empty()).then_some(storage),
+            },
+        );
+    }
+    config.established = Some(established_configs);
+
+    let implicit_aliases: HashMap<String, Option<String>> = config
+        .implicit
+        .iter()
+        .flatten()
+        .map(|(alias, config)| {
+            let address = config
+                .public_key
+                .as_ref()
+                .and_then(|pk| pk.to_public_key().ok())
+                .map(|pk| Address::from(&pk).encode());
+            (alias.clone(), address)
+        })
+        .collect();
+    let mut implicit_configs = HashMap::new();
+    for address in &implicit {
+        let public_key: common::PublicKey =
+            read_required(state, &pk_key(address))?;
+        implicit_configs.insert(
+            alias(&implicit_aliases, address),
+            ImplicitAccountConfig {
+                public_key: Some(HexString(public_key.to_string())),
+            },
+        );
+    }
+    config.implicit = Some(implicit_configs);
+
+    // Parameters
+    let implicit_vp: Vec<u8> = state
+        .get(&param_storage::get_implicit_vp_key())
+        .cloned()
+        .ok_or_else(|| eyre!("Missing implicit VP parameter"))?;
+    let epoch_duration: EpochDuration =
+        read_required(state, &param_storage::get_epoch_duration_storage_key())?;
+    let max_expected_time_per_block: DurationSecs = read_required(
+        state,
+        &param_storage::get_max_expected_time_per_block_key(),
+    )?;
+    let max_proposal_bytes: ProposalBytes =
+        read_required(state, &param_storage::get_max_proposal_bytes_key())?;
+    let vp_whitelist: Vec<String> =
+        read_required(state, &param_storage::get_vp_whitelist_storage_key())?;
+    let tx_whitelist: Vec<String> =
+        read_required(state, &param_storage::get_tx_whitelist_storage_key())?;
+    config.parameters = genesis_config::ParametersConfig {
+        max_proposal_bytes,
+        min_num_of_blocks: epoch_duration.min_num_of_blocks,
+        max_expected_time_per_block: max_expected_time_per_block.0 as i64,
+        vp_whitelist: Some(vp_whitelist),
+        tx_whitelist: Some(tx_whitelist),
+        implicit_vp: wasm.name(&implicit_vp)?,
+        epochs_per_year: read_required(
+            state,
+            &param_storage::get_epochs_per_year_key(),
+        )?,
+        pos_gain_p: read_required(state, &param_storage::get_pos_gain_p_key())?,
+        pos_gain_d: read_required(state, &param_storage::get_pos_gain_d_key())?,
+        #[cfg(not(feature = "mainnet"))]
+        wrapper_tx_fees: read(
+            state,
+            &param_storage::get_wrapper_tx_fees_key(),
+        )?,
+    };
+
+    // A change of the PoS parameters that takes effect at the pipeline offset
+    // is carried over into the new chain
+    let PosParams {
+        max_validator_slots,
+        pipeline_len,
+        unbonding_len,
+        tm_votes_per_token,
+        block_proposer_reward,
+        block_vote_reward,
+        max_inflation_rate,
+        target_staked_ratio,
+        duplicate_vote_min_slash_rate,
+        light_client_attack_min_slash_rate,
+    } = epoched_pos_params
+        .get_at_offset(epoch, DynEpochOffset::PipelineLen, &epoched_pos_params)
+        .cloned()
+        .unwrap_or_else(|| pos_params.clone());
+    config.pos_params = genesis_config::PosParamsConfig {
+        max_validator_slots,
+        pipeline_len,
+        unbonding_len,
+        tm_votes_per_token,
+        block_proposer_reward,
+        block_vote_reward,
+        max_inflation_rate,
+        target_staked_ratio,
+        duplicate_vote_min_slash_rate,
+        light_client_attack_min_slash_rate,
+    };
+
+    let min_proposal_fund: token::Amount =
+        read_required(state, &gov_storage::get_min_proposal_fund_key())?;
+    let min_expedited_proposal_fund: token::Amount = read_required(
+        state,
+        &gov_storage::get_min_expedited_proposal_fund_key(),
+    )?;
+    config.gov_params = genesis_config::GovernanceParamsConfig {
+        min_proposal_fund: u64::from(min_proposal_fund) / token::SCALE,
+        max_proposal_code_size: read_required(
+            state,
+            &gov_storage::get_max_proposal_code_size_key(),
+        )?,
+        min_proposal_period: read_required(
+            state,
+            &gov_storage::get_min_proposal_period_key(),
+        )?,
+        max_proposal_period: read_required(
+            state,
+            &gov_storage::get_max_proposal_period_key(),
+        )?,
+        max_proposal_content_size: read_required(
+            state,
+            &gov_storage::get_max_proposal_content_key(),
+        )?,
+        min_proposal_grace_epochs: read_required(
+            state,
+            &gov_storage::get_min_proposal_grace_epoch_key(),
+        )?,
+        quorum: read_required(state, &gov_storage::get_quorum_key())?,
+        pass_threshold: read_required(
+            state,
+            &gov_storage::get_pass_threshold_key(),
+        )?,
+        veto_threshold: read_required(
+            state,
+            &gov_storage::get_veto_threshold_key(),
+        )?,
+        burn_missed_quorum_deposit: read_required(
+            state,
+            &gov_storage::get_burn_missed_quorum_deposit_key(),
+        )?,
+        min_expedited_proposal_fund: u64::from(min_expedited_proposal_fund)
+            / token::SCALE,
+        min_expedited_proposal_period: read_required(
+            state,
+            &gov_storage::get_min_expedited_proposal_period_key(),
+        )?,
+        expedited_pass_threshold: read_required(
+            state,
+            &gov_storage::get_expedited_pass_threshold_key(),
+        )?,
+    };
+
+    config.state = Some(export_state(state, height, epoch, &pos_params)?);
+    config.wasm = std::mem::take(&mut wasm.used);
+    Ok(config)
+}
+
+/// Export the balances, PoS bonds and unbonds and governance proposals from
+/// the given state.
+fn export_state(
+    state: &State,
+    height: BlockHeight,
+    epoch: Epoch,
+    pos_params: &PosParams,
+) -> eyre::Result<ExportedStateConfig> {
+    let mut balances: HashMap<String, HashMap<String, token::Amount>> =
+        HashMap::new();
+    let mut bonds = vec![];
+    let mut unbonds = vec![];
+    let mut proposal_ids = BTreeSet::new();
+    // The latest epoch at which the bonds and unbonds can be updated
+    let last_update_epoch = epoch
+        + std::cmp::max(pos_params.pipeline_len, pos_params.unbonding_len);
+    for (key, value) in state {
+        if let Some(owner) = token::is_any_token_balance_key(key) {
+            if let Some(DbKeySeg::AddressSeg(token)) = key.segments.first() {
+                let amount = token::Amount::try_from_slice(value)?;
+                balances
+                    .entry(token.encode())
+                    .or_default()
+                    .insert(owner.encode(), amount);
+            }
+        } else if let Some(bond_id) = pos::is_bond_key(key) {
+            let slashes: Slashes =
+                read(state, &pos::validator_slashes_key(&bond_id.validator))?
+                    .unwrap_or_default();
+            let bond = Bonds::try_from_slice(value)?.get(last_update_epoch);
+            if let Some(bond) = bond {
+                let mut total: u64 = 0;
+                for (start_epoch, delta) in bond.pos_deltas {
+                    let delta: u64 = delta.into();
+                    total += delta;
+                    for slash in &slashes {
+                        if slash.epoch <= start_epoch {
+                            total -= decimal_mult_u64(slash.rate, delta);
+                        }
+                    }
+                }
+                let neg_deltas: u64 = bond.neg_deltas.into();
+                let amount = token::Amount::from(total - neg_deltas);
+                if amount != token::Amount::default() {
+                    bonds.push(ExportedBondConfig {
+                        source: bond_id.source.encode(),
+                        validator: bond_id.validator.encode(),
+                        amount,
+                    });
+                }
+            }
+        } else if let Some(bond_id) = pos::is_unbond_key(key) {
+            let slashes: Slashes =
+                read(state, &pos::validator_slashes_key(&bond_id.validator))?
+                    .unwrap_or_default();
+            let unbond = Unbonds::try_from_slice(value)?.get(last_update_epoch);
+            if let Some(unbond) = unbond {
+                for ((start_epoch, withdraw_epoch), delta) in unbond.deltas {
+                    let mut amount: u64 = delta.into();
+                    for slash in &slashes {
+                        if slash.epoch >= start_epoch
+                            && slash.epoch <= withdraw_epoch
+                        {
+                            amount -=
+                                decimal_mult_u64(slash.rate, u64::from(delta));
+                        }
+                    }
+                    unbonds.push(ExportedUnbondConfig {
+                        source: bond_id.source.encode(),
+                        validator: bond_id.validator.encode(),
+                        amount: token::Amount::from(amount),
+                        withdraw_epoch: withdraw_epoch.0,
+                    });
+                }
+            }
+        } else if gov_storage::is_author_key(key) {
+            if let Some(id) = gov_storage::get_proposal_id(key) {
+                proposal_ids.insert(id);
+            }
+        }
+    }
+
+    let mut proposals = vec![];
+    for id in proposal_ids {
+        let content = read_raw(state, &gov_storage::get_content_key(id))?;
+        let author: Address =
+            read_required(state, &gov_storage::get_author_key(id))?;
+        let voting_start_epoch: Epoch =
+            read_required(state, &gov_storage::get_voting_start_epoch_key(id))?;
+        let voting_end_epoch: Epoch =
+            read_required(state, &gov_storage::get_voting_end_epoch_key(id))?;
+        let grace_epoch: Epoch =
+            read_required(state, &gov_storage::get_grace_epoch_key(id))?;
+        let funds: token::Amount =
+            read(state, &gov_storage::get_funds_key(id))?.unwrap_or_default();
+        let proposal_code = state
+            .get(&gov_storage::get_proposal_code_key(id))
+            .map(|code| HexString(HEXLOWER.encode(code)));
+        // The proposals stored before the types were introduced are of the
+        // default type
+        let proposal_type: ProposalType =
+            read(state, &gov_storage::get_proposal_type_key(id))?
+                .unwrap_or_default();
+        let expedited: bool = read(state, &gov_storage::get_expedited_key(id))?
+            .unwrap_or_default();
+        let status: Option<ProposalStatus> =
+            read(state, &gov_storage::get_status_key(id))?;
+        let vote_prefix = gov_storage::get_proposal_vote_prefix_key(id);
+        let mut votes = vec![];
+        for (key, value) in state.range(vote_prefix.clone()..) {
+            if key.split_prefix(&vote_prefix).is_none() {
+                break;
+            }
+            if let (Some(voter), Some(delegator)) = (
+                gov_storage::get_voter_address(key),
+                gov_storage::get_vote_delegation_address(key),
+            ) {
+                votes.push(ExportedVoteConfig {
+                    voter: voter.encode(),
+                    delegator: delegator.encode(),
+                    vote: ProposalVote::try_from_slice(value)?,
+                });
+            }
+        }
+        proposals.push(ExportedProposalConfig {
+            id,
+            content: HexString(HEXLOWER.encode(&content)),
+            author: author.encode(),
+            voting_start_epoch: voting_start_epoch.0,
+            voting_end_epoch: voting_end_epoch.0,
+            grace_epoch: grace_epoch.0,
+            funds,
+            proposal_code,
+            proposal_type: match proposal_type {
+                ProposalType::Default(_) => None,
+                proposal_type => Some(proposal_type),
+            },
+            expedited,
+            status,
+            votes,
+        });
+    }
+    let proposal_counter: u64 =
+        read(state, &gov_storage::get_counter_key())?.unwrap_or_default();
+
+    Ok(ExportedStateConfig {
+        height: height.0,
+        epoch: epoch.0,
+        proposal_counter,
+        bonds,
+        unbonds,
+        proposals,
+        balances,
+    })
+}
+
+fn read_raw(state: &State, key: &Key) -> eyre::Result<Vec<u8>> {
+    state
+        .get(key)
+        .cloned()
+        .ok_or_else(|| eyre!("Missing value of the storage key {}", key))
+}
+
+#[cfg(test)]
+mod test_export {
+    use namada::ledger::storage::types;
+    use namada::types::address::{self, testing};
+    use namada::types::chain::ChainId;
+    use namada::types::storage::BlockHash;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    /// Test that the state at a past height is reconstructed from the diffs
+    /// and that its balances are exported.
+    #[test]
+    fn test_export_state_at_past_height() {
+        let db_path =
+            TempDir::new().expect("Unable to create a temporary DB directory");
+        let mut storage = PersistentStorage::open(
+            db_path.path(),
+            ChainId::default(),
+            address::nam(),
+            None,
+        );
+        let owner = testing::established_address_1();
+        let balance_key = token::balance_key(&address::nam(), &owner);
+        let modified_key = Key::parse("modified").unwrap();
+        let deleted_key = Key::parse("deleted").unwrap();
+        let created_key = Key::parse("created").unwrap();
+
+        storage
+            .begin_block(BlockHash::default(), BlockHeight(1))
+            .unwrap();
+        storage
+            .write(&balance_key, types::encode(&token::Amount::whole(10)))
+            .unwrap();
+        storage.write(&modified_key, types::encode(&1_u64)).unwrap();
+        storage.write(&deleted_key, types::encode(&1_u64)).unwrap();
+        storage.commit().unwrap();
+
+        storage
+            .begin_block(BlockHash::default(), BlockHeight(2))
+            .unwrap();
+        storage
+            .write(&balance_key, types::encode(&token::Amount::whole(5)))
+            .unwrap();
+        storage.write(&modified_key, types::encode(&2_u64)).unwrap();
+        storage.delete(&deleted_key).unwrap();
+        storage.write(&created_key, types::encode(&2_u64)).unwrap();
+        storage.commit().unwrap();
+
+        let state = read_state_at_height(&storage, BlockHeight(1)).unwrap();
+        assert_eq!(read::<u64>(&state, &modified_key).unwrap(), Some(1));
+        assert_eq!(read::<u64>(&state, &deleted_key).unwrap(), Some(1));
+        assert!(!state.contains_key(&created_key));
+
+        let exported = export_state(
+            &state,
+            BlockHeight(1),
+            Epoch::default(),
+            &PosParams::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            exported.balances[&address::nam().encode()][&owner.encode()],
+            token::Amount::whole(10)
+        );
+
+        let state = read_state_at_height(&storage, BlockHeight(2)).unwrap();
+        assert_eq!(read::<u64>(&state, &modified_key).unwrap(), Some(2));
+        assert!(!state.contains_key(&deleted_key));
+        assert_eq!(read::<u64>(&state, &created_key).unwrap(), Some(2));
+
+        assert!(read_state_at_height(&storage, BlockHeight(3)).is_err());
+    }
+}
@@ -1,8 +1,11 @@
 mod abortable;
 mod broadcaster;
+mod check_db;
+mod export;
 mod shell;
 mod shims;
 pub mod storage;
+mod subscriptions;
 pub mod tendermint_node;
 
 use std::convert::TryInto;
@@ -31,6 +34,7 @@ use crate::node::ledger::config::genesis;
 use crate::node::ledger::shell::{Error, MempoolTxType, Shell};
 use crate::node::ledger::shims::abcipp_shim::AbcippShim;
 use crate::node::ledger::shims::abcipp_shim_types::shim::{Request, Response};
+use crate::node::ledger::subscriptions::SubscriptionServer;
 use crate::{config, wasm_loader};
 
 /// Env. var to set a number of Tokio RT worker threads
@@ -140,16 +144,18 @@ impl Shell {
                 Ok(Response::CheckTx(self.mempool_validate(&tx.tx, r#type)))
             }
             Request::ListSnapshots(_) => {
-                Ok(Response::ListSnapshots(Default::default()))
+                Ok(Response::ListSnapshots(self.list_snapshots()))
             }
-            Request::OfferSnapshot(_) => {
-                Ok(Response::OfferSnapshot(Default::default()))
+            Request::OfferSnapshot(req) => {
+                tracing::debug!("Request OfferSnapshot");
+                Ok(Response::OfferSnapshot(self.offer_snapshot(req)))
             }
-            Request::LoadSnapshotChunk(_) => {
-                Ok(Response::LoadSnapshotChunk(Default::default()))
+            Request::LoadSnapshotChunk(req) => {
+                Ok(Response::LoadSnapshotChunk(self.load_snapshot_chunk(req)))
             }
-            Request::ApplySnapshotChunk(_) => {
-                Ok(Response::ApplySnapshotChunk(Default::default()))
+            Request::ApplySnapshotChunk(req) => {
+                tracing::debug!("Request ApplySnapshotChunk");
+                Ok(Response::ApplySnapshotChunk(self.apply_snapshot_chunk(req)))
             }
         }
     }
@@ -198,6 +204,24 @@ pub fn reset(config: config::Ledger) -> Result<(), shell::Error> {
     shell::reset(config)
 }
 
+/// Exports the ledger's state at the given height into a genesis config
+pub fn export(
+    config: config::Ledger,
+    wasm_dir: PathBuf,
+    args: crate::cli::args::LedgerExport,
+) -> eyre::Result<()> {
+    export::export(config, wasm_dir, args)
+}
+
+/// Checks the integrity of the ledger's DB and optionally repairs the merkle
+/// tree of the last committed block
+pub fn check_db(
+    config: config::Ledger,
+    args: crate::cli::args::LedgerCheckDb,
+) -> eyre::Result<()> {
+    check_db::check_db(config, args)
+}
+
 /// Runs and monitors a few concurrent tasks.
 ///
 /// This includes:
@@ -205,6 +229,8 @@ pub fn reset(config: config::Ledger) -> Result<(), shell::Error> {
 ///   - A shell which contains an ABCI server, for talking to the Tendermint
 ///     node.
 ///   - A [`Broadcaster`], for the ledger to submit txs to Tendermint's mempool.
+///   - A [`SubscriptionServer`], for the clients to subscribe to the ledger's
+///     events.
 ///
 /// All must be alive for correct functioning.
 async fn run_aux(config: config::Ledger, wasm_dir: PathBuf) {
@@ -219,21 +245,23 @@ async fn run_aux(config: config::Ledger, wasm_dir: PathBuf) {
 
     // Start ABCI server and broadcaster (the latter only if we are a validator
     // node)
-    let (abci, broadcaster, shell_handler) = start_abci_broadcaster_shell(
-        &mut spawner,
-        wasm_dir,
-        setup_data,
-        config,
-    );
+    let (abci, broadcaster, subscriptions, shell_handler) =
+        start_abci_broadcaster_shell(
+            &mut spawner,
+            wasm_dir,
+            setup_data,
+            config,
+        );
 
     // Wait for interrupt signal or abort message
     let aborted = spawner.wait_for_abort().await.child_terminated();
 
     // Wait for all managed tasks to finish.
-    let res = tokio::try_join!(tendermint_node, abci, broadcaster);
+    let res =
+        tokio::try_join!(tendermint_node, abci, broadcaster, subscriptions);
 
     match res {
-        Ok((tendermint_res, abci_res, _)) => {
+        Ok((tendermint_res, abci_res, _, _)) => {
             // we ignore errors on user-initiated shutdown
             if aborted {
                 if let Err(err) = tendermint_res {
@@ -362,10 +390,11 @@ async fn run_aux_setup(
     }
 }
 
-/// Launches two tasks into the asynchronous runtime:
+/// Launches three tasks into the asynchronous runtime:
 ///
 ///   1. An ABCI server.
 ///   2. A service for broadcasting transactions via an HTTP client.
+///   3. A WebSocket service of event subscriptions.
 ///
 /// Lastly, this function executes an ABCI shell on a new OS thread.
 fn start_abci_broadcaster_shell(
@@ -376,6 +405,7 @@ fn start_abci_broadcaster_shell(
 ) -> (
     task::JoinHandle<shell::Result<()>>,
     task::JoinHandle<()>,
+    task::JoinHandle<()>,
     thread::JoinHandle<()>,
 ) {
     let rpc_address = config.tendermint.rpc_address.to_string();
@@ -424,6 +454,15 @@ fn start_abci_broadcaster_shell(
     // Construct our ABCI application.
     let tendermint_mode = config.tendermint.tendermint_mode.clone();
     let ledger_address = config.shell.ledger_address;
+    let subscription_address = config.shell.events_subscription_address;
+    let max_connections = config
+        .shell
+        .events_subscription_max_connections
+        .unwrap_or(subscriptions::DEFAULT_MAX_CONNECTIONS);
+    let max_subscriptions = config
+        .shell
+        .events_subscription_max_queries
+        .unwrap_or(subscriptions::DEFAULT_MAX_SUBSCRIPTIONS);
     #[cfg(not(feature = "dev"))]
     let genesis = genesis::genesis(&config.shell.base_dir, &config.chain_id);
     #[cfg(feature = "dev")]
@@ -454,6 +493,47 @@ fn start_abci_broadcaster_shell(
             let _ = abci_abort_send.send(());
         });
 
+    // Start the event subscriptions, if enabled
+    let subscriptions = if let Some(address) = subscription_address {
+        let events_sender = shell.events_sender();
+        let (sub_abort_send, sub_abort_recv) =
+            tokio::sync::oneshot::channel::<()>();
+
+        spawner
+            .spawn_abortable("Event subscriptions", move |aborter| async move {
+                match SubscriptionServer::bind(
+                    address,
+                    max_connections,
+                    max_subscriptions,
+                    events_sender,
+                )
+                .await
+                {
+                    Ok(server) => {
+                        server.run(sub_abort_recv).await;
+                        tracing::info!(
+                            "Event subscriptions are no longer running."
+                        );
+                    }
+                    Err(err) => {
+                        tracing::error!(
+                            "Failed to start the event subscriptions at {}: \
+                             {}",
+                            address,
+                            err
+                        );
+                    }
+                }
+
+                drop(aborter);
+            })
+            .with_cleanup(async move {
+                let _ = sub_abort_send.send(());
+            })
+    } else {
+        spawn_dummy_task(())
+    };
+
     // Start the shell in a new OS thread
     let thread_builder = thread::Builder::new().name("ledger-shell".into());
     let shell_handler = thread_builder
@@ -471,7 +551,7 @@ fn start_abci_broadcaster_shell(
         })
         .expect("Must be able to start a thread for the shell");
 
-    (abci, broadcaster, shell_handler)
+    (abci, broadcaster, subscriptions, shell_handler)
 }
 
 /// Runs the an asynchronous ABCI server with four sub-components for consensus,
@@ -1,13 +1,22 @@
 //! Implementation of the `FinalizeBlock` ABCI++ method for the Shell
 
+use namada::ledger::pgf;
+use std::collections::BTreeSet;
+
 use namada::ledger::pos::types::into_tm_voting_power;
+use namada::ledger::pos::BondId;
 use namada::ledger::protocol;
+use namada::ledger::slash_fund;
 use namada::ledger::storage::write_log::StorageModification;
 use namada::ledger::storage_api::StorageRead;
+use namada::proto::SignedTxData;
 use namada::types::storage::{BlockHash, BlockResults, Header};
 use namada::types::token::Amount;
+use namada::types::transaction::pos::Bond;
 
-use super::governance::execute_governance_proposals;
+use super::governance::{
+    execute_governance_proposals, update_proposal_statuses,
+};
 use super::*;
 use crate::facade::tendermint_proto::abci::Misbehavior as Evidence;
 use crate::facade::tendermint_proto::crypto::PublicKey as TendermintPublicKey;
@@ -51,8 +60,41 @@ where
             self.update_state(req.header, req.hash, req.byzantine_validators);
 
         if new_epoch {
+            // Pay the installments of the vested treasury spends before the
+            // proposals, whose spends pay their first installment at once
+            let (current_epoch, _gas) = self.storage.get_current_epoch();
+            match slash_fund::pay_vesting(&mut self.storage, current_epoch) {
+                Ok(payments) => {
+                    response.events.extend(
+                        payments.iter().map(Event::new_treasury_payment_event),
+                    );
+                }
+                Err(err) => {
+                    tracing::error!(
+                        "Failed to pay the vested treasury spends: {err}"
+                    )
+                }
+            }
+
+            // Start and end the voting periods before the proposals at their
+            // grace epoch are tallied
+            update_proposal_statuses(self, &mut response);
             let _proposals_result =
                 execute_governance_proposals(self, &mut response)?;
+
+            // Pay the public goods funding of the new epoch, after the
+            // proposals that may have elected a new council
+            let native_token = self.storage.native_token.clone();
+            match pgf::pay_funding(&mut self.storage, &native_token) {
+                Ok(minted) => {
+                    tracing::info!("Paid {minted} of public goods funding")
+                }
+                Err(err) => {
+                    tracing::error!(
+                        "Failed to pay the public goods funding: {err}"
+                    )
+                }
+            }
         }
 
         let wrapper_fees = self.get_wrapper_tx_fees();
@@ -134,6 +176,9 @@ where
                 continue;
             }
 
+            // The data of a decrypted tx, decoded into an event only if the
+            // tx is accepted
+            let mut tx_data = None;
             let mut tx_event = match &tx_type {
                 TxType::Wrapper(wrapper) => {
                     let mut tx_event = Event::new_tx_event(&tx_type, height.0);
@@ -235,6 +280,7 @@ where
                                 namada::core::types::hash::Hash(tx.code_hash())
                                     .to_string(),
                             );
+                            tx_data = tx.data.clone();
                         }
                         DecryptedTx::Undecryptable(_) => {
                             event["log"] =
@@ -296,6 +342,16 @@ where
                             let event = Event::from(ibc_event.clone());
                             response.events.push(event);
                         }
+                        if let Some(event) = tx_data.and_then(|data| {
+                            tx_data_event(
+                                &data,
+                                &tx_event["hash"],
+                                height,
+                                &result.changed_keys,
+                            )
+                        }) {
+                            response.events.push(event);
+                        }
                         match serde_json::to_string(
                             &result.initialized_accounts,
                         ) {
@@ -365,6 +421,8 @@ where
             .finalize_transaction()
             .map_err(|_| Error::GasOverflow)?;
 
+        self.store_events(height, &response.events);
+        self.send_events(height, &response.events);
         self.event_log_mut().log_events(response.events.clone());
 
         Ok(response)
@@ -410,7 +468,10 @@ where
     fn update_epoch(&self, response: &mut shim::response::FinalizeBlock) {
         // Apply validator set update
         let (current_epoch, _gas) = self.storage.get_current_epoch();
-        let pos_params = self.storage.read_pos_params();
+        let pos_params = self
+            .storage
+            .read_pos_params()
+            .expect("PoS parameters should be readable");
         // TODO ABCI validator updates on block H affects the validator set
         // on block H+2, do we need to update a block earlier?
         self.storage.validator_set_update(current_epoch, |update| {
@@ -443,6 +504,50 @@ where
     }
 }
 
+/// Decode the data of an accepted transfer or bond tx into an event with its
+/// details. The tx is recognized from its result rather than from its code,
+/// which may be any code allowed on chain: the data must decode into a
+/// transfer that changed the target's balance or into a bond that changed the
+/// bond of its source.
+fn tx_data_event(
+    tx_data: &[u8],
+    tx_hash: &str,
+    height: BlockHeight,
+    changed_keys: &BTreeSet<Key>,
+) -> Option<Event> {
+    let data = SignedTxData::try_from_slice(tx_data).ok()?.data?;
+    if let Ok(transfer) = token::Transfer::try_from_slice(&data) {
+        let target_key = match &transfer.sub_prefix {
+            Some(sub_prefix) => {
+                let prefix = token::multitoken_balance_prefix(
+                    &transfer.token,
+                    sub_prefix,
+                );
+                token::multitoken_balance_key(&prefix, &transfer.target)
+            }
+            None => token::balance_key(&transfer.token, &transfer.target),
+        };
+        if changed_keys.contains(&target_key) {
+            return Some(Event::new_transfer_event(
+                tx_hash, height.0, &transfer,
+            ));
+        }
+    }
+    if let Ok(bond) = Bond::try_from_slice(&data) {
+        let bond_id = BondId {
+            source: bond
+                .source
+                .clone()
+                .unwrap_or_else(|| bond.validator.clone()),
+            validator: bond.validator.clone(),
+        };
+        if changed_keys.contains(&pos::bond_key(&bond_id)) {
+            return Some(Event::new_bond_event(tx_hash, height.0, &bond));
+        }
+    }
+    None
+}
+
 /// We test the failure cases of [`finalize_block`]. The happy flows
 /// are covered by the e2e tests.
 #[cfg(test)]
@@ -798,4 +903,85 @@ mod test_finalize_block {
         }
         assert_eq!(counter, 2);
     }
+
+    /// Test that the transfer and bond events are decoded from the data of
+    /// the txs only when their results changed the transferred balance or the
+    /// bond, independently of the tx code.
+    #[test]
+    fn test_tx_data_event() {
+        use namada::types::address::testing::{
+            established_address_1, established_address_2,
+        };
+
+        let keypair = gen_keypair();
+        let tx_hash = "hash";
+        let height = BlockHeight(1);
+        let signed_data = |data: Vec<u8>| {
+            Tx::new("any_code".as_bytes().to_owned(), Some(data))
+                .sign(&keypair)
+                .data
+                .unwrap()
+        };
+
+        let transfer = token::Transfer {
+            source: established_address_1(),
+            target: established_address_2(),
+            token: address::nam(),
+            sub_prefix: None,
+            amount: Amount::from(10),
+            key: None,
+            shielded: None,
+        };
+        let transfer_data = signed_data(transfer.try_to_vec().unwrap());
+        let target_key = token::balance_key(&transfer.token, &transfer.target);
+        assert!(tx_data_event(
+            &transfer_data,
+            tx_hash,
+            height,
+            &BTreeSet::new()
+        )
+        .is_none());
+        let event = tx_data_event(
+            &transfer_data,
+            tx_hash,
+            height,
+            &BTreeSet::from([target_key]),
+        )
+        .expect("The transfer event must be emitted");
+        assert_eq!(
+            event,
+            Event::new_transfer_event(tx_hash, height.0, &transfer)
+        );
+
+        let bond = Bond {
+            validator: established_address_1(),
+            amount: Amount::from(10),
+            source: Some(established_address_2()),
+        };
+        let bond_data = signed_data(bond.try_to_vec().unwrap());
+        let bond_key = pos::bond_key(&BondId {
+            source: established_address_2(),
+            validator: established_address_1(),
+        });
+        // A self-bond of the validator is not this bond
+        let self_bond_key = pos::bond_key(&BondId {
+            source: established_address_1(),
+            validator: established_address_1(),
+        });
+        assert!(tx_data_event(
+            &bond_data,
+            tx_hash,
+            height,
+            &BTreeSet::from([self_bond_key])
+        )
+        .is_none());
+        let event = tx_data_event(
+            &bond_data,
+            tx_hash,
+            height,
+            &BTreeSet::from([bond_key]),
+        )
+        .expect("The bond event must be emitted");
+        assert_eq!(event, Event::new_bond_event(tx_hash, height.0, &bond));
+    }
 }
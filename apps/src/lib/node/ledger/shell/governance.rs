@@ -1,16 +1,22 @@
-use namada::core::ledger::slash_fund::ADDRESS as slash_fund_address;
+use namada::core::ledger::slash_fund::{self, ADDRESS as slash_fund_address};
 use namada::ledger::events::EventType;
 use namada::ledger::governance::{
     storage as gov_storage, ADDRESS as gov_address,
 };
+use namada::ledger::native_vp::governance::parameter_change;
 use namada::ledger::native_vp::governance::utils::{
-    compute_tally, get_proposal_votes, ProposalEvent,
+    compute_proposal_result, is_expedited, read_pass_through_validators,
+    read_proposal_votes, ProposalEvent,
 };
+use namada::ledger::pgf::storage as pgf_storage;
 use namada::ledger::protocol;
 use namada::ledger::storage::types::encode;
 use namada::ledger::storage::{DBIter, StorageHasher, DB};
+use namada::ledger::storage_api;
 use namada::types::address::Address;
-use namada::types::governance::TallyResult;
+use namada::types::governance::{
+    ProposalResult, ProposalStatus, ProposalType, TallyResult,
+};
 use namada::types::storage::Epoch;
 use namada::types::token;
 
@@ -22,6 +28,137 @@ pub struct ProposalsResult {
     rejected: Vec<u64>,
 }
 
+/// What happens to the funds locked by a proposal at the end of its voting
+/// period
+enum ProposalDeposit {
+    /// The funds are returned to the author
+    Refund,
+    /// The funds are forfeited to the slash fund
+    SlashFund,
+    /// The funds are burned
+    Burn,
+}
+
+impl ProposalDeposit {
+    /// The funds are refunded when the votes reach the quorum, whatever the
+    /// result, unless the proposal is vetoed. Otherwise, they're burned or
+    /// sent to the slash fund according to the governance parameter.
+    fn from_result(
+        result: &ProposalResult,
+        burn_missed_quorum_deposit: bool,
+    ) -> Self {
+        if !result.quorum_reached {
+            if burn_missed_quorum_deposit {
+                Self::Burn
+            } else {
+                Self::SlashFund
+            }
+        } else if result.result == TallyResult::Vetoed {
+            Self::Burn
+        } else {
+            Self::Refund
+        }
+    }
+}
+
+/// Update the status of the proposals whose voting period starts or ends at
+/// the beginning of the current epoch
+pub fn update_proposal_statuses<D, H>(
+    shell: &mut Shell<D, H>,
+    response: &mut shim::response::FinalizeBlock,
+) where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    let (current_epoch, _gas) = shell.storage.get_current_epoch();
+    let counter = shell
+        .read_storage_key::<u64>(&gov_storage::get_counter_key())
+        .unwrap_or_default();
+    for id in 0..counter {
+        // The proposals stored before the statuses were introduced have none
+        let status = match shell.read_storage_key::<ProposalStatus>(
+            &gov_storage::get_status_key(id),
+        ) {
+            Some(
+                status @ (ProposalStatus::Pending | ProposalStatus::Voting),
+            ) => status,
+            _ => continue,
+        };
+        let voting_start_epoch = shell.read_storage_key::<Epoch>(
+            &gov_storage::get_voting_start_epoch_key(id),
+        );
+        let voting_end_epoch = shell.read_storage_key::<Epoch>(
+            &gov_storage::get_voting_end_epoch_key(id),
+        );
+        let new_status = match (voting_start_epoch, voting_end_epoch) {
+            (Some(voting_start_epoch), Some(voting_end_epoch)) => {
+                ProposalStatus::at_epoch(
+                    current_epoch,
+                    voting_start_epoch,
+                    voting_end_epoch,
+                )
+            }
+            _ => {
+                tracing::error!(
+                    "The proposal ID {id} is missing its voting period"
+                );
+                continue;
+            }
+        };
+        if new_status != status {
+            if new_status == ProposalStatus::Ended {
+                record_pass_through_validators(shell, id);
+            }
+            set_proposal_status(shell, response, id, new_status);
+        }
+    }
+}
+
+/// Record the validators that pass through the votes of their delegators at
+/// the end of the voting period of a proposal, so that the later changes of
+/// their setting don't affect its tally
+fn record_pass_through_validators<D, H>(shell: &mut Shell<D, H>, id: u64)
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    match read_pass_through_validators(&shell.storage) {
+        Ok(validators) => {
+            shell
+                .storage
+                .write(
+                    &gov_storage::get_pass_through_validators_key(id),
+                    encode(&validators),
+                )
+                .expect("Should be able to write to storage.");
+        }
+        Err(err) => tracing::error!(
+            "Failed to read the vote pass-through validators of proposal ID \
+             {id} with error {err}"
+        ),
+    }
+}
+
+/// Store the new status of a proposal and emit an event for the transition
+fn set_proposal_status<D, H>(
+    shell: &mut Shell<D, H>,
+    response: &mut shim::response::FinalizeBlock,
+    id: u64,
+    status: ProposalStatus,
+) where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    shell
+        .storage
+        .write(&gov_storage::get_status_key(id), encode(&status))
+        .expect("Should be able to write to storage.");
+    let (epoch, _gas) = shell.storage.get_current_epoch();
+    response
+        .events
+        .push(Event::new_proposal_status_event(id, status, epoch));
+}
+
 pub fn execute_governance_proposals<D, H>(
     shell: &mut Shell<D, H>,
     response: &mut shim::response::FinalizeBlock,
@@ -31,10 +168,16 @@ where
     H: StorageHasher + Sync + 'static,
 {
     let mut proposals_result = ProposalsResult::default();
+    let burn_missed_quorum_deposit = shell
+        .read_storage_key::<bool>(
+            &gov_storage::get_burn_missed_quorum_deposit_key(),
+        )
+        .unwrap_or_default();
 
     for id in std::mem::take(&mut shell.proposal_data) {
         let proposal_funds_key = gov_storage::get_funds_key(id);
         let proposal_end_epoch_key = gov_storage::get_voting_end_epoch_key(id);
+        let proposal_author_key = gov_storage::get_author_key(id);
 
         let funds = shell
             .read_storage_key::<token::Amount>(&proposal_funds_key)
@@ -49,138 +192,74 @@ where
                     "Invalid proposal end_epoch.".to_string(),
                 )
             })?;
+        let proposal_author = shell
+            .read_storage_key::<Address>(&proposal_author_key)
+            .ok_or_else(|| {
+                Error::BadProposal(id, "Invalid proposal author.".to_string())
+            })?;
 
-        let votes = get_proposal_votes(&shell.storage, proposal_end_epoch, id);
-        let is_accepted = votes.and_then(|votes| {
-            compute_tally(&shell.storage, proposal_end_epoch, votes)
+        let is_expedited = is_expedited(&shell.storage, id).unwrap_or_default();
+        let votes = read_proposal_votes(&shell.storage, proposal_end_epoch, id);
+        let proposal_result = votes.and_then(|votes| {
+            compute_proposal_result(
+                &shell.storage,
+                proposal_end_epoch,
+                votes,
+                is_expedited,
+            )
         });
 
-        let transfer_address = match is_accepted {
-            Ok(true) => {
-                let proposal_author_key = gov_storage::get_author_key(id);
-                let proposal_author = shell
-                    .read_storage_key::<Address>(&proposal_author_key)
-                    .ok_or_else(|| {
-                        Error::BadProposal(
-                            id,
-                            "Invalid proposal author.".to_string(),
-                        )
-                    })?;
-
-                let proposal_code_key = gov_storage::get_proposal_code_key(id);
-                let proposal_code =
-                    shell.read_storage_key_bytes(&proposal_code_key);
-                match proposal_code {
-                    Some(proposal_code) => {
-                        let tx = Tx::new(proposal_code, Some(encode(&id)));
-                        let tx_type =
-                            TxType::Decrypted(DecryptedTx::Decrypted {
-                                tx,
-                                #[cfg(not(feature = "mainnet"))]
-                                has_valid_pow: false,
-                            });
-                        let pending_execution_key =
-                            gov_storage::get_proposal_execution_key(id);
-                        shell
-                            .storage
-                            .write(&pending_execution_key, "")
-                            .expect("Should be able to write to storage.");
-                        let tx_result = protocol::apply_tx(
-                            tx_type,
-                            0, /*  this is used to compute the fee
-                                * based on the code size. We dont
-                                * need it here. */
-                            TxIndex::default(),
-                            &mut BlockGasMeter::default(),
-                            &mut shell.write_log,
-                            &shell.storage,
-                            &mut shell.vp_wasm_cache,
-                            &mut shell.tx_wasm_cache,
-                        );
-                        shell
-                            .storage
-                            .delete(&pending_execution_key)
-                            .expect("Should be able to delete the storage.");
-                        match tx_result {
-                            Ok(tx_result) => {
-                                if tx_result.is_accepted() {
-                                    shell.write_log.commit_tx();
-                                    let proposal_event: Event =
-                                        ProposalEvent::new(
-                                            EventType::Proposal.to_string(),
-                                            TallyResult::Passed,
-                                            id,
-                                            true,
-                                            true,
-                                        )
-                                        .into();
-                                    response.events.push(proposal_event);
-                                    proposals_result.passed.push(id);
-
-                                    proposal_author
-                                } else {
-                                    shell.write_log.drop_tx();
-                                    let proposal_event: Event =
-                                        ProposalEvent::new(
-                                            EventType::Proposal.to_string(),
-                                            TallyResult::Passed,
-                                            id,
-                                            true,
-                                            false,
-                                        )
-                                        .into();
-                                    response.events.push(proposal_event);
-                                    proposals_result.rejected.push(id);
-
-                                    slash_fund_address
-                                }
-                            }
-                            Err(_e) => {
-                                shell.write_log.drop_tx();
-                                let proposal_event: Event = ProposalEvent::new(
-                                    EventType::Proposal.to_string(),
-                                    TallyResult::Passed,
-                                    id,
-                                    true,
-                                    false,
-                                )
-                                .into();
-                                response.events.push(proposal_event);
-                                proposals_result.rejected.push(id);
-
-                                slash_fund_address
-                            }
-                        }
-                    }
-                    None => {
-                        let proposal_event: Event = ProposalEvent::new(
-                            EventType::Proposal.to_string(),
-                            TallyResult::Passed,
-                            id,
-                            false,
-                            false,
-                        )
-                        .into();
-                        response.events.push(proposal_event);
-                        proposals_result.passed.push(id);
-
-                        proposal_author
-                    }
-                }
-            }
-            Ok(false) => {
-                let proposal_event: Event = ProposalEvent::new(
-                    EventType::Proposal.to_string(),
-                    TallyResult::Rejected,
+        let deposit = match proposal_result {
+            Ok(proposal_result)
+                if is_expedited
+                    && proposal_result.result == TallyResult::Rejected =>
+            {
+                // The funds stay locked until the normal tally
+                fall_back_to_normal_proposal(shell, id)?;
+                set_proposal_status(
+                    shell,
+                    response,
                     id,
-                    false,
-                    false,
+                    ProposalStatus::Voting,
+                );
+                continue;
+            }
+            Ok(proposal_result) => {
+                if proposal_result.result == TallyResult::Passed {
+                    set_proposal_status(
+                        shell,
+                        response,
+                        id,
+                        ProposalStatus::Passed,
+                    );
+                    execute_proposal(
+                        shell,
+                        response,
+                        &mut proposals_result,
+                        id,
+                    )?;
+                } else {
+                    let proposal_event: Event = ProposalEvent::new(
+                        EventType::Proposal.to_string(),
+                        proposal_result.result.clone(),
+                        id,
+                        false,
+                        false,
+                    )
+                    .into();
+                    response.events.push(proposal_event);
+                    proposals_result.rejected.push(id);
+                    set_proposal_status(
+                        shell,
+                        response,
+                        id,
+                        ProposalStatus::Rejected,
+                    );
+                }
+                ProposalDeposit::from_result(
+                    &proposal_result,
+                    burn_missed_quorum_deposit,
                 )
-                .into();
-                response.events.push(proposal_event);
-                proposals_result.rejected.push(id);
-
-                slash_fund_address
             }
             Err(err) => {
                 tracing::error!(
@@ -196,20 +275,655 @@ where
                 )
                 .into();
                 response.events.push(proposal_event);
+                set_proposal_status(
+                    shell,
+                    response,
+                    id,
+                    ProposalStatus::Rejected,
+                );
 
-                slash_fund_address
+                ProposalDeposit::SlashFund
             }
         };
 
         let native_token = shell.storage.native_token.clone();
-        // transfer proposal locked funds
-        shell.storage.transfer(
+        match deposit {
+            ProposalDeposit::Refund => {
+                shell.storage.transfer(
+                    &native_token,
+                    funds,
+                    &gov_address,
+                    &proposal_author,
+                );
+            }
+            ProposalDeposit::SlashFund => {
+                shell.storage.transfer(
+                    &native_token,
+                    funds,
+                    &gov_address,
+                    &slash_fund_address,
+                );
+                let (epoch, _gas) = shell.storage.get_current_epoch();
+                if let Err(err) = slash_fund::record_deposit(
+                    &mut shell.storage,
+                    id,
+                    epoch,
+                    funds,
+                ) {
+                    tracing::error!(
+                        "Failed to record the funds of proposal ID {id} in \
+                         the treasury history with error {err}"
+                    );
+                }
+            }
+            ProposalDeposit::Burn => {
+                if let Err(err) = storage_api::token::burn(
+                    &mut shell.storage,
+                    &native_token,
+                    &gov_address,
+                    funds,
+                ) {
+                    tracing::error!(
+                        "Failed to burn the funds of proposal ID {id} with \
+                         error {err}"
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(proposals_result)
+}
+
+/// Turn an expedited proposal that failed into a normal proposal. Its voting
+/// period is extended by the min. proposal period from the current epoch and
+/// it's committed again at its new grace epoch, when it's tallied with the
+/// normal rules.
+fn fall_back_to_normal_proposal<D, H>(
+    shell: &mut Shell<D, H>,
+    id: u64,
+) -> Result<()>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    let min_proposal_period = shell
+        .read_storage_key::<u64>(&gov_storage::get_min_proposal_period_key())
+        .ok_or_else(|| {
+            Error::BadProposal(
+                id,
+                "Missing the min. proposal period.".to_string(),
+            )
+        })?;
+    let min_grace_epochs = shell
+        .read_storage_key::<u64>(
+            &gov_storage::get_min_proposal_grace_epoch_key(),
+        )
+        .ok_or_else(|| {
+            Error::BadProposal(
+                id,
+                "Missing the min. proposal grace epochs.".to_string(),
+            )
+        })?;
+    let (current_epoch, _gas) = shell.storage.get_current_epoch();
+    let voting_end_epoch = current_epoch + min_proposal_period;
+    let grace_epoch = voting_end_epoch + min_grace_epochs;
+
+    shell
+        .storage
+        .write(
+            &gov_storage::get_voting_end_epoch_key(id),
+            encode(&voting_end_epoch),
+        )
+        .expect("Should be able to write to storage.");
+    shell
+        .storage
+        .write(&gov_storage::get_grace_epoch_key(id), encode(&grace_epoch))
+        .expect("Should be able to write to storage.");
+    shell
+        .storage
+        .write(
+            &gov_storage::get_committing_proposals_key(id, grace_epoch.0),
+            encode(&()),
+        )
+        .expect("Should be able to write to storage.");
+    shell
+        .storage
+        .delete(&gov_storage::get_expedited_key(id))
+        .expect("Should be able to delete the storage.");
+    tracing::info!(
+        "The expedited proposal ID {id} failed, its voting period is \
+         extended until epoch {voting_end_epoch} with the normal rules"
+    );
+    Ok(())
+}
+
+/// Store the status of a proposal that passed after the execution of its
+/// effect. A proposal without any effect is always executed.
+fn set_execution_status<D, H>(
+    shell: &mut Shell<D, H>,
+    response: &mut shim::response::FinalizeBlock,
+    id: u64,
+    is_executed: bool,
+) where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    let status = if is_executed {
+        ProposalStatus::Executed
+    } else {
+        ProposalStatus::ExecutionFailed
+    };
+    set_proposal_status(shell, response, id, status)
+}
+
+/// Execute a proposal that passed according to its type
+fn execute_proposal<D, H>(
+    shell: &mut Shell<D, H>,
+    response: &mut shim::response::FinalizeBlock,
+    proposals_result: &mut ProposalsResult,
+    id: u64,
+) -> Result<()>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    let proposal_type_key = gov_storage::get_proposal_type_key(id);
+    let proposal_type = shell
+        .read_storage_key::<ProposalType>(&proposal_type_key)
+        .ok_or_else(|| {
+            Error::BadProposal(id, "Invalid proposal type.".to_string())
+        })?;
+
+    match proposal_type {
+        ProposalType::Default(_) => {
+            let proposal_code_key = gov_storage::get_proposal_code_key(id);
+            let proposal_code =
+                shell.read_storage_key_bytes(&proposal_code_key);
+            match proposal_code {
+                Some(proposal_code) => {
+                    let tx = Tx::new(proposal_code, Some(encode(&id)));
+                    let tx_type = TxType::Decrypted(DecryptedTx::Decrypted {
+                        tx,
+                        #[cfg(not(feature = "mainnet"))]
+                        has_valid_pow: false,
+                    });
+                    let pending_execution_key =
+                        gov_storage::get_proposal_execution_key(id);
+                    shell
+                        .storage
+                        .write(&pending_execution_key, "")
+                        .expect("Should be able to write to storage.");
+                    let tx_result = protocol::apply_tx(
+                        tx_type,
+                        0, /*  this is used to compute the fee
+                            * based on the code size. We dont
+                            * need it here. */
+                        TxIndex::default(),
+                        &mut BlockGasMeter::default(),
+                        &mut shell.write_log,
+                        &shell.storage,
+                        &mut shell.vp_wasm_cache,
+                        &mut shell.tx_wasm_cache,
+                    );
+                    shell
+                        .storage
+                        .delete(&pending_execution_key)
+                        .expect("Should be able to delete the storage.");
+                    let is_executed = match tx_result {
+                        Ok(tx_result) if tx_result.is_accepted() => {
+                            shell.write_log.commit_tx();
+                            true
+                        }
+                        _ => {
+                            shell.write_log.drop_tx();
+                            false
+                        }
+                    };
+                    let proposal_event: Event = ProposalEvent::new(
+                        EventType::Proposal.to_string(),
+                        TallyResult::Passed,
+                        id,
+                        true,
+                        is_executed,
+                    )
+                    .into();
+                    response.events.push(proposal_event);
+                    if is_executed {
+                        proposals_result.passed.push(id);
+                    } else {
+                        proposals_result.rejected.push(id);
+                    }
+                    set_execution_status(shell, response, id, is_executed);
+                }
+                None => {
+                    let proposal_event: Event = ProposalEvent::new(
+                        EventType::Proposal.to_string(),
+                        TallyResult::Passed,
+                        id,
+                        false,
+                        false,
+                    )
+                    .into();
+                    response.events.push(proposal_event);
+                    proposals_result.passed.push(id);
+                    // There's nothing to execute, so it's done
+                    set_execution_status(shell, response, id, true);
+                }
+            }
+        }
+        ProposalType::ParameterChange { key, value } => {
+            // The change is applied natively, but it's reported like the
+            // execution of some proposal code
+            let is_applied =
+                match parameter_change::apply(&mut shell.storage, &key, &value)
+                {
+                    Ok(()) => true,
+                    Err(err) => {
+                        tracing::error!(
+                            "Failed to apply the parameter change of \
+                             proposal ID {id} with error {err}"
+                        );
+                        false
+                    }
+                };
+            let proposal_event: Event = ProposalEvent::new(
+                EventType::Proposal.to_string(),
+                TallyResult::Passed,
+                id,
+                true,
+                is_applied,
+            )
+            .into();
+            response.events.push(proposal_event);
+            if is_applied {
+                proposals_result.passed.push(id);
+            } else {
+                proposals_result.rejected.push(id);
+            }
+            set_execution_status(shell, response, id, is_applied);
+        }
+        ProposalType::TextOnly => {
+            let proposal_event: Event = ProposalEvent::new(
+                EventType::Proposal.to_string(),
+                TallyResult::Passed,
+                id,
+                false,
+                false,
+            )
+            .into();
+            response.events.push(proposal_event);
+            proposals_result.passed.push(id);
+            // There's nothing to execute, so it's done
+            set_execution_status(shell, response, id, true);
+        }
+        ProposalType::PgfCouncil(council) => {
+            shell
+                .storage
+                .write(&pgf_storage::get_council_key(), encode(&council))
+                .expect("Should be able to write to storage.");
+            let proposal_event: Event = ProposalEvent::new(
+                EventType::Proposal.to_string(),
+                TallyResult::Passed,
+                id,
+                false,
+                false,
+            )
+            .into();
+            response.events.push(proposal_event);
+            proposals_result.passed.push(id);
+            set_execution_status(shell, response, id, true);
+        }
+        ProposalType::TreasurySpend(spend) => {
+            // The payment is made natively, but it's reported like the
+            // execution of some proposal code
+            let (epoch, _gas) = shell.storage.get_current_epoch();
+            let is_paid = match slash_fund::spend(
+                &mut shell.storage,
+                id,
+                epoch,
+                &spend,
+            ) {
+                Ok(Some(payment)) => {
+                    response
+                        .events
+                        .push(Event::new_treasury_payment_event(&payment));
+                    true
+                }
+                Ok(None) => {
+                    tracing::error!(
+                        "The treasury doesn't have enough balance for the \
+                         spend of proposal ID {id}"
+                    );
+                    false
+                }
+                Err(err) => {
+                    tracing::error!(
+                        "Failed to spend the treasury for proposal ID {id} \
+                         with error {err}"
+                    );
+                    false
+                }
+            };
+            let proposal_event: Event = ProposalEvent::new(
+                EventType::Proposal.to_string(),
+                TallyResult::Passed,
+                id,
+                true,
+                is_paid,
+            )
+            .into();
+            response.events.push(proposal_event);
+            if is_paid {
+                proposals_result.passed.push(id);
+            } else {
+                proposals_result.rejected.push(id);
+            }
+            set_execution_status(shell, response, id, is_paid);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_governance {
+    use namada::ledger::pos::namada_proof_of_stake::PosReadOnly;
+    use namada::types::address::testing::established_address_1;
+    use namada::types::governance::ProposalVote;
+
+    use super::*;
+    use crate::node::ledger::shell::test_utils::*;
+
+    /// Test that the statuses of the proposals follow their voting period
+    /// and that an event is emitted for each transition
+    #[test]
+    fn test_update_proposal_statuses() {
+        let (mut shell, _) = setup();
+        let current_epoch = Epoch(3);
+        shell.storage.block.epoch = current_epoch;
+
+        let proposals = [
+            // Pending until the next epoch
+            (Epoch(4), Epoch(6), ProposalStatus::Pending),
+            // The voting period starts at the current epoch
+            (Epoch(3), Epoch(6), ProposalStatus::Pending),
+            // The voting period ended at the previous epoch
+            (Epoch(0), Epoch(2), ProposalStatus::Voting),
+            // Already tallied
+            (Epoch(0), Epoch(2), ProposalStatus::Executed),
+        ];
+        for (id, (voting_start_epoch, voting_end_epoch, status)) in
+            proposals.iter().enumerate()
+        {
+            let id = id as u64;
+            shell
+                .storage
+                .write(
+                    &gov_storage::get_voting_start_epoch_key(id),
+                    encode(voting_start_epoch),
+                )
+                .unwrap();
+            shell
+                .storage
+                .write(
+                    &gov_storage::get_voting_end_epoch_key(id),
+                    encode(voting_end_epoch),
+                )
+                .unwrap();
+            shell
+                .storage
+                .write(&gov_storage::get_status_key(id), encode(status))
+                .unwrap();
+        }
+        shell
+            .storage
+            .write(
+                &gov_storage::get_counter_key(),
+                encode(&(proposals.len() as u64)),
+            )
+            .unwrap();
+
+        let mut response = shim::response::FinalizeBlock::default();
+        update_proposal_statuses(&mut shell, &mut response);
+
+        let statuses: Vec<_> = (0..proposals.len() as u64)
+            .map(|id| {
+                shell
+                    .read_storage_key::<ProposalStatus>(
+                        &gov_storage::get_status_key(id),
+                    )
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ProposalStatus::Pending,
+                ProposalStatus::Voting,
+                ProposalStatus::Ended,
+                ProposalStatus::Executed,
+            ]
+        );
+        assert_eq!(
+            response.events,
+            vec![
+                Event::new_proposal_status_event(
+                    1,
+                    ProposalStatus::Voting,
+                    current_epoch
+                ),
+                Event::new_proposal_status_event(
+                    2,
+                    ProposalStatus::Ended,
+                    current_epoch
+                ),
+            ]
+        );
+    }
+
+    /// Test that the vote pass-through setting of a validator at the end of
+    /// the voting period of a proposal applies to its tally, while the
+    /// current setting applies to the proposals still in voting
+    #[test]
+    fn test_pass_through_recorded_at_voting_end() {
+        let (mut shell, _) = setup();
+        let validator = shell
+            .storage
+            .validator_addresses(Epoch(0))
+            .unwrap()
+            .into_iter()
+            .next()
+            .unwrap();
+        let pass_through_key =
+            gov_storage::get_vote_pass_through_key(&validator);
+        shell
+            .storage
+            .write(&pass_through_key, encode(&true))
+            .unwrap();
+
+        // The first proposal's voting period ends before the second's
+        let proposals = [(Epoch(0), Epoch(0)), (Epoch(0), Epoch(2))];
+        for (id, (voting_start_epoch, voting_end_epoch)) in
+            proposals.iter().enumerate()
+        {
+            let id = id as u64;
+            shell
+                .storage
+                .write(
+                    &gov_storage::get_voting_start_epoch_key(id),
+                    encode(voting_start_epoch),
+                )
+                .unwrap();
+            shell
+                .storage
+                .write(
+                    &gov_storage::get_voting_end_epoch_key(id),
+                    encode(voting_end_epoch),
+                )
+                .unwrap();
+            shell
+                .storage
+                .write(
+                    &gov_storage::get_status_key(id),
+                    encode(&ProposalStatus::Voting),
+                )
+                .unwrap();
+        }
+        shell
+            .storage
+            .write(
+                &gov_storage::get_counter_key(),
+                encode(&(proposals.len() as u64)),
+            )
+            .unwrap();
+
+        shell.storage.block.epoch = Epoch(1);
+        let mut response = shim::response::FinalizeBlock::default();
+        update_proposal_statuses(&mut shell, &mut response);
+
+        // The validator opts out after the end of the first voting period
+        shell.storage.delete(&pass_through_key).unwrap();
+
+        let votes = read_proposal_votes(&shell.storage, Epoch(0), 0).unwrap();
+        assert!(votes.pass_through.contains_key(&validator));
+        let votes = read_proposal_votes(&shell.storage, Epoch(0), 1).unwrap();
+        assert!(votes.pass_through.is_empty());
+    }
+
+    /// Test that the passed proposals without any effect end up executed,
+    /// like the ones with an effect
+    #[test]
+    fn test_execute_proposal_without_effect() {
+        let (mut shell, _) = setup();
+        let current_epoch = shell.storage.get_current_epoch().0;
+
+        let proposal_types =
+            [ProposalType::TextOnly, ProposalType::Default(None)];
+        for (id, proposal_type) in proposal_types.iter().enumerate() {
+            let id = id as u64;
+            shell
+                .storage
+                .write(
+                    &gov_storage::get_proposal_type_key(id),
+                    encode(proposal_type),
+                )
+                .unwrap();
+
+            let mut response = shim::response::FinalizeBlock::default();
+            let mut proposals_result = ProposalsResult::default();
+            execute_proposal(
+                &mut shell,
+                &mut response,
+                &mut proposals_result,
+                id,
+            )
+            .unwrap();
+
+            assert_eq!(proposals_result.passed, vec![id]);
+            assert!(proposals_result.rejected.is_empty());
+            assert_eq!(
+                shell.read_storage_key::<ProposalStatus>(
+                    &gov_storage::get_status_key(id)
+                ),
+                Some(ProposalStatus::Executed)
+            );
+            assert!(response.events.contains(
+                &Event::new_proposal_status_event(
+                    id,
+                    ProposalStatus::Executed,
+                    current_epoch
+                )
+            ));
+        }
+    }
+
+    /// Test that the deposit of a vetoed proposal is burned, which deducts it
+    /// from the total supply
+    #[test]
+    fn test_burn_vetoed_proposal_deposit() {
+        let (mut shell, _) = setup();
+        let (current_epoch, _gas) = shell.storage.get_current_epoch();
+        let native_token = shell.storage.native_token.clone();
+        let author = established_address_1();
+        let funds = token::Amount::whole(500);
+        let id = 0;
+
+        // Lock the deposit of the proposal
+        storage_api::token::mint(
+            &mut shell.storage,
+            &author,
             &native_token,
+            &gov_address,
             funds,
+        )
+        .unwrap();
+        shell
+            .storage
+            .write(&gov_storage::get_funds_key(id), encode(&funds))
+            .unwrap();
+        shell
+            .storage
+            .write(
+                &gov_storage::get_voting_end_epoch_key(id),
+                encode(&current_epoch),
+            )
+            .unwrap();
+        shell
+            .storage
+            .write(&gov_storage::get_author_key(id), encode(&author))
+            .unwrap();
+
+        // All the validators veto the proposal
+        for validator in
+            shell.storage.validator_addresses(current_epoch).unwrap()
+        {
+            shell
+                .storage
+                .write(
+                    &gov_storage::get_vote_proposal_key(
+                        id,
+                        validator.clone(),
+                        validator,
+                    ),
+                    encode(&ProposalVote::NayWithVeto),
+                )
+                .unwrap();
+        }
+
+        let balance_before = storage_api::token::read_balance(
+            &shell.storage,
+            &native_token,
             &gov_address,
-            &transfer_address,
+        )
+        .unwrap();
+        let supply_before = storage_api::token::read_total_supply(
+            &shell.storage,
+            &native_token,
+        )
+        .unwrap();
+
+        shell.proposal_data.insert(id);
+        let mut response = shim::response::FinalizeBlock::default();
+        let proposals_result =
+            execute_governance_proposals(&mut shell, &mut response).unwrap();
+
+        assert_eq!(proposals_result.rejected, vec![id]);
+        assert_eq!(
+            storage_api::token::read_balance(
+                &shell.storage,
+                &native_token,
+                &gov_address
+            )
+            .unwrap(),
+            balance_before.checked_sub(funds).unwrap()
+        );
+        assert_eq!(
+            storage_api::token::read_total_supply(
+                &shell.storage,
+                &native_token
+            )
+            .unwrap(),
+            supply_before.checked_sub(funds).unwrap()
         );
     }
-
-    Ok(proposals_result)
 }
@@ -1,12 +1,17 @@
 //! Implementation of chain initialization for the Shell
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::hash::Hash;
 
 #[cfg(not(feature = "mainnet"))]
 use namada::core::ledger::testnet_pow;
+use namada::ledger::governance::storage as gov_storage;
 use namada::ledger::parameters::Parameters;
 use namada::ledger::pos::into_tm_voting_power;
+use namada::ledger::pos::types::{Bond, BondId, Bonds, Unbond, Unbonds};
+use namada::ledger::storage_api;
+use namada::types::governance::ProposalStatus;
 use namada::types::key::*;
+use namada::types::storage::Epoch;
 #[cfg(not(feature = "dev"))]
 use sha2::{Digest, Sha256};
 
@@ -229,6 +234,12 @@ where
         }
 
         // Initialize genesis token accounts
+        let mut tokens: BTreeSet<Address> = genesis
+            .token_accounts
+            .iter()
+            .map(|token| token.address.clone())
+            .collect();
+        tokens.insert(self.storage.native_token.clone());
         for genesis::TokenAccount {
             address,
             vp_code_path,
@@ -357,6 +368,18 @@ where
         );
         ibc::init_genesis_storage(&mut self.storage);
 
+        // Carry over the state exported from an existing chain, if any
+        if let Some(state) = genesis.state {
+            self.import_exported_state(state, &genesis.validators);
+        }
+
+        // The total supply of the tokens is initialized from all the genesis
+        // balances, which includes the tokens credited to the PoS account
+        for token in &tokens {
+            storage_api::token::init_total_supply(&mut self.storage, token)
+                .expect("Unable to initialize the total supply of a token");
+        }
+
         // Set the initial validator set
         for validator in genesis.validators {
             let mut abci_validator = abci::ValidatorUpdate::default();
@@ -374,6 +397,203 @@ where
         }
         Ok(response)
     }
+
+    /// Import the state exported from an existing chain with `namadan ledger
+    /// export`. This must be called after the genesis accounts and the PoS
+    /// system are initialized. The epochs of the unbonds and proposals are
+    /// shifted so that they are relative to the genesis epoch of the new
+    /// chain.
+    fn import_exported_state(
+        &mut self,
+        state: genesis::ExportedState,
+        validators: &[genesis::Validator],
+    ) {
+        let genesis::ExportedState {
+            height: _,
+            epoch: exported_epoch,
+            proposal_counter,
+            balances,
+            mut bonds,
+            unbonds,
+            proposals,
+        } = state;
+        let (current_epoch, _gas) = self.storage.get_current_epoch();
+        let shift_epoch = |epoch: Epoch| {
+            current_epoch + epoch.0.saturating_sub(exported_epoch.0)
+        };
+
+        // Replace the validators' genesis self-bonds with the exported bonds.
+        // A validator without an exported self-bond gets an empty one.
+        for validator in validators {
+            let address = &validator.pos_data.address;
+            bonds
+                .entry(BondId {
+                    source: address.clone(),
+                    validator: address.clone(),
+                })
+                .or_default();
+        }
+        for (bond_id, amount) in bonds {
+            let mut pos_deltas = HashMap::default();
+            if amount != token::Amount::default() {
+                pos_deltas.insert(current_epoch, amount);
+            }
+            let bond = Bond {
+                pos_deltas,
+                neg_deltas: token::Amount::default(),
+            };
+            let bonds = Bonds::init_at_genesis(bond, current_epoch);
+            self.storage
+                .write(&pos::bond_key(&bond_id), bonds.try_to_vec().unwrap())
+                .expect("Unable to write exported bond");
+        }
+
+        let mut unbonds_by_id: HashMap<BondId, Unbond> = HashMap::default();
+        for ((bond_id, withdraw_epoch), amount) in unbonds {
+            let unbond = unbonds_by_id.entry(bond_id).or_default();
+            *unbond
+                .deltas
+                .entry((current_epoch, shift_epoch(withdraw_epoch)))
+                .or_default() += amount;
+        }
+        for (bond_id, unbond) in unbonds_by_id {
+            let unbonds = Unbonds::init_at_genesis(unbond, current_epoch);
+            self.storage
+                .write(
+                    &pos::unbond_key(&bond_id),
+                    unbonds.try_to_vec().unwrap(),
+                )
+                .expect("Unable to write exported unbond");
+        }
+
+        for genesis::ExportedProposal {
+            id,
+            content,
+            author,
+            voting_start_epoch,
+            voting_end_epoch,
+            grace_epoch,
+            funds,
+            proposal_code,
+            proposal_type,
+            expedited,
+            status,
+            votes,
+        } in proposals
+        {
+            self.storage
+                .write(&gov_storage::get_content_key(id), content)
+                .expect("Unable to write exported proposal");
+            self.storage
+                .write(
+                    &gov_storage::get_author_key(id),
+                    author.try_to_vec().unwrap(),
+                )
+                .expect("Unable to write exported proposal");
+            self.storage
+                .write(
+                    &gov_storage::get_voting_start_epoch_key(id),
+                    shift_epoch(voting_start_epoch).try_to_vec().unwrap(),
+                )
+                .expect("Unable to write exported proposal");
+            self.storage
+                .write(
+                    &gov_storage::get_voting_end_epoch_key(id),
+                    shift_epoch(voting_end_epoch).try_to_vec().unwrap(),
+                )
+                .expect("Unable to write exported proposal");
+            let new_grace_epoch = shift_epoch(grace_epoch);
+            self.storage
+                .write(
+                    &gov_storage::get_grace_epoch_key(id),
+                    new_grace_epoch.try_to_vec().unwrap(),
+                )
+                .expect("Unable to write exported proposal");
+            self.storage
+                .write(
+                    &gov_storage::get_funds_key(id),
+                    funds.try_to_vec().unwrap(),
+                )
+                .expect("Unable to write exported proposal");
+            if let Some(proposal_code) = proposal_code {
+                self.storage
+                    .write(
+                        &gov_storage::get_proposal_code_key(id),
+                        proposal_code,
+                    )
+                    .expect("Unable to write exported proposal");
+            }
+            self.storage
+                .write(
+                    &gov_storage::get_proposal_type_key(id),
+                    proposal_type.try_to_vec().unwrap(),
+                )
+                .expect("Unable to write exported proposal");
+            if expedited {
+                self.storage
+                    .write(
+                        &gov_storage::get_expedited_key(id),
+                        true.try_to_vec().unwrap(),
+                    )
+                    .expect("Unable to write exported proposal");
+            }
+            // A proposal exported before the statuses were introduced gets
+            // the status of its voting period at the exported epoch
+            let status = status.unwrap_or_else(|| {
+                ProposalStatus::at_epoch(
+                    exported_epoch,
+                    voting_start_epoch,
+                    voting_end_epoch,
+                )
+            });
+            self.storage
+                .write(
+                    &gov_storage::get_status_key(id),
+                    status.try_to_vec().unwrap(),
+                )
+                .expect("Unable to write exported proposal");
+            for ((voter, delegator), vote) in votes {
+                self.storage
+                    .write(
+                        &gov_storage::get_vote_proposal_key(
+                            id, voter, delegator,
+                        ),
+                        vote.try_to_vec().unwrap(),
+                    )
+                    .expect("Unable to write exported proposal vote");
+            }
+            // Proposals are executed at the start of their grace epoch, so
+            // only the ones that haven't been executed yet are committed
+            if grace_epoch > exported_epoch {
+                self.storage
+                    .write(
+                        &gov_storage::get_committing_proposals_key(
+                            id,
+                            new_grace_epoch.0,
+                        ),
+                        ().try_to_vec().unwrap(),
+                    )
+                    .expect("Unable to write exported proposal");
+            }
+        }
+        self.storage
+            .write(
+                &gov_storage::get_counter_key(),
+                proposal_counter.try_to_vec().unwrap(),
+            )
+            .expect("Unable to write exported proposal counter");
+
+        // The balances are written last to override the balances of the
+        // genesis accounts and the tokens credited to the PoS account
+        for ((token, owner), amount) in balances {
+            self.storage
+                .write(
+                    &token::balance_key(&token, &owner),
+                    amount.try_to_vec().unwrap(),
+                )
+                .expect("Unable to write exported balance");
+        }
+    }
 }
 
 trait HashMapExt<K, V>
@@ -11,6 +11,7 @@ mod init_chain;
 mod prepare_proposal;
 mod process_proposal;
 mod queries;
+mod snapshots;
 mod stats;
 
 use std::collections::HashSet;
@@ -19,11 +20,14 @@ use std::mem;
 use std::path::{Path, PathBuf};
 #[allow(unused_imports)]
 use std::rc::Rc;
+use std::sync::Arc;
 
 use borsh::{BorshDeserialize, BorshSerialize};
 use namada::ledger::events::log::EventLog;
+use namada::ledger::events::store as event_store;
 use namada::ledger::events::Event;
 use namada::ledger::gas::BlockGasMeter;
+use namada::ledger::masp::update_note_commitment_frontiers;
 use namada::ledger::pos::namada_proof_of_stake::types::{
     ActiveValidator, ValidatorSetUpdate,
 };
@@ -63,6 +67,9 @@ use crate::facade::tendermint_proto::crypto::public_key;
 use crate::facade::tower_abci::{request, response};
 use crate::node::ledger::shims::abcipp_shim_types::shim;
 use crate::node::ledger::shims::abcipp_shim_types::shim::response::TxResult;
+use crate::node::ledger::subscriptions::{
+    events_channel, BlockEvents, EventsSender,
+};
 use crate::node::ledger::{storage, tendermint_node};
 #[allow(unused_imports)]
 use crate::wallet::ValidatorData;
@@ -220,10 +227,25 @@ where
     /// limit the how many block heights in the past can the storage be
     /// queried for reading values.
     storage_read_past_height_limit: Option<u64>,
+    /// Path to the directory with state-sync snapshots
+    snapshots_dir: PathBuf,
+    /// Taken from config `snapshot_interval`. When set, a state-sync
+    /// snapshot is taken every this many blocks.
+    snapshot_interval: Option<u64>,
+    /// Taken from config `snapshots_to_keep`
+    snapshots_to_keep: u64,
+    /// A snapshot that is being restored via state-sync
+    snapshot_restore: Option<snapshots::SnapshotRestore>,
     /// Proposal execution tracking
     pub proposal_data: HashSet<u64>,
     /// Log of events emitted by `FinalizeBlock` ABCI calls.
     event_log: EventLog,
+    /// Taken from config `events_retention_blocks`. When set, the events of
+    /// the older blocks are pruned from the persistent event store.
+    events_retention_blocks: Option<u64>,
+    /// The sender of the events of every finalized block to the event
+    /// subscriptions
+    events_sender: EventsSender,
 }
 
 impl<D, H> Shell<D, H>
@@ -248,6 +270,12 @@ where
         let mode = config.tendermint.tendermint_mode;
         let storage_read_past_height_limit =
             config.shell.storage_read_past_height_limit;
+        let snapshot_interval = config.shell.snapshot_interval;
+        let events_retention_blocks = config.shell.events_retention_blocks;
+        let snapshots_to_keep = config
+            .shell
+            .snapshots_to_keep
+            .unwrap_or(snapshots::DEFAULT_SNAPSHOTS_TO_KEEP);
         if !Path::new(&base_dir).is_dir() {
             std::fs::create_dir(&base_dir)
                 .expect("Creating directory for Namada should not fail");
@@ -266,6 +294,7 @@ where
             base_dir.join(chain_id.as_str()).join("vp_wasm_cache");
         let tx_wasm_cache_dir =
             base_dir.join(chain_id.as_str()).join("tx_wasm_cache");
+        let snapshots_dir = base_dir.join(chain_id.as_str()).join("snapshots");
         // load in keys and address from wallet if mode is set to `Validator`
         let mode = match mode {
             TendermintMode::Validator => {
@@ -333,9 +362,15 @@ where
                 tx_wasm_compilation_cache as usize,
             ),
             storage_read_past_height_limit,
+            snapshots_dir,
+            snapshot_interval,
+            snapshots_to_keep,
+            snapshot_restore: None,
             proposal_data: HashSet::new(),
             // TODO: config event log params
             event_log: EventLog::default(),
+            events_retention_blocks,
+            events_sender: events_channel(),
         }
     }
 
@@ -351,6 +386,52 @@ where
         &mut self.event_log
     }
 
+    /// Return the sender of the events of every finalized block, to which
+    /// the event subscriptions subscribe.
+    pub fn events_sender(&self) -> &EventsSender {
+        &self.events_sender
+    }
+
+    /// Send the events emitted by the block at the given height to the
+    /// event subscriptions, if there are any.
+    fn send_events(&self, height: BlockHeight, events: &[Event]) {
+        if self.events_sender.receiver_count() > 0 {
+            // This can only fail when the last receiver has just been dropped
+            let _ = self.events_sender.send(Arc::new(BlockEvents {
+                height,
+                events: events.to_vec(),
+            }));
+        }
+    }
+
+    /// Write the events emitted by the block at the given height into the
+    /// persistent event store and prune the events that are past the
+    /// retention.
+    fn store_events(&mut self, height: BlockHeight, events: &[Event]) {
+        if let Err(err) =
+            event_store::write_events(&mut self.storage.db, height, events)
+        {
+            tracing::error!(
+                "Failed to write the events at height {} to the DB: {}",
+                height,
+                err
+            );
+        }
+        if let Some(below) = self
+            .events_retention_blocks
+            .and_then(|retention| (height.0 + 1).checked_sub(retention))
+        {
+            if let Err(err) = self.storage.db.prune_events(BlockHeight(below)) {
+                tracing::error!(
+                    "Failed to prune the events below height {} from the DB: \
+                     {}",
+                    below,
+                    err
+                );
+            }
+        }
+    }
+
     /// Iterate over the wrapper txs in order
     #[allow(dead_code)]
     fn iter_tx_queue(&mut self) -> impl Iterator<Item = &WrapperTxInQueue> {
@@ -418,7 +499,16 @@ where
         if !self.byzantine_validators.is_empty() {
             let byzantine_validators =
                 mem::take(&mut self.byzantine_validators);
-            let pos_params = self.storage.read_pos_params();
+            let pos_params = match self.storage.read_pos_params() {
+                Ok(pos_params) => pos_params,
+                Err(err) => {
+                    tracing::error!(
+                        "Cannot read the PoS parameters to apply slashes: {}",
+                        err
+                    );
+                    return;
+                }
+            };
             let current_epoch = self.storage.block.epoch;
             for evidence in byzantine_validators {
                 tracing::info!("Processing evidence {evidence:?}.");
@@ -550,6 +640,13 @@ where
         self.write_log
             .commit_block(&mut self.storage)
             .expect("Expected committing block write log success");
+        // store the note commitment frontiers of the new shielded transfers
+        if let Err(err) = update_note_commitment_frontiers(&mut self.storage) {
+            tracing::error!(
+                "Failed to update the note commitment frontiers with error \
+                 {err}"
+            );
+        }
         // store the block's data in DB
         self.storage.commit().unwrap_or_else(|e| {
             tracing::error!(
@@ -565,6 +662,7 @@ where
             self.storage.last_height,
         );
         response.data = root.0;
+        self.maybe_take_snapshot();
         response
     }
 
@@ -92,7 +92,7 @@ where
         // get the current epoch
         let (current_epoch, _) = self.storage.get_current_epoch();
         // get the PoS params
-        let pos_params = self.storage.read_pos_params();
+        let pos_params = self.storage.read_pos_params().ok()?;
         // get the active validator set
         self.storage
             .read_validator_set()
@@ -0,0 +1,637 @@
+//! Implementation of the state-sync snapshot ABCI methods for the Shell:
+//! `ListSnapshots`, `OfferSnapshot`, `LoadSnapshotChunk` and
+//! `ApplySnapshotChunk`.
+//!
+//! When enabled with the `snapshot_interval` config, a snapshot is taken on
+//! commit of every block whose height is a multiple of the interval. A
+//! snapshot contains the block's state and all the key-value pairs from the
+//! storage subspace, borsh encoded and split into chunks of at most
+//! [`CHUNK_SIZE`] bytes, each written into a separate file. The snapshot's
+//! metadata lists the hashes of its chunks.
+//!
+//! The merkle tree is not included in a snapshot. Instead, it is rebuilt from
+//! the restored subspace and its root is checked against the app hash trusted
+//! by Tendermint's light client before anything gets written into the DB.
+
+use std::fs;
+use std::io::{self, Write};
+
+use namada::ledger::storage::merkle_tree::MerkleRoot;
+use namada::types::address::EstablishedAddressGen;
+use namada::types::hash::Hash;
+use namada::types::internal::TxQueue;
+use namada::types::storage::{BlockHash, BlockResults, Epoch, Epochs};
+
+use super::*;
+use crate::facade::tendermint_proto::abci::{
+    response_apply_snapshot_chunk, response_offer_snapshot,
+    RequestApplySnapshotChunk, RequestLoadSnapshotChunk, RequestOfferSnapshot,
+    ResponseApplySnapshotChunk, ResponseListSnapshots,
+    ResponseLoadSnapshotChunk, ResponseOfferSnapshot, Snapshot,
+};
+
+/// The version of the snapshot format produced by this node
+pub const SNAPSHOT_FORMAT: u32 = 1;
+/// The maximum size of a single chunk in bytes. Tendermint limits the chunks
+/// to 16MB.
+pub const CHUNK_SIZE: usize = 10 * 1024 * 1024;
+/// The default number of the most recent snapshots to keep
+pub const DEFAULT_SNAPSHOTS_TO_KEEP: u64 = 2;
+/// The name of the file with the metadata in a snapshot's directory
+const METADATA_FILE: &str = "metadata";
+
+/// Errors from taking or restoring a snapshot
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Snapshot I/O error: {0}")]
+    Io(io::Error),
+    #[error("Snapshot storage error: {0}")]
+    Storage(namada::ledger::storage::Error),
+    #[error("Snapshot decoding error: {0}")]
+    Decoding(io::Error),
+    #[error("Invalid snapshot: {0}")]
+    Invalid(String),
+}
+
+/// The state of the block from which a snapshot was taken that is not part of
+/// the storage subspace
+#[derive(Debug, BorshSerialize, BorshDeserialize)]
+struct SnapshotBlockState {
+    hash: BlockHash,
+    height: BlockHeight,
+    epoch: Epoch,
+    pred_epochs: Epochs,
+    next_epoch_min_start_height: BlockHeight,
+    next_epoch_min_start_time: DateTimeUtc,
+    address_gen: EstablishedAddressGen,
+    results: BlockResults,
+    tx_queue: TxQueue,
+}
+
+/// The metadata of a snapshot, stored next to its chunks
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+pub struct SnapshotMetadata {
+    /// The height of the block from which the snapshot was taken
+    pub height: BlockHeight,
+    /// The snapshot format
+    pub format: u32,
+    /// Hashes of the snapshot's chunks, in order
+    pub chunk_hashes: Vec<Hash>,
+}
+
+impl SnapshotMetadata {
+    /// The hash of the snapshot, committing to all of its chunks
+    pub fn hash(&self) -> Hash {
+        Self::hash_chunk_hashes(&self.chunk_hashes)
+    }
+
+    fn hash_chunk_hashes(chunk_hashes: &[Hash]) -> Hash {
+        let bytes: Vec<u8> =
+            chunk_hashes.iter().flat_map(|hash| hash.0).collect();
+        Hash::sha256(bytes)
+    }
+
+    /// Convert into the snapshot description sent to Tendermint. The chunk
+    /// hashes are passed in the metadata so that the chunks can be verified
+    /// by the receiving node.
+    fn to_snapshot(&self) -> Snapshot {
+        Snapshot {
+            height: self.height.0,
+            format: self.format,
+            chunks: self.chunk_hashes.len() as u32,
+            hash: self.hash().0.to_vec(),
+            metadata: self.chunk_hashes.try_to_vec().unwrap(),
+        }
+    }
+}
+
+/// A snapshot that has been accepted from a peer and whose chunks are being
+/// applied
+#[derive(Debug)]
+pub struct SnapshotRestore {
+    /// The height of the snapshot
+    height: BlockHeight,
+    /// The app hash at the snapshot's height, trusted by Tendermint
+    app_hash: Vec<u8>,
+    /// Hashes of the expected chunks
+    chunk_hashes: Vec<Hash>,
+    /// The chunks received so far
+    chunks: Vec<Option<Vec<u8>>>,
+}
+
+/// Writes the borsh encoded snapshot data into chunk files
+struct ChunkWriter {
+    dir: PathBuf,
+    buf: Vec<u8>,
+    chunk_hashes: Vec<Hash>,
+}
+
+impl ChunkWriter {
+    fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            buf: Vec::with_capacity(CHUNK_SIZE),
+            chunk_hashes: vec![],
+        }
+    }
+
+    /// Write the buffered data into a new chunk file
+    fn write_chunk(&mut self) -> io::Result<()> {
+        let chunk = mem::replace(&mut self.buf, Vec::with_capacity(CHUNK_SIZE));
+        let index = self.chunk_hashes.len() as u32;
+        self.chunk_hashes.push(Hash::sha256(&chunk));
+        fs::write(self.dir.join(chunk_file_name(index)), chunk)
+    }
+
+    /// Write the remaining data and return the hashes of all the chunks
+    fn finish(mut self) -> io::Result<Vec<Hash>> {
+        if !self.buf.is_empty() || self.chunk_hashes.is_empty() {
+            self.write_chunk()?;
+        }
+        Ok(self.chunk_hashes)
+    }
+}
+
+impl Write for ChunkWriter {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        let len = data.len().min(CHUNK_SIZE - self.buf.len());
+        self.buf.extend_from_slice(&data[..len]);
+        if self.buf.len() == CHUNK_SIZE {
+            self.write_chunk()?;
+        }
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn chunk_file_name(index: u32) -> String {
+    format!("chunk-{}", index)
+}
+
+impl<D, H> Shell<D, H>
+where
+    D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
+    H: StorageHasher + Sync + 'static,
+{
+    /// Take a snapshot of the last committed block if the configured
+    /// snapshot interval has been reached. Failures are only logged, as they
+    /// must not affect the consensus.
+    pub(super) fn maybe_take_snapshot(&self) {
+        let interval = match self.snapshot_interval {
+            Some(interval) if interval > 0 => interval,
+            _ => return,
+        };
+        let height = self.storage.last_height;
+        if height.0 == 0 || height.0 % interval != 0 {
+            return;
+        }
+        match self.take_snapshot() {
+            Ok(metadata) => {
+                tracing::info!(
+                    "Took a state-sync snapshot at height {} with {} chunks",
+                    height,
+                    metadata.chunk_hashes.len()
+                );
+            }
+            Err(err) => {
+                tracing::error!(
+                    "Failed to take a state-sync snapshot at height {}: {}",
+                    height,
+                    err
+                );
+            }
+        }
+        if let Err(err) = self.prune_snapshots() {
+            tracing::error!("Failed to prune old snapshots: {}", err);
+        }
+    }
+
+    /// Take a snapshot of the last committed block
+    pub(super) fn take_snapshot(
+        &self,
+    ) -> std::result::Result<SnapshotMetadata, Error> {
+        let height = self.storage.last_height;
+        let dir = self.snapshots_dir.join(height.to_string());
+        // Write into a temporary directory first, so that an incomplete
+        // snapshot is never listed
+        let tmp_dir = self.snapshots_dir.join(format!("{}.tmp", height));
+        if tmp_dir.exists() {
+            fs::remove_dir_all(&tmp_dir).map_err(Error::Io)?;
+        }
+        fs::create_dir_all(&tmp_dir).map_err(Error::Io)?;
+
+        let mut writer = ChunkWriter::new(tmp_dir.clone());
+        let block_state = SnapshotBlockState {
+            hash: self.storage.block.hash.clone(),
+            height,
+            epoch: self.storage.last_epoch,
+            pred_epochs: self.storage.block.pred_epochs.clone(),
+            next_epoch_min_start_height: self
+                .storage
+                .next_epoch_min_start_height,
+            next_epoch_min_start_time: self.storage.next_epoch_min_start_time,
+            address_gen: self.storage.address_gen.clone(),
+            results: self.storage.block.results.clone(),
+            tx_queue: self.storage.tx_queue.clone(),
+        };
+        block_state.serialize(&mut writer).map_err(Error::Io)?;
+        // The subspace entries are terminated with `None`
+        for (key, value, _gas) in self.storage.db.iter_prefix(&Key::default()) {
+            Some((key, value))
+                .serialize(&mut writer)
+                .map_err(Error::Io)?;
+        }
+        Option::<(String, Vec<u8>)>::None
+            .serialize(&mut writer)
+            .map_err(Error::Io)?;
+        let chunk_hashes = writer.finish().map_err(Error::Io)?;
+
+        let metadata = SnapshotMetadata {
+            height,
+            format: SNAPSHOT_FORMAT,
+            chunk_hashes,
+        };
+        fs::write(tmp_dir.join(METADATA_FILE), metadata.try_to_vec().unwrap())
+            .map_err(Error::Io)?;
+        if dir.exists() {
+            fs::remove_dir_all(&dir).map_err(Error::Io)?;
+        }
+        fs::rename(&tmp_dir, &dir).map_err(Error::Io)?;
+        Ok(metadata)
+    }
+
+    /// Remove the oldest snapshots over the configured limit
+    fn prune_snapshots(&self) -> std::result::Result<(), Error> {
+        let mut snapshots = self.read_snapshots()?;
+        let to_keep = self.snapshots_to_keep as usize;
+        if snapshots.len() > to_keep {
+            snapshots.sort_by_key(|metadata| metadata.height);
+            let to_remove = snapshots.len() - to_keep;
+            for metadata in snapshots.into_iter().take(to_remove) {
+                let dir = self.snapshots_dir.join(metadata.height.to_string());
+                fs::remove_dir_all(dir).map_err(Error::Io)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Read the metadata of all the complete snapshots stored on disk
+    fn read_snapshots(
+        &self,
+    ) -> std::result::Result<Vec<SnapshotMetadata>, Error> {
+        if !self.snapshots_dir.is_dir() {
+            return Ok(vec![]);
+        }
+        let mut snapshots = vec![];
+        for entry in fs::read_dir(&self.snapshots_dir).map_err(Error::Io)? {
+            let entry = entry.map_err(Error::Io)?;
+            let is_snapshot = entry
+                .file_name()
+                .to_str()
+                .map(|name| name.parse::<u64>().is_ok())
+                .unwrap_or_default();
+            if !is_snapshot {
+                continue;
+            }
+            match fs::read(entry.path().join(METADATA_FILE)) {
+                Ok(bytes) => {
+                    let metadata = SnapshotMetadata::try_from_slice(&bytes)
+                        .map_err(Error::Decoding)?;
+                    snapshots.push(metadata);
+                }
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(Error::Io(err)),
+            }
+        }
+        Ok(snapshots)
+    }
+
+    /// List the snapshots available on this node
+    pub fn list_snapshots(&self) -> ResponseListSnapshots {
+        match self.read_snapshots() {
+            Ok(snapshots) => ResponseListSnapshots {
+                snapshots: snapshots
+                    .iter()
+                    .map(SnapshotMetadata::to_snapshot)
+                    .collect(),
+            },
+            Err(err) => {
+                tracing::error!("Failed to list snapshots: {}", err);
+                Default::default()
+            }
+        }
+    }
+
+    /// Load a chunk of a snapshot available on this node
+    pub fn load_snapshot_chunk(
+        &self,
+        req: RequestLoadSnapshotChunk,
+    ) -> ResponseLoadSnapshotChunk {
+        if req.format != SNAPSHOT_FORMAT {
+            return Default::default();
+        }
+        let path = self
+            .snapshots_dir
+            .join(req.height.to_string())
+            .join(chunk_file_name(req.chunk));
+        match fs::read(&path) {
+            Ok(chunk) => ResponseLoadSnapshotChunk { chunk },
+            Err(err) => {
+                tracing::error!(
+                    "Failed to load snapshot chunk {} at height {}: {}",
+                    req.chunk,
+                    req.height,
+                    err
+                );
+                Default::default()
+            }
+        }
+    }
+
+    /// Decide whether to accept a snapshot offered by a peer. Snapshots are
+    /// only accepted when the node has no state yet.
+    pub fn offer_snapshot(
+        &mut self,
+        req: RequestOfferSnapshot,
+    ) -> ResponseOfferSnapshot {
+        use response_offer_snapshot::Result as OfferResult;
+
+        let result = match req.snapshot {
+            _ if self.storage.last_height.0 != 0 => {
+                tracing::info!(
+                    "Rejecting a snapshot, the node's state is already \
+                     initialized"
+                );
+                OfferResult::Abort
+            }
+            None => OfferResult::Reject,
+            Some(snapshot) if snapshot.format != SNAPSHOT_FORMAT => {
+                OfferResult::RejectFormat
+            }
+            Some(snapshot) => {
+                match Vec::<Hash>::try_from_slice(&snapshot.metadata) {
+                    Ok(chunk_hashes)
+                        if chunk_hashes.len() == snapshot.chunks as usize
+                            && SnapshotMetadata::hash_chunk_hashes(
+                                &chunk_hashes,
+                            )
+                            .0
+                            .as_slice()
+                                == snapshot.hash.as_slice() =>
+                    {
+                        tracing::info!(
+                            "Accepted a snapshot at height {} with {} chunks",
+                            snapshot.height,
+                            snapshot.chunks
+                        );
+                        self.snapshot_restore = Some(SnapshotRestore {
+                            height: BlockHeight(snapshot.height),
+                            app_hash: req.app_hash,
+                            chunks: vec![None; chunk_hashes.len()],
+                            chunk_hashes,
+                        });
+                        OfferResult::Accept
+                    }
+                    _ => OfferResult::Reject,
+                }
+            }
+        };
+        ResponseOfferSnapshot {
+            result: result.into(),
+        }
+    }
+
+    /// Apply a chunk of the snapshot accepted in
+    /// [`Shell::offer_snapshot`]. Once all the chunks have been received,
+    /// the state is restored and verified against the trusted app hash.
+    pub fn apply_snapshot_chunk(
+        &mut self,
+        req: RequestApplySnapshotChunk,
+    ) -> ResponseApplySnapshotChunk {
+        use response_apply_snapshot_chunk::Result as ApplyResult;
+
+        let restore = match self.snapshot_restore.as_mut() {
+            Some(restore) => restore,
+            None => {
+                return ResponseApplySnapshotChunk {
+                    result: ApplyResult::Abort.into(),
+                    ..Default::default()
+                };
+            }
+        };
+        let index = req.index as usize;
+        match restore.chunk_hashes.get(index) {
+            Some(hash) if *hash == Hash::sha256(&req.chunk) => {
+                restore.chunks[index] = Some(req.chunk);
+            }
+            Some(_) => {
+                tracing::info!(
+                    "Received an invalid snapshot chunk {} from {}",
+                    index,
+                    req.sender
+                );
+                return ResponseApplySnapshotChunk {
+                    result: ApplyResult::Retry.into(),
+                    refetch_chunks: vec![req.index],
+                    reject_senders: vec![req.sender],
+                };
+            }
+            None => {
+                return ResponseApplySnapshotChunk {
+                    result: ApplyResult::RejectSnapshot.into(),
+                    ..Default::default()
+                };
+            }
+        }
+        if restore.chunks.iter().any(Option::is_none) {
+            return ResponseApplySnapshotChunk {
+                result: ApplyResult::Accept.into(),
+                ..Default::default()
+            };
+        }
+
+        let restore = self.snapshot_restore.take().unwrap();
+        let result = match self.restore_snapshot(restore) {
+            Ok(()) => {
+                tracing::info!(
+                    "Restored the state from a snapshot at height {}",
+                    self.storage.last_height
+                );
+                ApplyResult::Accept
+            }
+            Err(err) => {
+                tracing::error!("Failed to restore a snapshot: {}", err);
+                ApplyResult::RejectSnapshot
+            }
+        };
+        ResponseApplySnapshotChunk {
+            result: result.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Restore the storage from the complete snapshot data. The DB is only
+    /// written to if the rebuilt merkle tree matches the trusted app hash.
+    fn restore_snapshot(
+        &mut self,
+        restore: SnapshotRestore,
+    ) -> std::result::Result<(), Error> {
+        let data: Vec<u8> =
+            restore.chunks.into_iter().flatten().flatten().collect();
+        let mut data = data.as_slice();
+        let block_state = SnapshotBlockState::deserialize(&mut data)
+            .map_err(Error::Decoding)?;
+        if block_state.height != restore.height {
+            return Err(Error::Invalid(format!(
+                "Expected a snapshot at height {}, got {}",
+                restore.height, block_state.height
+            )));
+        }
+        let mut subspace = vec![];
+        while let Some((key, value)) =
+            Option::<(String, Vec<u8>)>::deserialize(&mut data)
+                .map_err(Error::Decoding)?
+        {
+            let key = Key::parse(key).map_err(|err| {
+                Error::Invalid(format!("Invalid storage key: {}", err))
+            })?;
+            subspace.push((key, value));
+        }
+        if !data.is_empty() {
+            return Err(Error::Invalid(
+                "Unexpected trailing snapshot data".to_string(),
+            ));
+        }
+
+        let storage = &mut self.storage;
+        storage.block.hash = block_state.hash;
+        storage.block.height = block_state.height;
+        storage.block.epoch = block_state.epoch;
+        storage.block.pred_epochs = block_state.pred_epochs;
+        storage.block.results = block_state.results;
+        storage.next_epoch_min_start_height =
+            block_state.next_epoch_min_start_height;
+        storage.next_epoch_min_start_time =
+            block_state.next_epoch_min_start_time;
+        storage.address_gen = block_state.address_gen;
+        storage.tx_queue = block_state.tx_queue;
+
+        let tree = storage
+            .rebuild_merkle_tree(
+                subspace.iter().map(|(key, value)| (key.clone(), value)),
+            )
+            .map_err(Error::Storage)?;
+        let root = tree.root();
+        if root.0 != restore.app_hash {
+            return Err(Error::Invalid(format!(
+                "The restored merkle root {} doesn't match the trusted app \
+                 hash {}",
+                root,
+                MerkleRoot(restore.app_hash)
+            )));
+        }
+
+        let mut batch = D::batch();
+        for (key, value) in subspace {
+            storage
+                .db
+                .batch_write_subspace_val(
+                    &mut batch,
+                    block_state.height,
+                    &key,
+                    value,
+                )
+                .map_err(Error::Storage)?;
+        }
+        storage.db.exec_batch(batch).map_err(Error::Storage)?;
+        storage.block.tree = tree;
+        storage.commit().map_err(Error::Storage)?;
+        // Reload the state to also restore the derived data
+        storage.load_last_state().map_err(Error::Storage)
+    }
+}
+
+#[cfg(test)]
+mod test_snapshots {
+    use namada::types::storage::BlockHash;
+
+    use super::*;
+    use crate::node::ledger::shell::test_utils::*;
+
+    /// Test that a snapshot taken by one node can be restored by another and
+    /// that a snapshot that doesn't match the trusted app hash is rejected.
+    #[test]
+    fn test_snapshot_restore() {
+        let (mut shell, _) = setup();
+        for i in 0..100_u64 {
+            let key = Key::parse(format!("key{}", i)).unwrap();
+            shell.storage.write(&key, i.try_to_vec().unwrap()).unwrap();
+        }
+        shell
+            .storage
+            .begin_block(BlockHash::default(), BlockHeight(1))
+            .unwrap();
+        shell.commit();
+        let app_hash = shell.storage.merkle_root().0;
+
+        let metadata = shell.take_snapshot().expect("Test failed");
+        let snapshots = shell.list_snapshots().snapshots;
+        assert_eq!(snapshots.len(), 1);
+        let snapshot = snapshots[0].clone();
+        assert_eq!(snapshot.height, 1);
+        assert_eq!(snapshot.chunks as usize, metadata.chunk_hashes.len());
+
+        let restore = |app_hash: Vec<u8>| {
+            let (mut new_shell, _) = TestShell::new();
+            let offer = new_shell.offer_snapshot(RequestOfferSnapshot {
+                snapshot: Some(snapshot.clone()),
+                app_hash,
+            });
+            assert_eq!(
+                offer.result,
+                i32::from(response_offer_snapshot::Result::Accept)
+            );
+            let mut result = 0;
+            for index in 0..snapshot.chunks {
+                let chunk = shell
+                    .load_snapshot_chunk(RequestLoadSnapshotChunk {
+                        height: snapshot.height,
+                        format: snapshot.format,
+                        chunk: index,
+                    })
+                    .chunk;
+                result = new_shell
+                    .apply_snapshot_chunk(RequestApplySnapshotChunk {
+                        index,
+                        chunk,
+                        sender: "peer".to_string(),
+                    })
+                    .result;
+            }
+            (new_shell, result)
+        };
+
+        // A snapshot with a wrong app hash must be rejected
+        let (new_shell, result) = restore(vec![0; 32]);
+        assert_eq!(
+            result,
+            i32::from(response_apply_snapshot_chunk::Result::RejectSnapshot)
+        );
+        assert_eq!(new_shell.storage.last_height, BlockHeight(0));
+
+        let (new_shell, result) = restore(app_hash.clone());
+        assert_eq!(
+            result,
+            i32::from(response_apply_snapshot_chunk::Result::Accept)
+        );
+        assert_eq!(new_shell.storage.last_height, BlockHeight(1));
+        assert_eq!(new_shell.storage.merkle_root().0, app_hash);
+        for i in 0..100_u64 {
+            let key = Key::parse(format!("key{}", i)).unwrap();
+            assert_eq!(new_shell.read_storage_key::<u64>(&key), Some(i));
+        }
+    }
+}
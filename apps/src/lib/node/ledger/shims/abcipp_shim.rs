@@ -24,6 +24,7 @@ use crate::config;
 #[cfg(not(feature = "abcipp"))]
 use crate::facade::tendermint_proto::abci::RequestBeginBlock;
 use crate::facade::tower_abci::{BoxError, Request as Req, Response as Resp};
+use crate::node::ledger::subscriptions::EventsSender;
 
 /// The shim wraps the shell, which implements ABCI++.
 /// The shim makes a crude translation between the ABCI interface currently used
@@ -77,6 +78,11 @@ impl AbcippShim {
         )
     }
 
+    /// Get the sender of the events of every finalized block
+    pub fn events_sender(&self) -> EventsSender {
+        self.service.events_sender().clone()
+    }
+
     #[cfg(not(feature = "abcipp"))]
     /// Get the hash of the txs in the block
     pub fn get_hash(&self) -> Hash {
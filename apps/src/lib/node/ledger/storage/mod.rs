@@ -54,8 +54,9 @@ mod tests {
     use itertools::Itertools;
     use namada::ledger::storage::types;
     use namada::ledger::storage_api;
+    use namada::types::address::{Address, InternalAddress};
     use namada::types::chain::ChainId;
-    use namada::types::storage::{BlockHash, BlockHeight, Key};
+    use namada::types::storage::{BlockHash, BlockHeight, Key, KeySeg};
     use namada::types::{address, storage};
     use proptest::collection::vec;
     use proptest::prelude::*;
@@ -348,6 +349,183 @@ mod tests {
         Ok(())
     }
 
+    /// Test reading a prefix and getting the Merkle proofs at past heights.
+    #[test]
+    fn test_prefix_and_proofs_with_height() {
+        let db_path =
+            TempDir::new().expect("Unable to create a temporary DB directory");
+        let mut storage = PersistentStorage::open(
+            db_path.path(),
+            ChainId::default(),
+            address::nam(),
+            None,
+        );
+
+        // The keys are in the IBC sub-tree, the only one that supports
+        // non-existence proofs
+        let prefix =
+            Key::from(Address::Internal(InternalAddress::Ibc).to_db_key())
+                .push(&"prefix".to_owned())
+                .unwrap();
+        let key_a = prefix.push(&"a".to_owned()).unwrap();
+        let key_b = prefix.push(&"b".to_owned()).unwrap();
+        let key_c = prefix.push(&"c".to_owned()).unwrap();
+        // A key outside of the prefix
+        let key_other =
+            Key::from(Address::Internal(InternalAddress::Ibc).to_db_key())
+                .push(&"other".to_owned())
+                .unwrap();
+
+        // At height 0, write "a", "b" and the other key
+        storage.begin_block(BlockHash::default(), 0.into()).unwrap();
+        storage.write(&key_a, types::encode(&0_u64)).unwrap();
+        storage.write(&key_b, types::encode(&0_u64)).unwrap();
+        storage.write(&key_other, types::encode(&0_u64)).unwrap();
+        storage.commit().unwrap();
+        // At height 1, delete "a" and the other key and write "c"
+        storage.begin_block(BlockHash::default(), 1.into()).unwrap();
+        storage.delete(&key_a).unwrap();
+        storage.delete(&key_other).unwrap();
+        storage.write(&key_c, types::encode(&1_u64)).unwrap();
+        storage.commit().unwrap();
+        // At height 2, update "b"
+        storage.begin_block(BlockHash::default(), 2.into()).unwrap();
+        storage.write(&key_b, types::encode(&2_u64)).unwrap();
+        storage.commit().unwrap();
+
+        let expected = [
+            vec![(&key_a, 0_u64), (&key_b, 0)],
+            vec![(&key_b, 0), (&key_c, 1)],
+            vec![(&key_b, 2), (&key_c, 1)],
+        ];
+        for (height, expected) in expected.into_iter().enumerate() {
+            let height = BlockHeight::from(height as u64);
+            let (pairs, _gas) =
+                storage.read_prefix_with_height(&prefix, height).unwrap();
+            let pairs: Vec<(storage::Key, u64)> = pairs
+                .into_iter()
+                .map(|(key, value)| (key, types::decode(value).unwrap()))
+                .collect();
+            let expected: Vec<(storage::Key, u64)> = expected
+                .into_iter()
+                .map(|(key, value)| (key.clone(), value))
+                .collect();
+            assert_eq!(pairs, expected, "Unexpected pairs at height {height}");
+
+            // Every existing key must have an existence proof at this height,
+            // the same when the proofs of all the keys are made together
+            let values: Vec<Vec<u8>> = pairs
+                .iter()
+                .map(|(_key, value)| types::encode(value))
+                .collect();
+            let proofs = storage
+                .get_existence_proofs(
+                    pairs
+                        .iter()
+                        .zip(&values)
+                        .map(|((key, _value), value)| (key, value.as_slice())),
+                    height,
+                )
+                .unwrap();
+            assert_eq!(proofs.len(), pairs.len());
+            for ((key, value), proof) in pairs.iter().zip(proofs) {
+                let single_proof = storage
+                    .get_existence_proof(key, &types::encode(value), height)
+                    .unwrap_or_else(|err| {
+                        panic!(
+                            "No existence proof for {key} at height {height}: \
+                             {err}"
+                        )
+                    });
+                assert_eq!(proof, single_proof);
+            }
+        }
+
+        // The deleted and not yet written keys must have non-existence proofs
+        for (key, height) in [(&key_a, 1_u64), (&key_a, 2), (&key_c, 0)] {
+            let height = BlockHeight::from(height);
+            storage.get_non_existence_proof(key, height).unwrap_or_else(
+                |err| {
+                    panic!(
+                        "No non-existence proof for {key} at height \
+                         {height}: {err}"
+                    )
+                },
+            );
+        }
+
+        // The past trees must match the roots committed at their heights
+        let last_root = storage.merkle_root().0;
+        assert_eq!(
+            storage.read_merkle_tree(2.into()).unwrap().root().0,
+            last_root
+        );
+        assert_ne!(
+            storage.read_merkle_tree(1.into()).unwrap().root().0,
+            last_root
+        );
+        // There's no tree at a height that hasn't been committed
+        assert!(storage.read_merkle_tree(3.into()).is_err());
+    }
+
+    /// Test reading the changes committed at a block height.
+    #[test]
+    fn test_read_changes() {
+        let db_path =
+            TempDir::new().expect("Unable to create a temporary DB directory");
+        let mut storage = PersistentStorage::open(
+            db_path.path(),
+            ChainId::default(),
+            address::nam(),
+            None,
+        );
+
+        let prefix = storage::Key::parse("prefix").unwrap();
+        let key_a = prefix.push(&"a".to_owned()).unwrap();
+        let key_b = prefix.push(&"b".to_owned()).unwrap();
+        let other_key = storage::Key::parse("other").unwrap();
+
+        storage.begin_block(BlockHash::default(), 0.into()).unwrap();
+        storage.write(&key_a, vec![0]).unwrap();
+        storage.write(&other_key, vec![0]).unwrap();
+        storage.commit().unwrap();
+
+        storage.begin_block(BlockHash::default(), 1.into()).unwrap();
+        storage.delete(&key_a).unwrap();
+        storage.write(&key_b, vec![1]).unwrap();
+        storage.write(&other_key, vec![1]).unwrap();
+        storage.commit().unwrap();
+
+        let changes = |height: u64, prefix: Option<&storage::Key>| {
+            let (changes, _gas) =
+                storage.read_changes(height.into(), prefix).unwrap();
+            changes
+                .into_iter()
+                .map(|change| (change.key, change.old_value, change.new_value))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            changes(0, None),
+            vec![
+                (other_key.clone(), None, Some(vec![0])),
+                (key_a.clone(), None, Some(vec![0])),
+            ]
+        );
+        assert_eq!(
+            changes(1, None),
+            vec![
+                (other_key.clone(), Some(vec![0]), Some(vec![1])),
+                (key_a.clone(), Some(vec![0]), None),
+                (key_b.clone(), None, Some(vec![1])),
+            ]
+        );
+        assert_eq!(
+            changes(1, Some(&prefix)),
+            vec![(key_a, Some(vec![0]), None), (key_b, None, Some(vec![1])),]
+        );
+        assert!(changes(2, None).is_empty());
+    }
+
     /// Test the prefix iterator with RocksDB.
     #[test]
     fn test_persistent_storage_prefix_iter() {
@@ -26,6 +26,13 @@
 //!     - `new/{dyn}`: value set in block height `h`
 //!     - `old/{dyn}`: value from predecessor block height
 //!   - `header`: block's header
+//!
+//! The events emitted by the blocks are stored in the `events` column family:
+//! - `height/{h}/{i}`: the `i`-th event emitted by the block at height `h`
+//! - `tx_hash/{hash}/{h}/{i}`: the primary key of the event, if it was emitted
+//!   by the transaction with the given hash
+//! - `type/{event_type}/{h}/{i}`: the primary key of the event of the given
+//!   type
 
 use std::cmp::Ordering;
 use std::path::Path;
@@ -35,7 +42,8 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use namada::ledger::storage::types::PrefixIterator;
 use namada::ledger::storage::{
     types, BlockStateRead, BlockStateWrite, DBIter, DBWriteBatch, Error,
-    MerkleTreeStoresRead, Result, StoreType, DB,
+    EventIndex, MerkleTreeStoresRead, MerkleTreeStoresWrite, Result, StoreType,
+    StoredEvent, DB,
 };
 use namada::types::internal::TxQueue;
 use namada::types::storage::{
@@ -43,8 +51,9 @@ use namada::types::storage::{
 };
 use namada::types::time::DateTimeUtc;
 use rocksdb::{
-    BlockBasedOptions, Direction, FlushOptions, IteratorMode, Options,
-    ReadOptions, SliceTransform, WriteBatch, WriteOptions,
+    BlockBasedOptions, ColumnFamilyDescriptor, Direction, FlushOptions,
+    IteratorMode, Options, ReadOptions, SliceTransform, WriteBatch,
+    WriteOptions,
 };
 
 use crate::config::utils::num_of_threads;
@@ -55,6 +64,9 @@ use crate::config::utils::num_of_threads;
 const ENV_VAR_ROCKSDB_COMPACTION_THREADS: &str =
     "NAMADA_ROCKSDB_COMPACTION_THREADS";
 
+/// The column family of the event store
+const EVENTS_CF: &str = "events";
+
 /// RocksDB handle
 #[derive(Debug)]
 pub struct RocksDB(rocksdb::DB);
@@ -112,9 +124,14 @@ pub fn open(
     cf_opts.set_comparator("key_comparator", key_comparator);
     let extractor = SliceTransform::create_fixed_prefix(20);
     cf_opts.set_prefix_extractor(extractor);
-    // TODO use column families
 
-    rocksdb::DB::open_cf_descriptors(&cf_opts, path, vec![])
+    // The event store is kept in its own column family with the default
+    // bytewise comparator
+    let mut events_cf_opts = Options::default();
+    events_cf_opts.set_compression_type(rocksdb::DBCompressionType::Zstd);
+    let events_cf = ColumnFamilyDescriptor::new(EVENTS_CF, events_cf_opts);
+
+    rocksdb::DB::open_cf_descriptors(&cf_opts, path, vec![events_cf])
         .map(RocksDB)
         .map_err(|e| Error::DBError(e.into_string()))
 }
@@ -231,6 +248,13 @@ impl RocksDB {
         Ok(())
     }
 
+    /// Get the column family of the event store
+    fn events_cf(&self) -> Result<&rocksdb::ColumnFamily> {
+        self.0.cf_handle(EVENTS_CF).ok_or_else(|| {
+            Error::DBError("Missing the events column family".to_owned())
+        })
+    }
+
     fn exec_batch(&mut self, batch: WriteBatch) -> Result<()> {
         let mut write_opts = WriteOptions::default();
         write_opts.disable_wal(true);
@@ -479,30 +503,11 @@ impl DB for RocksDB {
 
         let prefix_key = Key::from(height.to_db_key());
         // Merkle tree
-        {
-            let prefix_key = prefix_key
-                .push(&"tree".to_owned())
-                .map_err(Error::KeyError)?;
-            for st in StoreType::iter() {
-                let prefix_key = prefix_key
-                    .push(&st.to_string())
-                    .map_err(Error::KeyError)?;
-                let root_key = prefix_key
-                    .push(&"root".to_owned())
-                    .map_err(Error::KeyError)?;
-                batch.put(
-                    root_key.to_string(),
-                    types::encode(merkle_tree_stores.root(st)),
-                );
-                let store_key = prefix_key
-                    .push(&"store".to_owned())
-                    .map_err(Error::KeyError)?;
-                batch.put(
-                    store_key.to_string(),
-                    merkle_tree_stores.store(st).encode(),
-                );
-            }
-        }
+        batch_write_merkle_tree_stores(
+            &mut batch,
+            height,
+            &merkle_tree_stores,
+        )?;
         // Block header
         {
             if let Some(h) = header {
@@ -621,6 +626,16 @@ impl DB for RocksDB {
         Ok(Some(merkle_tree_stores))
     }
 
+    fn write_merkle_tree_stores(
+        &mut self,
+        height: BlockHeight,
+        stores: &MerkleTreeStoresWrite,
+    ) -> Result<()> {
+        let mut batch = WriteBatch::default();
+        batch_write_merkle_tree_stores(&mut batch, height, stores)?;
+        self.exec_batch(batch)
+    }
+
     fn read_subspace_val(&self, key: &Key) -> Result<Option<Vec<u8>>> {
         let subspace_key =
             Key::parse("subspace").map_err(Error::KeyError)?.join(key);
@@ -882,6 +897,100 @@ impl DB for RocksDB {
 
         Ok(prev_len)
     }
+
+    fn write_events(
+        &mut self,
+        height: BlockHeight,
+        events: &[StoredEvent],
+    ) -> Result<()> {
+        let cf = self.events_cf()?;
+        let mut batch = WriteBatch::default();
+        for (position, event) in events.iter().enumerate() {
+            let mut keys = event.db_keys(height, position).into_iter();
+            let primary_key = keys.next().expect("There is a primary key");
+            for key in keys {
+                batch.put_cf(cf, key, &primary_key);
+            }
+            batch.put_cf(cf, primary_key, types::encode(event));
+        }
+        // Unlike the blocks' data, the events are written with the WAL, so
+        // they don't have to be flushed
+        self.0
+            .write(batch)
+            .map_err(|e| Error::DBError(e.into_string()))
+    }
+
+    fn read_events(
+        &self,
+        index: &EventIndex,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<StoredEvent>> {
+        let cf = self.events_cf()?;
+        let prefix = index.db_prefix();
+        let mut read_opts = ReadOptions::default();
+        read_opts.set_iterate_upper_bound(prefix_upper_bound(&prefix));
+        let iter = self.0.iterator_cf_opt(
+            cf,
+            read_opts,
+            IteratorMode::From(prefix.as_bytes(), Direction::Forward),
+        );
+        let mut events = vec![];
+        for entry in iter.skip(offset).take(limit) {
+            let (_key, value) =
+                entry.map_err(|e| Error::DBError(e.into_string()))?;
+            let bytes = match index {
+                EventIndex::Height(_) => Some(value.to_vec()),
+                // The secondary indices point to the primary key
+                EventIndex::TxHash(_) | EventIndex::EventType(_) => self
+                    .0
+                    .get_cf(cf, value)
+                    .map_err(|e| Error::DBError(e.into_string()))?,
+            };
+            if let Some(bytes) = bytes {
+                events.push(types::decode(bytes).map_err(Error::CodingError)?);
+            }
+        }
+        Ok(events)
+    }
+
+    fn prune_events(&mut self, below: BlockHeight) -> Result<()> {
+        let cf = self.events_cf()?;
+        let prefix = StoredEvent::primary_db_prefix();
+        let mut read_opts = ReadOptions::default();
+        read_opts
+            .set_iterate_upper_bound(EventIndex::Height(below).db_prefix());
+        let iter = self.0.iterator_cf_opt(
+            cf,
+            read_opts,
+            IteratorMode::From(prefix.as_bytes(), Direction::Forward),
+        );
+        let mut batch = WriteBatch::default();
+        for entry in iter {
+            let (key, value) =
+                entry.map_err(|e| Error::DBError(e.into_string()))?;
+            let key = String::from_utf8(key.to_vec()).map_err(|e| {
+                Error::Temporary {
+                    error: format!(
+                        "Cannot convert path from utf8 bytes to string: {}",
+                        e
+                    ),
+                }
+            })?;
+            let (height, position) = match StoredEvent::parse_db_key(&key) {
+                Some(parsed) => parsed,
+                None => return unknown_key_error(&key),
+            };
+            let event: StoredEvent =
+                types::decode(value).map_err(Error::CodingError)?;
+            for key in event.db_keys(height, position) {
+                batch.delete_cf(cf, key);
+            }
+        }
+        self.0
+            .write(batch)
+            .map_err(|e| Error::DBError(e.into_string()))
+    }
 }
 
 impl<'iter> DBIter<'iter> for RocksDB {
@@ -913,6 +1022,61 @@ impl<'iter> DBIter<'iter> for RocksDB {
         );
         PersistentPrefixIterator(PrefixIterator::new(iter, db_prefix))
     }
+
+    fn iter_old_diffs(
+        &'iter self,
+        height: BlockHeight,
+        prefix: &Key,
+    ) -> PersistentPrefixIterator<'iter> {
+        iter_diffs_prefix(self, height, prefix, true)
+    }
+
+    fn iter_new_diffs(
+        &'iter self,
+        height: BlockHeight,
+        prefix: &Key,
+    ) -> PersistentPrefixIterator<'iter> {
+        iter_diffs_prefix(self, height, prefix, false)
+    }
+}
+
+/// Add the merkle tree stores with the given height into the batch
+fn batch_write_merkle_tree_stores(
+    batch: &mut WriteBatch,
+    height: BlockHeight,
+    stores: &MerkleTreeStoresWrite,
+) -> Result<()> {
+    let prefix_key = Key::from(height.to_db_key())
+        .push(&"tree".to_owned())
+        .map_err(Error::KeyError)?;
+    for st in StoreType::iter() {
+        let prefix_key =
+            prefix_key.push(&st.to_string()).map_err(Error::KeyError)?;
+        let root_key = prefix_key
+            .push(&"root".to_owned())
+            .map_err(Error::KeyError)?;
+        batch.put(root_key.to_string(), types::encode(stores.root(st)));
+        let store_key = prefix_key
+            .push(&"store".to_owned())
+            .map_err(Error::KeyError)?;
+        batch.put(store_key.to_string(), stores.store(st).encode());
+    }
+    Ok(())
+}
+
+fn iter_diffs_prefix<'iter>(
+    db: &'iter RocksDB,
+    height: BlockHeight,
+    prefix: &Key,
+    is_old: bool,
+) -> PersistentPrefixIterator<'iter> {
+    let db_prefix = format!(
+        "{}/diffs/{}/",
+        height.raw(),
+        if is_old { "old" } else { "new" }
+    );
+    let prefix = format!("{}{}", db_prefix, prefix);
+    iter_db_prefix(db, db_prefix, prefix)
 }
 
 fn iter_prefix<'iter>(
@@ -921,15 +1085,20 @@ fn iter_prefix<'iter>(
 ) -> PersistentPrefixIterator<'iter> {
     let db_prefix = "subspace/".to_owned();
     let prefix = format!("{}{}", db_prefix, prefix);
+    iter_db_prefix(db, db_prefix, prefix)
+}
 
+/// Iterate the DB entries whose keys start with the given `prefix`. The
+/// `db_prefix` is stripped from the keys yielded by the iterator.
+fn iter_db_prefix<'iter>(
+    db: &'iter RocksDB,
+    db_prefix: String,
+    prefix: String,
+) -> PersistentPrefixIterator<'iter> {
     let mut read_opts = ReadOptions::default();
     // don't use the prefix bloom filter
     read_opts.set_total_order_seek(true);
-    let mut upper_prefix = prefix.clone().into_bytes();
-    if let Some(last) = upper_prefix.pop() {
-        upper_prefix.push(last + 1);
-    }
-    read_opts.set_iterate_upper_bound(upper_prefix);
+    read_opts.set_iterate_upper_bound(prefix_upper_bound(&prefix));
 
     let iter = db.0.iterator_opt(
         IteratorMode::From(prefix.as_bytes(), Direction::Forward),
@@ -938,6 +1107,15 @@ fn iter_prefix<'iter>(
     PersistentPrefixIterator(PrefixIterator::new(iter, db_prefix))
 }
 
+/// The smallest key that is greater than all the keys with the given prefix
+fn prefix_upper_bound(prefix: &str) -> Vec<u8> {
+    let mut upper_prefix = prefix.as_bytes().to_vec();
+    if let Some(last) = upper_prefix.pop() {
+        upper_prefix.push(last + 1);
+    }
+    upper_prefix
+}
+
 #[derive(Debug)]
 pub struct PersistentPrefixIterator<'a>(
     PrefixIterator<rocksdb::DBIterator<'a>>,
@@ -1143,4 +1321,68 @@ mod test {
             db.read_subspace_val(&key).expect("read should succeed");
         assert_eq!(latest_value, Some(vec![2_u8, 2, 2, 2]));
     }
+
+    /// Test that the events can be read back by every index after the DB is
+    /// reopened, and that they can be pruned.
+    #[test]
+    fn test_events() {
+        let dir = tempdir().unwrap();
+        let event =
+            |event_type: &str, tx_hash: Option<&str>, data: u8| StoredEvent {
+                event_type: event_type.to_owned(),
+                tx_hash: tx_hash.map(str::to_owned),
+                event: vec![data],
+            };
+        {
+            let mut db = open(dir.path(), None).unwrap();
+            db.write_events(
+                BlockHeight(9),
+                &[event("accepted", Some("tx"), 0), event("proposal", None, 1)],
+            )
+            .unwrap();
+            // Heights are ordered numerically
+            db.write_events(
+                BlockHeight(10),
+                &[event("applied", Some("tx"), 2)],
+            )
+            .unwrap();
+        }
+        let mut db = open(dir.path(), None).unwrap();
+
+        let events = db
+            .read_events(&EventIndex::Height(BlockHeight(9)), 0, 10)
+            .unwrap();
+        assert_eq!(
+            events,
+            vec![event("accepted", Some("tx"), 0), event("proposal", None, 1)]
+        );
+        let events = db
+            .read_events(&EventIndex::TxHash("tx".to_owned()), 0, 10)
+            .unwrap();
+        assert_eq!(
+            events,
+            vec![
+                event("accepted", Some("tx"), 0),
+                event("applied", Some("tx"), 2)
+            ]
+        );
+        let events = db
+            .read_events(&EventIndex::TxHash("tx".to_owned()), 1, 10)
+            .unwrap();
+        assert_eq!(events, vec![event("applied", Some("tx"), 2)]);
+        let events = db
+            .read_events(&EventIndex::EventType("proposal".to_owned()), 0, 1)
+            .unwrap();
+        assert_eq!(events, vec![event("proposal", None, 1)]);
+
+        db.prune_events(BlockHeight(10)).unwrap();
+        let events = db
+            .read_events(&EventIndex::TxHash("tx".to_owned()), 0, 10)
+            .unwrap();
+        assert_eq!(events, vec![event("applied", Some("tx"), 2)]);
+        let events = db
+            .read_events(&EventIndex::EventType("proposal".to_owned()), 0, 10)
+            .unwrap();
+        assert!(events.is_empty());
+    }
 }
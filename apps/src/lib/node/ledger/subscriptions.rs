@@ -0,0 +1,462 @@
+//! A WebSocket service that streams the typed events emitted by the ledger to
+//! the clients subscribed to them. The protocol is described in
+//! [`namada::ledger::events::subscription`].
+
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use async_tungstenite::tokio::accept_async;
+use async_tungstenite::tungstenite::Message;
+use async_tungstenite::WebSocketStream;
+use futures::{AsyncRead, AsyncWrite, SinkExt, StreamExt};
+use namada::ledger::events::log::query::QueryMatcher;
+use namada::ledger::events::subscription::{
+    parse_query, ClientMessage, ServerMessage,
+};
+use namada::ledger::events::typed::NamadaEvent;
+use namada::ledger::events::Event;
+use namada::types::storage::BlockHeight;
+use tokio::net::TcpListener;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::{self, Receiver, Sender};
+use tokio::sync::Semaphore;
+
+/// The number of blocks' events buffered for every connection. A connection
+/// that falls behind by more blocks misses their events.
+pub const EVENTS_CHANNEL_CAPACITY: usize = 64;
+
+/// The default maximum number of concurrent connections to the service
+pub const DEFAULT_MAX_CONNECTIONS: usize = 100;
+
+/// The default maximum number of active subscriptions of a single connection
+pub const DEFAULT_MAX_SUBSCRIPTIONS: usize = 10;
+
+/// The events emitted by `FinalizeBlock` for a single block
+#[derive(Clone, Debug)]
+pub struct BlockEvents {
+    /// The height of the block
+    pub height: BlockHeight,
+    /// The events of the block
+    pub events: Vec<Event>,
+}
+
+/// The sender of the blocks' events to the service's connections
+pub type EventsSender = Sender<Arc<BlockEvents>>;
+
+/// Create the channel of the blocks' events. The receiver isn't needed
+/// because every connection subscribes to the sender.
+pub fn events_channel() -> EventsSender {
+    broadcast::channel(EVENTS_CHANNEL_CAPACITY).0
+}
+
+/// The subscription service bound to its address
+pub struct SubscriptionServer {
+    listener: TcpListener,
+    max_connections: usize,
+    max_subscriptions: usize,
+    sender: EventsSender,
+}
+
+type Subscriptions = BTreeMap<u64, QueryMatcher>;
+
+impl SubscriptionServer {
+    /// Bind the service to the given address
+    pub async fn bind(
+        address: SocketAddr,
+        max_connections: usize,
+        max_subscriptions: usize,
+        sender: EventsSender,
+    ) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(address).await?;
+        Ok(Self {
+            listener,
+            max_connections,
+            max_subscriptions,
+            sender,
+        })
+    }
+
+    /// The address the service is bound to
+    pub fn local_addr(&self) -> std::io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Accept the connections until an abort signal is received
+    pub async fn run(self, abort_recv: tokio::sync::oneshot::Receiver<()>) {
+        tracing::info!(
+            "Event subscriptions are available at ws://{}",
+            self.local_addr()
+                .map(|addr| addr.to_string())
+                .unwrap_or_default()
+        );
+        tokio::select! {
+            _ = self.accept_loop() => {},
+            _ = abort_recv => {
+                tracing::info!("Shutting down event subscriptions...");
+            },
+        }
+    }
+
+    async fn accept_loop(&self) {
+        let connections = Arc::new(Semaphore::new(self.max_connections));
+        loop {
+            let (stream, peer) = match self.listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(err) => {
+                    tracing::warn!(
+                        "Failed to accept an event subscription connection: \
+                         {}",
+                        err
+                    );
+                    continue;
+                }
+            };
+            let permit = match connections.clone().try_acquire_owned() {
+                Ok(permit) => permit,
+                Err(_) => {
+                    tracing::debug!(
+                        "Refused an event subscription connection from {}, \
+                         the maximum number of connections has been reached",
+                        peer
+                    );
+                    continue;
+                }
+            };
+            let receiver = self.sender.subscribe();
+            let max_subscriptions = self.max_subscriptions;
+            tokio::spawn(async move {
+                match accept_async(stream).await {
+                    Ok(ws) => {
+                        serve_connection(ws, receiver, max_subscriptions).await
+                    }
+                    Err(err) => {
+                        tracing::debug!(
+                            "Failed the WebSocket handshake with {}: {}",
+                            peer,
+                            err
+                        );
+                    }
+                }
+                drop(permit);
+            });
+        }
+    }
+}
+
+/// Serve the client's messages and send it the events matching its
+/// subscriptions until it disconnects
+async fn serve_connection<S>(
+    mut ws: WebSocketStream<S>,
+    mut receiver: Receiver<Arc<BlockEvents>>,
+    max_subscriptions: usize,
+) where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut subscriptions = Subscriptions::new();
+    loop {
+        let replies = tokio::select! {
+            msg = ws.next() => match msg {
+                Some(Ok(Message::Text(text))) => {
+                    vec![handle_client_message(
+                        &mut subscriptions,
+                        max_subscriptions,
+                        &text,
+                    )]
+                }
+                Some(Ok(Message::Ping(data))) => {
+                    if ws.send(Message::Pong(data)).await.is_err() {
+                        return;
+                    }
+                    continue;
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => continue,
+            },
+            block = receiver.recv() => match block {
+                Ok(block) => matching_events(&subscriptions, &block),
+                Err(RecvError::Lagged(missed)) => vec![ServerMessage::Error {
+                    id: None,
+                    message: format!(
+                        "The events of {} blocks have been missed",
+                        missed
+                    ),
+                }],
+                Err(RecvError::Closed) => return,
+            },
+        };
+        for reply in replies {
+            let text = serde_json::to_string(&reply)
+                .expect("Encoding a server message shouldn't fail");
+            if ws.send(Message::Text(text)).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Apply a message from a client to its subscriptions and reply to it
+fn handle_client_message(
+    subscriptions: &mut Subscriptions,
+    max_subscriptions: usize,
+    text: &str,
+) -> ServerMessage {
+    let msg: ClientMessage = match serde_json::from_str(text) {
+        Ok(msg) => msg,
+        Err(err) => {
+            return ServerMessage::Error {
+                id: None,
+                message: format!("Invalid message: {}", err),
+            };
+        }
+    };
+    match msg {
+        ClientMessage::Subscribe { id, query } => {
+            if subscriptions.contains_key(&id) {
+                return ServerMessage::Error {
+                    id: Some(id),
+                    message: "The subscription ID is already in use".to_owned(),
+                };
+            }
+            if subscriptions.len() >= max_subscriptions {
+                return ServerMessage::Error {
+                    id: Some(id),
+                    message: format!(
+                        "The maximum number of {} subscriptions has been \
+                         reached",
+                        max_subscriptions
+                    ),
+                };
+            }
+            match parse_query(&query) {
+                Ok(matcher) => {
+                    subscriptions.insert(id, matcher);
+                    ServerMessage::Subscribed { id }
+                }
+                Err(err) => ServerMessage::Error {
+                    id: Some(id),
+                    message: format!("Invalid query: {}", err),
+                },
+            }
+        }
+        ClientMessage::Unsubscribe { id } => {
+            if subscriptions.remove(&id).is_some() {
+                ServerMessage::Unsubscribed { id }
+            } else {
+                ServerMessage::Error {
+                    id: Some(id),
+                    message: "No such subscription".to_owned(),
+                }
+            }
+        }
+    }
+}
+
+/// Find the typed events of the block that match any of the subscriptions
+fn matching_events(
+    subscriptions: &Subscriptions,
+    block: &BlockEvents,
+) -> Vec<ServerMessage> {
+    if subscriptions.is_empty() {
+        return vec![];
+    }
+    let mut messages = vec![];
+    for event in &block.events {
+        let typed = match NamadaEvent::try_from(event) {
+            Ok(typed) => typed,
+            Err(_) => continue,
+        };
+        for (id, matcher) in subscriptions {
+            if matcher.matches(event) {
+                messages.push(ServerMessage::Event {
+                    id: *id,
+                    height: block.height,
+                    event: typed.clone(),
+                });
+            }
+        }
+    }
+    messages
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use async_tungstenite::tokio::connect_async;
+    use namada::ledger::events::typed;
+    use namada::types::address::{self, nam};
+    use namada::types::token;
+    use namada::types::transaction::pos;
+
+    use super::*;
+
+    fn subscribe(id: u64, query: &str) -> String {
+        serde_json::to_string(&ClientMessage::Subscribe {
+            id,
+            query: query.to_owned(),
+        })
+        .unwrap()
+    }
+
+    /// Test the handling of the clients' subscriptions.
+    #[test]
+    fn test_handle_client_message() {
+        let mut subscriptions = Subscriptions::new();
+
+        let reply = handle_client_message(
+            &mut subscriptions,
+            2,
+            &subscribe(1, "bond.amount>10"),
+        );
+        assert_eq!(reply, ServerMessage::Subscribed { id: 1 });
+
+        // The IDs must be unique
+        let reply =
+            handle_client_message(&mut subscriptions, 2, &subscribe(1, ""));
+        assert!(matches!(reply, ServerMessage::Error { id: Some(1), .. }));
+
+        // The query must be valid
+        let reply = handle_client_message(
+            &mut subscriptions,
+            2,
+            &subscribe(2, "bond.amount>"),
+        );
+        assert!(matches!(reply, ServerMessage::Error { id: Some(2), .. }));
+
+        let reply =
+            handle_client_message(&mut subscriptions, 2, &subscribe(2, ""));
+        assert_eq!(reply, ServerMessage::Subscribed { id: 2 });
+
+        // The number of subscriptions is limited
+        let reply =
+            handle_client_message(&mut subscriptions, 2, &subscribe(3, ""));
+        assert!(matches!(reply, ServerMessage::Error { id: Some(3), .. }));
+
+        let reply = handle_client_message(
+            &mut subscriptions,
+            2,
+            r#"{"type": "unsubscribe", "id": 1}"#,
+        );
+        assert_eq!(reply, ServerMessage::Unsubscribed { id: 1 });
+        assert_eq!(subscriptions.len(), 1);
+
+        let reply = handle_client_message(&mut subscriptions, 2, "{}");
+        assert!(matches!(reply, ServerMessage::Error { id: None, .. }));
+    }
+
+    /// Test that only the typed events matching the subscriptions are sent.
+    #[test]
+    fn test_matching_events() {
+        let source = address::testing::established_address_1();
+        let validator = address::testing::established_address_2();
+        let transfer = token::Transfer {
+            source: source.clone(),
+            target: validator.clone(),
+            token: nam(),
+            sub_prefix: None,
+            amount: token::Amount::whole(5),
+            key: None,
+            shielded: None,
+        };
+        let bond = pos::Bond {
+            validator,
+            amount: token::Amount::whole(20),
+            source: None,
+        };
+        let block = BlockEvents {
+            height: BlockHeight(3),
+            events: vec![
+                Event::new_transfer_event("HASH1", 3, &transfer),
+                Event::new_bond_event("HASH2", 3, &bond),
+            ],
+        };
+
+        let mut subscriptions = Subscriptions::new();
+        assert!(matching_events(&subscriptions, &block).is_empty());
+
+        subscriptions.insert(1, parse_query("bond.amount>10").unwrap());
+        subscriptions.insert(
+            2,
+            parse_query(&format!("transfer.source='{}'", source)).unwrap(),
+        );
+        subscriptions.insert(3, parse_query("").unwrap());
+        let ids: Vec<(u64, String)> = matching_events(&subscriptions, &block)
+            .into_iter()
+            .map(|msg| match msg {
+                ServerMessage::Event {
+                    id,
+                    height: BlockHeight(3),
+                    event: NamadaEvent::Transfer(typed::Transfer { hash, .. }),
+                }
+                | ServerMessage::Event {
+                    id,
+                    height: BlockHeight(3),
+                    event: NamadaEvent::Bond(typed::Bond { hash, .. }),
+                } => (id, hash),
+                msg => panic!("Unexpected message {:?}", msg),
+            })
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                (2, "HASH1".to_owned()),
+                (3, "HASH1".to_owned()),
+                (1, "HASH2".to_owned()),
+                (3, "HASH2".to_owned()),
+            ]
+        );
+    }
+
+    /// Test subscribing to the events over a WebSocket and the limit of
+    /// connections.
+    #[tokio::test]
+    async fn test_subscription_server() {
+        let sender = events_channel();
+        let server = SubscriptionServer::bind(
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0),
+            1,
+            DEFAULT_MAX_SUBSCRIPTIONS,
+            sender.clone(),
+        )
+        .await
+        .unwrap();
+        let url = format!("ws://{}", server.local_addr().unwrap());
+        let (_abort_send, abort_recv) = tokio::sync::oneshot::channel();
+        tokio::spawn(server.run(abort_recv));
+
+        let (mut ws, _) = connect_async(&url).await.unwrap();
+        ws.send(Message::Text(subscribe(7, ""))).await.unwrap();
+        let reply = ws.next().await.unwrap().unwrap();
+        assert_eq!(
+            serde_json::from_str::<ServerMessage>(reply.to_text().unwrap())
+                .unwrap(),
+            ServerMessage::Subscribed { id: 7 }
+        );
+
+        // Only a single connection is allowed
+        assert!(connect_async(&url).await.is_err());
+
+        let bond = pos::Bond {
+            validator: address::testing::established_address_1(),
+            amount: token::Amount::whole(1),
+            source: None,
+        };
+        sender
+            .send(Arc::new(BlockEvents {
+                height: BlockHeight(1),
+                events: vec![Event::new_bond_event("HASH", 1, &bond)],
+            }))
+            .unwrap();
+        let reply = ws.next().await.unwrap().unwrap();
+        assert!(matches!(
+            serde_json::from_str::<ServerMessage>(reply.to_text().unwrap())
+                .unwrap(),
+            ServerMessage::Event {
+                id: 7,
+                height: BlockHeight(1),
+                event: NamadaEvent::Bond(_),
+            }
+        ));
+    }
+}
@@ -1,6 +1,8 @@
 use std::fmt::Display;
 
 use borsh::{BorshDeserialize, BorshSerialize};
+use rust_decimal::Decimal;
+use thiserror::Error;
 
 use super::storage as gov_storage;
 use crate::ledger::storage::types::encode;
@@ -32,6 +34,25 @@ pub struct GovParams {
     pub max_proposal_content_size: u64,
     /// Minimum epochs between end and grace epochs
     pub min_proposal_grace_epochs: u64,
+    /// Minimum share of the total voting power that must vote on a proposal,
+    /// including the abstain votes, for its tally to be valid
+    pub quorum: Decimal,
+    /// Minimum share of the yay and nay votes that must be yay for a
+    /// proposal to pass
+    pub pass_threshold: Decimal,
+    /// Minimum share of all the votes that must be nay with veto for a
+    /// proposal to be vetoed, which burns its funds
+    pub veto_threshold: Decimal,
+    /// Are the funds of a proposal whose votes don't reach the quorum burned?
+    /// Otherwise, they're sent to the slash fund.
+    pub burn_missed_quorum_deposit: bool,
+    /// Minimum amount of locked funds for an expedited proposal
+    pub min_expedited_proposal_fund: u64,
+    /// Minimum expedited proposal voting period in epochs
+    pub min_expedited_proposal_period: u64,
+    /// Minimum share of the yay and nay votes that must be yay for an
+    /// expedited proposal to pass
+    pub expedited_pass_threshold: Decimal,
 }
 
 impl Display for GovParams {
@@ -40,13 +61,23 @@ impl Display for GovParams {
             f,
             "Min. proposal fund: {}\nMax. proposal code size: {}\nMin. \
              proposal period: {}\nMax. proposal period: {}\nMax. proposal \
-             content size: {}\nMin. proposal grace epochs: {}",
+             content size: {}\nMin. proposal grace epochs: {}\nQuorum: \
+             {}\nPass threshold: {}\nVeto threshold: {}\nBurn missed quorum \
+             deposit: {}\nMin. expedited proposal fund: {}\nMin. expedited \
+             proposal period: {}\nExpedited pass threshold: {}",
             self.min_proposal_fund,
             self.max_proposal_code_size,
             self.min_proposal_period,
             self.max_proposal_period,
             self.max_proposal_content_size,
-            self.min_proposal_grace_epochs
+            self.min_proposal_grace_epochs,
+            self.quorum,
+            self.pass_threshold,
+            self.veto_threshold,
+            self.burn_missed_quorum_deposit,
+            self.min_expedited_proposal_fund,
+            self.min_expedited_proposal_period,
+            self.expedited_pass_threshold
         )
     }
 }
@@ -60,11 +91,69 @@ impl Default for GovParams {
             max_proposal_period: 27,
             max_proposal_content_size: 10_000,
             min_proposal_grace_epochs: 6,
+            quorum: Decimal::new(334, 3),
+            pass_threshold: Decimal::new(667, 3),
+            veto_threshold: Decimal::new(334, 3),
+            burn_missed_quorum_deposit: false,
+            min_expedited_proposal_fund: 2_500,
+            min_expedited_proposal_period: 1,
+            expedited_pass_threshold: Decimal::new(75, 2),
         }
     }
 }
 
+#[allow(missing_docs)]
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    #[error(
+        "Expedited pass threshold must be >= pass threshold. Got expedited: \
+         {0}, normal: {1}"
+    )]
+    ExpeditedPassThresholdTooLow(Decimal, Decimal),
+    #[error(
+        "Min. expedited proposal period must be <= min. proposal period. Got \
+         expedited: {0}, normal: {1}"
+    )]
+    ExpeditedProposalPeriodTooLong(u64, u64),
+    #[error(
+        "Min. expedited proposal fund must be >= min. proposal fund. Got \
+         expedited: {0}, normal: {1}"
+    )]
+    ExpeditedProposalFundTooLow(u64, u64),
+}
+
 impl GovParams {
+    /// Validate governance parameters values. Returns an empty list if the
+    /// values are valid.
+    #[must_use]
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = vec![];
+
+        // An expedited proposal must not be easier to pass than a normal one
+        if self.expedited_pass_threshold < self.pass_threshold {
+            errors.push(ValidationError::ExpeditedPassThresholdTooLow(
+                self.expedited_pass_threshold,
+                self.pass_threshold,
+            ))
+        }
+
+        if self.min_expedited_proposal_period > self.min_proposal_period {
+            errors.push(ValidationError::ExpeditedProposalPeriodTooLong(
+                self.min_expedited_proposal_period,
+                self.min_proposal_period,
+            ))
+        }
+
+        if self.min_expedited_proposal_fund < self.min_proposal_fund {
+            errors.push(ValidationError::ExpeditedProposalFundTooLow(
+                self.min_expedited_proposal_fund,
+                self.min_proposal_fund,
+            ))
+        }
+
+        errors
+    }
+
     /// Initialize governance parameters into storage
     pub fn init_storage<DB, H>(&self, storage: &mut Storage<DB, H>)
     where
@@ -78,6 +167,13 @@ impl GovParams {
             max_proposal_period,
             max_proposal_content_size,
             min_proposal_grace_epochs,
+            quorum,
+            pass_threshold,
+            veto_threshold,
+            burn_missed_quorum_deposit,
+            min_expedited_proposal_fund,
+            min_expedited_proposal_period,
+            expedited_pass_threshold,
         } = self;
 
         let min_proposal_fund_key = gov_storage::get_min_proposal_fund_key();
@@ -122,9 +218,87 @@ impl GovParams {
             )
             .expect("Should be able to write to storage");
 
+        let quorum_key = gov_storage::get_quorum_key();
+        storage
+            .write(&quorum_key, encode(quorum))
+            .expect("Should be able to write to storage");
+
+        let pass_threshold_key = gov_storage::get_pass_threshold_key();
+        storage
+            .write(&pass_threshold_key, encode(pass_threshold))
+            .expect("Should be able to write to storage");
+
+        let veto_threshold_key = gov_storage::get_veto_threshold_key();
+        storage
+            .write(&veto_threshold_key, encode(veto_threshold))
+            .expect("Should be able to write to storage");
+
+        let burn_missed_quorum_deposit_key =
+            gov_storage::get_burn_missed_quorum_deposit_key();
+        storage
+            .write(
+                &burn_missed_quorum_deposit_key,
+                encode(burn_missed_quorum_deposit),
+            )
+            .expect("Should be able to write to storage");
+
+        let min_expedited_proposal_fund_key =
+            gov_storage::get_min_expedited_proposal_fund_key();
+        let amount = Amount::whole(*min_expedited_proposal_fund);
+        storage
+            .write(&min_expedited_proposal_fund_key, encode(&amount))
+            .expect("Should be able to write to storage");
+
+        let min_expedited_proposal_period_key =
+            gov_storage::get_min_expedited_proposal_period_key();
+        storage
+            .write(
+                &min_expedited_proposal_period_key,
+                encode(min_expedited_proposal_period),
+            )
+            .expect("Should be able to write to storage");
+
+        let expedited_pass_threshold_key =
+            gov_storage::get_expedited_pass_threshold_key();
+        storage
+            .write(
+                &expedited_pass_threshold_key,
+                encode(expedited_pass_threshold),
+            )
+            .expect("Should be able to write to storage");
+
         let counter_key = gov_storage::get_counter_key();
         storage
             .write(&counter_key, encode(&u64::MIN))
             .expect("Should be able to write to storage");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_gov_params() {
+        assert!(GovParams::default().validate().is_empty());
+
+        let params = GovParams {
+            pass_threshold: Decimal::new(8, 1),
+            min_proposal_period: 1,
+            min_expedited_proposal_period: 2,
+            min_proposal_fund: 3_000,
+            ..Default::default()
+        };
+        assert_eq!(
+            params.validate(),
+            vec![
+                ValidationError::ExpeditedPassThresholdTooLow(
+                    Decimal::new(75, 2),
+                    Decimal::new(8, 1)
+                ),
+                ValidationError::ExpeditedProposalPeriodTooLong(2, 1),
+                ValidationError::ExpeditedProposalFundTooLow(2_500, 3_000),
+            ]
+        );
+    }
+}
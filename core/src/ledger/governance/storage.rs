@@ -1,9 +1,10 @@
 use crate::ledger::governance::ADDRESS;
 use crate::types::address::Address;
-use crate::types::storage::{DbKeySeg, Key, KeySeg};
+use crate::types::storage::{BlockHeight, DbKeySeg, Key, KeySeg};
 
 const PROPOSAL_PREFIX: &str = "proposal";
 const PROPOSAL_VOTE: &str = "vote";
+const PROPOSAL_VOTE_HISTORY: &str = "history";
 const PROPOSAL_AUTHOR: &str = "author";
 const PROPOSAL_CONTENT: &str = "content";
 const PROPOSAL_START_EPOCH: &str = "start_epoch";
@@ -11,6 +12,10 @@ const PROPOSAL_END_EPOCH: &str = "end_epoch";
 const PROPOSAL_GRACE_EPOCH: &str = "grace_epoch";
 const PROPOSAL_FUNDS: &str = "funds";
 const PROPOSAL_CODE: &str = "proposal_code";
+const PROPOSAL_TYPE: &str = "proposal_type";
+const PROPOSAL_EXPEDITED: &str = "expedited";
+const PROPOSAL_STATUS: &str = "status";
+const PROPOSAL_PASS_THROUGH: &str = "pass_through";
 const PROPOSAL_COMMITTING_EPOCH: &str = "epoch";
 
 const MIN_PROPOSAL_FUND_KEY: &str = "min_fund";
@@ -19,8 +24,16 @@ const MIN_PROPOSAL_PERIOD_KEY: &str = "min_period";
 const MAX_PROPOSAL_PERIOD_KEY: &str = "max_period";
 const MAX_PROPOSAL_CONTENT_SIZE_KEY: &str = "max_content";
 const MIN_GRACE_EPOCH_KEY: &str = "min_grace_epoch";
+const QUORUM_KEY: &str = "quorum";
+const PASS_THRESHOLD_KEY: &str = "pass_threshold";
+const VETO_THRESHOLD_KEY: &str = "veto_threshold";
+const BURN_MISSED_QUORUM_DEPOSIT_KEY: &str = "burn_missed_quorum_deposit";
+const MIN_EXPEDITED_PROPOSAL_FUND_KEY: &str = "min_expedited_fund";
+const MIN_EXPEDITED_PROPOSAL_PERIOD_KEY: &str = "min_expedited_period";
+const EXPEDITED_PASS_THRESHOLD_KEY: &str = "expedited_pass_threshold";
 const COUNTER_KEY: &str = "counter";
 const PENDING_PROPOSAL: &str = "pending";
+const VOTE_PASS_THROUGH: &str = "vote_pass_through";
 
 /// Check if key is inside governance address space
 pub fn is_governance_key(key: &Key) -> bool {
@@ -47,6 +60,42 @@ pub fn is_vote_key(key: &Key) -> bool {
     }
 }
 
+/// Check if a key is a vote history key
+pub fn is_vote_history_key(key: &Key) -> bool {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(id),
+            DbKeySeg::StringSeg(vote_history),
+            DbKeySeg::AddressSeg(_validator_address),
+            DbKeySeg::AddressSeg(_address),
+            DbKeySeg::StringSeg(height),
+        ] if addr == &ADDRESS
+            && prefix == PROPOSAL_PREFIX
+            && vote_history == PROPOSAL_VOTE_HISTORY =>
+        {
+            id.parse::<u64>().is_ok() && height.parse::<u64>().is_ok()
+        }
+        _ => false,
+    }
+}
+
+/// Check if a key is a validator's vote pass-through key and return the
+/// validator's address
+pub fn is_vote_pass_through_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(pass_through),
+            DbKeySeg::AddressSeg(validator),
+        ] if addr == &ADDRESS && pass_through == VOTE_PASS_THROUGH => {
+            Some(validator)
+        }
+        _ => None,
+    }
+}
+
 /// Check if key is author key
 pub fn is_author_key(key: &Key) -> bool {
     match &key.segments[..] {
@@ -83,6 +132,60 @@ pub fn is_proposal_code_key(key: &Key) -> bool {
     }
 }
 
+/// Check if key is proposal type key
+pub fn is_proposal_type_key(key: &Key) -> bool {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(id),
+            DbKeySeg::StringSeg(proposal_type),
+        ] if addr == &ADDRESS
+            && prefix == PROPOSAL_PREFIX
+            && proposal_type == PROPOSAL_TYPE =>
+        {
+            id.parse::<u64>().is_ok()
+        }
+        _ => false,
+    }
+}
+
+/// Check if key is proposal expedited key
+pub fn is_expedited_key(key: &Key) -> bool {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(id),
+            DbKeySeg::StringSeg(expedited),
+        ] if addr == &ADDRESS
+            && prefix == PROPOSAL_PREFIX
+            && expedited == PROPOSAL_EXPEDITED =>
+        {
+            id.parse::<u64>().is_ok()
+        }
+        _ => false,
+    }
+}
+
+/// Check if key is proposal status key
+pub fn is_status_key(key: &Key) -> bool {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(id),
+            DbKeySeg::StringSeg(status),
+        ] if addr == &ADDRESS
+            && prefix == PROPOSAL_PREFIX
+            && status == PROPOSAL_STATUS =>
+        {
+            id.parse::<u64>().is_ok()
+        }
+        _ => false,
+    }
+}
+
 /// Check if key is grace epoch key
 pub fn is_grace_epoch_key(key: &Key) -> bool {
     match &key.segments[..] {
@@ -245,6 +348,68 @@ pub fn is_min_grace_epoch_key(key: &Key) -> bool {
                     && min_grace_epoch_param == MIN_GRACE_EPOCH_KEY)
 }
 
+/// Check if key is a quorum param key
+pub fn is_quorum_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+                    DbKeySeg::AddressSeg(addr),
+                    DbKeySeg::StringSeg(quorum_param),
+                ] if addr == &ADDRESS && quorum_param == QUORUM_KEY)
+}
+
+/// Check if key is a pass threshold param key
+pub fn is_pass_threshold_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+                    DbKeySeg::AddressSeg(addr),
+                    DbKeySeg::StringSeg(pass_threshold_param),
+                ] if addr == &ADDRESS
+                    && pass_threshold_param == PASS_THRESHOLD_KEY)
+}
+
+/// Check if key is a veto threshold param key
+pub fn is_veto_threshold_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+                    DbKeySeg::AddressSeg(addr),
+                    DbKeySeg::StringSeg(veto_threshold_param),
+                ] if addr == &ADDRESS
+                    && veto_threshold_param == VETO_THRESHOLD_KEY)
+}
+
+/// Check if key is a burn missed quorum deposit param key
+pub fn is_burn_missed_quorum_deposit_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+                    DbKeySeg::AddressSeg(addr),
+                    DbKeySeg::StringSeg(burn_param),
+                ] if addr == &ADDRESS
+                    && burn_param == BURN_MISSED_QUORUM_DEPOSIT_KEY)
+}
+
+/// Check if key is a min expedited proposal fund param key
+pub fn is_min_expedited_proposal_fund_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+                    DbKeySeg::AddressSeg(addr),
+                    DbKeySeg::StringSeg(min_funds_param),
+                ] if addr == &ADDRESS
+                    && min_funds_param == MIN_EXPEDITED_PROPOSAL_FUND_KEY)
+}
+
+/// Check if key is a min expedited proposal period param key
+pub fn is_min_expedited_proposal_period_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+                    DbKeySeg::AddressSeg(addr),
+                    DbKeySeg::StringSeg(min_period_param),
+                ] if addr == &ADDRESS
+                    && min_period_param == MIN_EXPEDITED_PROPOSAL_PERIOD_KEY)
+}
+
+/// Check if key is an expedited pass threshold param key
+pub fn is_expedited_pass_threshold_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+                    DbKeySeg::AddressSeg(addr),
+                    DbKeySeg::StringSeg(pass_threshold_param),
+                ] if addr == &ADDRESS
+                    && pass_threshold_param == EXPEDITED_PASS_THRESHOLD_KEY)
+}
+
 /// Check if key is parameter key
 pub fn is_parameter_key(key: &Key) -> bool {
     is_min_proposal_fund_key(key)
@@ -253,6 +418,13 @@ pub fn is_parameter_key(key: &Key) -> bool {
         || is_min_proposal_period_key(key)
         || is_max_proposal_period_key(key)
         || is_min_grace_epoch_key(key)
+        || is_quorum_key(key)
+        || is_pass_threshold_key(key)
+        || is_veto_threshold_key(key)
+        || is_burn_missed_quorum_deposit_key(key)
+        || is_min_expedited_proposal_fund_key(key)
+        || is_min_expedited_proposal_period_key(key)
+        || is_expedited_pass_threshold_key(key)
 }
 
 /// Check if key is start epoch or end epoch key
@@ -309,6 +481,55 @@ pub fn get_min_proposal_grace_epoch_key() -> Key {
         .expect("Cannot obtain a storage key")
 }
 
+/// Get quorum key
+pub fn get_quorum_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&QUORUM_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get pass threshold key
+pub fn get_pass_threshold_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&PASS_THRESHOLD_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get veto threshold key
+pub fn get_veto_threshold_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&VETO_THRESHOLD_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get burn missed quorum deposit key
+pub fn get_burn_missed_quorum_deposit_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&BURN_MISSED_QUORUM_DEPOSIT_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get min expedited proposal fund key
+pub fn get_min_expedited_proposal_fund_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&MIN_EXPEDITED_PROPOSAL_FUND_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get min expedited proposal period key
+pub fn get_min_expedited_proposal_period_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&MIN_EXPEDITED_PROPOSAL_PERIOD_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get expedited pass threshold key
+pub fn get_expedited_pass_threshold_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&EXPEDITED_PASS_THRESHOLD_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
 /// Get key of proposal ids counter
 pub fn get_counter_key() -> Key {
     Key::from(ADDRESS.to_db_key())
@@ -379,6 +600,43 @@ pub fn get_proposal_code_key(id: u64) -> Key {
         .expect("Cannot obtain a storage key")
 }
 
+/// Get proposal type key
+pub fn get_proposal_type_key(id: u64) -> Key {
+    proposal_prefix()
+        .push(&id.to_string())
+        .expect("Cannot obtain a storage key")
+        .push(&PROPOSAL_TYPE.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get proposal expedited key
+pub fn get_expedited_key(id: u64) -> Key {
+    proposal_prefix()
+        .push(&id.to_string())
+        .expect("Cannot obtain a storage key")
+        .push(&PROPOSAL_EXPEDITED.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get proposal status key
+pub fn get_status_key(id: u64) -> Key {
+    proposal_prefix()
+        .push(&id.to_string())
+        .expect("Cannot obtain a storage key")
+        .push(&PROPOSAL_STATUS.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key of the validators that passed through the votes of their
+/// delegators when the voting period of a proposal ended
+pub fn get_pass_through_validators_key(id: u64) -> Key {
+    proposal_prefix()
+        .push(&id.to_string())
+        .expect("Cannot obtain a storage key")
+        .push(&PROPOSAL_PASS_THROUGH.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
 /// Get the proposal committing key prefix
 pub fn get_commiting_proposals_prefix(epoch: u64) -> Key {
     proposal_prefix()
@@ -417,6 +675,46 @@ pub fn get_vote_proposal_key(
         .expect("Cannot obtain a storage key")
 }
 
+/// Get proposal vote history prefix key
+pub fn get_proposal_vote_history_prefix_key(id: u64) -> Key {
+    proposal_prefix()
+        .push(&id.to_string())
+        .expect("Cannot obtain a storage key")
+        .push(&PROPOSAL_VOTE_HISTORY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key of a vote in the vote history of a proposal, at the height
+/// of the block in which it was cast
+pub fn get_vote_history_key(
+    id: u64,
+    voter_address: Address,
+    delegation_address: Address,
+    height: BlockHeight,
+) -> Key {
+    get_proposal_vote_history_prefix_key(id)
+        .push(&delegation_address)
+        .expect("Cannot obtain a storage key")
+        .push(&voter_address)
+        .expect("Cannot obtain a storage key")
+        .push(&height)
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the prefix of the vote pass-through keys of the validators
+pub fn get_vote_pass_through_prefix() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&VOTE_PASS_THROUGH.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the vote pass-through key of a validator
+pub fn get_vote_pass_through_key(validator: &Address) -> Key {
+    get_vote_pass_through_prefix()
+        .push(validator)
+        .expect("Cannot obtain a storage key")
+}
+
 /// Get the proposal execution key
 pub fn get_proposal_execution_key(id: u64) -> Key {
     Key::from(ADDRESS.to_db_key())
@@ -479,3 +777,16 @@ pub fn get_voter_address(key: &Key) -> Option<&Address> {
         None => None,
     }
 }
+
+/// Get the block height from a vote history key
+pub fn get_vote_history_height(key: &Key) -> Option<BlockHeight> {
+    match key.get_at(6) {
+        Some(height) => match height {
+            DbKeySeg::AddressSeg(_) => None,
+            DbKeySeg::StringSeg(res) => {
+                res.parse::<u64>().ok().map(BlockHeight)
+            }
+        },
+        None => None,
+    }
+}
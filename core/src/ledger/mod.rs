@@ -5,6 +5,7 @@ pub mod governance;
 #[cfg(any(feature = "abciplus", feature = "abcipp"))]
 pub mod ibc;
 pub mod parameters;
+pub mod pgf;
 pub mod slash_fund;
 pub mod storage;
 pub mod storage_api;
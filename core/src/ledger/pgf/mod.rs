@@ -0,0 +1,215 @@
+//! Public goods funding (PGF) library code
+//!
+//! The PGF is controlled by a council elected by a governance proposal. The
+//! council registers the continuous funding streams, which pay a set amount
+//! of the native token to their recipients every epoch, and approves the
+//! retroactive one-off payments, which are paid at the start of the next
+//! epoch. Both are minted by the PGF account, which adds them to the total
+//! supply of the native token, and they're limited by the council's spending
+//! cap per epoch.
+
+use crate::ledger::storage_api::{self, StorageRead, StorageWrite};
+use crate::types::address::{Address, InternalAddress};
+use crate::types::governance::PgfCouncil;
+use crate::types::storage::Key;
+use crate::types::token;
+
+/// The PGF internal address
+pub const ADDRESS: Address = Address::Internal(InternalAddress::Pgf);
+
+/// PGF storage
+pub mod storage;
+
+/// Read the PGF council, if one has been elected
+pub fn read_council<S>(storage: &S) -> storage_api::Result<Option<PgfCouncil>>
+where
+    S: StorageRead,
+{
+    storage.read(&storage::get_council_key())
+}
+
+/// Read all the continuous funding streams by their recipient
+pub fn read_streams<S>(
+    storage: &S,
+) -> storage_api::Result<Vec<(Address, token::Amount)>>
+where
+    S: StorageRead,
+{
+    read_payments(storage, &storage::stream_prefix(), storage::is_stream_key)
+}
+
+/// Read all the pending retroactive payments by their recipient
+pub fn read_retro_payments<S>(
+    storage: &S,
+) -> storage_api::Result<Vec<(Address, token::Amount)>>
+where
+    S: StorageRead,
+{
+    read_payments(
+        storage,
+        &storage::retro_payment_prefix(),
+        storage::is_retro_payment_key,
+    )
+}
+
+fn read_payments<S>(
+    storage: &S,
+    prefix: &Key,
+    get_recipient: fn(&Key) -> Option<&Address>,
+) -> storage_api::Result<Vec<(Address, token::Amount)>>
+where
+    S: StorageRead,
+{
+    storage_api::iter_prefix(storage, prefix)?
+        .filter_map(|result| match result {
+            Ok((key, amount)) => get_recipient(&key)
+                .map(|recipient| Ok((recipient.clone(), amount))),
+            Err(err) => Some(Err(err)),
+        })
+        .collect()
+}
+
+/// Pay the continuous funding streams and then the pending retroactive
+/// payments of the epoch by minting the native token to their recipients
+/// with the PGF account as the minter.
+/// The payments that would exceed the council's spending cap are skipped
+/// and the skipped retroactive payments stay pending for the next epochs.
+/// Nothing is paid until a council has been elected. Returns the total
+/// minted amount.
+pub fn pay_funding<S>(
+    storage: &mut S,
+    native_token: &Address,
+) -> storage_api::Result<token::Amount>
+where
+    S: StorageRead + StorageWrite,
+{
+    let council = match read_council(storage)? {
+        Some(council) => council,
+        None => return Ok(token::Amount::default()),
+    };
+    let streams = read_streams(storage)?;
+    let retro_payments = read_retro_payments(storage)?;
+
+    let mut budget = council.spending_cap;
+    let mut pay = |storage: &mut S, recipient: &Address, amount| {
+        if amount > budget {
+            return Ok(false);
+        }
+        budget -= amount;
+        storage_api::token::mint(
+            storage,
+            &ADDRESS,
+            native_token,
+            recipient,
+            amount,
+        )?;
+        Ok::<_, storage_api::Error>(true)
+    };
+    for (recipient, amount) in streams {
+        pay(storage, &recipient, amount)?;
+    }
+    for (recipient, amount) in retro_payments {
+        if pay(storage, &recipient, amount)? {
+            storage.delete(&storage::get_retro_payment_key(&recipient))?;
+        }
+    }
+    Ok(council.spending_cap - budget)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ledger::storage::testing::TestStorage;
+    use crate::types::address::{self, nam};
+
+    /// Test paying the funding within the council's spending cap.
+    #[test]
+    fn test_pay_funding() {
+        let mut storage = TestStorage::default();
+        let native_token = nam();
+        let stream_recipient = address::testing::established_address_1();
+        let retro_recipient = address::testing::established_address_2();
+        let balance = |storage: &TestStorage, owner: &Address| {
+            storage_api::token::read_balance(storage, &native_token, owner)
+                .unwrap()
+        };
+        let total_supply = |storage: &TestStorage| {
+            storage_api::token::read_total_supply(storage, &native_token)
+                .unwrap()
+        };
+        let minted_by_pgf = |storage: &TestStorage| {
+            storage_api::token::read_minted(storage, &native_token, &ADDRESS)
+                .unwrap()
+        };
+        let initial_supply = token::Amount::whole(1000);
+        StorageWrite::write(
+            &mut storage,
+            &token::total_supply_key(&native_token),
+            initial_supply,
+        )
+        .unwrap();
+
+        StorageWrite::write(
+            &mut storage,
+            &storage::get_stream_key(&stream_recipient),
+            token::Amount::whole(10),
+        )
+        .unwrap();
+        StorageWrite::write(
+            &mut storage,
+            &storage::get_retro_payment_key(&retro_recipient),
+            token::Amount::whole(15),
+        )
+        .unwrap();
+
+        // Nothing is paid without a council
+        let minted = pay_funding(&mut storage, &native_token).unwrap();
+        assert_eq!(minted, token::Amount::default());
+        assert_eq!(balance(&storage, &stream_recipient), 0.into());
+
+        StorageWrite::write(
+            &mut storage,
+            &storage::get_council_key(),
+            PgfCouncil {
+                address: address::testing::established_address_3(),
+                spending_cap: token::Amount::whole(20),
+            },
+        )
+        .unwrap();
+
+        // The retroactive payment exceeds the rest of the budget
+        let minted = pay_funding(&mut storage, &native_token).unwrap();
+        assert_eq!(minted, token::Amount::whole(10));
+        assert_eq!(
+            balance(&storage, &stream_recipient),
+            token::Amount::whole(10)
+        );
+        assert_eq!(balance(&storage, &retro_recipient), 0.into());
+        assert_eq!(read_retro_payments(&storage).unwrap().len(), 1);
+        assert_eq!(total_supply(&storage), initial_supply + minted);
+        assert_eq!(minted_by_pgf(&storage), minted);
+
+        // Once the stream is removed, the retroactive payment is paid once
+        StorageWrite::delete(
+            &mut storage,
+            &storage::get_stream_key(&stream_recipient),
+        )
+        .unwrap();
+        let minted = pay_funding(&mut storage, &native_token).unwrap();
+        assert_eq!(minted, token::Amount::whole(15));
+        assert_eq!(
+            balance(&storage, &retro_recipient),
+            token::Amount::whole(15)
+        );
+        assert!(read_retro_payments(&storage).unwrap().is_empty());
+        let minted = pay_funding(&mut storage, &native_token).unwrap();
+        assert_eq!(minted, token::Amount::default());
+
+        // Every payment has been added to the total supply
+        assert_eq!(
+            total_supply(&storage),
+            initial_supply + token::Amount::whole(25)
+        );
+        assert_eq!(minted_by_pgf(&storage), token::Amount::whole(25));
+    }
+}
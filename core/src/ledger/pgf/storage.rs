@@ -0,0 +1,87 @@
+//! Public goods funding storage
+
+use crate::ledger::pgf::ADDRESS;
+use crate::types::address::Address;
+use crate::types::storage::{DbKeySeg, Key, KeySeg};
+
+const COUNCIL_KEY: &str = "council";
+const STREAM_PREFIX: &str = "stream";
+const RETRO_PAYMENT_PREFIX: &str = "retro";
+
+/// Check if a key is inside the PGF address space
+pub fn is_pgf_key(key: &Key) -> bool {
+    matches!(&key.segments[0], DbKeySeg::AddressSeg(addr) if addr == &ADDRESS)
+}
+
+/// Check if a key is the PGF council key
+pub fn is_council_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+        DbKeySeg::AddressSeg(addr),
+        DbKeySeg::StringSeg(council),
+    ] if addr == &ADDRESS && council == COUNCIL_KEY)
+}
+
+/// Check if a key is the key of a continuous funding stream and return the
+/// recipient of the stream
+pub fn is_stream_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::AddressSeg(recipient),
+        ] if addr == &ADDRESS && prefix == STREAM_PREFIX => {
+            Some(recipient)
+        }
+        _ => None,
+    }
+}
+
+/// Check if a key is the key of a pending retroactive payment and return the
+/// recipient of the payment
+pub fn is_retro_payment_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(addr),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::AddressSeg(recipient),
+        ] if addr == &ADDRESS && prefix == RETRO_PAYMENT_PREFIX => {
+            Some(recipient)
+        }
+        _ => None,
+    }
+}
+
+/// Get the key of the PGF council
+pub fn get_council_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&COUNCIL_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the prefix of the continuous funding streams
+pub fn stream_prefix() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&STREAM_PREFIX.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key of the continuous funding stream of a recipient
+pub fn get_stream_key(recipient: &Address) -> Key {
+    stream_prefix()
+        .push(recipient)
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the prefix of the pending retroactive payments
+pub fn retro_payment_prefix() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&RETRO_PAYMENT_PREFIX.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key of the pending retroactive payment of a recipient
+pub fn get_retro_payment_key(recipient: &Address) -> Key {
+    retro_payment_prefix()
+        .push(recipient)
+        .expect("Cannot obtain a storage key")
+}
@@ -1,8 +1,284 @@
 //! SlashFund library code
+//!
+//! The slash fund is the treasury of the governance. It receives the funds
+//! locked by the proposals that are forfeited and it's spent by the treasury
+//! spend proposals, whose payments are executed natively when they pass.
 
+use crate::ledger::storage_api::{self, StorageRead, StorageWrite};
 use crate::types::address::{Address, InternalAddress};
+use crate::types::governance::{
+    TreasuryPayment, TreasuryRecord, TreasurySpend, TreasuryVesting,
+};
+use crate::types::storage::Epoch;
+use crate::types::token;
 
 /// Internal SlashFund address
 pub const ADDRESS: Address = Address::Internal(InternalAddress::SlashFund);
 
 pub mod storage;
+
+/// Read the balance of a token in the treasury
+pub fn read_balance<S>(
+    storage: &S,
+    token: &Address,
+) -> storage_api::Result<token::Amount>
+where
+    S: StorageRead,
+{
+    let balance = storage.read(&token::balance_key(token, &ADDRESS))?;
+    Ok(balance.unwrap_or_default())
+}
+
+/// Read the vested treasury spends that are still to be paid
+pub fn read_vesting<S>(storage: &S) -> storage_api::Result<Vec<TreasuryVesting>>
+where
+    S: StorageRead,
+{
+    storage_api::iter_prefix(storage, &storage::vesting_prefix())?
+        .map(|result| result.map(|(_key, vesting)| vesting))
+        .collect()
+}
+
+/// Read the history of the treasury from the oldest record
+pub fn read_history<S>(storage: &S) -> storage_api::Result<Vec<TreasuryRecord>>
+where
+    S: StorageRead,
+{
+    let length: u64 = storage
+        .read(&storage::get_history_length_key())?
+        .unwrap_or_default();
+    (0..length)
+        .map(|index| {
+            storage
+                .read(&storage::get_history_key(index))?
+                .ok_or_else(|| {
+                    storage_api::Error::new_const(
+                        "A record of the treasury history is missing",
+                    )
+                })
+        })
+        .collect()
+}
+
+/// Record the funds of a proposal that were forfeited to the treasury
+pub fn record_deposit<S>(
+    storage: &mut S,
+    proposal_id: u64,
+    epoch: Epoch,
+    amount: token::Amount,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    push_history(
+        storage,
+        TreasuryRecord::Deposit {
+            proposal_id,
+            epoch,
+            amount,
+        },
+    )
+}
+
+/// Execute the treasury spend of a passed proposal. The whole amount must be
+/// available in the treasury, otherwise nothing is paid and `None` is
+/// returned. Without vesting, the amount is paid at once. Otherwise, only the
+/// first installment is paid and the rest is paid by [`pay_vesting`] in the
+/// next epochs. Returns the payment that has been made.
+pub fn spend<S>(
+    storage: &mut S,
+    proposal_id: u64,
+    epoch: Epoch,
+    spend: &TreasurySpend,
+) -> storage_api::Result<Option<TreasuryPayment>>
+where
+    S: StorageRead + StorageWrite,
+{
+    if read_balance(storage, &spend.token)? < spend.amount {
+        return Ok(None);
+    }
+    let vesting = TreasuryVesting {
+        proposal_id,
+        recipient: spend.recipient.clone(),
+        token: spend.token.clone(),
+        remaining_amount: spend.amount,
+        remaining_epochs: spend.vesting_epochs.unwrap_or(1),
+    };
+    pay_installment(storage, epoch, vesting)
+}
+
+/// Pay the installments of the vested treasury spends that are due in the
+/// epoch. An installment that can't be paid because the treasury doesn't
+/// have enough balance is retried in the next epochs. Returns the payments
+/// that have been made.
+pub fn pay_vesting<S>(
+    storage: &mut S,
+    epoch: Epoch,
+) -> storage_api::Result<Vec<TreasuryPayment>>
+where
+    S: StorageRead + StorageWrite,
+{
+    let mut payments = vec![];
+    for vesting in read_vesting(storage)? {
+        if let Some(payment) = pay_installment(storage, epoch, vesting)? {
+            payments.push(payment);
+        }
+    }
+    Ok(payments)
+}
+
+/// Pay the next installment of a treasury spend, if the treasury has enough
+/// balance, and update the rest of the spend that is still to be paid
+fn pay_installment<S>(
+    storage: &mut S,
+    epoch: Epoch,
+    mut vesting: TreasuryVesting,
+) -> storage_api::Result<Option<TreasuryPayment>>
+where
+    S: StorageRead + StorageWrite,
+{
+    let amount = vesting.next_installment();
+    let mut balance = read_balance(storage, &vesting.token)?;
+    if balance < amount {
+        return Ok(None);
+    }
+    balance.spend(&amount);
+    storage.write(&token::balance_key(&vesting.token, &ADDRESS), balance)?;
+    let recipient_key = token::balance_key(&vesting.token, &vesting.recipient);
+    let mut recipient_balance: token::Amount =
+        storage.read(&recipient_key)?.unwrap_or_default();
+    recipient_balance.receive(&amount);
+    storage.write(&recipient_key, recipient_balance)?;
+
+    vesting.remaining_amount.spend(&amount);
+    vesting.remaining_epochs = vesting.remaining_epochs.saturating_sub(1);
+    let vesting_key = storage::get_vesting_key(vesting.proposal_id);
+    if vesting.remaining_epochs == 0 {
+        storage.delete(&vesting_key)?;
+    } else {
+        storage.write(&vesting_key, &vesting)?;
+    }
+
+    let payment = TreasuryPayment {
+        proposal_id: vesting.proposal_id,
+        epoch,
+        recipient: vesting.recipient,
+        token: vesting.token,
+        amount,
+    };
+    push_history(storage, TreasuryRecord::Payment(payment.clone()))?;
+    Ok(Some(payment))
+}
+
+fn push_history<S>(
+    storage: &mut S,
+    record: TreasuryRecord,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let length_key = storage::get_history_length_key();
+    let length: u64 = storage.read(&length_key)?.unwrap_or_default();
+    storage.write(&storage::get_history_key(length), record)?;
+    storage.write(&length_key, length + 1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ledger::storage::testing::TestStorage;
+    use crate::types::address::{self, nam};
+
+    /// Test spending the treasury at once and with vesting.
+    #[test]
+    fn test_treasury_spend() {
+        let mut storage = TestStorage::default();
+        let native_token = nam();
+        let recipient = address::testing::established_address_1();
+        let balance = |storage: &TestStorage, owner: &Address| {
+            StorageRead::read::<token::Amount>(
+                storage,
+                &token::balance_key(&native_token, owner),
+            )
+            .unwrap()
+            .unwrap_or_default()
+        };
+        let mut spend = TreasurySpend {
+            recipient: recipient.clone(),
+            token: native_token.clone(),
+            amount: token::Amount::whole(10),
+            vesting_epochs: None,
+        };
+
+        // Nothing is paid when the treasury doesn't have the whole amount
+        StorageWrite::write(
+            &mut storage,
+            &token::balance_key(&native_token, &ADDRESS),
+            token::Amount::whole(5),
+        )
+        .unwrap();
+        let payment = super::spend(&mut storage, 0, Epoch(1), &spend).unwrap();
+        assert!(payment.is_none());
+        assert_eq!(balance(&storage, &recipient), 0.into());
+
+        record_deposit(&mut storage, 1, Epoch(1), token::Amount::whole(20))
+            .unwrap();
+        StorageWrite::write(
+            &mut storage,
+            &token::balance_key(&native_token, &ADDRESS),
+            token::Amount::whole(25),
+        )
+        .unwrap();
+
+        // The amount is paid at once without vesting
+        let payment = super::spend(&mut storage, 2, Epoch(2), &spend)
+            .unwrap()
+            .unwrap();
+        assert_eq!(payment.amount, token::Amount::whole(10));
+        assert_eq!(balance(&storage, &recipient), token::Amount::whole(10));
+        assert_eq!(
+            read_balance(&storage, &native_token).unwrap(),
+            token::Amount::whole(15)
+        );
+        assert!(read_vesting(&storage).unwrap().is_empty());
+
+        // With vesting, the first installment is paid at once and the last
+        // one pays the remainder of the amount
+        spend.amount = token::Amount::from(10);
+        spend.vesting_epochs = Some(3);
+        let payment = super::spend(&mut storage, 3, Epoch(2), &spend)
+            .unwrap()
+            .unwrap();
+        assert_eq!(payment.amount, token::Amount::from(3));
+        let payments = pay_vesting(&mut storage, Epoch(3)).unwrap();
+        assert_eq!(payments.len(), 1);
+        assert_eq!(payments[0].amount, token::Amount::from(3));
+        let payments = pay_vesting(&mut storage, Epoch(4)).unwrap();
+        assert_eq!(payments[0].amount, token::Amount::from(4));
+        assert!(read_vesting(&storage).unwrap().is_empty());
+        assert!(pay_vesting(&mut storage, Epoch(5)).unwrap().is_empty());
+        assert_eq!(
+            balance(&storage, &recipient),
+            token::Amount::whole(10) + token::Amount::from(10)
+        );
+
+        let history = read_history(&storage).unwrap();
+        assert_eq!(history.len(), 5);
+        assert_eq!(
+            history[0],
+            TreasuryRecord::Deposit {
+                proposal_id: 1,
+                epoch: Epoch(1),
+                amount: token::Amount::whole(20),
+            }
+        );
+        assert!(matches!(
+            &history[4],
+            TreasuryRecord::Payment(TreasuryPayment {
+                proposal_id: 3,
+                epoch: Epoch(4),
+                ..
+            })
+        ));
+    }
+}
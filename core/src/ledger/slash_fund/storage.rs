@@ -1,8 +1,43 @@
 //! Slash fund storage
 
-use crate::types::storage::{DbKeySeg, Key};
+use crate::ledger::slash_fund::ADDRESS;
+use crate::types::storage::{DbKeySeg, Key, KeySeg};
+
+const VESTING_PREFIX: &str = "vesting";
+const HISTORY_PREFIX: &str = "history";
+const HISTORY_LENGTH_KEY: &str = "history_length";
 
 /// Check if a key is a slash fund key
 pub fn is_slash_fund_key(key: &Key) -> bool {
-    matches!(&key.segments[0], DbKeySeg::AddressSeg(addr) if addr == &super::ADDRESS)
+    matches!(&key.segments[0], DbKeySeg::AddressSeg(addr) if addr == &ADDRESS)
+}
+
+/// Get the prefix of the vested treasury spends that are still to be paid
+pub fn vesting_prefix() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&VESTING_PREFIX.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key of the vested treasury spend of a proposal
+pub fn get_vesting_key(proposal_id: u64) -> Key {
+    vesting_prefix()
+        .push(&proposal_id.to_string())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key of a record of the treasury history by its index
+pub fn get_history_key(index: u64) -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&HISTORY_PREFIX.to_owned())
+        .expect("Cannot obtain a storage key")
+        .push(&index.to_string())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Get the key of the number of records of the treasury history
+pub fn get_history_length_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&HISTORY_LENGTH_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
 }
@@ -46,6 +46,8 @@ pub enum Error {
     Ics23MultiLeaf,
     #[error("A Tendermint proof can only be constructed from an ICS23 proof.")]
     TendermintProof,
+    #[error("Invalid proof: {0}")]
+    InvalidProof(String),
 }
 
 /// Result for functions that may fail
@@ -433,6 +435,7 @@ impl<H: StorageHasher + Default> MerkleTree<H> {
 }
 
 /// The root hash of the merkle tree as bytes
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct MerkleRoot(pub Vec<u8>);
 
 impl From<H256> for MerkleRoot {
@@ -550,6 +553,126 @@ pub struct Proof {
     pub base_proof: CommitmentProof,
 }
 
+impl Proof {
+    /// Verify the proof of the given value of the key, or of the absence of
+    /// the key when the value is `None`. On success, returns the root of the
+    /// Merkle tree that the proof was made from, which must then be checked
+    /// against a trusted root.
+    pub fn verify<H>(&self, value: Option<&[u8]>) -> Result<MerkleRoot>
+    where
+        H: StorageHasher + Default,
+    {
+        let (store_type, sub_key) = StoreType::sub_key(&self.key)?;
+        let specs = if store_type == StoreType::Ibc {
+            ics23_specs::ibc_proof_specs::<H>()
+        } else {
+            ics23_specs::proof_specs::<H>()
+        };
+        let invalid = || {
+            Error::InvalidProof(format!(
+                "The proof doesn't match the key {}",
+                self.key
+            ))
+        };
+
+        // First, the sub proof is verified. Next the base proof is verified
+        // with the sub root
+        let sub_key = sub_key.to_string();
+        let sub_root = match (&self.sub_proof.proof, value) {
+            (Some(Ics23Proof::Exist(ep)), Some(value)) => {
+                let sub_root = existence_root(ep)?;
+                ics23::verify_membership(
+                    &self.sub_proof,
+                    &specs[0],
+                    &sub_root,
+                    sub_key.as_bytes(),
+                    value,
+                )
+                .then_some(sub_root)
+            }
+            (Some(Ics23Proof::Nonexist(nep)), None) => {
+                let neighbour = nep
+                    .left
+                    .as_ref()
+                    .or(nep.right.as_ref())
+                    .ok_or_else(invalid)?;
+                let sub_root = existence_root(neighbour)?;
+                ics23::verify_non_membership(
+                    &self.sub_proof,
+                    &specs[0],
+                    &sub_root,
+                    sub_key.as_bytes(),
+                )
+                .then_some(sub_root)
+            }
+            _ => None,
+        }
+        .ok_or_else(invalid)?;
+
+        let base_root = match &self.base_proof.proof {
+            Some(Ics23Proof::Exist(ep)) => {
+                let base_root = existence_root(ep)?;
+                ics23::verify_membership(
+                    &self.base_proof,
+                    &specs[1],
+                    &base_root,
+                    store_type.to_string().as_bytes(),
+                    &sub_root,
+                )
+                .then_some(base_root)
+            }
+            _ => None,
+        }
+        .ok_or_else(invalid)?;
+        Ok(MerkleRoot(base_root))
+    }
+
+    /// Decode the proofs from the operations of a Tendermint proof, in which
+    /// every storage key proof is made of a sub proof followed by a base
+    /// proof.
+    #[cfg(any(feature = "tendermint", feature = "tendermint-abcipp"))]
+    pub fn from_tm_proof(
+        proof: &crate::tendermint::merkle::proof::Proof,
+    ) -> Result<Vec<Self>> {
+        use prost::Message;
+
+        let decode = |data: &[u8]| {
+            CommitmentProof::decode(data)
+                .map_err(|err| Error::InvalidProof(err.to_string()))
+        };
+        let chunks = proof.ops.chunks_exact(2);
+        if !chunks.remainder().is_empty() {
+            return Err(Error::InvalidProof(
+                "Every key must have a sub proof and a base proof".to_owned(),
+            ));
+        }
+        chunks
+            .map(|ops| {
+                let (sub_op, base_op) = (&ops[0], &ops[1]);
+                if sub_op.key != base_op.key {
+                    return Err(Error::InvalidProof(
+                        "The sub and base proofs are for different keys"
+                            .to_owned(),
+                    ));
+                }
+                let key = std::str::from_utf8(&sub_op.key)
+                    .map_err(|err| Error::InvalidProof(err.to_string()))?;
+                Ok(Self {
+                    key: Key::parse(key).map_err(Error::InvalidKey)?,
+                    sub_proof: decode(&sub_op.data)?,
+                    base_proof: decode(&base_op.data)?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Calculate the root from an ICS23 existence proof
+fn existence_root(proof: &ExistenceProof) -> Result<Vec<u8>> {
+    ics23::calculate_existence_root(proof)
+        .map_err(|err| Error::InvalidProof(err.to_string()))
+}
+
 #[cfg(any(feature = "tendermint", feature = "tendermint-abcipp"))]
 impl From<Proof> for crate::tendermint::merkle::proof::Proof {
     fn from(
@@ -874,4 +997,41 @@ mod test {
         );
         assert!(basetree_verification_res);
     }
+
+    #[test]
+    fn test_proof_verify() {
+        let mut tree = MerkleTree::<Sha256Hasher>::default();
+
+        let key_prefix: Key =
+            Address::Internal(InternalAddress::Ibc).to_db_key().into();
+        let ibc_key = key_prefix.push(&"test".to_string()).unwrap();
+        let ibc_non_key = key_prefix.push(&"test2".to_string()).unwrap();
+        let key_prefix: Key =
+            Address::Internal(InternalAddress::PoS).to_db_key().into();
+        let pos_key = key_prefix.push(&"test".to_string()).unwrap();
+
+        let ibc_val = [1u8; 8].to_vec();
+        tree.update(&ibc_key, ibc_val.clone()).unwrap();
+        let pos_val = [2u8; 8].to_vec();
+        tree.update(&pos_key, pos_val.clone()).unwrap();
+        let root = tree.root();
+
+        for (key, val) in [(&ibc_key, &ibc_val), (&pos_key, &pos_val)] {
+            let MembershipProof::ICS23(proof) = tree
+                .get_sub_tree_existence_proof(
+                    std::array::from_ref(key),
+                    vec![val],
+                )
+                .unwrap();
+            let proof = tree.get_sub_tree_proof(key, proof).unwrap();
+            assert_eq!(proof.verify::<Sha256Hasher>(Some(val)).unwrap(), root);
+            // The proof must not verify another value or the key's absence
+            assert!(proof.verify::<Sha256Hasher>(Some(&[3u8; 8])).is_err());
+            assert!(proof.verify::<Sha256Hasher>(None).is_err());
+        }
+
+        let proof = tree.get_non_existence_proof(&ibc_non_key).unwrap();
+        assert_eq!(proof.verify::<Sha256Hasher>(None).unwrap(), root);
+        assert!(proof.verify::<Sha256Hasher>(Some(&ibc_val)).is_err());
+    }
 }
@@ -2,17 +2,21 @@
 
 use std::cell::RefCell;
 use std::collections::{btree_map, BTreeMap};
-use std::ops::Bound::{Excluded, Included};
+use std::ops::Bound::{Excluded, Included, Unbounded};
 use std::path::Path;
 use std::str::FromStr;
 
 use borsh::{BorshDeserialize, BorshSerialize};
 
-use super::merkle_tree::{MerkleTreeStoresRead, StoreType};
+use super::merkle_tree::{
+    MerkleTreeStoresRead, MerkleTreeStoresWrite, StoreType,
+};
 use super::{
     BlockStateRead, BlockStateWrite, DBIter, DBWriteBatch, Error, Result, DB,
 };
-use crate::ledger::storage::types::{self, KVBytes, PrefixIterator};
+use crate::ledger::storage::types::{
+    self, EventIndex, KVBytes, PrefixIterator, StoredEvent,
+};
 #[cfg(feature = "ferveo-tpke")]
 use crate::types::internal::TxQueue;
 use crate::types::storage::{
@@ -206,30 +210,7 @@ impl DB for MockDB {
 
         let prefix_key = Key::from(height.to_db_key());
         // Merkle tree
-        {
-            let prefix_key = prefix_key
-                .push(&"tree".to_owned())
-                .map_err(Error::KeyError)?;
-            for st in StoreType::iter() {
-                let prefix_key = prefix_key
-                    .push(&st.to_string())
-                    .map_err(Error::KeyError)?;
-                let root_key = prefix_key
-                    .push(&"root".to_owned())
-                    .map_err(Error::KeyError)?;
-                self.0.borrow_mut().insert(
-                    root_key.to_string(),
-                    types::encode(merkle_tree_stores.root(st)),
-                );
-                let store_key = prefix_key
-                    .push(&"store".to_owned())
-                    .map_err(Error::KeyError)?;
-                self.0.borrow_mut().insert(
-                    store_key.to_string(),
-                    merkle_tree_stores.store(st).encode(),
-                );
-            }
-        }
+        self.write_merkle_tree_stores(height, &merkle_tree_stores)?;
         // Block header
         {
             if let Some(h) = header {
@@ -345,6 +326,33 @@ impl DB for MockDB {
         Ok(Some(merkle_tree_stores))
     }
 
+    fn write_merkle_tree_stores(
+        &mut self,
+        height: BlockHeight,
+        stores: &MerkleTreeStoresWrite,
+    ) -> Result<()> {
+        let prefix_key = Key::from(height.to_db_key())
+            .push(&"tree".to_owned())
+            .map_err(Error::KeyError)?;
+        for st in StoreType::iter() {
+            let prefix_key =
+                prefix_key.push(&st.to_string()).map_err(Error::KeyError)?;
+            let root_key = prefix_key
+                .push(&"root".to_owned())
+                .map_err(Error::KeyError)?;
+            self.0
+                .borrow_mut()
+                .insert(root_key.to_string(), types::encode(stores.root(st)));
+            let store_key = prefix_key
+                .push(&"store".to_owned())
+                .map_err(Error::KeyError)?;
+            self.0
+                .borrow_mut()
+                .insert(store_key.to_string(), stores.store(st).encode());
+        }
+        Ok(())
+    }
+
     fn read_subspace_val(&self, key: &Key) -> Result<Option<Vec<u8>>> {
         let key = Key::parse("subspace").map_err(Error::KeyError)?.join(key);
         Ok(self.0.borrow().get(&key.to_string()).cloned())
@@ -437,6 +445,81 @@ impl DB for MockDB {
             None => 0,
         })
     }
+
+    fn write_events(
+        &mut self,
+        height: BlockHeight,
+        events: &[StoredEvent],
+    ) -> Result<()> {
+        let mut db = self.0.borrow_mut();
+        for (position, event) in events.iter().enumerate() {
+            let mut keys = event.db_keys(height, position).into_iter();
+            let primary_key = keys.next().expect("There is a primary key");
+            for key in keys {
+                db.insert(events_key(&key), primary_key.as_bytes().to_vec());
+            }
+            db.insert(events_key(&primary_key), types::encode(event));
+        }
+        Ok(())
+    }
+
+    fn read_events(
+        &self,
+        index: &EventIndex,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<StoredEvent>> {
+        let db = self.0.borrow();
+        let prefix = events_key(&index.db_prefix());
+        let mut events = vec![];
+        for (_key, value) in db
+            .range::<String, _>((Included(&prefix), Unbounded))
+            .take_while(|(key, _value)| key.starts_with(&prefix))
+            .skip(offset)
+            .take(limit)
+        {
+            let bytes = match index {
+                EventIndex::Height(_) => Some(value),
+                // The secondary indices point to the primary key
+                EventIndex::TxHash(_) | EventIndex::EventType(_) => {
+                    let primary_key = String::from_utf8(value.clone())
+                        .map_err(|e| Error::Temporary {
+                            error: format!(
+                                "Cannot convert key from utf8 bytes: {}",
+                                e
+                            ),
+                        })?;
+                    db.get(&events_key(&primary_key))
+                }
+            };
+            if let Some(bytes) = bytes {
+                events.push(types::decode(bytes).map_err(Error::CodingError)?);
+            }
+        }
+        Ok(events)
+    }
+
+    fn prune_events(&mut self, below: BlockHeight) -> Result<()> {
+        let mut db = self.0.borrow_mut();
+        let prefix = events_key(&StoredEvent::primary_db_prefix());
+        let upper_bound = events_key(&EventIndex::Height(below).db_prefix());
+        let pruned: Vec<(String, Vec<u8>)> = db
+            .range::<String, _>((Included(&prefix), Excluded(&upper_bound)))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        for (key, value) in pruned {
+            let event: StoredEvent =
+                types::decode(value).map_err(Error::CodingError)?;
+            let (height, position) = key
+                .strip_prefix(EVENTS_PREFIX)
+                .and_then(StoredEvent::parse_db_key)
+                .ok_or_else(|| Error::UnknownKey { key: key.clone() })?;
+            for key in event.db_keys(height, position) {
+                db.remove(&events_key(&key));
+            }
+        }
+        Ok(())
+    }
 }
 
 impl<'iter> DBIter<'iter> for MockDB {
@@ -455,6 +538,38 @@ impl<'iter> DBIter<'iter> for MockDB {
         let iter = self.0.borrow().clone().into_iter();
         MockPrefixIterator::new(MockIterator { prefix, iter }, db_prefix)
     }
+
+    fn iter_old_diffs(
+        &'iter self,
+        height: BlockHeight,
+        prefix: &Key,
+    ) -> MockPrefixIterator {
+        iter_diffs_prefix(self, height, prefix, true)
+    }
+
+    fn iter_new_diffs(
+        &'iter self,
+        height: BlockHeight,
+        prefix: &Key,
+    ) -> MockPrefixIterator {
+        iter_diffs_prefix(self, height, prefix, false)
+    }
+}
+
+fn iter_diffs_prefix(
+    db: &MockDB,
+    height: BlockHeight,
+    prefix: &Key,
+    is_old: bool,
+) -> MockPrefixIterator {
+    let db_prefix = format!(
+        "{}/diffs/{}/",
+        height.raw(),
+        if is_old { "old" } else { "new" }
+    );
+    let prefix = format!("{}{}", db_prefix, prefix);
+    let iter = db.0.borrow().clone().into_iter();
+    MockPrefixIterator::new(MockIterator { prefix, iter }, db_prefix)
 }
 
 /// A prefix iterator base for the [`MockPrefixIterator`].
@@ -524,6 +639,13 @@ impl DBWriteBatch for MockDBWriteBatch {
     }
 }
 
+/// The prefix of the event store's keys in the [`MockDB`]
+const EVENTS_PREFIX: &str = "events/";
+
+fn events_key(key: &str) -> String {
+    format!("{}{}", EVENTS_PREFIX, key)
+}
+
 fn unknown_key_error(key: &str) -> Result<()> {
     Err(Error::UnknownKey {
         key: key.to_owned(),
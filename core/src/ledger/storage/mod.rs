@@ -8,7 +8,7 @@ pub mod traits;
 pub mod types;
 
 use core::fmt::Debug;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use borsh::{BorshDeserialize, BorshSerialize};
 use masp_primitives::asset_type::AssetType;
@@ -28,6 +28,7 @@ use rayon::iter::{
 use rayon::prelude::ParallelSlice;
 use thiserror::Error;
 pub use traits::{Sha256Hasher, StorageHasher};
+pub use types::{EventIndex, StoredEvent};
 
 use crate::ledger::gas::MIN_STORAGE_GAS;
 use crate::ledger::parameters::{self, EpochDuration, Parameters};
@@ -47,7 +48,7 @@ use crate::types::chain::{ChainId, CHAIN_ID_LENGTH};
 use crate::types::internal::TxQueue;
 use crate::types::storage::{
     BlockHash, BlockHeight, BlockResults, Epoch, Epochs, Header, Key, KeySeg,
-    TxIndex, BLOCK_HASH_LENGTH,
+    StorageChange, TxIndex, BLOCK_HASH_LENGTH,
 };
 use crate::types::time::DateTimeUtc;
 use crate::types::token;
@@ -235,6 +236,14 @@ pub trait DB: std::fmt::Debug {
         height: BlockHeight,
     ) -> Result<Option<MerkleTreeStoresRead>>;
 
+    /// Write the merkle tree stores with the given height, replacing the ones
+    /// written with the block's metadata
+    fn write_merkle_tree_stores(
+        &mut self,
+        height: BlockHeight,
+        stores: &MerkleTreeStoresWrite,
+    ) -> Result<()>;
+
     /// Read the latest value for account subspace key from the DB
     fn read_subspace_val(&self, key: &Key) -> Result<Option<Vec<u8>>>;
 
@@ -294,6 +303,27 @@ pub trait DB: std::fmt::Debug {
         height: BlockHeight,
         key: &Key,
     ) -> Result<i64>;
+
+    /// Write the events emitted at the given height into the event store,
+    /// indexed by the height, the hash of their transaction and their type
+    fn write_events(
+        &mut self,
+        height: BlockHeight,
+        events: &[StoredEvent],
+    ) -> Result<()>;
+
+    /// Read the events with the given index from the event store, ordered by
+    /// the height and the position in their block. The first `offset` events
+    /// are skipped and at most `limit` events are returned.
+    fn read_events(
+        &self,
+        index: &EventIndex,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<StoredEvent>>;
+
+    /// Delete the events emitted below the given height from the event store
+    fn prune_events(&mut self, below: BlockHeight) -> Result<()>;
 }
 
 /// A database prefix iterator.
@@ -307,6 +337,22 @@ pub trait DBIter<'iter> {
 
     /// Read results subspace key value pairs from the DB
     fn iter_results(&'iter self) -> Self::PrefixIter;
+
+    /// Read the old values of the account subspace keys with the given prefix
+    /// that have been modified or deleted at the given height
+    fn iter_old_diffs(
+        &'iter self,
+        height: BlockHeight,
+        prefix: &Key,
+    ) -> Self::PrefixIter;
+
+    /// Read the new values of the account subspace keys with the given prefix
+    /// that have been written at the given height
+    fn iter_new_diffs(
+        &'iter self,
+        height: BlockHeight,
+        prefix: &Key,
+    ) -> Self::PrefixIter;
 }
 
 /// Atomic batch write.
@@ -511,6 +557,94 @@ where
         (self.db.iter_prefix(prefix), prefix.len() as _)
     }
 
+    /// Returns the key-value pairs with the given prefix as they were at the
+    /// given height, ordered by storage keys, and the gas cost. For heights
+    /// before the last committed height, the keys that have been deleted
+    /// since are found from the old values in the DB diffs. Only the diffs of
+    /// the keys with the prefix are read, but one lookup is made for every
+    /// height since the given one, so the callers should limit how far in the
+    /// past it can be.
+    pub fn read_prefix_with_height(
+        &self,
+        prefix: &Key,
+        height: BlockHeight,
+    ) -> Result<(Vec<(Key, Vec<u8>)>, u64)> {
+        let (iter, mut gas) = self.iter_prefix(prefix);
+        if height >= self.last_height {
+            let mut pairs = vec![];
+            for (key, value, iter_gas) in iter {
+                let key = Key::parse(key).map_err(Error::KeyError)?;
+                pairs.push((key, value));
+                gas += iter_gas;
+            }
+            return Ok((pairs, gas));
+        }
+
+        // Collect the keys that exist now and the keys that have been
+        // modified or deleted after the given height. Only the diffs of the
+        // keys with the prefix are iterated.
+        let mut keys = BTreeSet::new();
+        for (key, _value, iter_gas) in iter {
+            keys.insert(key);
+            gas += iter_gas;
+        }
+        for diff_height in height.0 + 1..=self.last_height.0 {
+            for (key, _value, iter_gas) in
+                self.db.iter_old_diffs(BlockHeight(diff_height), prefix)
+            {
+                keys.insert(key);
+                gas += iter_gas;
+            }
+        }
+
+        let mut pairs = vec![];
+        for key in keys {
+            let key = Key::parse(key).map_err(Error::KeyError)?;
+            let (value, read_gas) = self.read_with_height(&key, height)?;
+            gas += read_gas;
+            if let Some(value) = value {
+                pairs.push((key, value));
+            }
+        }
+        Ok((pairs, gas))
+    }
+
+    /// Returns the changes of the account subspace keys committed at the
+    /// given height, ordered by storage keys, and the gas cost. If a prefix
+    /// is given, only the keys matching it are included.
+    pub fn read_changes(
+        &self,
+        height: BlockHeight,
+        prefix: Option<&Key>,
+    ) -> Result<(Vec<StorageChange>, u64)> {
+        let default_prefix = Key::default();
+        let prefix = prefix.unwrap_or(&default_prefix);
+        let mut gas = 0;
+        let mut changes: BTreeMap<String, (Option<Vec<u8>>, Option<Vec<u8>>)> =
+            BTreeMap::new();
+        for (key, old_value, iter_gas) in self.db.iter_old_diffs(height, prefix)
+        {
+            changes.entry(key).or_default().0 = Some(old_value);
+            gas += iter_gas;
+        }
+        for (key, new_value, iter_gas) in self.db.iter_new_diffs(height, prefix)
+        {
+            changes.entry(key).or_default().1 = Some(new_value);
+            gas += iter_gas;
+        }
+        let changes = changes
+            .into_iter()
+            .map(|(key, (old_value, new_value))| {
+                Ok(StorageChange {
+                    key: Key::parse(key).map_err(Error::KeyError)?,
+                    old_value,
+                    new_value,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok((changes, gas))
+    }
+
     /// Returns a prefix iterator and the gas cost
     pub fn iter_results(&self) -> (<D as DBIter<'_>>::PrefixIter, u64) {
         (self.db.iter_results(), 0)
@@ -607,7 +741,23 @@ where
         (self.block.hash.clone(), BLOCK_HASH_LENGTH as _)
     }
 
-    /// Get the existence proof
+    /// Rebuild the Merkle tree as it was committed at the given height from
+    /// the tree stores persisted in the DB. Returns
+    /// [`Error::NoMerkleTree`] if the stores at this height are not
+    /// available.
+    pub fn read_merkle_tree(
+        &self,
+        height: BlockHeight,
+    ) -> Result<MerkleTree<H>> {
+        match self.db.read_merkle_tree_stores(height)? {
+            Some(stores) => Ok(MerkleTree::<H>::new(stores)),
+            None => Err(Error::NoMerkleTree { height }),
+        }
+    }
+
+    /// Get the existence proof of the given key with its value at the given
+    /// height. For heights before the last committed height, the proof is
+    /// made from the Merkle tree rebuilt from the DB.
     #[cfg(any(feature = "tendermint", feature = "tendermint-abcipp"))]
     pub fn get_existence_proof(
         &self,
@@ -615,60 +765,64 @@ where
         value: StorageBytes,
         height: BlockHeight,
     ) -> Result<Proof> {
+        let mut proofs =
+            self.get_existence_proofs(std::iter::once((key, value)), height)?;
+        Ok(proofs.remove(0))
+    }
+
+    /// Get the existence proofs of the given keys with their values at the
+    /// given height. For heights before the last committed height, the
+    /// Merkle tree is rebuilt from the DB only once for all the keys.
+    #[cfg(any(feature = "tendermint", feature = "tendermint-abcipp"))]
+    pub fn get_existence_proofs<'a>(
+        &self,
+        pairs: impl IntoIterator<Item = (&'a Key, StorageBytes<'a>)>,
+        height: BlockHeight,
+    ) -> Result<Vec<Proof>> {
         use std::array;
 
-        if height >= self.get_block_height().0 {
-            let MembershipProof::ICS23(proof) = self
-                .block
-                .tree
-                .get_sub_tree_existence_proof(array::from_ref(key), vec![value])
-                .map_err(Error::MerkleTreeError)?;
-            self.block
-                .tree
-                .get_sub_tree_proof(key, proof)
-                .map(Into::into)
-                .map_err(Error::MerkleTreeError)
+        let past_tree;
+        let tree = if height >= self.last_height {
+            &self.block.tree
         } else {
-            match self.db.read_merkle_tree_stores(height)? {
-                Some(stores) => {
-                    let tree = MerkleTree::<H>::new(stores);
-                    let MembershipProof::ICS23(proof) = tree
-                        .get_sub_tree_existence_proof(
-                            array::from_ref(key),
-                            vec![value],
-                        )
-                        .map_err(Error::MerkleTreeError)?;
-                    tree.get_sub_tree_proof(key, proof)
-                        .map(Into::into)
-                        .map_err(Error::MerkleTreeError)
-                }
-                None => Err(Error::NoMerkleTree { height }),
-            }
-        }
+            past_tree = self.read_merkle_tree(height)?;
+            &past_tree
+        };
+        pairs
+            .into_iter()
+            .map(|(key, value)| {
+                let MembershipProof::ICS23(proof) = tree
+                    .get_sub_tree_existence_proof(
+                        array::from_ref(key),
+                        vec![value],
+                    )
+                    .map_err(Error::MerkleTreeError)?;
+                tree.get_sub_tree_proof(key, proof)
+                    .map(Into::into)
+                    .map_err(Error::MerkleTreeError)
+            })
+            .collect()
     }
 
-    /// Get the non-existence proof
+    /// Get the non-existence proof of the given key at the given height. For
+    /// heights before the last committed height, the proof is made from the
+    /// Merkle tree rebuilt from the DB.
     #[cfg(any(feature = "tendermint", feature = "tendermint-abcipp"))]
     pub fn get_non_existence_proof(
         &self,
         key: &Key,
         height: BlockHeight,
     ) -> Result<Proof> {
-        if height >= self.last_height {
-            self.block
-                .tree
-                .get_non_existence_proof(key)
-                .map(Into::into)
-                .map_err(Error::MerkleTreeError)
+        let past_tree;
+        let tree = if height >= self.last_height {
+            &self.block.tree
         } else {
-            match self.db.read_merkle_tree_stores(height)? {
-                Some(stores) => MerkleTree::<H>::new(stores)
-                    .get_non_existence_proof(key)
-                    .map(Into::into)
-                    .map_err(Error::MerkleTreeError),
-                None => Err(Error::NoMerkleTree { height }),
-            }
-        }
+            past_tree = self.read_merkle_tree(height)?;
+            &past_tree
+        };
+        tree.get_non_existence_proof(key)
+            .map(Into::into)
+            .map_err(Error::MerkleTreeError)
     }
 
     /// Get the current (yet to be committed) block epoch
@@ -926,31 +1080,51 @@ where
 
     /// Update the merkle tree with epoch data
     fn update_epoch_in_merkle_tree(&mut self) -> Result<()> {
+        for (key, value) in self.epoch_merkle_tree_entries()? {
+            self.block.tree.update(&key, value)?;
+        }
+        Ok(())
+    }
+
+    /// The epoch data entries that are only tracked in the merkle tree and
+    /// are not persisted in the subspace
+    fn epoch_merkle_tree_entries(&self) -> Result<[(Key, Vec<u8>); 3]> {
         let key_prefix: Key =
             Address::Internal(InternalAddress::PoS).to_db_key().into();
 
-        let key = key_prefix
+        let height_key = key_prefix
             .push(&"epoch_start_height".to_string())
             .map_err(Error::KeyError)?;
-        self.block
-            .tree
-            .update(&key, types::encode(&self.next_epoch_min_start_height))?;
-
-        let key = key_prefix
+        let time_key = key_prefix
             .push(&"epoch_start_time".to_string())
             .map_err(Error::KeyError)?;
-        self.block
-            .tree
-            .update(&key, types::encode(&self.next_epoch_min_start_time))?;
 
-        let key = key_prefix
-            .push(&"current_epoch".to_string())
-            .map_err(Error::KeyError)?;
-        self.block
-            .tree
-            .update(&key, types::encode(&self.block.epoch))?;
+        Ok([
+            (height_key, types::encode(&self.next_epoch_min_start_height)),
+            (time_key, types::encode(&self.next_epoch_min_start_time)),
+            (current_epoch_key(), types::encode(&self.block.epoch)),
+        ])
+    }
 
-        Ok(())
+    /// Build a new merkle tree from the given subspace key-value pairs and
+    /// the current epoch data. With all the subspace entries of the current
+    /// block, the root of the resulting tree must match the root of the
+    /// block's tree.
+    pub fn rebuild_merkle_tree<V>(
+        &self,
+        subspace: impl IntoIterator<Item = (Key, V)>,
+    ) -> Result<MerkleTree<H>>
+    where
+        V: AsRef<[u8]>,
+    {
+        let mut tree = MerkleTree::<H>::default();
+        for (key, value) in subspace {
+            tree.update(&key, value)?;
+        }
+        for (key, value) in self.epoch_merkle_tree_entries()? {
+            tree.update(&key, value)?;
+        }
+        Ok(tree)
     }
 
     /// Start write batch.
@@ -992,6 +1166,14 @@ where
     }
 }
 
+/// Get the key of the current epoch in the merkle tree. The epoch is only
+/// tracked in the merkle tree and it's not persisted in the subspace.
+pub fn current_epoch_key() -> Key {
+    Key::from(Address::Internal(InternalAddress::PoS).to_db_key())
+        .push(&"current_epoch".to_string())
+        .expect("Cannot obtain a storage key")
+}
+
 impl<D, H> StorageRead for Storage<D, H>
 where
     D: DB + for<'iter_> DBIter<'iter_>,
@@ -3,6 +3,8 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use thiserror::Error;
 
+use crate::types::storage::BlockHeight;
+
 #[allow(missing_docs)]
 #[derive(Error, Debug)]
 pub enum Error {
@@ -32,6 +34,90 @@ where
     T::try_from_slice(bytes.as_ref()).map_err(Error::DeserializationError)
 }
 
+/// The prefix of the event store's primary keys, which index the events by
+/// height
+const EVENTS_BY_HEIGHT: &str = "height";
+/// The prefix of the event store's keys that index the events by the hash of
+/// their transaction
+const EVENTS_BY_TX_HASH: &str = "tx_hash";
+/// The prefix of the event store's keys that index the events by their type
+const EVENTS_BY_TYPE: &str = "type";
+
+/// An event encoded for the DB's event store, together with the values it's
+/// indexed by.
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct StoredEvent {
+    /// The type of the event
+    pub event_type: String,
+    /// The hash of the transaction that emitted the event, if any
+    pub tx_hash: Option<String>,
+    /// The encoded event
+    pub event: Vec<u8>,
+}
+
+/// An index of the DB's event store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventIndex {
+    /// The events emitted at the given height
+    Height(BlockHeight),
+    /// The events emitted by the transaction with the given hash
+    TxHash(String),
+    /// The events of the given type
+    EventType(String),
+}
+
+impl EventIndex {
+    /// The prefix of the keys of this index in the event store. The keys
+    /// within the prefix are ordered by the height and the position of the
+    /// events in their block.
+    pub fn db_prefix(&self) -> String {
+        match self {
+            EventIndex::Height(height) => {
+                format!("{}/{:020}/", EVENTS_BY_HEIGHT, height.0)
+            }
+            EventIndex::TxHash(tx_hash) => {
+                format!("{}/{}/", EVENTS_BY_TX_HASH, tx_hash)
+            }
+            EventIndex::EventType(event_type) => {
+                format!("{}/{}/", EVENTS_BY_TYPE, event_type)
+            }
+        }
+    }
+}
+
+impl StoredEvent {
+    /// The keys of the event in the event store, given the height at which it
+    /// was emitted and its position among the block's events. The first key
+    /// is the primary key under which the event is written. The other keys
+    /// belong to the secondary indices, whose values are the primary key.
+    pub fn db_keys(&self, height: BlockHeight, position: usize) -> Vec<String> {
+        let suffix = format!("{:020}/{:010}", height.0, position);
+        let mut keys = vec![
+            format!("{}/{}", EVENTS_BY_HEIGHT, suffix),
+            format!("{}/{}/{}", EVENTS_BY_TYPE, self.event_type, suffix),
+        ];
+        if let Some(tx_hash) = &self.tx_hash {
+            keys.push(format!("{}/{}/{}", EVENTS_BY_TX_HASH, tx_hash, suffix));
+        }
+        keys
+    }
+
+    /// Parse the height and the position of an event from its primary key in
+    /// the event store.
+    pub fn parse_db_key(key: &str) -> Option<(BlockHeight, usize)> {
+        let mut segments = key.strip_prefix(EVENTS_BY_HEIGHT)?.split('/');
+        let _empty = segments.next()?;
+        let height = segments.next()?.parse().ok()?;
+        let position = segments.next()?.parse().ok()?;
+        Some((BlockHeight(height), position))
+    }
+
+    /// The prefix of all the primary keys in the event store.
+    pub fn primary_db_prefix() -> String {
+        format!("{}/", EVENTS_BY_HEIGHT)
+    }
+}
+
 /// A key-value pair as raw bytes
 pub type KVBytes = (Box<[u8]>, Box<[u8]>);
 
@@ -0,0 +1,207 @@
+//! Token storage API
+
+use super::{Error, Result, StorageRead, StorageWrite};
+use crate::types::address::Address;
+use crate::types::token;
+
+/// Read the balance of the given owner in the given token. The balance is
+/// zero when the owner has never held the token.
+pub fn read_balance<S>(
+    storage: &S,
+    token: &Address,
+    owner: &Address,
+) -> Result<token::Amount>
+where
+    S: StorageRead,
+{
+    let balance = storage.read(&token::balance_key(token, owner))?;
+    Ok(balance.unwrap_or_default())
+}
+
+/// Read the total supply of the given token, which is maintained by
+/// [`mint`] and [`burn`] and initialized at genesis.
+pub fn read_total_supply<S>(
+    storage: &S,
+    token: &Address,
+) -> Result<token::Amount>
+where
+    S: StorageRead,
+{
+    let supply = storage.read(&token::total_supply_key(token))?;
+    Ok(supply.unwrap_or_default())
+}
+
+/// Read the amount of the given token minted by the given minter
+pub fn read_minted<S>(
+    storage: &S,
+    token: &Address,
+    minter: &Address,
+) -> Result<token::Amount>
+where
+    S: StorageRead,
+{
+    let minted = storage.read(&token::minted_key(token, minter))?;
+    Ok(minted.unwrap_or_default())
+}
+
+/// Mint the amount of the given token to the target. The minted amount is
+/// added to the total supply of the token and to the amount minted by the
+/// minter, which tracks the source of the new tokens.
+pub fn mint<S>(
+    storage: &mut S,
+    minter: &Address,
+    token: &Address,
+    target: &Address,
+    amount: token::Amount,
+) -> Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let balance = read_balance(storage, token, target)?
+        .checked_add(amount)
+        .ok_or_else(|| Error::new_const("The balance would overflow"))?;
+    let supply = read_total_supply(storage, token)?
+        .checked_add(amount)
+        .ok_or_else(|| Error::new_const("The total supply would overflow"))?;
+    let minted = read_minted(storage, token, minter)?
+        .checked_add(amount)
+        .ok_or_else(|| Error::new_const("The minted amount would overflow"))?;
+    storage.write(&token::balance_key(token, target), balance)?;
+    storage.write(&token::total_supply_key(token), supply)?;
+    storage.write(&token::minted_key(token, minter), minted)
+}
+
+/// Burn the amount of the given token from the owner's balance. The burned
+/// amount is deducted from the total supply of the token.
+pub fn burn<S>(
+    storage: &mut S,
+    token: &Address,
+    owner: &Address,
+    amount: token::Amount,
+) -> Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let balance = read_balance(storage, token, owner)?
+        .checked_sub(amount)
+        .ok_or_else(|| Error::new_const("Insufficient balance to burn"))?;
+    let supply = read_total_supply(storage, token)?
+        .checked_sub(amount)
+        .ok_or_else(|| {
+            Error::new_const("The burned amount exceeds the total supply")
+        })?;
+    storage.write(&token::balance_key(token, owner), balance)?;
+    storage.write(&token::total_supply_key(token), supply)
+}
+
+/// Initialize the total supply of the given token from the sum of all its
+/// balances without a multitoken sub-prefix. This iterates all the balances,
+/// so it should only be used at genesis.
+pub fn init_total_supply<S>(storage: &mut S, token: &Address) -> Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let mut supply = token::Amount::default();
+    for iter_result in super::iter_prefix::<token::Amount>(
+        &*storage,
+        &token::balance_prefix(token),
+    )? {
+        let (key, amount) = iter_result?;
+        if token::is_balance_key(token, &key).is_some() {
+            supply = supply.checked_add(amount).ok_or_else(|| {
+                Error::new_const("The total supply would overflow")
+            })?;
+        }
+    }
+    storage.write(&token::total_supply_key(token), supply)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ledger::storage::testing::TestStorage;
+    use crate::types::address::{self, nam};
+
+    /// Test that minting and burning maintain the total supply.
+    #[test]
+    fn test_mint_and_burn_total_supply() {
+        let mut storage = TestStorage::default();
+        let token = nam();
+        let minter = address::testing::established_address_1();
+        let owner = address::testing::established_address_2();
+        let other_owner = address::testing::established_address_3();
+
+        // The genesis balances are counted in the total supply
+        StorageWrite::write(
+            &mut storage,
+            &token::balance_key(&token, &owner),
+            token::Amount::whole(5),
+        )
+        .unwrap();
+        init_total_supply(&mut storage, &token).unwrap();
+        assert_eq!(
+            read_total_supply(&storage, &token).unwrap(),
+            token::Amount::whole(5)
+        );
+
+        mint(
+            &mut storage,
+            &minter,
+            &token,
+            &owner,
+            token::Amount::whole(10),
+        )
+        .unwrap();
+        mint(
+            &mut storage,
+            &minter,
+            &token,
+            &other_owner,
+            token::Amount::whole(3),
+        )
+        .unwrap();
+        assert_eq!(
+            read_balance(&storage, &token, &owner).unwrap(),
+            token::Amount::whole(15)
+        );
+        assert_eq!(
+            read_balance(&storage, &token, &other_owner).unwrap(),
+            token::Amount::whole(3)
+        );
+        assert_eq!(
+            read_total_supply(&storage, &token).unwrap(),
+            token::Amount::whole(18)
+        );
+        assert_eq!(
+            read_minted(&storage, &token, &minter).unwrap(),
+            token::Amount::whole(13)
+        );
+
+        burn(&mut storage, &token, &owner, token::Amount::whole(4)).unwrap();
+        assert_eq!(
+            read_balance(&storage, &token, &owner).unwrap(),
+            token::Amount::whole(11)
+        );
+        assert_eq!(
+            read_total_supply(&storage, &token).unwrap(),
+            token::Amount::whole(14)
+        );
+
+        // Burning more than the balance fails without any change
+        assert!(burn(
+            &mut storage,
+            &token,
+            &other_owner,
+            token::Amount::whole(4)
+        )
+        .is_err());
+        assert_eq!(
+            read_balance(&storage, &token, &other_owner).unwrap(),
+            token::Amount::whole(3)
+        );
+        assert_eq!(
+            read_total_supply(&storage, &token).unwrap(),
+            token::Amount::whole(14)
+        );
+    }
+}
@@ -59,6 +59,8 @@ mod internal {
         "ano::Governance                              ";
     pub const SLASH_FUND: &str =
         "ano::Slash Fund                              ";
+    pub const PGF: &str =
+        "ano::Public Goods Funding                    ";
     pub const IBC: &str =
         "ibc::Inter-Blockchain Communication          ";
     pub const IBC_ESCROW: &str =
@@ -186,6 +188,7 @@ impl Address {
                     InternalAddress::SlashFund => {
                         internal::SLASH_FUND.to_string()
                     }
+                    InternalAddress::Pgf => internal::PGF.to_string(),
                     InternalAddress::Ibc => internal::IBC.to_string(),
                     InternalAddress::IbcToken(hash) => {
                         format!("{}::{}", PREFIX_IBC, hash)
@@ -248,6 +251,7 @@ impl Address {
                 internal::SLASH_FUND => {
                     Ok(Address::Internal(InternalAddress::SlashFund))
                 }
+                internal::PGF => Ok(Address::Internal(InternalAddress::Pgf)),
                 internal::ETH_BRIDGE => {
                     Ok(Address::Internal(InternalAddress::EthBridge))
                 }
@@ -464,6 +468,8 @@ pub enum InternalAddress {
     Governance,
     /// SlashFund address for governance
     SlashFund,
+    /// Public goods funding
+    Pgf,
     /// Bridge to Ethereum
     EthBridge,
 }
@@ -494,6 +500,7 @@ impl Display for InternalAddress {
                 Self::Parameters => "Parameters".to_string(),
                 Self::Governance => "Governance".to_string(),
                 Self::SlashFund => "SlashFund".to_string(),
+                Self::Pgf => "PGF".to_string(),
                 Self::Ibc => "IBC".to_string(),
                 Self::IbcToken(hash) => format!("IbcToken: {}", hash),
                 Self::IbcEscrow => "IbcEscrow".to_string(),
@@ -765,6 +772,7 @@ pub mod testing {
             InternalAddress::PosSlashPool => {}
             InternalAddress::Governance => {}
             InternalAddress::SlashFund => {}
+            InternalAddress::Pgf => {}
             InternalAddress::Parameters => {}
             InternalAddress::Ibc => {}
             InternalAddress::IbcToken(_) => {}
@@ -786,6 +794,7 @@ pub mod testing {
             Just(InternalAddress::IbcMint),
             Just(InternalAddress::Governance),
             Just(InternalAddress::SlashFund),
+            Just(InternalAddress::Pgf),
             Just(InternalAddress::EthBridge),
         ]
     }
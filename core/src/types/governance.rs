@@ -13,8 +13,8 @@ use crate::types::address::Address;
 use crate::types::hash::Hash;
 use crate::types::key::common::{self, Signature};
 use crate::types::key::SigScheme;
-use crate::types::storage::Epoch;
-use crate::types::token::SCALE;
+use crate::types::storage::{BlockHeight, Epoch};
+use crate::types::token::{self, SCALE};
 
 /// Type alias for vote power
 pub type VotePower = u128;
@@ -35,15 +35,32 @@ pub enum ProposalVote {
     Yay,
     /// No
     Nay,
+    /// Neither yes nor no, only counts towards the quorum
+    Abstain,
+    /// No, and the deposit of the proposal should be burned as it's spam or
+    /// harmful
+    NayWithVeto,
 }
 
 impl ProposalVote {
     /// Check if a vote is yay
     pub fn is_yay(&self) -> bool {
-        match self {
-            ProposalVote::Yay => true,
-            ProposalVote::Nay => false,
-        }
+        matches!(self, ProposalVote::Yay)
+    }
+
+    /// Check if a vote is nay
+    pub fn is_nay(&self) -> bool {
+        matches!(self, ProposalVote::Nay)
+    }
+
+    /// Check if a vote is abstain
+    pub fn is_abstain(&self) -> bool {
+        matches!(self, ProposalVote::Abstain)
+    }
+
+    /// Check if a vote is nay with veto
+    pub fn is_veto(&self) -> bool {
+        matches!(self, ProposalVote::NayWithVeto)
     }
 }
 
@@ -52,6 +69,8 @@ impl Display for ProposalVote {
         match self {
             ProposalVote::Yay => write!(f, "yay"),
             ProposalVote::Nay => write!(f, "nay"),
+            ProposalVote::Abstain => write!(f, "abstain"),
+            ProposalVote::NayWithVeto => write!(f, "nay-with-veto"),
         }
     }
 }
@@ -59,7 +78,7 @@ impl Display for ProposalVote {
 #[allow(missing_docs)]
 #[derive(Debug, Error)]
 pub enum ProposalVoteParseError {
-    #[error("Invalid vote. Vote shall be yay or nay.")]
+    #[error("Invalid vote. Vote shall be yay, nay, abstain or nay-with-veto.")]
     InvalidVote,
 }
 
@@ -71,6 +90,10 @@ impl FromStr for ProposalVote {
             Ok(ProposalVote::Yay)
         } else if s.eq("nay") {
             Ok(ProposalVote::Nay)
+        } else if s.eq("abstain") {
+            Ok(ProposalVote::Abstain)
+        } else if s.eq("nay-with-veto") {
+            Ok(ProposalVote::NayWithVeto)
         } else {
             Err(ProposalVoteParseError::InvalidVote)
         }
@@ -78,16 +101,20 @@ impl FromStr for ProposalVote {
 }
 
 /// The result of a proposal
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
 pub enum TallyResult {
     /// Proposal was accepted
     Passed,
     /// Proposal was rejected
     Rejected,
+    /// Proposal was rejected by the nay with veto votes
+    Vetoed,
     /// A critical error in tally computation
     Failed,
 }
 
 /// The result with votes of a proposal
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
 pub struct ProposalResult {
     /// The result of a proposal
     pub result: TallyResult,
@@ -95,26 +122,55 @@ pub struct ProposalResult {
     pub total_voting_power: VotePower,
     /// The total voting power from yay votes
     pub total_yay_power: VotePower,
-    /// The total voting power from nay votes (unused at the moment)
+    /// The total voting power from nay votes
     pub total_nay_power: VotePower,
+    /// The total voting power from abstain votes
+    pub total_abstain_power: VotePower,
+    /// The total voting power from nay with veto votes
+    pub total_veto_power: VotePower,
+    /// Did the votes reach the quorum of the total voting power?
+    pub quorum_reached: bool,
+}
+
+impl ProposalResult {
+    /// The total voting power of all the votes, which counts towards the
+    /// quorum
+    pub fn total_voted_power(&self) -> VotePower {
+        self.total_yay_power
+            + self.total_nay_power
+            + self.total_abstain_power
+            + self.total_veto_power
+    }
 }
 
 impl Display for ProposalResult {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let percentage = Decimal::checked_div(
-            self.total_yay_power.into(),
-            self.total_voting_power.into(),
-        )
-        .unwrap_or_default();
+        let percentage = |power: VotePower| {
+            Decimal::checked_div(power.into(), self.total_voting_power.into())
+                .and_then(|share| share.checked_mul(100.into()))
+                .unwrap_or_default()
+        };
 
         write!(
             f,
-            "{} with {} yay votes over {} ({:.2}%)",
+            "{} with {} yay votes ({:.2}%), {} nay votes ({:.2}%), {} \
+             abstain votes ({:.2}%) and {} nay with veto votes ({:.2}%) over \
+             {}",
             self.result,
             self.total_yay_power / SCALE as u128,
+            percentage(self.total_yay_power),
+            self.total_nay_power / SCALE as u128,
+            percentage(self.total_nay_power),
+            self.total_abstain_power / SCALE as u128,
+            percentage(self.total_abstain_power),
+            self.total_veto_power / SCALE as u128,
+            percentage(self.total_veto_power),
             self.total_voting_power / SCALE as u128,
-            percentage.checked_mul(100.into()).unwrap_or_default()
-        )
+        )?;
+        if !self.quorum_reached {
+            write!(f, ", below the quorum")?;
+        }
+        Ok(())
     }
 }
 
@@ -123,11 +179,466 @@ impl Display for TallyResult {
         match self {
             TallyResult::Passed => write!(f, "passed"),
             TallyResult::Rejected => write!(f, "rejected"),
+            TallyResult::Vetoed => write!(f, "vetoed"),
             TallyResult::Failed => write!(f, "failed"),
         }
     }
 }
 
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+/// The status of a proposal, which is stored with the proposal and updated by
+/// the protocol at the beginning of the epochs of its lifecycle
+pub enum ProposalStatus {
+    /// The voting period hasn't started yet
+    Pending,
+    /// The proposal can be voted on
+    Voting,
+    /// The voting period is over and the proposal waits for its tally at its
+    /// grace epoch
+    Ended,
+    /// The proposal passed and its effect, if any, is about to be executed
+    Passed,
+    /// The proposal was rejected, including when it was vetoed, it missed the
+    /// quorum or its tally failed
+    Rejected,
+    /// The proposal passed and its effect was executed
+    Executed,
+    /// The proposal passed but the execution of its effect failed
+    ExecutionFailed,
+}
+
+impl ProposalStatus {
+    /// Get the status of a proposal with the given voting period at the given
+    /// epoch, before it's tallied
+    pub fn at_epoch(
+        epoch: Epoch,
+        voting_start_epoch: Epoch,
+        voting_end_epoch: Epoch,
+    ) -> Self {
+        if epoch < voting_start_epoch {
+            ProposalStatus::Pending
+        } else if epoch <= voting_end_epoch {
+            ProposalStatus::Voting
+        } else {
+            ProposalStatus::Ended
+        }
+    }
+
+    /// Check if the voting period of a proposal with this status is over
+    pub fn is_voting_over(&self) -> bool {
+        !matches!(self, ProposalStatus::Pending | ProposalStatus::Voting)
+    }
+}
+
+impl Display for ProposalStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProposalStatus::Pending => write!(f, "pending"),
+            ProposalStatus::Voting => write!(f, "voting"),
+            ProposalStatus::Ended => write!(f, "ended"),
+            ProposalStatus::Passed => write!(f, "passed"),
+            ProposalStatus::Rejected => write!(f, "rejected"),
+            ProposalStatus::Executed => write!(f, "executed"),
+            ProposalStatus::ExecutionFailed => write!(f, "execution-failed"),
+        }
+    }
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, Error)]
+pub enum ProposalStatusParseError {
+    #[error(
+        "Invalid proposal status. Status shall be pending, voting, ended, \
+         passed, rejected, executed or execution-failed."
+    )]
+    InvalidStatus,
+}
+
+impl FromStr for ProposalStatus {
+    type Err = ProposalStatusParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(ProposalStatus::Pending),
+            "voting" => Ok(ProposalStatus::Voting),
+            "ended" => Ok(ProposalStatus::Ended),
+            "passed" => Ok(ProposalStatus::Passed),
+            "rejected" => Ok(ProposalStatus::Rejected),
+            "executed" => Ok(ProposalStatus::Executed),
+            "execution-failed" => Ok(ProposalStatus::ExecutionFailed),
+            _ => Err(ProposalStatusParseError::InvalidStatus),
+        }
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+/// The type of a proposal, which determines its effect when it passes
+pub enum ProposalType {
+    /// A proposal with the optional WASM code that is executed when it
+    /// passes. Once the proposal is stored, its code is kept apart and the
+    /// stored type doesn't contain it.
+    Default(Option<Vec<u8>>),
+    /// A proposal to change the value of a protocol, PoS or governance
+    /// parameter, which is applied natively when it passes
+    ParameterChange {
+        /// The name of the parameter prefixed with its module, e.g.
+        /// `governance/quorum`
+        key: String,
+        /// The new value of the parameter as it's parsed from a string
+        value: String,
+    },
+    /// A proposal without any effect other than the result of its vote
+    TextOnly,
+    /// A proposal to elect the council of the public goods funding, which
+    /// replaces the current council when it passes
+    PgfCouncil(PgfCouncil),
+    /// A proposal to spend some tokens of the treasury, i.e. the slash fund,
+    /// which are paid natively when it passes
+    TreasurySpend(TreasurySpend),
+}
+
+impl Default for ProposalType {
+    fn default() -> Self {
+        Self::Default(None)
+    }
+}
+
+impl Display for ProposalType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProposalType::Default(None) => write!(f, "default"),
+            ProposalType::Default(Some(code)) => {
+                write!(f, "default with {} bytes of WASM code", code.len())
+            }
+            ProposalType::ParameterChange { key, value } => {
+                write!(f, "parameter change of {key} to {value}")
+            }
+            ProposalType::TextOnly => write!(f, "text only"),
+            ProposalType::PgfCouncil(council) => {
+                write!(f, "election of the PGF council {council}")
+            }
+            ProposalType::TreasurySpend(spend) => {
+                write!(f, "treasury spend of {spend}")
+            }
+        }
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+/// The council of the public goods funding
+pub struct PgfCouncil {
+    /// The address of the council, whose signature is required to change
+    /// the funding
+    pub address: Address,
+    /// The maximum amount of the native token that may be paid in an epoch
+    /// by the continuous funding streams and the retroactive payments
+    pub spending_cap: token::Amount,
+}
+
+impl Display for PgfCouncil {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} with a spending cap of {} per epoch",
+            self.address, self.spending_cap
+        )
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+/// A spending of the treasury, i.e. the slash fund
+pub struct TreasurySpend {
+    /// The recipient of the payment
+    pub recipient: Address,
+    /// The paid token
+    pub token: Address,
+    /// The total paid amount
+    pub amount: token::Amount,
+    /// When set, the amount is vested in equal installments paid over this
+    /// number of epochs, starting from the epoch in which the proposal is
+    /// executed. Otherwise, it's paid at once.
+    pub vesting_epochs: Option<u64>,
+}
+
+impl Display for TreasurySpend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} to {}", self.amount, self.token, self.recipient)?;
+        if let Some(epochs) = self.vesting_epochs {
+            write!(f, " vested over {epochs} epochs")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+/// The rest of a vested treasury spend that is still to be paid
+pub struct TreasuryVesting {
+    /// The ID of the proposal of the spend
+    pub proposal_id: u64,
+    /// The recipient of the installments
+    pub recipient: Address,
+    /// The paid token
+    pub token: Address,
+    /// The amount that is still to be paid
+    pub remaining_amount: token::Amount,
+    /// The number of installments that are still to be paid, one per epoch
+    pub remaining_epochs: u64,
+}
+
+impl TreasuryVesting {
+    /// The amount of the next installment. The installments are equal, but
+    /// the last one also pays the remainder of their division.
+    pub fn next_installment(&self) -> token::Amount {
+        match self.remaining_epochs {
+            0 | 1 => self.remaining_amount,
+            epochs => {
+                token::Amount::from(u64::from(self.remaining_amount) / epochs)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+/// A payment made by the treasury for a treasury spend proposal
+pub struct TreasuryPayment {
+    /// The ID of the proposal of the spend
+    pub proposal_id: u64,
+    /// The epoch in which the payment was made
+    pub epoch: Epoch,
+    /// The recipient of the payment
+    pub recipient: Address,
+    /// The paid token
+    pub token: Address,
+    /// The paid amount
+    pub amount: token::Amount,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+/// A change of the treasury balance made by the governance
+pub enum TreasuryRecord {
+    /// The funds locked by a proposal that were forfeited to the treasury
+    Deposit {
+        /// The ID of the proposal
+        proposal_id: u64,
+        /// The epoch in which the funds were forfeited
+        epoch: Epoch,
+        /// The forfeited amount of the native token
+        amount: token::Amount,
+    },
+    /// A payment of a treasury spend proposal
+    Payment(TreasuryPayment),
+}
+
+/// The fields of the standard proposal content schema that must be present
+/// and not empty
+pub const REQUIRED_CONTENT_FIELDS: [&str; 7] = [
+    "title",
+    "authors",
+    "discussions-to",
+    "created",
+    "license",
+    "abstract",
+    "motivation",
+];
+
+/// The optional fields of the standard proposal content schema
+pub const OPTIONAL_CONTENT_FIELDS: [&str; 2] = ["details", "requires"];
+
+/// Check that a proposal content follows the standard schema, with all the
+/// required fields and no unknown field
+pub fn validate_content(
+    content: &BTreeMap<String, String>,
+) -> Result<(), ProposalError> {
+    for field in REQUIRED_CONTENT_FIELDS {
+        match content.get(field) {
+            Some(value) if !value.trim().is_empty() => {}
+            _ => {
+                return Err(ProposalError::MissingContentField(
+                    field.to_owned(),
+                ));
+            }
+        }
+    }
+    match content.keys().find(|field| {
+        !REQUIRED_CONTENT_FIELDS.contains(&field.as_str())
+            && !OPTIONAL_CONTENT_FIELDS.contains(&field.as_str())
+    }) {
+        Some(field) => Err(ProposalError::UnknownContentField(field.clone())),
+        None => Ok(()),
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+/// The content of a proposal as it is stored on chain
+pub enum ProposalContent {
+    /// The full content
+    Full(BTreeMap<String, String>),
+    /// The hash of a content that is published off-chain at the URL
+    Hash {
+        /// The hash of the content, see [`ProposalContent::hash`]
+        hash: Hash,
+        /// The URL of the content
+        url: String,
+    },
+}
+
+impl ProposalContent {
+    /// Hash a content. The hash is computed over its JSON serialization, so
+    /// that it doesn't depend on the formatting of the published document.
+    pub fn hash(content: &BTreeMap<String, String>) -> Hash {
+        let content_serialized = serde_json::to_vec(content)
+            .expect("Conversion to bytes shouldn't fail.");
+        Hash::sha256(content_serialized)
+    }
+
+    /// Check that the full content follows the standard schema and that a
+    /// hash has a URL
+    pub fn validate(&self) -> Result<(), ProposalError> {
+        match self {
+            ProposalContent::Full(content) => validate_content(content),
+            ProposalContent::Hash { url, .. } => {
+                if url.trim().is_empty() {
+                    Err(ProposalError::MissingContentUrl)
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    /// Check that a content fetched from the URL of a hash matches it
+    pub fn matches(&self, content: &BTreeMap<String, String>) -> bool {
+        match self {
+            ProposalContent::Full(full) => full == content,
+            ProposalContent::Hash { hash, .. } => hash == &Self::hash(content),
+        }
+    }
+}
+
+/// Decode the content of a proposal from storage. The proposals stored before
+/// the content could be a hash have their full content encoded as a map.
+pub fn decode_content(
+    bytes: impl AsRef<[u8]>,
+) -> std::io::Result<ProposalContent> {
+    let bytes = bytes.as_ref();
+    ProposalContent::try_from_slice(bytes).or_else(|err| {
+        BTreeMap::<String, String>::try_from_slice(bytes)
+            .map(ProposalContent::Full)
+            .map_err(|_| err)
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+/// A proposal as it is stored on chain
+pub struct ProposalInfo {
+    /// The proposal id
+    pub id: u64,
+    /// The proposal author address
+    pub author: Address,
+    /// The proposal content
+    pub content: ProposalContent,
+    /// The epoch from which voting is allowed
+    pub voting_start_epoch: Epoch,
+    /// The epoch from which voting is stopped
+    pub voting_end_epoch: Epoch,
+    /// The epoch from which this changes are executed
+    pub grace_epoch: Epoch,
+    /// The type of the proposal
+    pub r#type: ProposalType,
+    /// Is the proposal still expedited? A failed expedited proposal falls
+    /// back to a normal one.
+    pub expedited: bool,
+    /// The status of the proposal at the queried block
+    pub status: ProposalStatus,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+/// A vote with the voting power of the voter
+pub struct WeightedVote {
+    /// The vote
+    pub vote: ProposalVote,
+    /// The voting power of the vote
+    pub power: VotePower,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+/// All the votes cast on a proposal with their voting power at an epoch
+pub struct ProposalVotes {
+    /// The epoch at which the voting power is counted
+    pub epoch: Epoch,
+    /// The votes of the validators weighted by their stake
+    pub validators: BTreeMap<Address, WeightedVote>,
+    /// The votes of the delegators weighted by their bonds, by the delegator
+    /// and then by the validator of the bond
+    pub delegators: BTreeMap<Address, BTreeMap<Address, WeightedVote>>,
+    /// The stake of the validators that pass through the majority vote of
+    /// their delegators
+    pub pass_through: BTreeMap<Address, VotePower>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+/// A vote in the vote history of a proposal. A voter may change their vote
+/// until the end of the voting period, only their latest vote counts.
+pub struct VoteRecord {
+    /// The voter
+    pub voter: Address,
+    /// The validator of the voter's delegation, or the voter if they're a
+    /// validator
+    pub delegation: Address,
+    /// The vote
+    pub vote: ProposalVote,
+    /// The height of the block in which the vote was cast
+    pub height: BlockHeight,
+}
+
 #[derive(
     Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize,
 )]
@@ -147,6 +658,18 @@ pub struct Proposal {
     pub grace_epoch: Epoch,
     /// The code containing the storage changes
     pub proposal_code_path: Option<String>,
+    /// The URL at which the content is published. When it's set, only the
+    /// hash of the content and the URL are stored on chain.
+    #[serde(default)]
+    pub content_url: Option<String>,
+    /// The type of the proposal. A proposal with some code must be of the
+    /// default type.
+    #[serde(default)]
+    pub r#type: ProposalType,
+    /// Is the proposal expedited, with a shorter voting period, a higher
+    /// deposit and a stricter pass threshold?
+    #[serde(default)]
+    pub expedited: bool,
 }
 
 impl Display for Proposal {
@@ -156,10 +679,16 @@ impl Display for Proposal {
 }
 
 #[allow(missing_docs)]
-#[derive(Debug, Error)]
+#[derive(Debug, Error, PartialEq, Eq)]
 pub enum ProposalError {
     #[error("Invalid proposal data.")]
     InvalidProposalData,
+    #[error("The proposal content is missing the field {0}.")]
+    MissingContentField(String),
+    #[error("The proposal content has an unknown field {0}.")]
+    UnknownContentField(String),
+    #[error("The proposal content hash is missing its URL.")]
+    MissingContentUrl,
 }
 
 #[derive(
@@ -307,3 +836,63 @@ impl OfflineVote {
         .is_ok()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn standard_content() -> BTreeMap<String, String> {
+        REQUIRED_CONTENT_FIELDS
+            .iter()
+            .map(|field| (field.to_string(), format!("The {field}")))
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_content() {
+        let mut content = standard_content();
+        assert_eq!(validate_content(&content), Ok(()));
+
+        content.insert("details".to_string(), "The details".to_string());
+        assert_eq!(validate_content(&content), Ok(()));
+
+        content.insert("summary".to_string(), "The summary".to_string());
+        assert_eq!(
+            validate_content(&content),
+            Err(ProposalError::UnknownContentField("summary".to_string()))
+        );
+
+        let mut content = standard_content();
+        content.insert("abstract".to_string(), " ".to_string());
+        assert_eq!(
+            validate_content(&content),
+            Err(ProposalError::MissingContentField("abstract".to_string()))
+        );
+        content.remove("abstract");
+        assert_eq!(
+            validate_content(&content),
+            Err(ProposalError::MissingContentField("abstract".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_proposal_content_hash() {
+        let content = standard_content();
+        let hashed = ProposalContent::Hash {
+            hash: ProposalContent::hash(&content),
+            url: "https://example.com/proposal.json".to_string(),
+        };
+        assert_eq!(hashed.validate(), Ok(()));
+        assert!(hashed.matches(&content));
+
+        let mut other = content;
+        other.insert("title".to_string(), "Another title".to_string());
+        assert!(!hashed.matches(&other));
+
+        let no_url = ProposalContent::Hash {
+            hash: ProposalContent::hash(&other),
+            url: String::new(),
+        };
+        assert_eq!(no_url.validate(), Err(ProposalError::MissingContentUrl));
+    }
+}
@@ -162,6 +162,15 @@ impl Display for BlockHeight {
     }
 }
 
+impl FromStr for BlockHeight {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let raw: u64 = u64::from_str(s)?;
+        Ok(Self(raw))
+    }
+}
+
 impl Add<u64> for BlockHeight {
     type Output = BlockHeight;
 
@@ -1090,6 +1099,17 @@ pub struct PrefixValue {
     pub value: Vec<u8>,
 }
 
+/// A change of a storage key's value committed in a block.
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize, BorshSchema)]
+pub struct StorageChange {
+    /// Storage key
+    pub key: Key,
+    /// Raw value bytes before the change, `None` if the key has been created
+    pub old_value: Option<Vec<u8>>,
+    /// Raw value bytes after the change, `None` if the key has been deleted
+    pub new_value: Option<Vec<u8>>,
+}
+
 #[cfg(test)]
 mod tests {
     use proptest::prelude::*;
@@ -71,6 +71,13 @@ impl Amount {
         Self { micro: u64::MAX }
     }
 
+    /// Checked addition
+    pub fn checked_add(&self, amount: Amount) -> Option<Self> {
+        self.micro
+            .checked_add(amount.micro)
+            .map(|result| Self { micro: result })
+    }
+
     /// Checked subtraction
     pub fn checked_sub(&self, amount: Amount) -> Option<Self> {
         self.micro
@@ -261,6 +268,10 @@ impl From<Amount> for Change {
 
 /// Key segment for a balance key
 pub const BALANCE_STORAGE_KEY: &str = "balance";
+/// Key segment for the total supply key
+pub const TOTAL_SUPPLY_STORAGE_KEY: &str = "total_supply";
+/// Key segment prefix for the amounts minted by the minters of a token
+pub const MINTED_STORAGE_KEY: &str = "minted";
 /// Key segment for head shielded transaction pointer key
 pub const HEAD_TX_KEY: &str = "head-tx";
 /// Key segment prefix for shielded transaction key
@@ -269,6 +280,20 @@ pub const TX_KEY_PREFIX: &str = "tx-";
 pub const CONVERSION_KEY_PREFIX: &str = "conv";
 /// Key segment prefix for pinned shielded transactions
 pub const PIN_KEY_PREFIX: &str = "pin-";
+/// Key segment prefix for the note commitment frontiers after the shielded
+/// transactions, which are stored by the ledger
+pub const FRONTIER_KEY_PREFIX: &str = "frontier-";
+
+/// A balance of an owner in a token
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct TokenBalance {
+    /// The token address
+    pub token: Address,
+    /// The multitoken sub-prefix of the balance, if any
+    pub sub_prefix: Option<Key>,
+    /// The amount of the token
+    pub amount: Amount,
+}
 
 /// Obtain a storage key for user's balance.
 pub fn balance_key(token_addr: &Address, owner: &Address) -> Key {
@@ -286,6 +311,23 @@ pub fn balance_prefix(token_addr: &Address) -> Key {
         .expect("Cannot obtain a storage key")
 }
 
+/// Obtain a storage key for the total supply of a token, i.e. the sum of all
+/// the balances without a multitoken sub-prefix.
+pub fn total_supply_key(token_addr: &Address) -> Key {
+    Key::from(token_addr.to_db_key())
+        .push(&TOTAL_SUPPLY_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key for the amount of a token minted by the given minter.
+pub fn minted_key(token_addr: &Address, minter: &Address) -> Key {
+    Key::from(token_addr.to_db_key())
+        .push(&MINTED_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+        .push(&minter.to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
 /// Obtain a storage key prefix for multitoken balances.
 pub fn multitoken_balance_prefix(
     token_addr: &Address,
@@ -342,6 +384,14 @@ pub fn is_masp_key(key: &Key) -> bool {
                     || key.starts_with(PIN_KEY_PREFIX)))
 }
 
+/// Check if the given storage key is the key of a note commitment frontier of
+/// the masp, which only the ledger can write
+pub fn is_masp_frontier_key(key: &Key) -> bool {
+    matches!(&key.segments[..],
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(key)]
+            if *addr == masp() && key.starts_with(FRONTIER_KEY_PREFIX))
+}
+
 /// Check if the given storage key is multitoken balance key for the given
 /// token. If it is, returns the sub prefix and the owner.
 pub fn is_multitoken_balance_key<'a>(
@@ -2,7 +2,9 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
 
 use crate::types::address::Address;
-use crate::types::governance::{Proposal, ProposalError, ProposalVote};
+use crate::types::governance::{
+    Proposal, ProposalContent, ProposalError, ProposalType, ProposalVote,
+};
 use crate::types::storage::Epoch;
 
 /// A tx data type to hold proposal data
@@ -18,7 +20,7 @@ use crate::types::storage::Epoch;
 pub struct InitProposalData {
     /// The proposal id
     pub id: Option<u64>,
-    /// The proposal content
+    /// The proposal content, an encoded [`ProposalContent`]
     pub content: Vec<u8>,
     /// The proposal author address
     pub author: Address,
@@ -28,8 +30,10 @@ pub struct InitProposalData {
     pub voting_end_epoch: Epoch,
     /// The epoch from which this changes are executed
     pub grace_epoch: Epoch,
-    /// The code containing the storage changes
-    pub proposal_code: Option<Vec<u8>>,
+    /// The type of the proposal, including its code, if any
+    pub r#type: ProposalType,
+    /// Is the proposal expedited?
+    pub expedited: bool,
 }
 
 /// A tx data type to hold vote proposal data
@@ -53,27 +57,58 @@ pub struct VoteProposalData {
     pub delegations: Vec<Address>,
 }
 
+/// A tx data type to opt a validator in or out of passing through the
+/// majority vote of its delegators
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct VotePassThroughData {
+    /// The validator address
+    pub validator: Address,
+    /// Should the validator pass through the majority vote of its
+    /// delegators?
+    pub pass_through: bool,
+}
+
 impl TryFrom<Proposal> for InitProposalData {
     type Error = ProposalError;
 
     fn try_from(proposal: Proposal) -> Result<Self, Self::Error> {
-        let proposal_code = if let Some(path) = proposal.proposal_code_path {
-            match std::fs::read(path) {
-                Ok(bytes) => Some(bytes),
-                Err(_) => return Err(Self::Error::InvalidProposalData),
+        let r#type = match (proposal.proposal_code_path, proposal.r#type) {
+            (Some(path), ProposalType::Default(None)) => {
+                match std::fs::read(path) {
+                    Ok(bytes) => ProposalType::Default(Some(bytes)),
+                    Err(_) => return Err(Self::Error::InvalidProposalData),
+                }
             }
-        } else {
-            None
+            (Some(_), _) => return Err(Self::Error::InvalidProposalData),
+            (None, r#type) => r#type,
+        };
+        // The content is validated by the client and by the governance VP,
+        // so that the client can still build a proposal with `--force`
+        let content = match proposal.content_url {
+            Some(url) => ProposalContent::Hash {
+                hash: ProposalContent::hash(&proposal.content),
+                url,
+            },
+            None => ProposalContent::Full(proposal.content),
         };
 
         Ok(InitProposalData {
             id: proposal.id,
-            content: proposal.content.try_to_vec().unwrap(),
+            content: content.try_to_vec().unwrap(),
             author: proposal.author,
             voting_start_epoch: proposal.voting_start_epoch,
             voting_end_epoch: proposal.voting_end_epoch,
             grace_epoch: proposal.grace_epoch,
-            proposal_code,
+            r#type,
+            expedited: proposal.expedited,
         })
     }
 }
@@ -7,6 +7,8 @@ pub mod decrypted;
 pub mod encrypted;
 /// txs to manage governance
 pub mod governance;
+/// txs to manage the public goods funding
+pub mod pgf;
 pub mod pos;
 /// transaction protocols made by validators
 pub mod protocol;
@@ -0,0 +1,38 @@
+//! Types used for the public goods funding transactions
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::types::address::Address;
+use crate::types::token;
+
+/// A change of the public goods funding made by its council
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub enum PgfFundingAction {
+    /// Set the amount paid to the recipient every epoch by its continuous
+    /// funding stream. A zero amount removes the stream.
+    SetStream {
+        /// The recipient of the stream
+        recipient: Address,
+        /// The amount paid every epoch
+        amount: token::Amount,
+    },
+    /// Approve a retroactive payment to the recipient, which is paid at the
+    /// start of the next epoch within the spending cap. It replaces the
+    /// recipient's pending payment, if any, and a zero amount cancels it.
+    RetroPayment {
+        /// The recipient of the payment
+        recipient: Address,
+        /// The amount of the payment
+        amount: token::Amount,
+    },
+}
@@ -1,8 +1,10 @@
-//! Encoding spec markdown generator.
+//! Encoding spec markdown and JSON generator.
 //!
 //! When ran, this generator will:
 //! - Get schema for all the types to be included in encoding docs
 //! - Merge their definitions
+//! - Write the merged definitions together with the description of the RPC
+//!   router's paths into a JSON document
 //! - For each schema's declaration, look-up top-level definitions and format to
 //!   md table
 //! - For each non-top-level definition, format to md table
@@ -15,7 +17,7 @@
 #![deny(rustdoc::broken_intra_doc_links)]
 #![deny(rustdoc::private_intra_doc_links)]
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::io::Write;
 
 use borsh::{schema, BorshSchema};
@@ -23,6 +25,8 @@ use itertools::Itertools;
 use lazy_static::lazy_static;
 use madato::types::TableRow;
 use namada::ledger::parameters::Parameters;
+use namada::ledger::queries::schema::{RouteSchema, SegmentSchema};
+use namada::ledger::queries::RPC;
 use namada::proto::SignedTxData;
 use namada::types::address::Address;
 use namada::types::key::ed25519::{PublicKey, Signature};
@@ -34,6 +38,11 @@ use namada::types::{token, transaction};
 const OUTPUT_PATH: &str =
     "documentation/dev/src/specs/encoding/generated-borsh-spec.md";
 
+/// This generator will write the JSON document with the RPC paths and the
+/// borsh definitions into this `docs` file.
+const JSON_OUTPUT_PATH: &str =
+    "documentation/dev/src/specs/encoding/generated-rpc-spec.json";
+
 lazy_static! {
     /// Borsh types may be used by declarations. These are displayed differently in the [`md_fmt_type`].
     static ref BORSH_TYPES: HashSet<&'static str> =
@@ -109,6 +118,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     definitions.extend(tx_type_schema.definitions);
     definitions.extend(prefix_value_schema.definitions);
     // definitions.extend(pos_bonds_schema.definitions);
+
+    write_json_spec(&definitions)?;
+
     let mut tables: Vec<Table> = Vec::with_capacity(definitions.len());
 
     // Add the top-level definitions first
@@ -274,6 +286,103 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Write the description of all the RPC router's paths together with the
+/// borsh definitions into a JSON document, from which the clients in other
+/// languages can generate their bindings
+fn write_json_spec(
+    definitions: &HashMap<schema::Declaration, schema::Definition>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let routes: Vec<_> = RPC.schema().iter().map(route_to_json).collect();
+    // The map is ordered by the declarations
+    let definitions: serde_json::Map<_, _> = definitions
+        .iter()
+        .map(|(declaration, definition)| {
+            (declaration.clone(), definition_to_json(definition))
+        })
+        .collect();
+    let spec = serde_json::json!({
+        "routes": routes,
+        "definitions": definitions,
+    });
+    let file = std::fs::File::create(JSON_OUTPUT_PATH)?;
+    serde_json::to_writer_pretty(file, &spec)?;
+    Ok(())
+}
+
+fn route_to_json(route: &RouteSchema) -> serde_json::Value {
+    let segments: Vec<_> = route
+        .segments
+        .iter()
+        .map(|segment| match segment {
+            SegmentSchema::Literal(literal) => {
+                serde_json::json!({ "literal": literal })
+            }
+            SegmentSchema::Arg { name, ty, optional } => serde_json::json!({
+                "arg": name,
+                "type": ty,
+                "optional": optional,
+            }),
+        })
+        .collect();
+    serde_json::json!({
+        "path": route.path(),
+        "segments": segments,
+        "handler": route.handler,
+        "kind": route.kind.as_str(),
+        "proof": route.kind.supports_proof(),
+        "response": route.response,
+    })
+}
+
+fn definition_to_json(def: &schema::Definition) -> serde_json::Value {
+    match def {
+        schema::Definition::Array { length, elements } => serde_json::json!({
+            "kind": "array",
+            "length": length,
+            "elements": elements,
+        }),
+        schema::Definition::Sequence { elements } => serde_json::json!({
+            "kind": "sequence",
+            "elements": elements,
+        }),
+        schema::Definition::Tuple { elements } => serde_json::json!({
+            "kind": "tuple",
+            "elements": elements,
+        }),
+        schema::Definition::Enum { variants } => {
+            let variants: Vec<_> = variants
+                .iter()
+                .map(|(name, type_name)| {
+                    serde_json::json!({ "name": name, "type": type_name })
+                })
+                .collect();
+            serde_json::json!({
+                "kind": "enum",
+                "variants": variants,
+            })
+        }
+        schema::Definition::Struct { fields } => {
+            let fields: Vec<_> = match fields {
+                schema::Fields::NamedFields(fields) => fields
+                    .iter()
+                    .map(|(name, type_name)| {
+                        serde_json::json!({ "name": name, "type": type_name })
+                    })
+                    .collect(),
+                schema::Fields::UnnamedFields(fields) => fields
+                    .iter()
+                    .map(|type_name| serde_json::json!({ "type": type_name }))
+                    .collect(),
+                schema::Fields::Empty => vec![],
+            };
+            serde_json::json!({
+                "kind": "struct",
+                "fields": fields,
+            })
+        }
+    }
+}
+
 struct Table {
     name: String,
     desc: String,
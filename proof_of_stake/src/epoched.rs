@@ -7,6 +7,7 @@ use core::{cmp, fmt, ops};
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use namada_core::types::storage::Epoch;
 
+use crate::types::EpochedPosParams;
 use crate::PosParams;
 
 /// Data that may have values set for future epochs, up to an epoch at offset as
@@ -71,6 +72,11 @@ pub trait EpochOffset:
 {
     /// Find the value of a given offset from PoS parameters.
     fn value(params: &PosParams) -> u64;
+    /// Find the value of a given offset from the PoS parameters in effect in
+    /// the given epoch.
+    fn value_at(params: &EpochedPosParams, epoch: Epoch) -> u64 {
+        Self::value(params_at(params, epoch))
+    }
     /// Convert to [`DynEpochOffset`]
     fn dyn_offset() -> DynEpochOffset;
 }
@@ -140,6 +146,20 @@ impl DynEpochOffset {
             DynEpochOffset::PipelineLenMinusOne => params.pipeline_len - 1,
         }
     }
+
+    /// Find the value of a given offset from the PoS parameters in effect in
+    /// the given epoch.
+    pub fn value_at(&self, params: &EpochedPosParams, epoch: Epoch) -> u64 {
+        self.value(params_at(params, epoch))
+    }
+}
+
+/// Find the PoS parameters in effect in the given epoch. The parameters are
+/// initialized at genesis, so they're set for every epoch.
+pub(crate) fn params_at(params: &EpochedPosParams, epoch: Epoch) -> &PosParams {
+    params
+        .get(epoch)
+        .expect("PoS parameters must be set for every epoch")
 }
 
 impl<Data, Offset> Epoched<Data, Offset>
@@ -164,12 +184,14 @@ where
     pub fn init(
         value: Data,
         current_epoch: impl Into<Epoch>,
-        params: &PosParams,
+        params: &EpochedPosParams,
     ) -> Self {
-        let mut data = vec![None; Offset::value(params) as usize];
+        let current_epoch = current_epoch.into();
+        let offset = Offset::value_at(params, current_epoch);
+        let mut data = vec![None; offset as usize];
         data.push(Some(value));
         Self {
-            last_update: current_epoch.into(),
+            last_update: current_epoch,
             data,
             offset: PhantomData,
         }
@@ -183,15 +205,16 @@ where
     }
 
     /// Find the value at the offset from the given epoch or a nearest epoch
-    /// before it.
+    /// before it. The offset is the one in effect in the given epoch.
     pub fn get_at_offset(
         &self,
         epoch: impl Into<Epoch>,
         offset: DynEpochOffset,
-        params: &PosParams,
+        params: &EpochedPosParams,
     ) -> Option<&Data> {
-        let offset = offset.value(params);
-        let epoch_at_offset = epoch.into() + offset;
+        let epoch = epoch.into();
+        let offset = offset.value_at(params, epoch);
+        let epoch_at_offset = epoch + offset;
         let index: usize =
             (epoch_at_offset.sub_or_default(self.last_update)).into();
         self.get_at_index(index)
@@ -229,18 +252,22 @@ where
     fn update_data(
         &mut self,
         current_epoch: impl Into<Epoch>,
-        params: &PosParams,
+        params: &EpochedPosParams,
     ) {
         let epoch = current_epoch.into();
-        let offset = Offset::value(params) as usize;
+        let offset = Offset::value_at(params, epoch) as usize;
         let last_update = self.last_update;
-        let shift: usize =
-            cmp::min((epoch.sub_or_default(last_update)).into(), offset);
 
         // Resize the data if needed
         if self.data.len() < offset + 1 {
             self.data.resize_with(offset + 1, Default::default);
         }
+        // The data may be longer than the current offset, if it has been set
+        // with a greater offset before the parameters have changed
+        let shift: usize = cmp::min(
+            (epoch.sub_or_default(last_update)).into(),
+            self.data.len() - 1,
+        );
 
         if shift != 0 {
             let mid_point = cmp::min(shift, self.data.len());
@@ -269,12 +296,16 @@ where
         &mut self,
         value: Data,
         current_epoch: impl Into<Epoch>,
-        params: &PosParams,
+        params: &EpochedPosParams,
     ) {
+        let current_epoch = current_epoch.into();
         self.update_data(current_epoch, params);
 
-        let offset = Offset::value(params) as usize;
+        let offset = Offset::value_at(params, current_epoch) as usize;
         self.data[offset] = Some(value);
+        // Any value set for a later epoch with a greater offset before the
+        // parameters have changed is superseded by this value
+        self.data.truncate(offset + 1);
     }
 
     /// Update the values starting from the given epoch offset (which must not
@@ -285,10 +316,10 @@ where
         update_value: impl Fn(&mut Data, Epoch),
         current_epoch: impl Into<Epoch>,
         offset: DynEpochOffset,
-        params: &PosParams,
+        params: &EpochedPosParams,
     ) {
-        let offset = offset.value(params) as usize;
         let epoch = current_epoch.into();
+        let offset = offset.value_at(params, epoch) as usize;
         self.update_data(epoch, params);
 
         if let Some(data) = self.data.get_mut(offset).unwrap() {
@@ -349,9 +380,10 @@ where
     pub fn init(
         value: Data,
         current_epoch: impl Into<Epoch>,
-        params: &PosParams,
+        params: &EpochedPosParams,
     ) -> Self {
-        let index = Offset::value(params) as usize;
+        let current_epoch = current_epoch.into();
+        let index = Offset::value_at(params, current_epoch) as usize;
         Self::init_at_index(value, current_epoch, index)
     }
 
@@ -361,9 +393,10 @@ where
         value: Data,
         current_epoch: impl Into<Epoch>,
         offset: DynEpochOffset,
-        params: &PosParams,
+        params: &EpochedPosParams,
     ) -> Self {
-        let index = offset.value(params) as usize;
+        let current_epoch = current_epoch.into();
+        let index = offset.value_at(params, current_epoch) as usize;
         Self::init_at_index(value, current_epoch, index)
     }
 
@@ -391,15 +424,17 @@ where
     }
 
     /// Find the value at the offset from the given epoch as the sum of delta
-    /// values at and before the epoch offset.
+    /// values at and before the epoch offset. The offset is the one in effect
+    /// in the given epoch.
     pub fn get_at_offset(
         &self,
         epoch: impl Into<Epoch>,
         offset: DynEpochOffset,
-        params: &PosParams,
+        params: &EpochedPosParams,
     ) -> Option<Data> {
-        let offset = offset.value(params);
-        let epoch_at_offset = epoch.into() + offset;
+        let epoch = epoch.into();
+        let offset = offset.value_at(params, epoch);
+        let epoch_at_offset = epoch + offset;
         let index: usize =
             (epoch_at_offset.sub_or_default(self.last_update)).into();
         self.get_at_index(index)
@@ -454,18 +489,22 @@ where
     fn update_data(
         &mut self,
         current_epoch: impl Into<Epoch>,
-        params: &PosParams,
+        params: &EpochedPosParams,
     ) {
         let epoch = current_epoch.into();
-        let offset = Offset::value(params) as usize;
+        let offset = Offset::value_at(params, epoch) as usize;
         let last_update = self.last_update;
-        let shift: usize =
-            cmp::min((epoch.sub_or_default(last_update)).into(), offset);
 
         // Resize the data if needed
         if self.data.len() < offset + 1 {
             self.data.resize_with(offset + 1, Default::default);
         }
+        // The data may be longer than the current offset, if it has been set
+        // with a greater offset before the parameters have changed
+        let shift: usize = cmp::min(
+            (epoch.sub_or_default(last_update)).into(),
+            self.data.len() - 1,
+        );
 
         if shift != 0 {
             let mid_point = cmp::min(shift, self.data.len());
@@ -504,11 +543,12 @@ where
         &mut self,
         value: Data,
         current_epoch: impl Into<Epoch>,
-        params: &PosParams,
+        params: &EpochedPosParams,
     ) {
+        let current_epoch = current_epoch.into();
         self.update_data(current_epoch, params);
 
-        let offset = Offset::value(params) as usize;
+        let offset = Offset::value_at(params, current_epoch) as usize;
         self.data[offset] = self.data[offset].as_ref().map_or_else(
             || Some(value.clone()),
             |last_delta| Some(last_delta.clone() + value.clone()),
@@ -519,7 +559,7 @@ where
     pub fn delete_current(
         &mut self,
         current_epoch: impl Into<Epoch>,
-        params: &PosParams,
+        params: &EpochedPosParams,
     ) {
         self.update_data(current_epoch, params);
         self.data[0] = None;
@@ -532,10 +572,10 @@ where
         value: Data,
         current_epoch: impl Into<Epoch>,
         offset: DynEpochOffset,
-        params: &PosParams,
+        params: &EpochedPosParams,
     ) {
-        let offset = offset.value(params) as usize;
         let epoch = current_epoch.into();
+        let offset = offset.value_at(params, epoch) as usize;
         self.update_data(epoch, params);
 
         self.data[offset] = self.data[offset].as_ref().map_or_else(
@@ -551,7 +591,7 @@ where
         value: Data,
         current_epoch: impl Into<Epoch>,
         update_epoch: impl Into<Epoch>,
-        params: &PosParams,
+        params: &EpochedPosParams,
     ) {
         let current_epoch = current_epoch.into();
         let update_epoch = update_epoch.into();
@@ -572,11 +612,12 @@ where
         &self,
         mut f: impl FnMut(&Data, Epoch) -> bool,
         current_epoch: impl Into<Epoch>,
-        params: &PosParams,
+        params: &EpochedPosParams,
     ) {
         let epoch = current_epoch.into();
-        let offset = Offset::value(params) as usize;
-        for ix in (0..offset + 1).rev() {
+        let offset = Offset::value_at(params, epoch) as usize;
+        let len = cmp::max(offset + 1, self.data.len());
+        for ix in (0..len).rev() {
             if let Some(Some(current)) = self.data.get(ix) {
                 let keep_going = f(current, epoch + ix);
                 if !keep_going {
@@ -623,6 +664,136 @@ mod tests {
             sequential 1..20 => EpochedDeltaAbstractStateMachine<OffsetUnbondingLen>);
     }
 
+    /// Test that a value set with a greater offset before the offset has been
+    /// decreased is superseded by a value set with the decreased offset.
+    #[test]
+    fn test_epoched_with_decreased_offset() {
+        let params = PosParams {
+            pipeline_len: 3,
+            ..PosParams::default()
+        };
+        let decreased = PosParams {
+            pipeline_len: 1,
+            ..params.clone()
+        };
+        let (params, decreased) =
+            (constant_params(&params), constant_params(&decreased));
+        let mut data =
+            Epoched::<u64, OffsetPipelineLen>::init_at_genesis(0, Epoch(0));
+        data.set(1, Epoch(0), &params);
+        data.set(2, Epoch(1), &params);
+        assert_eq!(data.get(Epoch(4)), Some(&2));
+
+        data.set(3, Epoch(2), &decreased);
+        assert_eq!(data.get(Epoch(2)), Some(&0));
+        assert_eq!(data.get(Epoch(3)), Some(&3));
+        assert_eq!(data.get(Epoch(4)), Some(&3));
+
+        data.set(4, Epoch(10), &decreased);
+        assert_eq!(data.get(Epoch(10)), Some(&3));
+        assert_eq!(data.get(Epoch(11)), Some(&4));
+    }
+
+    /// Test that the deltas added with a greater offset before the offset has
+    /// been decreased are kept.
+    #[test]
+    fn test_epoched_delta_with_decreased_offset() {
+        let params = PosParams {
+            pipeline_len: 3,
+            ..PosParams::default()
+        };
+        let decreased = PosParams {
+            pipeline_len: 1,
+            ..params.clone()
+        };
+        let (params, decreased) =
+            (constant_params(&params), constant_params(&decreased));
+        let mut data = EpochedDelta::<u64, OffsetPipelineLen>::init_at_genesis(
+            1,
+            Epoch(0),
+        );
+        data.add(2, Epoch(0), &params);
+
+        data.add(4, Epoch(1), &decreased);
+        assert_eq!(data.get(Epoch(1)), Some(1));
+        assert_eq!(data.get(Epoch(2)), Some(5));
+        assert_eq!(data.get(Epoch(3)), Some(7));
+
+        data.add(8, Epoch(10), &decreased);
+        assert_eq!(data.get(Epoch(10)), Some(7));
+        assert_eq!(data.get(Epoch(11)), Some(15));
+    }
+
+    /// Test that the offsets are resolved from the parameters in effect in
+    /// each epoch when the unbonding length is changed across the pipeline
+    /// boundary.
+    #[test]
+    fn test_epoched_with_unbonding_len_change() {
+        let params = PosParams {
+            pipeline_len: 2,
+            unbonding_len: 6,
+            ..PosParams::default()
+        };
+        let decreased = PosParams {
+            unbonding_len: 3,
+            ..params.clone()
+        };
+        // The decreased unbonding length is in effect from the pipeline epoch
+        let mut epoched_params =
+            EpochedPosParams::init_at_genesis(params.clone(), Epoch(0));
+        let current_params = epoched_params.clone();
+        epoched_params.set(decreased.clone(), Epoch(0), &current_params);
+        assert_eq!(epoched_params.get(Epoch(1)), Some(&params));
+        assert_eq!(epoched_params.get(Epoch(2)), Some(&decreased));
+
+        let mut data =
+            Epoched::<u64, OffsetUnbondingLen>::init_at_genesis(0, Epoch(0));
+        data.set(1, Epoch(0), &epoched_params);
+        data.set(2, Epoch(1), &epoched_params);
+        assert_eq!(data.get(Epoch(5)), Some(&0));
+        assert_eq!(data.get(Epoch(6)), Some(&1));
+        assert_eq!(data.get(Epoch(7)), Some(&2));
+        assert_eq!(
+            data.get_at_offset(
+                Epoch(1),
+                DynEpochOffset::UnbondingLen,
+                &epoched_params
+            ),
+            Some(&2)
+        );
+
+        // From the pipeline epoch, the value is set at the decreased offset
+        // and it supersedes the values set with the greater offset
+        data.set(3, Epoch(2), &epoched_params);
+        assert_eq!(data.get(Epoch(4)), Some(&0));
+        assert_eq!(data.get(Epoch(5)), Some(&3));
+        assert_eq!(data.get(Epoch(7)), Some(&3));
+        assert_eq!(
+            data.get_at_offset(
+                Epoch(2),
+                DynEpochOffset::UnbondingLen,
+                &epoched_params
+            ),
+            Some(&3)
+        );
+
+        let mut deltas =
+            EpochedDelta::<u64, OffsetUnbondingLen>::init_at_genesis(
+                10,
+                Epoch(0),
+            );
+        deltas.add(1, Epoch(0), &epoched_params);
+        deltas.add(2, Epoch(2), &epoched_params);
+        assert_eq!(deltas.get(Epoch(4)), Some(10));
+        assert_eq!(deltas.get(Epoch(5)), Some(12));
+        assert_eq!(deltas.get(Epoch(6)), Some(13));
+    }
+
+    /// PoS parameters that are the same in every epoch
+    fn constant_params(params: &PosParams) -> EpochedPosParams {
+        EpochedPosParams::init_at_genesis(params.clone(), Epoch::default())
+    }
+
     /// Abstract representation of [`Epoched`].
     #[derive(Clone, Debug)]
     struct EpochedState<Data> {
@@ -816,7 +987,7 @@ mod tests {
                 let data = Epoched::init(
                     *value,
                     initial_state.last_update,
-                    &initial_state.params,
+                    &constant_params(&initial_state.params),
                 );
                 assert_eq!(
                     Some(*value),
@@ -888,7 +1059,7 @@ mod tests {
                         .map(|epoch| data.get(epoch).copied())
                         .collect();
 
-                    data.set(value, epoch, &params);
+                    data.set(value, epoch, &constant_params(&params));
 
                     // Post-conditions
                     assert_eq!(data.last_update, epoch);
@@ -946,7 +1117,7 @@ mod tests {
                         |val, epoch| update_value(val, epoch),
                         epoch,
                         update_offset,
-                        &params,
+                        &constant_params(&params),
                     );
 
                     // Post-conditions
@@ -1111,7 +1282,7 @@ mod tests {
                 let data = EpochedDelta::init(
                     *value,
                     initial_state.last_update,
-                    &initial_state.params,
+                    &constant_params(&initial_state.params),
                 );
                 assert_eq!(
                     Some(*value),
@@ -1182,7 +1353,7 @@ mod tests {
                         .map(|epoch| data.get(epoch))
                         .collect();
 
-                    data.add(change, epoch, &params);
+                    data.add(change, epoch, &constant_params(&params));
 
                     // Post-conditions
                     assert_eq!(data.last_update, epoch);
@@ -36,10 +36,10 @@ pub use parameters::PosParams;
 use rust_decimal::Decimal;
 use thiserror::Error;
 use types::{
-    ActiveValidator, Bonds, CommissionRates, GenesisValidator, Slash,
-    SlashType, Slashes, TotalDeltas, Unbond, Unbonds, ValidatorConsensusKeys,
-    ValidatorDeltas, ValidatorSet, ValidatorSetUpdate, ValidatorSets,
-    ValidatorState, ValidatorStates,
+    ActiveValidator, Bonds, CommissionRates, EpochedPosParams,
+    GenesisValidator, Slash, SlashType, Slashes, TotalDeltas, Unbond, Unbonds,
+    ValidatorConsensusKeys, ValidatorDeltas, ValidatorSet, ValidatorSetUpdate,
+    ValidatorSets, ValidatorState, ValidatorStates,
 };
 
 use crate::btree_set::BTreeSetShims;
@@ -67,8 +67,12 @@ pub trait PosReadOnly {
     /// Address of the staking token
     fn staking_token_address(&self) -> Address;
 
-    /// Read PoS parameters.
+    /// Read PoS parameters of the current epoch.
     fn read_pos_params(&self) -> Result<PosParams, storage_api::Error>;
+    /// Read PoS parameters of the current and future epochs.
+    fn read_epoched_pos_params(
+        &self,
+    ) -> Result<EpochedPosParams, storage_api::Error>;
     /// Read PoS validator's consensus key (used for signing block votes).
     fn read_validator_consensus_key(
         &self,
@@ -219,7 +223,7 @@ pub trait PosActions: PosReadOnly {
     /// Write PoS parameters.
     fn write_pos_params(
         &mut self,
-        params: &PosParams,
+        params: &EpochedPosParams,
     ) -> Result<(), storage_api::Error>;
     /// Write PoS validator's raw hash of its consensus key.
     fn write_validator_address_raw_hash(
@@ -287,7 +291,7 @@ pub trait PosActions: PosReadOnly {
     /// Delete an emptied PoS unbond (unbonded tokens from validator self-bond
     /// or a delegation).
     fn delete_unbond(&mut self, key: &BondId)
-    -> Result<(), storage_api::Error>;
+        -> Result<(), storage_api::Error>;
 
     /// Transfer tokens from the `src` to the `dest`.
     fn transfer(
@@ -307,7 +311,7 @@ pub trait PosActions: PosReadOnly {
         commission_rate: Decimal,
         max_commission_rate_change: Decimal,
     ) -> Result<(), storage_api::Error> {
-        let params = self.read_pos_params()?;
+        let params = self.read_epoched_pos_params()?;
         let mut validator_set = self.read_validator_set()?;
         if self.is_validator(address)? {
             return Err(BecomeValidatorError::AlreadyValidator(
@@ -367,7 +371,7 @@ pub trait PosActions: PosReadOnly {
                 .into());
             }
         }
-        let params = self.read_pos_params()?;
+        let params = self.read_epoched_pos_params()?;
         let validator_state = self.read_validator_state(validator)?;
         let source = source.unwrap_or(validator);
         let bond_id = BondId {
@@ -418,7 +422,7 @@ pub trait PosActions: PosReadOnly {
         amount: token::Amount,
         current_epoch: Epoch,
     ) -> Result<(), storage_api::Error> {
-        let params = self.read_pos_params()?;
+        let params = self.read_epoched_pos_params()?;
         let source = source.unwrap_or(validator);
         let bond_id = BondId {
             source: source.clone(),
@@ -481,7 +485,7 @@ pub trait PosActions: PosReadOnly {
         validator: &Address,
         current_epoch: Epoch,
     ) -> Result<token::Amount, storage_api::Error> {
-        let params = self.read_pos_params()?;
+        let params = self.read_epoched_pos_params()?;
         let source = source.unwrap_or(validator);
         let bond_id = BondId {
             source: source.clone(),
@@ -562,7 +566,7 @@ pub trait PosActions: PosReadOnly {
                     .into());
                 }
             };
-        let params = self.read_pos_params()?;
+        let params = self.read_epoched_pos_params()?;
         let rate_at_pipeline = *commission_rates
             .get_at_offset(current_epoch, DynEpochOffset::PipelineLen, &params)
             .expect("Could not find a rate in given epoch");
@@ -615,8 +619,12 @@ pub trait PosBase {
     /// Address of the slash pool, into which slashed tokens are transferred.
     const POS_SLASH_POOL_ADDRESS: Address;
 
-    /// Read PoS parameters.
-    fn read_pos_params(&self) -> PosParams;
+    /// Read PoS parameters of the current epoch.
+    fn read_pos_params(&self) -> Result<PosParams, storage_api::Error>;
+    /// Read PoS parameters of the current and future epochs.
+    fn read_epoched_pos_params(
+        &self,
+    ) -> Result<EpochedPosParams, storage_api::Error>;
     /// Read PoS raw hash of validator's consensus key.
     fn read_validator_address_raw_hash(
         &self,
@@ -648,7 +656,7 @@ pub trait PosBase {
     fn read_total_deltas(&self) -> TotalDeltas;
 
     /// Write PoS parameters.
-    fn write_pos_params(&mut self, params: &PosParams);
+    fn write_pos_params(&mut self, params: &EpochedPosParams);
     /// Write PoS validator's raw hash of its consensus key.
     fn write_validator_address_raw_hash(
         &mut self,
@@ -717,7 +725,10 @@ pub trait PosBase {
         validators: impl Iterator<Item = &'a GenesisValidator> + Clone + 'a,
         current_epoch: Epoch,
     ) -> Result<(), GenesisError> {
-        self.write_pos_params(params);
+        self.write_pos_params(&Epoched::init_at_genesis(
+            params.clone(),
+            current_epoch,
+        ));
 
         let GenesisData {
             validators,
@@ -906,9 +917,12 @@ pub trait PosBase {
             })?;
         let mut validator_set = self.read_validator_set();
         let mut total_deltas = self.read_total_deltas();
+        let epoched_params = self
+            .read_epoched_pos_params()
+            .map_err(SlashError::ParamsNotReadable)?;
 
         let slashed_change = slash(
-            params,
+            &epoched_params,
             current_epoch,
             validator,
             &validator_slash,
@@ -1008,6 +1022,8 @@ pub enum SlashError {
     VotingPowerOverflow(TryFromIntError),
     #[error("Unexpected negative stake {0} for validator {1}")]
     NegativeStake(i128, Address),
+    #[error("Cannot read the PoS parameters: {0}")]
+    ParamsNotReadable(storage_api::Error),
 }
 
 #[allow(missing_docs)]
@@ -1151,7 +1167,7 @@ fn init_genesis<'a>(
 /// A function to apply a slash to byzantine validator.
 #[allow(clippy::too_many_arguments)]
 fn slash(
-    params: &PosParams,
+    params: &EpochedPosParams,
     current_epoch: Epoch,
     validator: &Address,
     slash: &Slash,
@@ -1216,8 +1232,9 @@ struct BecomeValidatorData {
 }
 
 /// A function that initialized data for a new validator.
+#[allow(clippy::too_many_arguments)]
 fn become_validator(
-    params: &PosParams,
+    params: &EpochedPosParams,
     address: &Address,
     consensus_key: &common::PublicKey,
     validator_set: &mut ValidatorSets,
@@ -1240,13 +1257,15 @@ fn become_validator(
     );
 
     validator_set.update_from_offset(
-        |validator_set, _epoch| {
+        |validator_set, epoch| {
             let validator = WeightedValidator {
                 bonded_stake: 0,
                 address: address.clone(),
             };
-            if validator_set.active.len() < params.max_validator_slots as usize
-            {
+            // Use the number of slots in effect in the epoch being updated
+            let max_validator_slots =
+                epoched::params_at(params, epoch).max_validator_slots;
+            if validator_set.active.len() < max_validator_slots as usize {
                 validator_set.active.insert(validator);
             } else {
                 validator_set.inactive.insert(validator);
@@ -1274,7 +1293,7 @@ struct BondData {
 /// Bond tokens to a validator (self-bond or delegation).
 #[allow(clippy::too_many_arguments)]
 fn bond_tokens(
-    params: &PosParams,
+    params: &EpochedPosParams,
     validator_state: Option<ValidatorStates>,
     bond_id: &BondId,
     current_bond: Option<Bonds>,
@@ -1292,8 +1311,8 @@ fn bond_tokens(
         Some(validator_state) => {
             // Check that it's not inactive anywhere from the current epoch
             // to the pipeline offset
-            for epoch in
-                current_epoch.iter_range(OffsetPipelineLen::value(params))
+            for epoch in current_epoch
+                .iter_range(OffsetPipelineLen::value_at(params, current_epoch))
             {
                 if let Some(ValidatorState::Inactive) =
                     validator_state.get(epoch)
@@ -1314,9 +1333,10 @@ fn bond_tokens(
     };
     // Initialize the bond at the pipeline offset
     let update_offset = DynEpochOffset::PipelineLen;
-    value
-        .pos_deltas
-        .insert(current_epoch + update_offset.value(params), amount);
+    value.pos_deltas.insert(
+        current_epoch + update_offset.value_at(params, current_epoch),
+        amount,
+    );
     let bond = match current_bond {
         None => EpochedDelta::init_at_offset(
             value,
@@ -1379,7 +1399,7 @@ struct UnbondData {
 /// Unbond tokens from a validator's bond (self-bond or delegation).
 #[allow(clippy::too_many_arguments)]
 fn unbond_tokens(
-    params: &PosParams,
+    params: &EpochedPosParams,
     bond_id: &BondId,
     bond: &mut Bonds,
     unbond: Option<Unbonds>,
@@ -1421,8 +1441,9 @@ fn unbond_tokens(
                     return true;
                 }
                 let mut unbonded = HashMap::default();
-                let unbond_end =
-                    current_epoch + update_offset.value(params) - 1;
+                let unbond_end = current_epoch
+                    + update_offset.value_at(params, current_epoch)
+                    - 1;
                 // We need to accumulate the slashed delta for multiple
                 // slashes applicable to a bond, where
                 // each slash should be calculated from
@@ -1498,7 +1519,7 @@ fn unbond_tokens(
 /// Update validator set when a validator's receives a new bond and when its
 /// bond is unbonded (self-bond or delegation).
 fn update_validator_set(
-    params: &PosParams,
+    params: &EpochedPosParams,
     validator: &Address,
     token_change: token::Change,
     change_offset: DynEpochOffset,
@@ -1581,6 +1602,43 @@ fn update_validator_set(
     )
 }
 
+/// Update the validator sets from the pipeline offset onward after a change of
+/// the maximum number of validator slots. The validators with the least bonded
+/// stake are moved from the active to the inactive set, or the validators with
+/// the most bonded stake from the inactive to the active set, until the active
+/// set fills the slots in effect in the epoch being updated.
+pub fn update_validator_set_slots(
+    params: &EpochedPosParams,
+    validator_set: &mut ValidatorSets,
+    current_epoch: Epoch,
+) {
+    validator_set.update_from_offset(
+        |validator_set, epoch| {
+            let max_validator_slots =
+                epoched::params_at(params, epoch).max_validator_slots as usize;
+            while validator_set.active.len() > max_validator_slots {
+                match validator_set.active.pop_first_shim() {
+                    Some(min_active_validator) => {
+                        validator_set.inactive.insert(min_active_validator);
+                    }
+                    None => break,
+                }
+            }
+            while validator_set.active.len() < max_validator_slots {
+                match validator_set.inactive.pop_last_shim() {
+                    Some(max_inactive_validator) => {
+                        validator_set.active.insert(max_inactive_validator);
+                    }
+                    None => break,
+                }
+            }
+        },
+        current_epoch,
+        DynEpochOffset::PipelineLen,
+        params,
+    )
+}
+
 struct WithdrawData {
     pub unbond: Unbonds,
     pub withdrawn: token::Amount,
@@ -1589,7 +1647,7 @@ struct WithdrawData {
 
 /// Withdraw tokens from unbonds of self-bonds or delegations.
 fn withdraw_unbonds(
-    params: &PosParams,
+    params: &EpochedPosParams,
     bond_id: &BondId,
     unbond: Option<Unbonds>,
     slashes: Vec<Slash>,
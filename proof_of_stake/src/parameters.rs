@@ -1,14 +1,17 @@
 //! Proof-of-Stake system parameters
 
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use thiserror::Error;
 
 /// Proof-of-Stake system parameters, set at genesis and can only be changed via
-/// governance
-#[derive(Debug, Clone, BorshDeserialize, BorshSerialize)]
+/// governance. A change takes effect at the pipeline offset from the epoch in
+/// which it's applied.
+#[derive(
+    Debug, Clone, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema,
+)]
 pub struct PosParams {
     /// A maximum number of active validators
     pub max_validator_slots: u64,
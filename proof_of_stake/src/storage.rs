@@ -1,9 +1,11 @@
 //! Proof-of-Stake storage keys and storage integration via [`PosBase`] trait.
 
+use borsh::BorshDeserialize;
 use namada_core::ledger::storage::types::{decode, encode};
 use namada_core::ledger::storage::{self, Storage, StorageHasher};
+use namada_core::ledger::storage_api::{self, ResultExt};
 use namada_core::types::address::Address;
-use namada_core::types::storage::{DbKeySeg, Key, KeySeg};
+use namada_core::types::storage::{DbKeySeg, Epoch, Key, KeySeg};
 use namada_core::types::{key, token};
 use rust_decimal::Decimal;
 
@@ -47,6 +49,33 @@ pub fn is_params_key(key: &Key) -> bool {
     matches!(&key.segments[..], [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(key)] if addr == &ADDRESS && key == PARAMS_STORAGE_KEY)
 }
 
+/// Decode the PoS parameters stored under the [`params_key`]. The parameters
+/// used to be stored as a single [`PosParams`] value, which is read as the
+/// parameters of every epoch, so that the storage written before they were
+/// stored per epoch is still readable.
+pub fn decode_params(
+    bytes: impl AsRef<[u8]>,
+) -> std::io::Result<EpochedPosParams> {
+    let bytes = bytes.as_ref();
+    EpochedPosParams::try_from_slice(bytes).or_else(|err| {
+        PosParams::try_from_slice(bytes)
+            .map(|params| {
+                EpochedPosParams::init_at_genesis(params, Epoch::default())
+            })
+            .map_err(|_| err)
+    })
+}
+
+/// Find the PoS parameters in effect in the given epoch.
+pub fn params_at_epoch(
+    params: &EpochedPosParams,
+    epoch: Epoch,
+) -> storage_api::Result<PosParams> {
+    params.get(epoch).cloned().ok_or_else(|| {
+        storage_api::Error::new_const("PoS parameters must be set")
+    })
+}
+
 /// Storage key prefix for validator data.
 fn validator_prefix(validator: &Address) -> Key {
     Key::from(ADDRESS.to_db_key())
@@ -358,9 +387,17 @@ where
         self.native_token.clone()
     }
 
-    fn read_pos_params(&self) -> PosParams {
-        let (value, _gas) = self.read(&params_key()).unwrap();
-        decode(value.unwrap()).unwrap()
+    fn read_pos_params(&self) -> storage_api::Result<PosParams> {
+        let params = PosBase::read_epoched_pos_params(self)?;
+        params_at_epoch(&params, self.block.epoch)
+    }
+
+    fn read_epoched_pos_params(&self) -> storage_api::Result<EpochedPosParams> {
+        let (value, _gas) = self.read(&params_key()).into_storage_result()?;
+        let value = value.ok_or_else(|| {
+            storage_api::Error::new_const("PoS parameters must be set")
+        })?;
+        decode_params(value).into_storage_result()
     }
 
     fn read_validator_address_raw_hash(
@@ -437,7 +474,7 @@ where
         decode(value.unwrap()).unwrap()
     }
 
-    fn write_pos_params(&mut self, params: &PosParams) {
+    fn write_pos_params(&mut self, params: &EpochedPosParams) {
         self.write(&params_key(), encode(params)).unwrap();
     }
 
@@ -616,8 +653,15 @@ macro_rules! impl_pos_read_only {
             }
 
             fn read_pos_params(&self) -> namada_core::ledger::storage_api::Result<PosParams> {
-                let value = namada_core::ledger::storage_api::StorageRead::read_bytes(self, &params_key())?.unwrap();
-                Ok(namada_core::ledger::storage::types::decode(value).unwrap())
+                let current_epoch = namada_core::ledger::storage_api::StorageRead::get_block_epoch(self)?;
+                let params = <Self as $crate::PosReadOnly>::read_epoched_pos_params(self)?;
+                $crate::storage::params_at_epoch(&params, current_epoch)
+            }
+
+            fn read_epoched_pos_params(&self) -> namada_core::ledger::storage_api::Result<EpochedPosParams> {
+                let value = namada_core::ledger::storage_api::StorageRead::read_bytes(self, &params_key())?
+                    .ok_or_else(|| namada_core::ledger::storage_api::Error::new_const("PoS parameters must be set"))?;
+                namada_core::ledger::storage_api::ResultExt::into_storage_result($crate::storage::decode_params(value))
             }
 
             fn read_validator_consensus_key(
@@ -715,3 +759,35 @@ impl_pos_read_only! {
             DB: storage::DB + for<'iter> storage::DBIter<'iter> +'static,
             H: StorageHasher +'static,
 }
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::parameters::testing::arb_pos_params;
+
+    proptest! {
+        #[test]
+        fn test_decode_params(
+            params in arb_pos_params(),
+            epoch in 0..100_u64,
+        ) {
+            let epoched = EpochedPosParams::init_at_genesis(
+                params.clone(),
+                Epoch::default(),
+            );
+            let decoded = decode_params(encode(&epoched)).unwrap();
+            assert_eq!(decoded, epoched);
+
+            // The legacy encoding of a single `PosParams` value is read as
+            // the parameters of every epoch
+            let decoded = decode_params(encode(&params)).unwrap();
+            assert_eq!(decoded, epoched);
+            assert_eq!(
+                params_at_epoch(&decoded, Epoch(epoch)).unwrap(),
+                params
+            );
+        }
+    }
+}
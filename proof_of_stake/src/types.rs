@@ -36,6 +36,8 @@ pub type ValidatorSets = Epoched<ValidatorSet, OffsetUnbondingLen>;
 pub type TotalDeltas = EpochedDelta<token::Change, OffsetUnbondingLen>;
 /// Epoched validator commission rate
 pub type CommissionRates = Epoched<Decimal, OffsetPipelineLen>;
+/// Epoched PoS parameters.
+pub type EpochedPosParams = Epoched<PosParams, OffsetPipelineLen>;
 
 /// A genesis validator definition.
 #[derive(
@@ -15,11 +15,11 @@ use thiserror::Error;
 
 use crate::btree_set::BTreeSetShims;
 use crate::epoched::DynEpochOffset;
-use crate::parameters::PosParams;
 use crate::types::{
-    decimal_mult_i128, decimal_mult_u64, BondId, Bonds, CommissionRates, Slash,
-    Slashes, TotalDeltas, Unbonds, ValidatorConsensusKeys, ValidatorDeltas,
-    ValidatorSets, ValidatorState, ValidatorStates, WeightedValidator,
+    decimal_mult_i128, decimal_mult_u64, BondId, Bonds, CommissionRates,
+    EpochedPosParams, Slash, Slashes, TotalDeltas, Unbonds,
+    ValidatorConsensusKeys, ValidatorDeltas, ValidatorSets, ValidatorState,
+    ValidatorStates, WeightedValidator,
 };
 
 #[allow(missing_docs)]
@@ -144,6 +144,10 @@ pub enum Error {
          validator {0}, got {1}."
     )]
     InvalidMaxCommissionRateChange(Address, Decimal),
+    #[error("Unexpectedly missing PoS parameters")]
+    MissingParams,
+    #[error("Invalid PoS parameters in epoch {0}: {1}")]
+    InvalidParams(u64, String),
 }
 
 /// An update of PoS data.
@@ -180,6 +184,8 @@ pub enum DataUpdate {
     ValidatorSet(Data<ValidatorSets>),
     /// Total deltas update
     TotalDeltas(Data<TotalDeltas>),
+    /// PoS parameters update
+    Params(Data<EpochedPosParams>),
     /// Validator's address raw hash
     ValidatorAddressRawHash {
         /// Raw hash value
@@ -239,14 +245,18 @@ struct Constants {
     unbonding_offset: u64,
 }
 
-/// Validate the given list of PoS data `changes`. Returns empty list, if all
-/// the changes are valid.
+/// Validate the given list of PoS data `changes` with the PoS parameters in
+/// effect in each epoch. Returns empty list, if all the changes are valid.
 #[must_use]
 pub fn validate(
-    params: &PosParams,
+    epoched_params: &EpochedPosParams,
     changes: Vec<DataUpdate>,
     current_epoch: Epoch,
 ) -> Vec<Error> {
+    let params = match epoched_params.get(current_epoch) {
+        Some(params) => params,
+        None => return vec![Error::MissingParams],
+    };
     let pipeline_offset = DynEpochOffset::PipelineLen.value(params);
     let unbonding_offset = DynEpochOffset::UnbondingLen.value(params);
     let pipeline_epoch = current_epoch + pipeline_offset;
@@ -272,7 +282,7 @@ pub fn validate(
         total_deltas_by_epoch: _,
         bonded_stake_by_epoch,
         new_validators,
-    } = Validate::accumulate_changes(changes, params, &constants, &mut errors);
+    } = Validate::accumulate_changes(changes, &constants, &mut errors);
 
     // Check total deltas against bonds
     for (validator, total_delta) in total_deltas.iter() {
@@ -309,7 +319,12 @@ pub fn validate(
         for epoch in Epoch::iter_range(current_epoch, unbonding_offset + 1) {
             if let Some(post) = post.get_at_epoch(epoch) {
                 // Check that active validators length is not over the limit
-                if post.active.len() > params.max_validator_slots as usize {
+                // of the parameters in effect in this epoch
+                let max_validator_slots = epoched_params
+                    .get(epoch)
+                    .unwrap_or(params)
+                    .max_validator_slots;
+                if post.active.len() > max_validator_slots as usize {
                     errors.push(Error::TooManyActiveValidators)
                 }
                 // Check that all active have voting power >= any inactive
@@ -641,7 +656,6 @@ struct Validate {
 impl Validate {
     fn accumulate_changes(
         changes: Vec<DataUpdate>,
-        _params: &PosParams,
         constants: &Constants,
         errors: &mut Vec<Error>,
     ) -> Accumulator {
@@ -732,6 +746,7 @@ impl Validate {
                     total_deltas_by_epoch,
                     data,
                 ),
+                Params(data) => Self::params(constants, errors, data),
                 ValidatorAddressRawHash { raw_hash, data } => {
                     Self::validator_address_raw_hash(
                         errors,
@@ -1679,6 +1694,45 @@ impl Validate {
         }
     }
 
+    fn params(
+        constants: &Constants,
+        errors: &mut Vec<Error>,
+        data: Data<EpochedPosParams>,
+    ) {
+        match (data.pre, data.post) {
+            (Some(pre), Some(post)) => {
+                if post.last_update() != constants.current_epoch {
+                    errors.push(Error::InvalidLastUpdate)
+                }
+                // The parameters may only be changed from the pipeline epoch
+                for epoch in Epoch::iter_range(
+                    constants.current_epoch,
+                    constants.pipeline_offset,
+                ) {
+                    if pre.get(epoch) != post.get(epoch) {
+                        errors.push(Error::EpochedDataWrongEpoch {
+                            got: epoch.into(),
+                            expected: vec![constants.pipeline_epoch.into()],
+                        })
+                    }
+                }
+                // Any new parameters must be valid
+                for (index, params) in post.data.iter().enumerate() {
+                    if let Some(params) = params {
+                        let epoch = post.last_update() + index;
+                        for error in params.validate() {
+                            errors.push(Error::InvalidParams(
+                                epoch.into(),
+                                error.to_string(),
+                            ))
+                        }
+                    }
+                }
+            }
+            _ => errors.push(Error::MissingParams),
+        }
+    }
+
     fn validator_address_raw_hash(
         errors: &mut Vec<Error>,
         new_validators: &mut HashMap<Address, NewValidator>,
@@ -1,5 +1,8 @@
 //! Logic to do with events emitted by the ledger.
 pub mod log;
+pub mod store;
+pub mod subscription;
+pub mod typed;
 
 use std::collections::HashMap;
 use std::convert::TryFrom;
@@ -11,7 +14,11 @@ use thiserror::Error;
 
 use crate::ledger::native_vp::governance::utils::ProposalEvent;
 use crate::tendermint_proto::abci::EventAttribute;
+use crate::types::governance::{ProposalStatus, TreasuryPayment};
 use crate::types::ibc::IbcEvent;
+use crate::types::storage::Epoch;
+use crate::types::token;
+use crate::types::transaction::pos;
 #[cfg(feature = "ferveo-tpke")]
 use crate::types::transaction::{hash_tx, TxType};
 
@@ -49,6 +56,14 @@ pub enum EventType {
     Ibc(String),
     /// The proposal that has been executed
     Proposal,
+    /// The token transfer made by an applied transaction
+    Transfer,
+    /// The bond made by an applied transaction
+    Bond,
+    /// The payment made by the treasury for a treasury spend proposal
+    TreasuryPayment,
+    /// The transition of a proposal to a new status
+    ProposalStatus,
 }
 
 impl Display for EventType {
@@ -58,6 +73,10 @@ impl Display for EventType {
             EventType::Applied => write!(f, "applied"),
             EventType::Ibc(t) => write!(f, "{}", t),
             EventType::Proposal => write!(f, "proposal"),
+            EventType::Transfer => write!(f, "transfer"),
+            EventType::Bond => write!(f, "bond"),
+            EventType::TreasuryPayment => write!(f, "treasury_payment"),
+            EventType::ProposalStatus => write!(f, "proposal_status"),
         }?;
         Ok(())
     }
@@ -112,6 +131,86 @@ impl Event {
         event
     }
 
+    /// Creates a new event with the details of the token transfer made by
+    /// the applied transaction with the given hash
+    pub fn new_transfer_event(
+        tx_hash: impl Into<String>,
+        height: u64,
+        transfer: &token::Transfer,
+    ) -> Self {
+        let mut event = Event {
+            event_type: EventType::Transfer,
+            level: EventLevel::Tx,
+            attributes: HashMap::new(),
+        };
+        event["hash"] = tx_hash.into();
+        event["height"] = height.to_string();
+        event["source"] = transfer.source.encode();
+        event["target"] = transfer.target.encode();
+        event["token"] = transfer.token.encode();
+        if let Some(sub_prefix) = &transfer.sub_prefix {
+            event["sub_prefix"] = sub_prefix.to_string();
+        }
+        event["amount"] = transfer.amount.to_string();
+        event
+    }
+
+    /// Creates a new event with the details of the bond made by the applied
+    /// transaction with the given hash
+    pub fn new_bond_event(
+        tx_hash: impl Into<String>,
+        height: u64,
+        bond: &pos::Bond,
+    ) -> Self {
+        let mut event = Event {
+            event_type: EventType::Bond,
+            level: EventLevel::Tx,
+            attributes: HashMap::new(),
+        };
+        event["hash"] = tx_hash.into();
+        event["height"] = height.to_string();
+        event["validator"] = bond.validator.encode();
+        if let Some(source) = &bond.source {
+            event["source"] = source.encode();
+        }
+        event["amount"] = bond.amount.to_string();
+        event
+    }
+
+    /// Creates a new event with the details of a payment made by the
+    /// treasury for a treasury spend proposal
+    pub fn new_treasury_payment_event(payment: &TreasuryPayment) -> Self {
+        let mut event = Event {
+            event_type: EventType::TreasuryPayment,
+            level: EventLevel::Block,
+            attributes: HashMap::new(),
+        };
+        event["proposal_id"] = payment.proposal_id.to_string();
+        event["epoch"] = payment.epoch.to_string();
+        event["recipient"] = payment.recipient.encode();
+        event["token"] = payment.token.encode();
+        event["amount"] = payment.amount.to_string();
+        event
+    }
+
+    /// Creates a new event for the transition of a proposal to a new status
+    /// at the beginning of an epoch
+    pub fn new_proposal_status_event(
+        proposal_id: u64,
+        status: ProposalStatus,
+        epoch: Epoch,
+    ) -> Self {
+        let mut event = Event {
+            event_type: EventType::ProposalStatus,
+            level: EventLevel::Block,
+            attributes: HashMap::new(),
+        };
+        event["proposal_id"] = proposal_id.to_string();
+        event["status"] = status.to_string();
+        event["epoch"] = epoch.to_string();
+        event
+    }
+
     /// Check if the events keys contains a given string
     pub fn contains_key(&self, key: &str) -> bool {
         self.attributes.contains_key(key)
@@ -10,7 +10,7 @@ use circular_queue::CircularQueue;
 
 use crate::ledger::events::Event;
 
-pub mod dumb_queries;
+pub mod query;
 
 /// Parameters to configure the pruning of the event log.
 #[derive(Debug, Copy, Clone)]
@@ -65,11 +65,12 @@ impl EventLog {
         tracing::debug!(num_entries, "Added new entries to the event log");
     }
 
-    /// Returns a new iterator over this [`EventLog`].
+    /// Returns a new iterator over the events of this [`EventLog`] that
+    /// match the given query.
     #[inline]
     pub fn iter_with_matcher(
         &self,
-        matcher: dumb_queries::QueryMatcher,
+        matcher: query::QueryMatcher,
     ) -> impl Iterator<Item = &Event> {
         self.queue
             .iter()
@@ -89,7 +90,7 @@ mod tests {
     /// An accepted tx hash query.
     macro_rules! accepted {
         ($hash:expr) => {
-            dumb_queries::QueryMatcher::accepted(Hash::try_from($hash).unwrap())
+            query::QueryMatcher::accepted(Hash::try_from($hash).unwrap())
         };
     }
 
@@ -0,0 +1,650 @@
+//! Tendermint query parser and evaluator.
+//!
+//! A query is made of one or more conditions joined by `AND`, for example:
+//!
+//! ```text
+//! tm.event='NewBlock' AND applied.height>=10 AND applied.code EXISTS
+//! ```
+//!
+//! Every condition starts with a composite key of the form
+//! `<event_type>.<attr>`, which is compared against the attribute `<attr>` of
+//! the events of the type `<event_type>`. The special key `tm.event` takes the
+//! value `NewBlock` for all the events in the log, because they are all
+//! emitted by `FinalizeBlock` calls.
+//!
+//! The supported operators are `=`, `<`, `<=`, `>`, `>=`, `CONTAINS` and
+//! `EXISTS`. The operands can be strings (`'value'`), numbers (`10`, `0.5`),
+//! dates (`DATE 2022-12-31`) or times (`TIME 2022-12-31T23:59:59Z`). The
+//! ordering operators only accept number, date and time operands.
+
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::ledger::events::{Event, EventType};
+use crate::types::hash::Hash;
+use crate::types::time::DateTimeUtc;
+
+/// The key of the type of the Tendermint event.
+const TM_EVENT_KEY: &str = "tm.event";
+
+/// The value of [`TM_EVENT_KEY`] of all the events in the log.
+const TM_EVENT_NEW_BLOCK: &str = "NewBlock";
+
+/// Errors from parsing a Tendermint query.
+#[allow(missing_docs)]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    #[error("The query is empty")]
+    Empty,
+    #[error("Unexpected end of the query, expected {0}")]
+    UnexpectedEnd(&'static str),
+    #[error("Unexpected {found:?} at position {pos}, expected {expected}")]
+    Unexpected {
+        found: String,
+        pos: usize,
+        expected: &'static str,
+    },
+    #[error("Unterminated string starting at position {0}")]
+    UnterminatedString(usize),
+    #[error("Invalid date {0:?}, expected the format YYYY-MM-DD")]
+    InvalidDate(String),
+    #[error("Invalid time {0:?}, expected an RFC 3339 time")]
+    InvalidTime(String),
+    #[error(
+        "The operator {0} requires a number, a date or a time operand, got a \
+         string"
+    )]
+    StringOrdering(&'static str),
+}
+
+/// A [`QueryMatcher`] verifies if a Namada event matches a
+/// given Tendermint query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryMatcher {
+    conditions: Vec<Condition>,
+}
+
+/// A single condition of a [`QueryMatcher`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Condition {
+    /// The composite key of the event's attribute
+    pub key: String,
+    /// The operation applied on the attribute's value
+    pub op: Operation,
+}
+
+/// An operation of a [`Condition`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operation {
+    /// The value is equal to the operand
+    Eq(Operand),
+    /// The value is less than the operand
+    Lt(Operand),
+    /// The value is less than or equal to the operand
+    Le(Operand),
+    /// The value is greater than the operand
+    Gt(Operand),
+    /// The value is greater than or equal to the operand
+    Ge(Operand),
+    /// The value contains the given string
+    Contains(String),
+    /// The attribute is present
+    Exists,
+}
+
+/// An operand of an [`Operation`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operand {
+    /// A string, compared verbatim
+    String(String),
+    /// A number literal, compared as an integer when both sides are
+    /// integers, otherwise as a float
+    Number(String),
+    /// A date in the format `YYYY-MM-DD`
+    Date(String),
+    /// An RFC 3339 time
+    Time(DateTimeUtc),
+}
+
+impl QueryMatcher {
+    /// Returns a query with the given conditions joined by `AND`.
+    pub fn new(conditions: Vec<Condition>) -> Self {
+        Self { conditions }
+    }
+
+    /// The conditions of this query.
+    pub fn conditions(&self) -> &[Condition] {
+        &self.conditions
+    }
+
+    /// Checks if this [`QueryMatcher`] validates the
+    /// given [`Event`].
+    pub fn matches(&self, event: &Event) -> bool {
+        self.conditions
+            .iter()
+            .all(|condition| condition.matches(event))
+    }
+
+    /// Returns a query matching the given accepted transaction hash.
+    pub fn accepted(tx_hash: Hash) -> Self {
+        Self::tx_hash(EventType::Accepted, tx_hash)
+    }
+
+    /// Returns a query matching the given applied transaction hash.
+    pub fn applied(tx_hash: Hash) -> Self {
+        Self::tx_hash(EventType::Applied, tx_hash)
+    }
+
+    fn tx_hash(event_type: EventType, tx_hash: Hash) -> Self {
+        Self::new(vec![
+            Condition {
+                key: TM_EVENT_KEY.to_string(),
+                op: Operation::Eq(Operand::String(
+                    TM_EVENT_NEW_BLOCK.to_string(),
+                )),
+            },
+            Condition {
+                key: format!("{}.hash", event_type),
+                op: Operation::Eq(Operand::String(tx_hash.to_string())),
+            },
+        ])
+    }
+}
+
+impl Condition {
+    /// Checks if the given [`Event`] satisfies this condition.
+    pub fn matches(&self, event: &Event) -> bool {
+        let value = if self.key == TM_EVENT_KEY {
+            Some(TM_EVENT_NEW_BLOCK)
+        } else {
+            self.key
+                .split_once('.')
+                .filter(|(event_type, _attr)| {
+                    *event_type == event.event_type.to_string()
+                })
+                .and_then(|(_event_type, attr)| event.get(attr))
+                .map(String::as_str)
+        };
+        let value = match value {
+            Some(value) => value,
+            None => return false,
+        };
+        let ordering = |operand: &Operand| operand.compare_value(value);
+        match &self.op {
+            Operation::Eq(operand) => {
+                ordering(operand) == Some(Ordering::Equal)
+            }
+            Operation::Lt(operand) => ordering(operand) == Some(Ordering::Less),
+            Operation::Le(operand) => matches!(
+                ordering(operand),
+                Some(Ordering::Less | Ordering::Equal)
+            ),
+            Operation::Gt(operand) => {
+                ordering(operand) == Some(Ordering::Greater)
+            }
+            Operation::Ge(operand) => matches!(
+                ordering(operand),
+                Some(Ordering::Greater | Ordering::Equal)
+            ),
+            Operation::Contains(needle) => value.contains(needle.as_str()),
+            Operation::Exists => true,
+        }
+    }
+}
+
+impl Operand {
+    /// Compare an attribute's value with this operand. Returns `None` if the
+    /// value cannot be interpreted as the type of the operand.
+    fn compare_value(&self, value: &str) -> Option<Ordering> {
+        match self {
+            Operand::String(operand) => Some(value.cmp(operand.as_str())),
+            Operand::Number(operand) => {
+                match (value.parse::<i128>(), operand.parse::<i128>()) {
+                    (Ok(value), Ok(operand)) => Some(value.cmp(&operand)),
+                    _ => {
+                        let value = value.parse::<f64>().ok()?;
+                        let operand = operand.parse::<f64>().ok()?;
+                        value.partial_cmp(&operand)
+                    }
+                }
+            }
+            Operand::Date(operand) => {
+                // A time value is compared by its date
+                let value = parse_date(value).or_else(|| {
+                    DateTimeUtc::from_str(value)
+                        .ok()
+                        .map(|time| time.0.format("%Y-%m-%d").to_string())
+                })?;
+                Some(value.as_str().cmp(operand.as_str()))
+            }
+            Operand::Time(operand) => {
+                let value = DateTimeUtc::from_str(value).ok()?;
+                Some(value.cmp(operand))
+            }
+        }
+    }
+}
+
+/// Parse and normalize a date in the format `YYYY-MM-DD`.
+fn parse_date(date: &str) -> Option<String> {
+    if date.len() != 10 {
+        return None;
+    }
+    DateTimeUtc::from_str(&format!("{}T00:00:00Z", date))
+        .ok()
+        .map(|time| time.0.format("%Y-%m-%d").to_string())
+}
+
+impl FromStr for QueryMatcher {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Parser { input: s, pos: 0 }.parse()
+    }
+}
+
+impl Display for QueryMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, condition) in self.conditions.iter().enumerate() {
+            if i > 0 {
+                write!(f, " AND ")?;
+            }
+            write!(f, "{}", condition)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.op {
+            Operation::Eq(operand) => write!(f, "{} = {}", self.key, operand),
+            Operation::Lt(operand) => write!(f, "{} < {}", self.key, operand),
+            Operation::Le(operand) => write!(f, "{} <= {}", self.key, operand),
+            Operation::Gt(operand) => write!(f, "{} > {}", self.key, operand),
+            Operation::Ge(operand) => write!(f, "{} >= {}", self.key, operand),
+            Operation::Contains(needle) => {
+                write!(f, "{} CONTAINS '{}'", self.key, needle)
+            }
+            Operation::Exists => write!(f, "{} EXISTS", self.key),
+        }
+    }
+}
+
+impl Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::String(string) => write!(f, "'{}'", string),
+            Operand::Number(number) => write!(f, "{}", number),
+            Operand::Date(date) => write!(f, "DATE {}", date),
+            Operand::Time(time) => write!(f, "TIME {}", time.to_rfc3339()),
+        }
+    }
+}
+
+/// A recursive descent parser of Tendermint queries.
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn parse(mut self) -> Result<QueryMatcher, ParseError> {
+        self.skip_whitespace();
+        if self.rest().is_empty() {
+            return Err(ParseError::Empty);
+        }
+        let mut conditions = vec![self.parse_condition()?];
+        loop {
+            self.skip_whitespace();
+            if self.rest().is_empty() {
+                return Ok(QueryMatcher::new(conditions));
+            }
+            if !self.eat_keyword("AND") {
+                return Err(self.unexpected("`AND` or the end of the query"));
+            }
+            self.skip_whitespace();
+            conditions.push(self.parse_condition()?);
+        }
+    }
+
+    fn parse_condition(&mut self) -> Result<Condition, ParseError> {
+        let key = self.parse_key()?;
+        self.skip_whitespace();
+        let op = if self.eat("<=") {
+            Operation::Le(self.parse_ordering_operand("<=")?)
+        } else if self.eat(">=") {
+            Operation::Ge(self.parse_ordering_operand(">=")?)
+        } else if self.eat("<") {
+            Operation::Lt(self.parse_ordering_operand("<")?)
+        } else if self.eat(">") {
+            Operation::Gt(self.parse_ordering_operand(">")?)
+        } else if self.eat("=") {
+            Operation::Eq(self.parse_operand()?)
+        } else if self.eat_keyword("CONTAINS") {
+            self.skip_whitespace();
+            Operation::Contains(self.parse_string()?)
+        } else if self.eat_keyword("EXISTS") {
+            Operation::Exists
+        } else {
+            return Err(self.unexpected("an operator"));
+        };
+        Ok(Condition { key, op })
+    }
+
+    fn parse_key(&mut self) -> Result<String, ParseError> {
+        let key = self.take_while(is_key_char);
+        if key.is_empty() {
+            return Err(self.unexpected("an attribute key"));
+        }
+        Ok(key.to_string())
+    }
+
+    fn parse_ordering_operand(
+        &mut self,
+        op: &'static str,
+    ) -> Result<Operand, ParseError> {
+        match self.parse_operand()? {
+            Operand::String(_) => Err(ParseError::StringOrdering(op)),
+            operand => Ok(operand),
+        }
+    }
+
+    fn parse_operand(&mut self) -> Result<Operand, ParseError> {
+        self.skip_whitespace();
+        if self.rest().starts_with('\'') {
+            return self.parse_string().map(Operand::String);
+        }
+        if self.eat_keyword("DATE") {
+            self.skip_whitespace();
+            let date = self.take_while(|c| !c.is_whitespace());
+            return parse_date(date)
+                .map(Operand::Date)
+                .ok_or_else(|| ParseError::InvalidDate(date.to_string()));
+        }
+        if self.eat_keyword("TIME") {
+            self.skip_whitespace();
+            let time = self.take_while(|c| !c.is_whitespace());
+            return DateTimeUtc::from_str(time)
+                .map(Operand::Time)
+                .map_err(|_| ParseError::InvalidTime(time.to_string()));
+        }
+        let start = self.pos;
+        self.eat("-");
+        let int = self.take_while(|c| c.is_ascii_digit());
+        if int.is_empty() {
+            self.pos = start;
+            return Err(self.unexpected("an operand"));
+        }
+        if self.eat(".") {
+            self.take_while(|c| c.is_ascii_digit());
+        }
+        Ok(Operand::Number(self.input[start..self.pos].to_string()))
+    }
+
+    fn parse_string(&mut self) -> Result<String, ParseError> {
+        let start = self.pos;
+        if !self.eat("'") {
+            return Err(self.unexpected("a quoted string"));
+        }
+        let string = self.take_while(|c| c != '\'');
+        if !self.eat("'") {
+            return Err(ParseError::UnterminatedString(start));
+        }
+        Ok(string.to_string())
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.take_while(char::is_whitespace);
+    }
+
+    fn take_while(&mut self, pred: impl Fn(char) -> bool) -> &'a str {
+        let rest = self.rest();
+        let len = rest.find(|c| !pred(c)).unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    /// Consume the given token, if it's next in the input.
+    fn eat(&mut self, token: &str) -> bool {
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consume the given keyword, if it's next in the input and it's not a
+    /// prefix of a longer word.
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let rest = self.rest();
+        let is_keyword = rest.starts_with(keyword)
+            && !rest[keyword.len()..].starts_with(is_key_char);
+        if is_keyword {
+            self.pos += keyword.len();
+        }
+        is_keyword
+    }
+
+    fn unexpected(&self, expected: &'static str) -> ParseError {
+        match self.rest().split_whitespace().next() {
+            Some(found) => ParseError::Unexpected {
+                found: found.to_string(),
+                pos: self.pos,
+                expected,
+            },
+            None => ParseError::UnexpectedEnd(expected),
+        }
+    }
+}
+
+/// The characters allowed in attribute keys.
+fn is_key_char(c: char) -> bool {
+    !c.is_whitespace()
+        && !matches!(c, '\\' | '(' | ')' | '"' | '\'' | '=' | '<' | '>')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::events::EventLevel;
+
+    const HASH: &str =
+        "DEADBEEFDEADBEEFDEADBEEFDEADBEEFDEADBEEFDEADBEEFDEADBEEFDEADBEEF";
+
+    /// Return a new event with the given type and attributes.
+    fn event(event_type: EventType, attrs: &[(&str, &str)]) -> Event {
+        Event {
+            event_type,
+            level: EventLevel::Tx,
+            attributes: attrs
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+
+    /// Test if query matching is working as expected.
+    #[test]
+    fn test_tm_query_matching() {
+        let matcher = QueryMatcher::accepted(HASH.try_into().unwrap());
+
+        let tests = {
+            let event_1 = Event {
+                event_type: EventType::Accepted,
+                level: EventLevel::Block,
+                attributes: {
+                    let mut attrs = std::collections::HashMap::new();
+                    attrs.insert("hash".to_string(), HASH.to_string());
+                    attrs
+                },
+            };
+            let accepted_1 = true;
+
+            let event_2 = Event {
+                event_type: EventType::Applied,
+                level: EventLevel::Block,
+                attributes: {
+                    let mut attrs = std::collections::HashMap::new();
+                    attrs.insert("hash".to_string(), HASH.to_string());
+                    attrs
+                },
+            };
+            let accepted_2 = false;
+
+            [(event_1, accepted_1), (event_2, accepted_2)]
+        };
+
+        for (ref ev, status) in tests {
+            if matcher.matches(ev) != status {
+                panic!("Test failed");
+            }
+        }
+    }
+
+    /// Test that the query constructors are equal to their parsed queries.
+    #[test]
+    fn test_tm_query_constructors() {
+        let query = format!("tm.event='NewBlock' AND applied.hash='{}'", HASH);
+        let parsed: QueryMatcher = query.parse().unwrap();
+        assert_eq!(parsed, QueryMatcher::applied(HASH.try_into().unwrap()));
+    }
+
+    /// Test parsing of all the operators and operands, and that a parsed
+    /// query's string representation parses back into the same query.
+    #[test]
+    fn test_tm_query_parsing() {
+        let query: QueryMatcher =
+            "tm.event = 'NewBlock' AND applied.height<=10 \
+                                   AND applied.gas>0.5 AND \
+                                   send_packet.packet_src_channel CONTAINS \
+                                   'channel-' AND applied.code EXISTS AND \
+                                   applied.date>=DATE 2022-01-31 AND \
+                                   applied.time < TIME 2022-01-31T10:00:00Z"
+                .parse()
+                .unwrap();
+        let ops: Vec<_> = query
+            .conditions()
+            .iter()
+            .map(|condition| condition.op.clone())
+            .collect();
+        assert_eq!(
+            ops,
+            vec![
+                Operation::Eq(Operand::String("NewBlock".to_string())),
+                Operation::Le(Operand::Number("10".to_string())),
+                Operation::Gt(Operand::Number("0.5".to_string())),
+                Operation::Contains("channel-".to_string()),
+                Operation::Exists,
+                Operation::Ge(Operand::Date("2022-01-31".to_string())),
+                Operation::Lt(Operand::Time(
+                    "2022-01-31T10:00:00Z".parse().unwrap()
+                )),
+            ]
+        );
+        assert_eq!(query.conditions()[3].key, "send_packet.packet_src_channel");
+        let reparsed: QueryMatcher = query.to_string().parse().unwrap();
+        assert_eq!(query, reparsed);
+    }
+
+    /// Test that invalid queries are rejected.
+    #[test]
+    fn test_tm_query_parsing_errors() {
+        let parse = |query: &str| query.parse::<QueryMatcher>().unwrap_err();
+        assert_eq!(parse("  "), ParseError::Empty);
+        assert_eq!(
+            parse("applied.hash"),
+            ParseError::UnexpectedEnd("an operator")
+        );
+        assert_eq!(
+            parse("applied.hash="),
+            ParseError::UnexpectedEnd("an operand")
+        );
+        assert_eq!(
+            parse("applied.hash='abc"),
+            ParseError::UnterminatedString(13)
+        );
+        assert_eq!(
+            parse("applied.hash<'abc'"),
+            ParseError::StringOrdering("<")
+        );
+        assert_eq!(
+            parse("applied.date=DATE 2022-13-01"),
+            ParseError::InvalidDate("2022-13-01".to_string())
+        );
+        assert!(matches!(
+            parse("applied.code EXISTS OR applied.log EXISTS"),
+            ParseError::Unexpected { pos: 20, .. }
+        ));
+        assert!(matches!(
+            parse("applied.code EXISTS ANDapplied.log EXISTS"),
+            ParseError::Unexpected { pos: 20, .. }
+        ));
+    }
+
+    /// Test the evaluation of the operators against events' attributes.
+    #[test]
+    fn test_tm_query_evaluation() {
+        let applied = event(
+            EventType::Applied,
+            &[
+                ("height", "10"),
+                ("gas", "1.5"),
+                ("time", "2022-01-31T10:00:00Z"),
+                ("log", "Transaction is valid."),
+            ],
+        );
+        let send_packet = event(
+            EventType::Ibc("send_packet".to_string()),
+            &[("packet_src_channel", "channel-0")],
+        );
+        let matches = |query: &str| {
+            let query: QueryMatcher = query.parse().unwrap();
+            (query.matches(&applied), query.matches(&send_packet))
+        };
+
+        assert_eq!(matches("tm.event='NewBlock'"), (true, true));
+        assert_eq!(matches("tm.event='Tx'"), (false, false));
+        assert_eq!(matches("applied.height=10"), (true, false));
+        assert_eq!(matches("applied.height='10'"), (true, false));
+        assert_eq!(matches("applied.height<10"), (false, false));
+        assert_eq!(matches("applied.height<=10"), (true, false));
+        assert_eq!(matches("applied.height>9"), (true, false));
+        assert_eq!(matches("applied.height>=11"), (false, false));
+        assert_eq!(matches("applied.height>9.5"), (true, false));
+        assert_eq!(matches("applied.gas<2"), (true, false));
+        assert_eq!(matches("applied.log>1"), (false, false));
+        assert_eq!(matches("applied.log CONTAINS 'valid'"), (true, false));
+        assert_eq!(matches("applied.code EXISTS"), (false, false));
+        assert_eq!(matches("applied.log EXISTS"), (true, false));
+        assert_eq!(matches("applied.time=DATE 2022-01-31"), (true, false));
+        assert_eq!(matches("applied.time>DATE 2022-01-30"), (true, false));
+        assert_eq!(
+            matches("applied.time>TIME 2022-01-31T10:00:00+01:00"),
+            (true, false)
+        );
+        assert_eq!(
+            matches("applied.time<TIME 2022-01-31T10:00:00Z"),
+            (false, false)
+        );
+        assert_eq!(
+            matches("send_packet.packet_src_channel='channel-0'"),
+            (false, true)
+        );
+        assert_eq!(
+            matches(
+                "tm.event='NewBlock' AND send_packet.packet_src_channel \
+                 EXISTS AND applied.height EXISTS"
+            ),
+            (false, false)
+        );
+    }
+}
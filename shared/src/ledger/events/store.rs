@@ -0,0 +1,154 @@
+//! The persistent store of the events emitted by `FinalizeBlock` calls in the
+//! ledger.
+//!
+//! Unlike the [`super::log::EventLog`], the store survives restarts of the
+//! node. The events are written into the DB indexed by the height of their
+//! block, the hash of their transaction and their type, and they are read
+//! back in pages of at most [`PAGE_SIZE`] events.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::ledger::events::Event;
+use crate::ledger::storage::{self, EventIndex, StoredEvent, DB};
+use crate::types::storage::BlockHeight;
+
+/// The maximum number of events in a page read from the store.
+pub const PAGE_SIZE: usize = 100;
+
+/// Write the events emitted at the given height into the store.
+pub fn write_events<D>(
+    db: &mut D,
+    height: BlockHeight,
+    events: &[Event],
+) -> storage::Result<()>
+where
+    D: DB,
+{
+    let events: Vec<StoredEvent> = events
+        .iter()
+        .map(|event| StoredEvent {
+            event_type: event.event_type.to_string(),
+            tx_hash: event.get("hash").cloned(),
+            event: event
+                .try_to_vec()
+                .expect("Encoding an event shouldn't fail"),
+        })
+        .collect();
+    db.write_events(height, &events)
+}
+
+/// Read the page with the given number, starting from 0, of the events with
+/// the given index from the store. The events are ordered by the height and
+/// the position in their block.
+pub fn read_events<D>(
+    db: &D,
+    index: &EventIndex,
+    page: u64,
+) -> storage::Result<Vec<Event>>
+where
+    D: DB,
+{
+    let offset = (page as usize).saturating_mul(PAGE_SIZE);
+    db.read_events(index, offset, PAGE_SIZE)?
+        .into_iter()
+        .map(|event| {
+            Event::try_from_slice(&event.event)
+                .map_err(storage::Error::BorshCodingError)
+        })
+        .collect()
+}
+
+/// Read all the events emitted by the transaction with the given hash from
+/// the store.
+pub fn read_tx_events<D>(db: &D, tx_hash: String) -> storage::Result<Vec<Event>>
+where
+    D: DB,
+{
+    let index = EventIndex::TxHash(tx_hash);
+    let mut events = vec![];
+    for page in 0.. {
+        let page = read_events(db, &index, page)?;
+        let is_last_page = page.len() < PAGE_SIZE;
+        events.extend(page);
+        if is_last_page {
+            break;
+        }
+    }
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::ledger::events::{EventLevel, EventType};
+    use crate::ledger::storage::mockdb::MockDB;
+
+    /// Return a new event with the given type, tx hash and height attribute.
+    fn event(event_type: EventType, hash: &str, height: u64) -> Event {
+        Event {
+            event_type,
+            level: EventLevel::Tx,
+            attributes: HashMap::from([
+                ("hash".to_string(), hash.to_string()),
+                ("height".to_string(), height.to_string()),
+            ]),
+        }
+    }
+
+    /// Test reading the events back by every index, with pagination and
+    /// pruning.
+    #[test]
+    fn test_event_store() {
+        let mut db = MockDB::default();
+        for height in 1..=3 {
+            let events: Vec<Event> = (0..PAGE_SIZE as u64)
+                .map(|i| {
+                    event(
+                        EventType::Applied,
+                        &format!("{}-{}", height, i),
+                        height,
+                    )
+                })
+                .chain([event(EventType::Accepted, "tx", height)])
+                .collect();
+            write_events(&mut db, BlockHeight(height), &events).unwrap();
+        }
+
+        // Read by height, the last page has a single event
+        let index = EventIndex::Height(BlockHeight(2));
+        let first_page = read_events(&db, &index, 0).unwrap();
+        assert_eq!(first_page.len(), PAGE_SIZE);
+        assert_eq!(first_page[0], event(EventType::Applied, "2-0", 2));
+        let last_page = read_events(&db, &index, 1).unwrap();
+        assert_eq!(last_page, vec![event(EventType::Accepted, "tx", 2)]);
+        assert!(read_events(&db, &index, 2).unwrap().is_empty());
+
+        // Read by type, ordered by height
+        let index = EventIndex::EventType(EventType::Accepted.to_string());
+        let accepted = read_events(&db, &index, 0).unwrap();
+        let expected: Vec<Event> = (1..=3)
+            .map(|height| event(EventType::Accepted, "tx", height))
+            .collect();
+        assert_eq!(accepted, expected);
+
+        // Read by tx hash
+        let events = read_tx_events(&db, "1-10".to_string()).unwrap();
+        assert_eq!(events, vec![event(EventType::Applied, "1-10", 1)]);
+        let events = read_tx_events(&db, "tx".to_string()).unwrap();
+        assert_eq!(events, expected);
+
+        // Prune the events below height 3
+        db.prune_events(BlockHeight(3)).unwrap();
+        let index = EventIndex::Height(BlockHeight(2));
+        assert!(read_events(&db, &index, 0).unwrap().is_empty());
+        let events = read_tx_events(&db, "tx".to_string()).unwrap();
+        assert_eq!(events, vec![event(EventType::Accepted, "tx", 3)]);
+        assert!(read_tx_events(&db, "1-10".to_string()).unwrap().is_empty());
+        let index = EventIndex::EventType(EventType::Applied.to_string());
+        let applied = read_events(&db, &index, 0).unwrap();
+        assert_eq!(applied.len(), PAGE_SIZE);
+        assert_eq!(applied[0], event(EventType::Applied, "3-0", 3));
+    }
+}
@@ -0,0 +1,125 @@
+//! The protocol of the node's event subscription service.
+//!
+//! A client connects to the service over a WebSocket and sends JSON-encoded
+//! [`ClientMessage`]s as text frames to subscribe to the events matching a
+//! Tendermint query (see [`super::log::query`]), e.g.:
+//!
+//! ```json
+//! {"type": "subscribe", "id": 1, "query": "transfer.source='atest1...'"}
+//! ```
+//!
+//! The service replies with JSON-encoded [`ServerMessage`]s. Every event
+//! emitted by a block that matches a subscription's query and has a typed
+//! representation (see [`super::typed`]) is sent to the client tagged with
+//! the subscription's ID. A single event matching multiple subscriptions is
+//! sent once for each of them.
+
+use serde::{Deserialize, Serialize};
+
+use super::log::query::{ParseError, QueryMatcher};
+use super::typed::NamadaEvent;
+use crate::types::storage::BlockHeight;
+
+/// A message sent by a client to the subscription service
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// Subscribe to the events matching the query. The ID is chosen by the
+    /// client and must not be used by any of its active subscriptions.
+    Subscribe {
+        /// The ID of the subscription
+        id: u64,
+        /// A Tendermint query. An empty query matches all the events.
+        query: String,
+    },
+    /// Cancel the subscription with the given ID
+    Unsubscribe {
+        /// The ID of the subscription
+        id: u64,
+    },
+}
+
+/// A message sent by the subscription service to a client
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// The subscription has been accepted
+    Subscribed {
+        /// The ID of the subscription
+        id: u64,
+    },
+    /// The subscription has been cancelled
+    Unsubscribed {
+        /// The ID of the subscription
+        id: u64,
+    },
+    /// An event that matches the subscription's query
+    Event {
+        /// The ID of the subscription
+        id: u64,
+        /// The height of the block that emitted the event
+        height: BlockHeight,
+        /// The event
+        event: NamadaEvent,
+    },
+    /// A request of the client has failed or some events couldn't be
+    /// delivered
+    Error {
+        /// The ID of the subscription concerned, if any
+        id: Option<u64>,
+        /// The description of the error
+        message: String,
+    },
+}
+
+/// Parse the query of a subscription. Unlike a [`QueryMatcher`] parsed from
+/// a string, an empty query is valid and matches all the events.
+pub fn parse_query(query: &str) -> Result<QueryMatcher, ParseError> {
+    if query.trim().is_empty() {
+        Ok(QueryMatcher::new(vec![]))
+    } else {
+        query.parse()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Test that the messages are encoded the way they're documented.
+    #[test]
+    fn test_messages_encoding() {
+        let msg: ClientMessage = serde_json::from_str(
+            r#"{"type": "subscribe", "id": 1, "query": "bond.amount>10"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            msg,
+            ClientMessage::Subscribe {
+                id: 1,
+                query: "bond.amount>10".to_owned()
+            }
+        );
+        let msg: ClientMessage =
+            serde_json::from_str(r#"{"type": "unsubscribe", "id": 1}"#)
+                .unwrap();
+        assert_eq!(msg, ClientMessage::Unsubscribe { id: 1 });
+
+        let msg = ServerMessage::Error {
+            id: None,
+            message: "error".to_owned(),
+        };
+        assert_eq!(
+            serde_json::to_string(&msg).unwrap(),
+            r#"{"type":"error","id":null,"message":"error"}"#
+        );
+    }
+
+    /// Test that an empty query matches all the events.
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(parse_query(" "), Ok(QueryMatcher::new(vec![])));
+        assert!(parse_query("bond.amount>10").is_ok());
+        assert!(parse_query("bond.amount>").is_err());
+    }
+}
@@ -0,0 +1,465 @@
+//! Typed events decoded from the key-value attributes of the [`Event`]s
+//! emitted by `FinalizeBlock` calls in the ledger.
+
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::ledger::events::{Event, EventType};
+use crate::types::address::Address;
+use crate::types::governance::ProposalStatus;
+use crate::types::storage::{BlockHeight, Epoch};
+use crate::types::token;
+
+/// The key of the attribute that is only present in the IBC events of
+/// packets.
+const IBC_PACKET_SEQUENCE_KEY: &str = "packet_sequence";
+
+/// Errors from decoding a typed event.
+#[allow(missing_docs)]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    #[error("The events of type {0} have no typed representation")]
+    Unsupported(String),
+    #[error("The event of type {event_type} is missing the attribute {key}")]
+    MissingAttribute {
+        event_type: String,
+        key: &'static str,
+    },
+    #[error(
+        "The event of type {event_type} has an invalid value {value:?} of \
+         the attribute {key}"
+    )]
+    InvalidAttribute {
+        event_type: String,
+        key: &'static str,
+        value: String,
+    },
+}
+
+/// An event emitted by the ledger decoded into its type
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NamadaEvent {
+    /// A transaction was applied
+    TxApplied(TxApplied),
+    /// A token transfer was made by an applied transaction
+    Transfer(Transfer),
+    /// A bond was made by an applied transaction
+    Bond(Bond),
+    /// A governance proposal was executed at the end of its voting period
+    ProposalExecuted(ProposalExecuted),
+    /// A governance proposal transitioned to a new status
+    ProposalStatusChanged(ProposalStatusChanged),
+    /// A payment was made by the treasury for a treasury spend proposal
+    TreasuryPayment(TreasuryPayment),
+    /// An IBC packet was sent, received, acknowledged or timed out
+    IbcPacket(IbcPacket),
+}
+
+/// An applied transaction
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxApplied {
+    /// The hash of the transaction
+    pub hash: String,
+    /// The height of the block in which the transaction was applied
+    pub height: BlockHeight,
+    /// The result code of the transaction, `0` when it's been accepted
+    pub code: u32,
+    /// The gas used by the transaction
+    pub gas_used: Option<u64>,
+    /// The details of the result of the transaction
+    pub info: String,
+    /// The addresses of the accounts initialized by the transaction
+    pub initialized_accounts: Vec<Address>,
+}
+
+/// A token transfer
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transfer {
+    /// The hash of the transaction that made the transfer
+    pub hash: String,
+    /// The height of the block in which the transfer was made
+    pub height: BlockHeight,
+    /// The source of the transfer
+    pub source: Address,
+    /// The target of the transfer
+    pub target: Address,
+    /// The transferred token
+    pub token: Address,
+    /// The multitoken sub-prefix of the token, if any
+    pub sub_prefix: Option<String>,
+    /// The transferred amount
+    pub amount: token::Amount,
+}
+
+/// A bond
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bond {
+    /// The hash of the transaction that made the bond
+    pub hash: String,
+    /// The height of the block in which the bond was made
+    pub height: BlockHeight,
+    /// The validator bonded to
+    pub validator: Address,
+    /// The delegator, if it's not a self-bond
+    pub source: Option<Address>,
+    /// The bonded amount
+    pub amount: token::Amount,
+}
+
+/// An executed governance proposal
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProposalExecuted {
+    /// The ID of the proposal
+    pub id: u64,
+    /// The result of the tally of the proposal's votes
+    pub tally_result: String,
+    /// Does the proposal have code to be executed?
+    pub has_proposal_code: bool,
+    /// Has the proposal's code been executed successfully?
+    pub proposal_code_succeeded: bool,
+}
+
+/// The transition of a governance proposal to a new status
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProposalStatusChanged {
+    /// The ID of the proposal
+    pub id: u64,
+    /// The new status of the proposal
+    pub status: ProposalStatus,
+    /// The epoch at the beginning of which the status changed
+    pub epoch: Epoch,
+}
+
+/// A payment of the treasury
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TreasuryPayment {
+    /// The ID of the treasury spend proposal
+    pub proposal_id: u64,
+    /// The epoch in which the payment was made
+    pub epoch: Epoch,
+    /// The recipient of the payment
+    pub recipient: Address,
+    /// The paid token
+    pub token: Address,
+    /// The paid amount
+    pub amount: token::Amount,
+}
+
+/// An IBC packet event
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IbcPacket {
+    /// The type of the IBC event, e.g. `send_packet`
+    pub kind: String,
+    /// The sequence number of the packet
+    pub sequence: u64,
+    /// The source port of the packet
+    pub src_port: String,
+    /// The source channel of the packet
+    pub src_channel: String,
+    /// The destination port of the packet
+    pub dst_port: String,
+    /// The destination channel of the packet
+    pub dst_channel: String,
+    /// The data of the packet, if included in the event
+    pub data: Option<String>,
+}
+
+impl TryFrom<&Event> for NamadaEvent {
+    type Error = DecodeError;
+
+    fn try_from(event: &Event) -> Result<Self, Self::Error> {
+        let attrs = Attributes { event };
+        let typed = match &event.event_type {
+            EventType::Applied => NamadaEvent::TxApplied(TxApplied {
+                hash: attrs.get("hash")?.clone(),
+                height: attrs.parse("height")?,
+                code: attrs.parse("code")?,
+                gas_used: attrs.parse_opt("gas_used")?,
+                info: attrs.get_opt("info").cloned().unwrap_or_default(),
+                initialized_accounts: match attrs
+                    .get_opt("initialized_accounts")
+                {
+                    Some(accounts) => {
+                        serde_json::from_str(accounts).map_err(|_| {
+                            attrs.invalid("initialized_accounts", accounts)
+                        })?
+                    }
+                    None => vec![],
+                },
+            }),
+            EventType::Transfer => NamadaEvent::Transfer(Transfer {
+                hash: attrs.get("hash")?.clone(),
+                height: attrs.parse("height")?,
+                source: attrs.parse("source")?,
+                target: attrs.parse("target")?,
+                token: attrs.parse("token")?,
+                sub_prefix: attrs.get_opt("sub_prefix").cloned(),
+                amount: attrs.parse("amount")?,
+            }),
+            EventType::Bond => NamadaEvent::Bond(Bond {
+                hash: attrs.get("hash")?.clone(),
+                height: attrs.parse("height")?,
+                validator: attrs.parse("validator")?,
+                source: attrs.parse_opt("source")?,
+                amount: attrs.parse("amount")?,
+            }),
+            EventType::Proposal => {
+                NamadaEvent::ProposalExecuted(ProposalExecuted {
+                    id: attrs.parse("proposal_id")?,
+                    tally_result: attrs.get("tally_result")?.clone(),
+                    has_proposal_code: attrs.flag("has_proposal_code")?,
+                    proposal_code_succeeded: attrs
+                        .flag("proposal_code_exit_status")?,
+                })
+            }
+            EventType::ProposalStatus => {
+                NamadaEvent::ProposalStatusChanged(ProposalStatusChanged {
+                    id: attrs.parse("proposal_id")?,
+                    status: attrs.parse("status")?,
+                    epoch: attrs.parse("epoch")?,
+                })
+            }
+            EventType::TreasuryPayment => {
+                NamadaEvent::TreasuryPayment(TreasuryPayment {
+                    proposal_id: attrs.parse("proposal_id")?,
+                    epoch: attrs.parse("epoch")?,
+                    recipient: attrs.parse("recipient")?,
+                    token: attrs.parse("token")?,
+                    amount: attrs.parse("amount")?,
+                })
+            }
+            EventType::Ibc(kind)
+                if event.contains_key(IBC_PACKET_SEQUENCE_KEY) =>
+            {
+                NamadaEvent::IbcPacket(IbcPacket {
+                    kind: kind.clone(),
+                    sequence: attrs.parse(IBC_PACKET_SEQUENCE_KEY)?,
+                    src_port: attrs.get("packet_src_port")?.clone(),
+                    src_channel: attrs.get("packet_src_channel")?.clone(),
+                    dst_port: attrs.get("packet_dst_port")?.clone(),
+                    dst_channel: attrs.get("packet_dst_channel")?.clone(),
+                    data: attrs.get_opt("packet_data").cloned(),
+                })
+            }
+            event_type => {
+                return Err(DecodeError::Unsupported(event_type.to_string()));
+            }
+        };
+        Ok(typed)
+    }
+}
+
+/// A helper to read the attributes of an event
+struct Attributes<'a> {
+    event: &'a Event,
+}
+
+impl<'a> Attributes<'a> {
+    fn get_opt(&self, key: &str) -> Option<&'a String> {
+        self.event.get(key)
+    }
+
+    fn get(&self, key: &'static str) -> Result<&'a String, DecodeError> {
+        self.get_opt(key)
+            .ok_or_else(|| DecodeError::MissingAttribute {
+                event_type: self.event.event_type.to_string(),
+                key,
+            })
+    }
+
+    fn parse<T: FromStr>(&self, key: &'static str) -> Result<T, DecodeError> {
+        let value = self.get(key)?;
+        value.parse().map_err(|_| self.invalid(key, value))
+    }
+
+    fn parse_opt<T: FromStr>(
+        &self,
+        key: &'static str,
+    ) -> Result<Option<T>, DecodeError> {
+        match self.get_opt(key) {
+            Some(_) => self.parse(key).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Read a flag encoded like an exit status, i.e. `0` for `true`
+    fn flag(&self, key: &'static str) -> Result<bool, DecodeError> {
+        match self.get(key)?.as_str() {
+            "0" => Ok(true),
+            "1" => Ok(false),
+            value => Err(self.invalid(key, value)),
+        }
+    }
+
+    fn invalid(&self, key: &'static str, value: &str) -> DecodeError {
+        DecodeError::InvalidAttribute {
+            event_type: self.event.event_type.to_string(),
+            key,
+            value: value.to_owned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::ledger::events::EventLevel;
+    use crate::ledger::native_vp::governance::utils::ProposalEvent;
+    use crate::types::address::{self, nam};
+    use crate::types::governance::{self, TallyResult};
+    use crate::types::transaction::pos;
+
+    /// Test decoding the events built by the ledger into typed events.
+    #[test]
+    fn test_decode_typed_events() {
+        let source = address::testing::established_address_1();
+        let target = address::testing::established_address_2();
+        let amount = token::Amount::whole(10);
+
+        let transfer = token::Transfer {
+            source: source.clone(),
+            target: target.clone(),
+            token: nam(),
+            sub_prefix: None,
+            amount,
+            key: None,
+            shielded: None,
+        };
+        let event = Event::new_transfer_event("HASH", 10, &transfer);
+        assert_eq!(
+            NamadaEvent::try_from(&event),
+            Ok(NamadaEvent::Transfer(Transfer {
+                hash: "HASH".to_owned(),
+                height: BlockHeight(10),
+                source: source.clone(),
+                target: target.clone(),
+                token: nam(),
+                sub_prefix: None,
+                amount,
+            }))
+        );
+
+        let bond = pos::Bond {
+            validator: target.clone(),
+            amount,
+            source: Some(source.clone()),
+        };
+        let event = Event::new_bond_event("HASH", 11, &bond);
+        assert_eq!(
+            NamadaEvent::try_from(&event),
+            Ok(NamadaEvent::Bond(Bond {
+                hash: "HASH".to_owned(),
+                height: BlockHeight(11),
+                validator: target.clone(),
+                source: Some(source.clone()),
+                amount,
+            }))
+        );
+
+        let event: Event = ProposalEvent::new(
+            EventType::Proposal.to_string(),
+            TallyResult::Passed,
+            3,
+            true,
+            false,
+        )
+        .into();
+        assert_eq!(
+            NamadaEvent::try_from(&event),
+            Ok(NamadaEvent::ProposalExecuted(ProposalExecuted {
+                id: 3,
+                tally_result: "passed".to_owned(),
+                has_proposal_code: true,
+                proposal_code_succeeded: false,
+            }))
+        );
+
+        let event = Event::new_proposal_status_event(
+            4,
+            governance::ProposalStatus::ExecutionFailed,
+            Epoch(2),
+        );
+        assert_eq!(
+            NamadaEvent::try_from(&event),
+            Ok(NamadaEvent::ProposalStatusChanged(ProposalStatusChanged {
+                id: 4,
+                status: governance::ProposalStatus::ExecutionFailed,
+                epoch: Epoch(2),
+            }))
+        );
+
+        let payment = governance::TreasuryPayment {
+            proposal_id: 4,
+            epoch: Epoch(2),
+            recipient: target.clone(),
+            token: nam(),
+            amount,
+        };
+        let event = Event::new_treasury_payment_event(&payment);
+        assert_eq!(
+            NamadaEvent::try_from(&event),
+            Ok(NamadaEvent::TreasuryPayment(TreasuryPayment {
+                proposal_id: 4,
+                epoch: Epoch(2),
+                recipient: target,
+                token: nam(),
+                amount,
+            }))
+        );
+
+        let event = Event {
+            event_type: EventType::Applied,
+            level: EventLevel::Tx,
+            attributes: HashMap::from([
+                ("hash".to_owned(), "HASH".to_owned()),
+                ("height".to_owned(), "12".to_owned()),
+                ("code".to_owned(), "0".to_owned()),
+                ("gas_used".to_owned(), "100".to_owned()),
+                ("info".to_owned(), "Transaction is valid.".to_owned()),
+                (
+                    "initialized_accounts".to_owned(),
+                    serde_json::to_string(&[&source]).unwrap(),
+                ),
+            ]),
+        };
+        assert_eq!(
+            NamadaEvent::try_from(&event),
+            Ok(NamadaEvent::TxApplied(TxApplied {
+                hash: "HASH".to_owned(),
+                height: BlockHeight(12),
+                code: 0,
+                gas_used: Some(100),
+                info: "Transaction is valid.".to_owned(),
+                initialized_accounts: vec![source],
+            }))
+        );
+
+        // An IBC event that isn't about a packet has no typed representation
+        let event = Event {
+            event_type: EventType::Ibc("update_client".to_owned()),
+            level: EventLevel::Tx,
+            attributes: HashMap::new(),
+        };
+        assert_eq!(
+            NamadaEvent::try_from(&event),
+            Err(DecodeError::Unsupported("update_client".to_owned()))
+        );
+
+        // The attributes must be valid
+        let mut event = Event::new_transfer_event("HASH", 10, &transfer);
+        event["amount"] = "ten".to_owned();
+        assert_eq!(
+            NamadaEvent::try_from(&event),
+            Err(DecodeError::InvalidAttribute {
+                event_type: "transfer".to_owned(),
+                key: "amount",
+                value: "ten".to_owned(),
+            })
+        );
+    }
+}
@@ -9,7 +9,9 @@ use bellman::groth16::{prepare_verifying_key, PreparedVerifyingKey};
 use bls12_381::Bls12;
 use masp_primitives::asset_type::AssetType;
 use masp_primitives::consensus::BranchId::Sapling;
+use masp_primitives::merkle_tree::CommitmentTree;
 use masp_primitives::redjubjub::PublicKey;
+use masp_primitives::sapling::Node;
 use masp_primitives::transaction::components::{
     ConvertDescription, OutputDescription, SpendDescription,
 };
@@ -18,6 +20,13 @@ use masp_primitives::transaction::{
 };
 use masp_proofs::sapling::SaplingVerificationContext;
 
+use crate::ledger::storage_api::{self, StorageRead, StorageWrite};
+use crate::types::address::masp;
+use crate::types::storage::{BlockHeight, Epoch, Key, KeySeg, TxIndex};
+use crate::types::token::{
+    Transfer, FRONTIER_KEY_PREFIX, HEAD_TX_KEY, TX_KEY_PREFIX,
+};
+
 /// Env var to point to a dir with MASP parameters. When not specified,
 /// the default OS specific path is used.
 pub const ENV_VAR_MASP_PARAMS_DIR: &str = "NAMADA_MASP_PARAMS_DIR";
@@ -200,3 +209,95 @@ pub fn get_params_dir() -> PathBuf {
         masp_proofs::default_params_folder().unwrap()
     }
 }
+
+/// Store the note commitment frontiers after each of the shielded transfers
+/// accepted since the last stored frontier, so that the frontier after any
+/// number of transfers can be read without replaying the transfers from the
+/// first one. The transfers accepted before any frontier was stored are
+/// replayed once.
+pub fn update_note_commitment_frontiers<S>(
+    storage: &mut S,
+) -> storage_api::Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let head: u64 = storage.read(&head_tx_key())?.unwrap_or_default();
+    // The frontiers are stored in the order of the transfers, so the last
+    // one is the first found from the head
+    let mut count = head;
+    while count > 0 && !storage.has_key(&note_commitment_frontier_key(count))? {
+        count -= 1;
+    }
+    let mut tree = read_note_commitment_frontier(storage, count)?;
+    for index in count..head {
+        let (_epoch, _height, _tx_index, transfer): (
+            Epoch,
+            BlockHeight,
+            TxIndex,
+            Transfer,
+        ) = storage.read(&tx_key(index))?.ok_or_else(|| {
+            storage_api::Error::new(format!(
+                "The shielded transfer {index} is missing"
+            ))
+        })?;
+        let outputs = transfer
+            .shielded
+            .iter()
+            .flat_map(|shielded| &shielded.shielded_outputs);
+        for output in outputs {
+            let node = Node::new(output.cmu.to_bytes());
+            tree.append(node).map_err(|()| {
+                storage_api::Error::new_const(
+                    "The note commitment tree is full",
+                )
+            })?;
+        }
+        storage
+            .write(&note_commitment_frontier_key(index + 1), tree.clone())?;
+    }
+    Ok(())
+}
+
+/// Read the note commitment tree with the notes of the first `count`
+/// accepted shielded transfers, whose frontier must be stored
+pub fn read_note_commitment_frontier<S>(
+    storage: &S,
+    count: u64,
+) -> storage_api::Result<CommitmentTree<Node>>
+where
+    S: StorageRead,
+{
+    if count == 0 {
+        return Ok(CommitmentTree::empty());
+    }
+    storage
+        .read(&note_commitment_frontier_key(count))?
+        .ok_or_else(|| {
+            storage_api::Error::new(format!(
+                "The note commitment frontier after {count} shielded \
+                 transfers is missing"
+            ))
+        })
+}
+
+/// The key of the number of all the accepted shielded transfers
+pub fn head_tx_key() -> Key {
+    Key::from(masp().to_db_key())
+        .push(&HEAD_TX_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// The key of the accepted shielded transfer with the given index
+pub fn tx_key(index: u64) -> Key {
+    Key::from(masp().to_db_key())
+        .push(&(TX_KEY_PREFIX.to_owned() + &index.to_string()))
+        .expect("Cannot obtain a storage key")
+}
+
+/// The key of the note commitment frontier after the given number of accepted
+/// shielded transfers
+pub fn note_commitment_frontier_key(count: u64) -> Key {
+    Key::from(masp().to_db_key())
+        .push(&(FRONTIER_KEY_PREFIX.to_owned() + &count.to_string()))
+        .expect("Cannot obtain a storage key")
+}
@@ -13,5 +13,5 @@ pub mod storage;
 pub mod vp_host_fns;
 
 pub use namada_core::ledger::{
-    gas, governance, parameters, storage_api, tx_env, vp_env,
+    gas, governance, parameters, pgf, slash_fund, storage_api, tx_env, vp_env,
 };
@@ -1,20 +1,24 @@
 //! Governance VP
 
+pub mod parameter_change;
 pub mod utils;
 
 use std::collections::BTreeSet;
 
+use borsh::BorshDeserialize;
 use namada_core::ledger::governance::storage as gov_storage;
 use namada_core::ledger::storage;
 use namada_core::ledger::vp_env::VpEnv;
 use thiserror::Error;
-use utils::is_valid_validator_voting_period;
 
 use crate::ledger::native_vp;
 use crate::ledger::native_vp::{Ctx, NativeVp};
 use crate::ledger::pos::{self, BondId, Bonds};
 use crate::ledger::storage_api::StorageRead;
 use crate::types::address::{Address, InternalAddress};
+use crate::types::governance::{
+    ProposalContent, ProposalStatus, ProposalType, ProposalVote,
+};
 use crate::types::storage::{Epoch, Key};
 use crate::types::token;
 use crate::vm::WasmCacheAccess;
@@ -71,12 +75,26 @@ where
                 (KeyType::VOTE, Some(proposal_id)) => {
                     self.is_valid_vote_key(proposal_id, key, verifiers)
                 }
+                (KeyType::VOTE_HISTORY, Some(proposal_id)) => self
+                    .is_valid_vote_history_key(proposal_id, key, keys_changed),
+                (KeyType::VOTE_PASS_THROUGH, _) => {
+                    self.is_valid_vote_pass_through(key, verifiers)
+                }
                 (KeyType::CONTENT, Some(proposal_id)) => {
                     self.is_valid_content_key(proposal_id)
                 }
                 (KeyType::PROPOSAL_CODE, Some(proposal_id)) => {
                     self.is_valid_proposal_code(proposal_id)
                 }
+                (KeyType::PROPOSAL_TYPE, Some(proposal_id)) => {
+                    self.is_valid_proposal_type(proposal_id)
+                }
+                (KeyType::EXPEDITED, Some(proposal_id)) => {
+                    self.is_valid_expedited(proposal_id)
+                }
+                (KeyType::STATUS, Some(proposal_id)) => {
+                    self.is_valid_status(proposal_id)
+                }
                 (KeyType::GRACE_EPOCH, Some(proposal_id)) => {
                     self.is_valid_grace_epoch(proposal_id)
                 }
@@ -138,6 +156,8 @@ where
                 gov_storage::get_voting_start_epoch_key(counter),
                 gov_storage::get_voting_end_epoch_key(counter),
                 gov_storage::get_grace_epoch_key(counter),
+                gov_storage::get_proposal_type_key(counter),
+                gov_storage::get_status_key(counter),
             ]);
 
             // Check that expected set is a subset the actual one
@@ -206,18 +226,25 @@ where
                     )
                     .unwrap_or(false);
 
-                let is_valid_validator_voting_period =
-                    is_valid_validator_voting_period(
-                        current_epoch,
-                        pre_voting_start_epoch,
-                        pre_voting_end_epoch,
-                    );
+                // A voter may change their vote until the end of the voting
+                // period, every vote is recorded in the vote history
+                let vote_history_key = gov_storage::get_vote_history_key(
+                    proposal_id,
+                    voter_address.clone(),
+                    delegation_address.clone(),
+                    self.ctx.get_block_height()?,
+                );
+                let post_vote: Option<ProposalVote> =
+                    self.ctx.post().read(key)?;
+                let recorded_vote: Option<ProposalVote> =
+                    self.ctx.post().read(&vote_history_key)?;
 
                 let is_valid = pre_counter > proposal_id
                     && current_epoch >= pre_voting_start_epoch
                     && current_epoch <= pre_voting_end_epoch
-                    && (is_delegator
-                        || (is_validator && is_valid_validator_voting_period));
+                    && (is_delegator || is_validator)
+                    && post_vote.is_some()
+                    && post_vote == recorded_vote;
 
                 Ok(is_valid)
             }
@@ -225,6 +252,69 @@ where
         }
     }
 
+    /// Validate a vote history key, which records a vote cast in the current
+    /// block
+    pub fn is_valid_vote_history_key(
+        &self,
+        proposal_id: u64,
+        key: &Key,
+        keys_changed: &BTreeSet<Key>,
+    ) -> Result<bool> {
+        let voter = gov_storage::get_voter_address(key);
+        let delegation_address = gov_storage::get_vote_delegation_address(key);
+        let height = gov_storage::get_vote_history_height(key);
+        let (voter, delegation_address, height) =
+            match (voter, delegation_address, height) {
+                (Some(voter), Some(delegation_address), Some(height)) => {
+                    (voter, delegation_address, height)
+                }
+                _ => return Ok(false),
+            };
+
+        let vote_key = gov_storage::get_vote_proposal_key(
+            proposal_id,
+            voter.clone(),
+            delegation_address.clone(),
+        );
+        let post_vote: Option<ProposalVote> =
+            self.ctx.post().read(&vote_key)?;
+        let recorded_vote: Option<ProposalVote> = self.ctx.post().read(key)?;
+
+        Ok(height == self.ctx.get_block_height()?
+            && keys_changed.contains(&vote_key)
+            && recorded_vote.is_some()
+            && recorded_vote == post_vote)
+    }
+
+    /// Validate a vote pass-through key, which a validator may only set for
+    /// itself
+    pub fn is_valid_vote_pass_through(
+        &self,
+        key: &Key,
+        verifiers: &BTreeSet<Address>,
+    ) -> Result<bool> {
+        let validator = match gov_storage::is_vote_pass_through_key(key) {
+            Some(validator) => validator,
+            None => return Ok(false),
+        };
+
+        let pass_through: Option<bool> = self.ctx.post().read(key)?;
+        match pass_through {
+            Some(true) => {
+                let current_epoch = self.ctx.get_block_epoch()?;
+                self.is_validator(
+                    current_epoch,
+                    verifiers,
+                    validator,
+                    validator,
+                )
+            }
+            // Opting out removes the key
+            None => Ok(verifiers.contains(validator)),
+            Some(false) => Ok(false),
+        }
+    }
+
     /// Validate a content key
     pub fn is_valid_content_key(&self, proposal_id: u64) -> Result<bool> {
         let content_key: Key = gov_storage::get_content_key(proposal_id);
@@ -243,7 +333,15 @@ where
 
         match (post_content, max_content_length) {
             (Some(post_content), Some(max_content_length)) => {
-                Ok(post_content.len() < max_content_length)
+                if post_content.len() >= max_content_length {
+                    return Ok(false);
+                }
+                // The content must follow the standard schema or be the
+                // hash of an off-chain content with its URL
+                match ProposalContent::try_from_slice(&post_content) {
+                    Ok(content) => Ok(content.validate().is_ok()),
+                    Err(_) => Ok(false),
+                }
             }
             _ => Ok(false),
         }
@@ -260,6 +358,15 @@ where
             return Ok(false);
         }
 
+        // Only a proposal of the default type may have some code
+        let proposal_type: Option<ProposalType> = self
+            .ctx
+            .post()
+            .read(&gov_storage::get_proposal_type_key(proposal_id))?;
+        if proposal_type != Some(ProposalType::Default(None)) {
+            return Ok(false);
+        }
+
         let max_proposal_length: Option<usize> =
             self.ctx.pre().read(&max_code_size_parameter_key)?;
         let post_code: Option<Vec<u8>> = self.ctx.read_bytes_post(&code_key)?;
@@ -272,6 +379,70 @@ where
         }
     }
 
+    /// Validate a proposal_type key
+    pub fn is_valid_proposal_type(&self, proposal_id: u64) -> Result<bool> {
+        let type_key = gov_storage::get_proposal_type_key(proposal_id);
+
+        let has_pre_type: bool = self.ctx.has_key_pre(&type_key)?;
+        if has_pre_type {
+            return Ok(false);
+        }
+
+        let proposal_type: Option<ProposalType> =
+            self.ctx.post().read(&type_key)?;
+        match proposal_type {
+            // The code of a proposal is stored under its own key
+            Some(ProposalType::Default(None))
+            | Some(ProposalType::TextOnly)
+            | Some(ProposalType::PgfCouncil(_)) => Ok(true),
+            Some(ProposalType::ParameterChange { key, value }) => {
+                Ok(parameter_change::validate(&self.ctx.post(), &key, &value)
+                    .is_ok())
+            }
+            Some(ProposalType::TreasurySpend(spend)) => {
+                let is_positive = spend.amount != token::Amount::default();
+                Ok(is_positive && spend.vesting_epochs != Some(0))
+            }
+            Some(ProposalType::Default(Some(_))) | None => Ok(false),
+        }
+    }
+
+    /// Validate an expedited key, which may only be set to true on the
+    /// creation of a proposal
+    pub fn is_valid_expedited(&self, proposal_id: u64) -> Result<bool> {
+        let counter_key = gov_storage::get_counter_key();
+        let expedited_key = gov_storage::get_expedited_key(proposal_id);
+
+        let has_pre_expedited = self.ctx.has_key_pre(&expedited_key)?;
+        if has_pre_expedited {
+            return Ok(false);
+        }
+
+        let pre_counter: u64 =
+            self.ctx.pre().read(&counter_key)?.unwrap_or_default();
+        let expedited: Option<bool> = self.ctx.post().read(&expedited_key)?;
+        Ok(proposal_id >= pre_counter && expedited == Some(true))
+    }
+
+    /// Validate a status key. A new proposal must be pending, after which
+    /// its status is only updated by the protocol.
+    pub fn is_valid_status(&self, proposal_id: u64) -> Result<bool> {
+        let counter_key = gov_storage::get_counter_key();
+        let status_key = gov_storage::get_status_key(proposal_id);
+
+        let has_pre_status = self.ctx.has_key_pre(&status_key)?;
+        if has_pre_status {
+            return Ok(false);
+        }
+
+        let pre_counter: u64 =
+            self.ctx.pre().read(&counter_key)?.unwrap_or_default();
+        let status: Option<ProposalStatus> =
+            self.ctx.post().read(&status_key)?;
+        Ok(proposal_id >= pre_counter
+            && status == Some(ProposalStatus::Pending))
+    }
+
     /// Validate a grace_epoch key
     pub fn is_valid_grace_epoch(&self, proposal_id: u64) -> Result<bool> {
         let end_epoch_key = gov_storage::get_voting_end_epoch_key(proposal_id);
@@ -312,8 +483,6 @@ where
         let start_epoch_key =
             gov_storage::get_voting_start_epoch_key(proposal_id);
         let end_epoch_key = gov_storage::get_voting_end_epoch_key(proposal_id);
-        let min_period_parameter_key =
-            gov_storage::get_min_proposal_period_key();
 
         let current_epoch = self.ctx.get_block_epoch().ok();
 
@@ -327,8 +496,7 @@ where
         let start_epoch: Option<Epoch> =
             self.ctx.post().read(&start_epoch_key)?;
         let end_epoch: Option<Epoch> = self.ctx.post().read(&end_epoch_key)?;
-        let min_period: Option<u64> =
-            self.ctx.pre().read(&min_period_parameter_key)?;
+        let min_period = self.read_min_period(proposal_id)?;
 
         match (min_period, start_epoch, end_epoch, current_epoch) {
             (
@@ -352,8 +520,6 @@ where
         let start_epoch_key =
             gov_storage::get_voting_start_epoch_key(proposal_id);
         let end_epoch_key = gov_storage::get_voting_end_epoch_key(proposal_id);
-        let min_period_parameter_key =
-            gov_storage::get_min_proposal_period_key();
         let max_period_parameter_key =
             gov_storage::get_max_proposal_period_key();
 
@@ -369,8 +535,7 @@ where
         let start_epoch: Option<Epoch> =
             self.ctx.post().read(&start_epoch_key)?;
         let end_epoch: Option<Epoch> = self.ctx.post().read(&end_epoch_key)?;
-        let min_period: Option<u64> =
-            self.ctx.pre().read(&min_period_parameter_key)?;
+        let min_period = self.read_min_period(proposal_id)?;
         let max_period: Option<u64> =
             self.ctx.pre().read(&max_period_parameter_key)?;
         match (
@@ -407,7 +572,11 @@ where
         let funds_key = gov_storage::get_funds_key(proposal_id);
         let balance_key =
             token::balance_key(native_token_address, self.ctx.address);
-        let min_funds_parameter_key = gov_storage::get_min_proposal_fund_key();
+        let min_funds_parameter_key = if self.is_expedited(proposal_id)? {
+            gov_storage::get_min_expedited_proposal_fund_key()
+        } else {
+            gov_storage::get_min_proposal_fund_key()
+        };
 
         let min_funds_parameter: Option<token::Amount> =
             self.ctx.pre().read(&min_funds_parameter_key)?;
@@ -529,6 +698,24 @@ where
         }
     }
 
+    /// Is the proposal being created expedited?
+    fn is_expedited(&self, proposal_id: u64) -> Result<bool> {
+        let expedited_key = gov_storage::get_expedited_key(proposal_id);
+        let expedited: Option<bool> = self.ctx.post().read(&expedited_key)?;
+        Ok(expedited.unwrap_or_default())
+    }
+
+    /// Read the min. voting period of the proposal being created, which is
+    /// shorter for an expedited proposal
+    fn read_min_period(&self, proposal_id: u64) -> Result<Option<u64>> {
+        let min_period_parameter_key = if self.is_expedited(proposal_id)? {
+            gov_storage::get_min_expedited_proposal_period_key()
+        } else {
+            gov_storage::get_min_proposal_period_key()
+        };
+        Ok(self.ctx.pre().read(&min_period_parameter_key)?)
+    }
+
     /// Validate a governance parameter
     pub fn is_valid_parameter(&self, tx_data: &[u8]) -> Result<bool> {
         utils::is_proposal_accepted(self.ctx.storage, tx_data)
@@ -602,10 +789,20 @@ enum KeyType {
     #[allow(non_camel_case_types)]
     VOTE,
     #[allow(non_camel_case_types)]
+    VOTE_HISTORY,
+    #[allow(non_camel_case_types)]
+    VOTE_PASS_THROUGH,
+    #[allow(non_camel_case_types)]
     CONTENT,
     #[allow(non_camel_case_types)]
     PROPOSAL_CODE,
     #[allow(non_camel_case_types)]
+    PROPOSAL_TYPE,
+    #[allow(non_camel_case_types)]
+    EXPEDITED,
+    #[allow(non_camel_case_types)]
+    STATUS,
+    #[allow(non_camel_case_types)]
     PROPOSAL_COMMIT,
     #[allow(non_camel_case_types)]
     GRACE_EPOCH,
@@ -631,10 +828,20 @@ impl KeyType {
     fn from_key(key: &Key, native_token: &Address) -> Self {
         if gov_storage::is_vote_key(key) {
             Self::VOTE
+        } else if gov_storage::is_vote_history_key(key) {
+            KeyType::VOTE_HISTORY
+        } else if gov_storage::is_vote_pass_through_key(key).is_some() {
+            KeyType::VOTE_PASS_THROUGH
         } else if gov_storage::is_content_key(key) {
             KeyType::CONTENT
         } else if gov_storage::is_proposal_code_key(key) {
             KeyType::PROPOSAL_CODE
+        } else if gov_storage::is_proposal_type_key(key) {
+            KeyType::PROPOSAL_TYPE
+        } else if gov_storage::is_expedited_key(key) {
+            KeyType::EXPEDITED
+        } else if gov_storage::is_status_key(key) {
+            KeyType::STATUS
         } else if gov_storage::is_grace_epoch_key(key) {
             KeyType::GRACE_EPOCH
         } else if gov_storage::is_start_epoch_key(key) {
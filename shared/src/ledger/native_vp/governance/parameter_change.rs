@@ -0,0 +1,665 @@
+//! The changes of parameters proposed by the governance proposals of the
+//! [`ProposalType::ParameterChange`] type.
+//!
+//! A parameter is named by its module and its name, e.g. `governance/quorum`
+//! or `pos/max_validator_slots`, and its new value is parsed from a string.
+//! The modules are `parameters` for the protocol parameters, `pos` for the
+//! PoS parameters and `governance` for the governance parameters. A change
+//! is validated when the proposal is submitted and again against the
+//! parameters in storage when it's applied. A change of a PoS parameter takes
+//! effect at the pipeline offset from the epoch in which it's applied.
+//!
+//! [`ProposalType::ParameterChange`]: crate::types::governance::ProposalType::ParameterChange
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use borsh::BorshDeserialize;
+use rust_decimal::Decimal;
+use thiserror::Error;
+
+use crate::ledger::governance::storage as gov_storage;
+use crate::ledger::parameters::storage as params_storage;
+use crate::ledger::pos::namada_proof_of_stake::epoched::DynEpochOffset;
+use crate::ledger::pos::namada_proof_of_stake::update_validator_set_slots;
+use crate::ledger::pos::{self, EpochedPosParams, ValidatorSets};
+use crate::ledger::storage::types::encode;
+use crate::ledger::storage_api::{self, ResultExt, StorageRead, StorageWrite};
+use crate::types::chain::ProposalBytes;
+use crate::types::storage::{Epoch, Key};
+use crate::types::time::DurationSecs;
+use crate::types::token;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(
+        "The parameter {0} doesn't exist or cannot be changed by a proposal"
+    )]
+    UnknownParameter(String),
+    #[error("Invalid value {value:?} of the parameter {key}: {reason}")]
+    InvalidValue {
+        key: String,
+        value: String,
+        reason: String,
+    },
+    #[error("Storage error: {0}")]
+    StorageError(#[from] storage_api::Error),
+}
+
+/// Parameter change functions result
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Check that a change of the parameter `key` to the `value` is valid with
+/// the current parameters in storage
+pub fn validate<S>(storage: &S, key: &str, value: &str) -> Result<()>
+where
+    S: StorageRead,
+{
+    prepare(storage, key, value).map(|_| ())
+}
+
+/// Change the parameter `key` to the `value` in storage, if the change is
+/// valid
+pub fn apply<S>(storage: &mut S, key: &str, value: &str) -> Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let (storage_key, bytes) = prepare(storage, key, value)?;
+    storage.write_bytes(&storage_key, bytes)?;
+    if key == "pos/max_validator_slots" {
+        rebalance_validator_sets(storage)?;
+    }
+    Ok(())
+}
+
+/// Move the validators between the active and inactive PoS validator sets
+/// after a change of the maximum number of validator slots
+fn rebalance_validator_sets<S>(storage: &mut S) -> Result<()>
+where
+    S: StorageRead + StorageWrite,
+{
+    let (current_epoch, epoched_params) = read_pos_params(storage)?;
+    let validator_set_key = pos::validator_set_key();
+    let mut validator_set: ValidatorSets =
+        storage.read(&validator_set_key)?.ok_or_else(|| {
+            storage_api::Error::new_const("The PoS validator set is missing")
+        })?;
+    update_validator_set_slots(
+        &epoched_params,
+        &mut validator_set,
+        current_epoch,
+    );
+    storage.write(&validator_set_key, validator_set)?;
+    Ok(())
+}
+
+/// Find the storage key of the parameter and encode its new value
+fn prepare<S>(storage: &S, key: &str, value: &str) -> Result<(Key, Vec<u8>)>
+where
+    S: StorageRead,
+{
+    let change = Change { key, value };
+    match key.split_once('/') {
+        Some(("parameters", name)) => change.protocol_parameter(name),
+        Some(("pos", name)) => change.pos_parameter(storage, name),
+        Some(("governance", name)) => {
+            change.governance_parameter(storage, name)
+        }
+        _ => Err(change.unknown()),
+    }
+}
+
+/// A requested parameter change
+struct Change<'a> {
+    key: &'a str,
+    value: &'a str,
+}
+
+impl<'a> Change<'a> {
+    fn protocol_parameter(&self, name: &str) -> Result<(Key, Vec<u8>)> {
+        let change = match name {
+            "max_expected_time_per_block" => (
+                params_storage::get_max_expected_time_per_block_key(),
+                encode(&DurationSecs(self.parse()?)),
+            ),
+            "max_proposal_bytes" => {
+                let max_bytes = ProposalBytes::new(self.parse()?)
+                    .ok_or_else(|| self.invalid("out of range"))?;
+                (
+                    params_storage::get_max_proposal_bytes_key(),
+                    encode(&max_bytes),
+                )
+            }
+            "epochs_per_year" => {
+                let epochs_per_year: u64 = self.parse()?;
+                if epochs_per_year == 0 {
+                    return Err(self.invalid("must be positive"));
+                }
+                (
+                    params_storage::get_epochs_per_year_key(),
+                    encode(&epochs_per_year),
+                )
+            }
+            "pos_gain_p" => (
+                params_storage::get_pos_gain_p_key(),
+                encode(&self.parse_non_negative()?),
+            ),
+            "pos_gain_d" => (
+                params_storage::get_pos_gain_d_key(),
+                encode(&self.parse_non_negative()?),
+            ),
+            "vp_whitelist" => (
+                params_storage::get_vp_whitelist_storage_key(),
+                encode(&self.parse_list()),
+            ),
+            "tx_whitelist" => (
+                params_storage::get_tx_whitelist_storage_key(),
+                encode(&self.parse_list()),
+            ),
+            _ => return Err(self.unknown()),
+        };
+        Ok(change)
+    }
+
+    fn pos_parameter<S>(
+        &self,
+        storage: &S,
+        name: &str,
+    ) -> Result<(Key, Vec<u8>)>
+    where
+        S: StorageRead,
+    {
+        let (current_epoch, mut epoched_params) = read_pos_params(storage)?;
+        // Start from the parameters at the pipeline offset, which include any
+        // change already applied in the current epoch
+        let mut params = epoched_params
+            .get_at_offset(
+                current_epoch,
+                DynEpochOffset::PipelineLen,
+                &epoched_params,
+            )
+            .cloned()
+            .ok_or_else(missing_pos_params)?;
+        match name {
+            "max_validator_slots" => {
+                params.max_validator_slots = self.parse()?
+            }
+            "pipeline_len" => params.pipeline_len = self.parse()?,
+            "unbonding_len" => params.unbonding_len = self.parse()?,
+            "tm_votes_per_token" => {
+                params.tm_votes_per_token = self.parse_non_negative()?
+            }
+            "block_proposer_reward" => {
+                params.block_proposer_reward = self.parse_ratio()?
+            }
+            "block_vote_reward" => {
+                params.block_vote_reward = self.parse_ratio()?
+            }
+            "max_inflation_rate" => {
+                params.max_inflation_rate = self.parse_ratio()?
+            }
+            "target_staked_ratio" => {
+                params.target_staked_ratio = self.parse_ratio()?
+            }
+            "duplicate_vote_min_slash_rate" => {
+                params.duplicate_vote_min_slash_rate = self.parse_ratio()?
+            }
+            "light_client_attack_min_slash_rate" => {
+                params.light_client_attack_min_slash_rate =
+                    self.parse_ratio()?
+            }
+            _ => return Err(self.unknown()),
+        }
+        let errors = params.validate();
+        if !errors.is_empty() {
+            let reason = errors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(self.invalid(reason));
+        }
+        // The offset is given by the parameters before the change
+        let pre_params = epoched_params.clone();
+        epoched_params.set(params, current_epoch, &pre_params);
+        Ok((pos::params_key(), encode(&epoched_params)))
+    }
+
+    fn governance_parameter<S>(
+        &self,
+        storage: &S,
+        name: &str,
+    ) -> Result<(Key, Vec<u8>)>
+    where
+        S: StorageRead,
+    {
+        let change = match name {
+            "min_proposal_fund" => {
+                let min_proposal_fund: token::Amount = self.parse()?;
+                let min_expedited_proposal_fund: token::Amount =
+                    read_gov_parameter(
+                        storage,
+                        gov_storage::get_min_expedited_proposal_fund_key(),
+                    )?;
+                if min_proposal_fund > min_expedited_proposal_fund {
+                    return Err(self.invalid(format!(
+                        "must not be greater than the min. expedited \
+                         proposal fund {min_expedited_proposal_fund}"
+                    )));
+                }
+                (
+                    gov_storage::get_min_proposal_fund_key(),
+                    encode(&min_proposal_fund),
+                )
+            }
+            "max_proposal_code_size" => {
+                let max_proposal_code_size: u64 = self.parse()?;
+                (
+                    gov_storage::get_max_proposal_code_size_key(),
+                    encode(&max_proposal_code_size),
+                )
+            }
+            "min_proposal_period" => {
+                let min_proposal_period: u64 = self.parse()?;
+                let max_proposal_period: u64 = read_gov_parameter(
+                    storage,
+                    gov_storage::get_max_proposal_period_key(),
+                )?;
+                if min_proposal_period > max_proposal_period {
+                    return Err(self.invalid(format!(
+                        "must not be greater than the max. proposal period \
+                         {max_proposal_period}"
+                    )));
+                }
+                let min_expedited_proposal_period: u64 = read_gov_parameter(
+                    storage,
+                    gov_storage::get_min_expedited_proposal_period_key(),
+                )?;
+                if min_proposal_period < min_expedited_proposal_period {
+                    return Err(self.invalid(format!(
+                        "must not be less than the min. expedited proposal \
+                         period {min_expedited_proposal_period}"
+                    )));
+                }
+                (
+                    gov_storage::get_min_proposal_period_key(),
+                    encode(&min_proposal_period),
+                )
+            }
+            "max_proposal_period" => {
+                let max_proposal_period: u64 = self.parse()?;
+                let min_proposal_period: u64 = read_gov_parameter(
+                    storage,
+                    gov_storage::get_min_proposal_period_key(),
+                )?;
+                if max_proposal_period < min_proposal_period {
+                    return Err(self.invalid(format!(
+                        "must not be less than the min. proposal period \
+                         {min_proposal_period}"
+                    )));
+                }
+                (
+                    gov_storage::get_max_proposal_period_key(),
+                    encode(&max_proposal_period),
+                )
+            }
+            "max_proposal_content_size" => {
+                let max_proposal_content_size: u64 = self.parse()?;
+                (
+                    gov_storage::get_max_proposal_content_key(),
+                    encode(&max_proposal_content_size),
+                )
+            }
+            "min_proposal_grace_epochs" => {
+                let min_proposal_grace_epochs: u64 = self.parse()?;
+                (
+                    gov_storage::get_min_proposal_grace_epoch_key(),
+                    encode(&min_proposal_grace_epochs),
+                )
+            }
+            "quorum" => {
+                (gov_storage::get_quorum_key(), encode(&self.parse_ratio()?))
+            }
+            "pass_threshold" => {
+                let pass_threshold = self.parse_ratio()?;
+                let expedited_pass_threshold: Decimal = read_gov_parameter(
+                    storage,
+                    gov_storage::get_expedited_pass_threshold_key(),
+                )?;
+                if pass_threshold > expedited_pass_threshold {
+                    return Err(self.invalid(format!(
+                        "must not be greater than the expedited pass \
+                         threshold {expedited_pass_threshold}"
+                    )));
+                }
+                (
+                    gov_storage::get_pass_threshold_key(),
+                    encode(&pass_threshold),
+                )
+            }
+            "veto_threshold" => (
+                gov_storage::get_veto_threshold_key(),
+                encode(&self.parse_ratio()?),
+            ),
+            "burn_missed_quorum_deposit" => {
+                let burn_missed_quorum_deposit: bool = self.parse()?;
+                (
+                    gov_storage::get_burn_missed_quorum_deposit_key(),
+                    encode(&burn_missed_quorum_deposit),
+                )
+            }
+            "min_expedited_proposal_fund" => {
+                let min_expedited_proposal_fund: token::Amount =
+                    self.parse()?;
+                let min_proposal_fund: token::Amount = read_gov_parameter(
+                    storage,
+                    gov_storage::get_min_proposal_fund_key(),
+                )?;
+                if min_expedited_proposal_fund < min_proposal_fund {
+                    return Err(self.invalid(format!(
+                        "must not be less than the min. proposal fund \
+                         {min_proposal_fund}"
+                    )));
+                }
+                (
+                    gov_storage::get_min_expedited_proposal_fund_key(),
+                    encode(&min_expedited_proposal_fund),
+                )
+            }
+            "min_expedited_proposal_period" => {
+                let min_expedited_proposal_period: u64 = self.parse()?;
+                let max_proposal_period: u64 = read_gov_parameter(
+                    storage,
+                    gov_storage::get_max_proposal_period_key(),
+                )?;
+                if min_expedited_proposal_period == 0 {
+                    return Err(self.invalid("must not be zero"));
+                }
+                if min_expedited_proposal_period > max_proposal_period {
+                    return Err(self.invalid(format!(
+                        "must not be greater than the max. proposal period \
+                         {max_proposal_period}"
+                    )));
+                }
+                let min_proposal_period: u64 = read_gov_parameter(
+                    storage,
+                    gov_storage::get_min_proposal_period_key(),
+                )?;
+                if min_expedited_proposal_period > min_proposal_period {
+                    return Err(self.invalid(format!(
+                        "must not be greater than the min. proposal period \
+                         {min_proposal_period}"
+                    )));
+                }
+                (
+                    gov_storage::get_min_expedited_proposal_period_key(),
+                    encode(&min_expedited_proposal_period),
+                )
+            }
+            "expedited_pass_threshold" => {
+                let expedited_pass_threshold = self.parse_ratio()?;
+                let pass_threshold: Decimal = read_gov_parameter(
+                    storage,
+                    gov_storage::get_pass_threshold_key(),
+                )?;
+                if expedited_pass_threshold < pass_threshold {
+                    return Err(self.invalid(format!(
+                        "must not be less than the pass threshold \
+                         {pass_threshold}"
+                    )));
+                }
+                (
+                    gov_storage::get_expedited_pass_threshold_key(),
+                    encode(&expedited_pass_threshold),
+                )
+            }
+            _ => return Err(self.unknown()),
+        };
+        Ok(change)
+    }
+
+    fn parse<T>(&self) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.value.trim().parse().map_err(|err| self.invalid(err))
+    }
+
+    fn parse_non_negative(&self) -> Result<Decimal> {
+        let value: Decimal = self.parse()?;
+        if value.is_sign_negative() {
+            return Err(self.invalid("must not be negative"));
+        }
+        Ok(value)
+    }
+
+    /// Parse a decimal between 0 and 1 inclusive
+    fn parse_ratio(&self) -> Result<Decimal> {
+        let value = self.parse_non_negative()?;
+        if value > Decimal::ONE {
+            return Err(self.invalid("must not be greater than 1"));
+        }
+        Ok(value)
+    }
+
+    /// Parse a list of comma-separated values
+    fn parse_list(&self) -> Vec<String> {
+        self.value
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(str::to_owned)
+            .collect()
+    }
+
+    fn unknown(&self) -> Error {
+        Error::UnknownParameter(self.key.to_owned())
+    }
+
+    fn invalid(&self, reason: impl Display) -> Error {
+        Error::InvalidValue {
+            key: self.key.to_owned(),
+            value: self.value.to_owned(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// Read the PoS parameters of the current and future epochs together with the
+/// current epoch
+fn read_pos_params<S>(storage: &S) -> Result<(Epoch, EpochedPosParams)>
+where
+    S: StorageRead,
+{
+    let current_epoch = storage.get_block_epoch()?;
+    let bytes = storage
+        .read_bytes(&pos::params_key())?
+        .ok_or_else(missing_pos_params)?;
+    let epoched_params = pos::decode_params(bytes).into_storage_result()?;
+    Ok((current_epoch, epoched_params))
+}
+
+fn missing_pos_params() -> storage_api::Error {
+    storage_api::Error::new_const("The PoS parameters are missing")
+}
+
+/// Read a governance parameter, which must be present
+fn read_gov_parameter<S, T>(storage: &S, key: Key) -> Result<T>
+where
+    S: StorageRead,
+    T: BorshDeserialize,
+{
+    storage.read(&key)?.ok_or_else(|| {
+        storage_api::Error::new(format!(
+            "The governance parameter {key} is missing"
+        ))
+        .into()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ledger::governance::parameters::GovParams;
+    use crate::ledger::pos::PosParams;
+    use crate::ledger::storage::testing::TestStorage;
+    use crate::types::address;
+
+    /// Test validating and applying the changes of parameters of each module.
+    #[test]
+    fn test_parameter_change() {
+        let mut storage = TestStorage::default();
+        GovParams::default().init_storage(&mut storage);
+        StorageWrite::write(
+            &mut storage,
+            &pos::params_key(),
+            EpochedPosParams::init_at_genesis(
+                PosParams::default(),
+                Epoch::default(),
+            ),
+        )
+        .unwrap();
+        let validator_1 = pos::WeightedValidator {
+            bonded_stake: 2,
+            address: address::testing::established_address_1(),
+        };
+        let validator_2 = pos::WeightedValidator {
+            bonded_stake: 1,
+            address: address::testing::established_address_2(),
+        };
+        let validator_set = pos::ValidatorSet {
+            active: [validator_1.clone(), validator_2.clone()].into(),
+            inactive: Default::default(),
+        };
+        StorageWrite::write(
+            &mut storage,
+            &pos::validator_set_key(),
+            ValidatorSets::init_at_genesis(validator_set, Epoch::default()),
+        )
+        .unwrap();
+
+        apply(&mut storage, "governance/quorum", "0.5").unwrap();
+        let quorum: Option<Decimal> =
+            StorageRead::read(&storage, &gov_storage::get_quorum_key())
+                .unwrap();
+        assert_eq!(quorum, Some(Decimal::new(5, 1)));
+
+        apply(
+            &mut storage,
+            "governance/burn_missed_quorum_deposit",
+            "true",
+        )
+        .unwrap();
+        let burn: Option<bool> = StorageRead::read(
+            &storage,
+            &gov_storage::get_burn_missed_quorum_deposit_key(),
+        )
+        .unwrap();
+        assert_eq!(burn, Some(true));
+
+        // The PoS parameters change at the pipeline offset
+        apply(&mut storage, "pos/max_validator_slots", "1").unwrap();
+        apply(&mut storage, "pos/unbonding_len", "30").unwrap();
+        let params: EpochedPosParams =
+            StorageRead::read(&storage, &pos::params_key())
+                .unwrap()
+                .unwrap();
+        let pipeline_len = PosParams::default().pipeline_len;
+        assert_eq!(params.get(Epoch(0)), Some(&PosParams::default()));
+        assert_eq!(
+            params.get(Epoch(pipeline_len - 1)),
+            Some(&PosParams::default())
+        );
+        let changed = params.get(Epoch(pipeline_len)).unwrap();
+        assert_eq!(changed.max_validator_slots, 1);
+        assert_eq!(changed.unbonding_len, 30);
+        assert_eq!(changed.pipeline_len, pipeline_len);
+        // The validator sets are updated for the slots at the pipeline offset
+        let validator_sets: ValidatorSets =
+            StorageRead::read(&storage, &pos::validator_set_key())
+                .unwrap()
+                .unwrap();
+        let current = validator_sets.get(Epoch(0)).unwrap();
+        assert_eq!(current.active.len(), 2);
+        let pipeline = validator_sets.get(Epoch(pipeline_len)).unwrap();
+        assert_eq!(pipeline.active, [validator_1].into());
+        assert_eq!(pipeline.inactive, [validator_2].into());
+
+        apply(&mut storage, "parameters/epochs_per_year", "365").unwrap();
+        let epochs_per_year: Option<u64> = StorageRead::read(
+            &storage,
+            &params_storage::get_epochs_per_year_key(),
+        )
+        .unwrap();
+        assert_eq!(epochs_per_year, Some(365));
+
+        // The changes must be valid
+        assert!(matches!(
+            validate(&storage, "governance/quorum", "1.5"),
+            Err(Error::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            validate(&storage, "governance/burn_missed_quorum_deposit", "1"),
+            Err(Error::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            validate(&storage, "governance/min_proposal_period", "100"),
+            Err(Error::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            validate(&storage, "governance/min_expedited_proposal_period", "0"),
+            Err(Error::InvalidValue { .. })
+        ));
+        // The expedited proposals must not be easier to pass than the normal
+        // ones, whichever of the paired parameters is changed
+        assert!(matches!(
+            validate(&storage, "governance/pass_threshold", "0.8"),
+            Err(Error::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            validate(&storage, "governance/expedited_pass_threshold", "0.6"),
+            Err(Error::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            validate(&storage, "governance/min_proposal_fund", "3000"),
+            Err(Error::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            validate(&storage, "governance/min_expedited_proposal_fund", "100"),
+            Err(Error::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            validate(&storage, "governance/min_expedited_proposal_period", "4"),
+            Err(Error::InvalidValue { .. })
+        ));
+        // The constraints are checked against the parameters in storage
+        apply(&mut storage, "governance/expedited_pass_threshold", "0.9")
+            .unwrap();
+        validate(&storage, "governance/pass_threshold", "0.8").unwrap();
+        apply(&mut storage, "governance/min_proposal_period", "5").unwrap();
+        validate(&storage, "governance/min_expedited_proposal_period", "4")
+            .unwrap();
+        assert!(matches!(
+            validate(&storage, "governance/min_proposal_period", "0"),
+            Err(Error::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            validate(&storage, "pos/tm_votes_per_token", "2"),
+            Err(Error::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            validate(&storage, "pos/pipeline_len", "1"),
+            Err(Error::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            validate(&storage, "pos/pipeline_len", "30"),
+            Err(Error::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            validate(&storage, "quorum", "0.5"),
+            Err(Error::UnknownParameter(_))
+        ));
+    }
+}
@@ -1,30 +1,46 @@
 //! Governance utility functions
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::str::FromStr;
 
 use borsh::BorshDeserialize;
 use namada_proof_of_stake::PosReadOnly;
+use rust_decimal::Decimal;
 use thiserror::Error;
 
+use crate::ledger::governance::parameters::GovParams;
 use crate::ledger::governance::storage as gov_storage;
 use crate::ledger::pos::BondId;
 use crate::ledger::storage::{DBIter, Storage, StorageHasher, DB};
-use crate::ledger::storage_api;
+use crate::ledger::storage_api::{self, StorageRead};
 use crate::types::address::Address;
-use crate::types::governance::{ProposalVote, TallyResult, VotePower};
+use crate::types::governance::{
+    ProposalResult, ProposalVote, ProposalVotes, TallyResult, VotePower,
+    WeightedVote,
+};
 use crate::types::storage::{Epoch, Key};
-use crate::types::token;
 
 /// Proposal structure holding votes information necessary to compute the
 /// outcome
+#[derive(Default)]
 pub struct Votes {
     /// Map from validators who votes yay to their total stake amount
     pub yay_validators: HashMap<Address, VotePower>,
+    /// Map from validators who votes nay to their total stake amount
+    pub nay_validators: HashMap<Address, VotePower>,
+    /// Map from validators who votes abstain to their total stake amount
+    pub abstain_validators: HashMap<Address, VotePower>,
+    /// Map from validators who votes nay with veto to their total stake
+    /// amount
+    pub veto_validators: HashMap<Address, VotePower>,
     /// Map from delegation who votes yay to their bond amount
     pub yay_delegators: HashMap<Address, HashMap<Address, VotePower>>,
     /// Map from delegation who votes nay to their bond amount
     pub nay_delegators: HashMap<Address, HashMap<Address, VotePower>>,
+    /// Map from delegation who votes abstain to their bond amount
+    pub abstain_delegators: HashMap<Address, HashMap<Address, VotePower>>,
+    /// Map from delegation who votes nay with veto to their bond amount
+    pub veto_delegators: HashMap<Address, HashMap<Address, VotePower>>,
 }
 
 /// Proposal errors
@@ -74,56 +90,268 @@ impl ProposalEvent {
     }
 }
 
-/// Return a proposal result - accepted only when the result is `Ok(true)`.
-pub fn compute_tally<D, H>(
+/// The governance parameters that decide the result of a tally
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TallyParams {
+    /// Minimum share of the total voting power that must vote on a proposal
+    pub quorum: Decimal,
+    /// Minimum share of the yay, nay and nay with veto votes that must be
+    /// yay for a proposal to pass
+    pub pass_threshold: Decimal,
+    /// Minimum share of all the votes that must be nay with veto for a
+    /// proposal to be vetoed
+    pub veto_threshold: Decimal,
+}
+
+impl TallyParams {
+    /// Get the tally parameters of a proposal from the governance
+    /// parameters. An expedited proposal must reach the stricter expedited
+    /// pass threshold.
+    pub fn new(params: &GovParams, is_expedited: bool) -> Self {
+        let pass_threshold = if is_expedited {
+            params.expedited_pass_threshold
+        } else {
+            params.pass_threshold
+        };
+        Self {
+            quorum: params.quorum,
+            pass_threshold,
+            veto_threshold: params.veto_threshold,
+        }
+    }
+
+    /// Read the tally parameters of a proposal from storage
+    pub fn read<S>(storage: &S, is_expedited: bool) -> storage_api::Result<Self>
+    where
+        S: StorageRead,
+    {
+        let quorum =
+            read_tally_parameter(storage, gov_storage::get_quorum_key())?;
+        let pass_threshold_key = if is_expedited {
+            gov_storage::get_expedited_pass_threshold_key()
+        } else {
+            gov_storage::get_pass_threshold_key()
+        };
+        let pass_threshold = read_tally_parameter(storage, pass_threshold_key)?;
+        let veto_threshold = read_tally_parameter(
+            storage,
+            gov_storage::get_veto_threshold_key(),
+        )?;
+        Ok(Self {
+            quorum,
+            pass_threshold,
+            veto_threshold,
+        })
+    }
+}
+
+/// Compute the result of a proposal with the total voting power at the given
+/// epoch.
+pub fn compute_proposal_result<D, H>(
     storage: &Storage<D, H>,
     epoch: Epoch,
-    votes: Votes,
-) -> storage_api::Result<bool>
+    votes: ProposalVotes,
+    is_expedited: bool,
+) -> storage_api::Result<ProposalResult>
 where
     D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
     H: StorageHasher + Sync + 'static,
 {
     let total_stake: VotePower = storage.total_stake(epoch)?.into();
+    let params = TallyParams::read(storage, is_expedited)?;
+    Ok(compute_tally(votes, total_stake, &params))
+}
+
+/// Compute the result of a proposal from its votes and the total voting
+/// power. Both the ledger and the client tally the votes with this function,
+/// so they always agree. A validator that passes through the votes of its
+/// delegators votes like the majority of their voting power, if any.
+pub fn compute_tally(
+    mut votes: ProposalVotes,
+    total_voting_power: VotePower,
+    params: &TallyParams,
+) -> ProposalResult {
+    for (validator, power) in &votes.pass_through {
+        if let Some(vote) =
+            delegators_majority_vote(&votes.delegators, validator)
+        {
+            votes.validators.insert(
+                validator.clone(),
+                WeightedVote {
+                    vote,
+                    power: *power,
+                },
+            );
+        }
+    }
+    tally_votes(
+        votes.into(),
+        total_voting_power,
+        params.quorum,
+        params.pass_threshold,
+        params.veto_threshold,
+    )
+}
+
+/// Find the vote of the majority of the voting power of the delegators of a
+/// validator. There's no majority if no delegator voted or on a tie.
+fn delegators_majority_vote(
+    delegators: &BTreeMap<Address, BTreeMap<Address, WeightedVote>>,
+    validator: &Address,
+) -> Option<ProposalVote> {
+    let mut vote_powers: Vec<(ProposalVote, VotePower)> = vec![];
+    for WeightedVote { vote, power } in delegators
+        .values()
+        .filter_map(|delegations| delegations.get(validator))
+    {
+        match vote_powers.iter_mut().find(|(other, _)| other == vote) {
+            Some((_, total_power)) => *total_power += power,
+            None => vote_powers.push((vote.clone(), *power)),
+        }
+    }
+    let max_power = vote_powers
+        .iter()
+        .map(|(_, power)| *power)
+        .max()
+        .filter(|power| *power > 0)?;
+    let mut majority = vote_powers
+        .into_iter()
+        .filter(|(_, power)| *power == max_power);
+    match (majority.next(), majority.next()) {
+        (Some((vote, _)), None) => Some(vote),
+        _ => None,
+    }
+}
 
+/// Check if a proposal is expedited. An expedited proposal that fails falls
+/// back to a normal proposal, which removes its flag.
+pub fn is_expedited<S>(
+    storage: &S,
+    proposal_id: u64,
+) -> storage_api::Result<bool>
+where
+    S: StorageRead,
+{
+    let expedited_key = gov_storage::get_expedited_key(proposal_id);
+    Ok(storage.read(&expedited_key)?.unwrap_or_default())
+}
+
+/// Read a governance parameter that decides the result of a tally
+fn read_tally_parameter<S>(
+    storage: &S,
+    key: Key,
+) -> storage_api::Result<Decimal>
+where
+    S: StorageRead,
+{
+    storage.read(&key)?.ok_or_else(|| {
+        storage_api::Error::new(format!(
+            "The governance parameter {key} is missing"
+        ))
+    })
+}
+
+/// Tally the votes of a proposal. The votes of a delegator override the vote
+/// of the validator of their bond. The proposal passes when the votes reach
+/// the `quorum` of the total voting power and the yay votes reach the
+/// `pass_threshold` of the yay, nay and nay with veto votes. However, it's
+/// vetoed when the nay with veto votes reach the `veto_threshold` of all the
+/// votes.
+fn tally_votes(
+    votes: Votes,
+    total_voting_power: VotePower,
+    quorum: Decimal,
+    pass_threshold: Decimal,
+    veto_threshold: Decimal,
+) -> ProposalResult {
     let Votes {
         yay_validators,
+        nay_validators,
+        abstain_validators,
+        veto_validators,
         yay_delegators,
         nay_delegators,
+        abstain_delegators,
+        veto_delegators,
     } = votes;
 
-    let mut total_yay_staked_tokens = VotePower::from(0_u64);
-    for (_, amount) in yay_validators.clone().into_iter() {
-        total_yay_staked_tokens += amount;
-    }
+    let mut total_yay_power: VotePower = yay_validators.values().sum();
+    let mut total_nay_power: VotePower = nay_validators.values().sum();
+    let mut total_abstain_power: VotePower = abstain_validators.values().sum();
+    let mut total_veto_power: VotePower = veto_validators.values().sum();
 
-    // YAY: Add delegator amount whose validator didn't vote / voted nay
-    for (_, vote_map) in yay_delegators.iter() {
-        for (validator_address, vote_power) in vote_map.iter() {
-            if !yay_validators.contains_key(validator_address) {
-                total_yay_staked_tokens += vote_power;
+    for (delegators, vote) in [
+        (&yay_delegators, ProposalVote::Yay),
+        (&nay_delegators, ProposalVote::Nay),
+        (&abstain_delegators, ProposalVote::Abstain),
+        (&veto_delegators, ProposalVote::NayWithVeto),
+    ] {
+        for vote_map in delegators.values() {
+            for (validator_address, vote_power) in vote_map {
+                // Remove the delegator's amount from the vote of its
+                // validator, if any
+                if yay_validators.contains_key(validator_address) {
+                    total_yay_power -= vote_power;
+                } else if nay_validators.contains_key(validator_address) {
+                    total_nay_power -= vote_power;
+                } else if abstain_validators.contains_key(validator_address) {
+                    total_abstain_power -= vote_power;
+                } else if veto_validators.contains_key(validator_address) {
+                    total_veto_power -= vote_power;
+                }
+                match vote {
+                    ProposalVote::Yay => total_yay_power += vote_power,
+                    ProposalVote::Nay => total_nay_power += vote_power,
+                    ProposalVote::Abstain => total_abstain_power += vote_power,
+                    ProposalVote::NayWithVeto => total_veto_power += vote_power,
+                }
             }
         }
     }
 
-    // NAY: Remove delegator amount whose validator validator vote yay
-    for (_, vote_map) in nay_delegators.iter() {
-        for (validator_address, vote_power) in vote_map.iter() {
-            if yay_validators.contains_key(validator_address) {
-                total_yay_staked_tokens -= vote_power;
-            }
-        }
+    let total_voted_power = total_yay_power
+        + total_nay_power
+        + total_abstain_power
+        + total_veto_power;
+    let quorum_reached = Decimal::from(total_voted_power)
+        >= quorum * Decimal::from(total_voting_power);
+    let threshold_reached = total_yay_power > 0
+        && Decimal::from(total_yay_power)
+            >= pass_threshold
+                * Decimal::from(
+                    total_yay_power + total_nay_power + total_veto_power,
+                );
+    let vetoed = total_veto_power > 0
+        && Decimal::from(total_veto_power)
+            >= veto_threshold * Decimal::from(total_voted_power);
+    let result = if quorum_reached && vetoed {
+        TallyResult::Vetoed
+    } else if quorum_reached && threshold_reached {
+        TallyResult::Passed
+    } else {
+        TallyResult::Rejected
+    };
+    ProposalResult {
+        result,
+        total_voting_power,
+        total_yay_power,
+        total_nay_power,
+        total_abstain_power,
+        total_veto_power,
+        quorum_reached,
     }
-
-    Ok(3 * total_yay_staked_tokens >= 2 * total_stake)
 }
 
-/// Prepare Votes structure to compute proposal tally
-pub fn get_proposal_votes<D, H>(
+/// Read all the votes cast on a proposal with the voting power of the voters
+/// at the given epoch. The votes of delegators are broken down by the
+/// validators of their bonds. Only the latest vote of a voter is kept. The
+/// validators pass through the votes of their delegators according to their
+/// setting when the voting period ended, or the current one before that.
+pub fn read_proposal_votes<D, H>(
     storage: &Storage<D, H>,
     epoch: Epoch,
     proposal_id: u64,
-) -> storage_api::Result<Votes>
+) -> storage_api::Result<ProposalVotes>
 where
     D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
     H: StorageHasher + Sync + 'static,
@@ -134,84 +362,152 @@ where
         gov_storage::get_proposal_vote_prefix_key(proposal_id);
     let (vote_iter, _) = storage.iter_prefix(&vote_prefix_key);
 
-    let mut yay_validators = HashMap::new();
-    let mut yay_delegators: HashMap<Address, HashMap<Address, VotePower>> =
-        HashMap::new();
-    let mut nay_delegators: HashMap<Address, HashMap<Address, VotePower>> =
-        HashMap::new();
+    let mut validator_votes: BTreeMap<Address, WeightedVote> = BTreeMap::new();
+    let mut delegator_votes: BTreeMap<
+        Address,
+        BTreeMap<Address, WeightedVote>,
+    > = BTreeMap::new();
 
     for (key, vote_bytes, _) in vote_iter {
         let vote_key = Key::from_str(key.as_str()).ok();
         let vote = ProposalVote::try_from_slice(&vote_bytes[..]).ok();
-        match (vote_key, vote) {
-            (Some(key), Some(vote)) => {
-                let voter_address = gov_storage::get_voter_address(&key);
-                match voter_address {
-                    Some(voter_address) => {
-                        if vote.is_yay() && validators.contains(voter_address) {
-                            let amount: VotePower = storage
-                                .validator_stake(voter_address, epoch)?
-                                .into();
-                            yay_validators
-                                .insert(voter_address.clone(), amount);
-                        } else if !validators.contains(voter_address) {
-                            let validator_address =
-                                gov_storage::get_vote_delegation_address(&key);
-                            match validator_address {
-                                Some(validator) => {
-                                    let bond_id = BondId {
-                                        source: voter_address.clone(),
-                                        validator: validator.clone(),
-                                    };
-                                    let amount =
-                                        storage.bond_amount(&bond_id, epoch)?;
-                                    if amount != token::Amount::default() {
-                                        if vote.is_yay() {
-                                            let entry = yay_delegators
-                                                .entry(voter_address.to_owned())
-                                                .or_default();
-                                            entry.insert(
-                                                validator.to_owned(),
-                                                VotePower::from(amount),
-                                            );
-                                        } else {
-                                            let entry = nay_delegators
-                                                .entry(voter_address.to_owned())
-                                                .or_default();
-                                            entry.insert(
-                                                validator.to_owned(),
-                                                VotePower::from(amount),
-                                            );
-                                        }
-                                    }
-                                }
-                                None => continue,
-                            }
-                        }
-                    }
-                    None => continue,
-                }
-            }
+        let (key, vote) = match (vote_key, vote) {
+            (Some(key), Some(vote)) => (key, vote),
             _ => continue,
+        };
+        let voter_address = match gov_storage::get_voter_address(&key) {
+            Some(voter_address) => voter_address,
+            None => continue,
+        };
+        if validators.contains(voter_address) {
+            let power: VotePower =
+                storage.validator_stake(voter_address, epoch)?.into();
+            validator_votes
+                .insert(voter_address.clone(), WeightedVote { vote, power });
+        } else {
+            let validator = match gov_storage::get_vote_delegation_address(&key)
+            {
+                Some(validator) => validator,
+                None => continue,
+            };
+            let bond_id = BondId {
+                source: voter_address.clone(),
+                validator: validator.clone(),
+            };
+            let power: VotePower = storage.bond_amount(&bond_id, epoch)?.into();
+            delegator_votes
+                .entry(voter_address.clone())
+                .or_default()
+                .insert(validator.clone(), WeightedVote { vote, power });
         }
     }
 
-    Ok(Votes {
-        yay_validators,
-        yay_delegators,
-        nay_delegators,
+    let pass_through_key =
+        gov_storage::get_pass_through_validators_key(proposal_id);
+    let pass_through_validators =
+        match StorageRead::read(storage, &pass_through_key)? {
+            Some(pass_through_validators) => pass_through_validators,
+            None => read_pass_through_validators(storage)?,
+        };
+    let mut pass_through: BTreeMap<Address, VotePower> = BTreeMap::new();
+    for validator in pass_through_validators {
+        if !validators.contains(&validator) {
+            continue;
+        }
+        let power: VotePower =
+            storage.validator_stake(&validator, epoch)?.into();
+        pass_through.insert(validator, power);
+    }
+
+    Ok(ProposalVotes {
+        epoch,
+        validators: validator_votes,
+        delegators: delegator_votes,
+        pass_through,
     })
 }
 
-/// Calculate the valid voting window for validator given a proposal epoch
-/// details
-pub fn is_valid_validator_voting_period(
-    current_epoch: Epoch,
-    voting_start_epoch: Epoch,
-    voting_end_epoch: Epoch,
-) -> bool {
-    voting_start_epoch < voting_end_epoch
-        && current_epoch * 3 <= voting_start_epoch + voting_end_epoch * 2
+/// Read the validators that currently pass through the votes of their
+/// delegators
+pub fn read_pass_through_validators<S>(
+    storage: &S,
+) -> storage_api::Result<BTreeSet<Address>>
+where
+    S: StorageRead,
+{
+    let prefix = gov_storage::get_vote_pass_through_prefix();
+    let mut validators = BTreeSet::new();
+    for iter_result in storage_api::iter_prefix_bytes(storage, &prefix)? {
+        let (key, _) = iter_result?;
+        if let Some(validator) = gov_storage::is_vote_pass_through_key(&key) {
+            validators.insert(validator.clone());
+        }
+    }
+    Ok(validators)
+}
+
+impl Votes {
+    /// Add the vote of a validator with its total stake amount
+    pub fn add_validator_vote(
+        &mut self,
+        validator: Address,
+        vote: &ProposalVote,
+        power: VotePower,
+    ) {
+        let validators = match vote {
+            ProposalVote::Yay => &mut self.yay_validators,
+            ProposalVote::Nay => &mut self.nay_validators,
+            ProposalVote::Abstain => &mut self.abstain_validators,
+            ProposalVote::NayWithVeto => &mut self.veto_validators,
+        };
+        validators.insert(validator, power);
+    }
+
+    /// Add the vote of a delegator with the amount of its bond to the given
+    /// validator
+    pub fn add_delegator_vote(
+        &mut self,
+        delegator: Address,
+        validator: Address,
+        vote: &ProposalVote,
+        power: VotePower,
+    ) {
+        let delegators = match vote {
+            ProposalVote::Yay => &mut self.yay_delegators,
+            ProposalVote::Nay => &mut self.nay_delegators,
+            ProposalVote::Abstain => &mut self.abstain_delegators,
+            ProposalVote::NayWithVeto => &mut self.veto_delegators,
+        };
+        delegators
+            .entry(delegator)
+            .or_default()
+            .insert(validator, power);
+    }
+}
+
+impl From<ProposalVotes> for Votes {
+    /// Only the votes of delegators with some bonded tokens count in the
+    /// tally
+    fn from(votes: ProposalVotes) -> Self {
+        let mut tally_votes = Votes::default();
+        for (validator, vote) in votes.validators {
+            tally_votes.add_validator_vote(validator, &vote.vote, vote.power);
+        }
+        for (delegator, delegations) in votes.delegators {
+            for (validator, vote) in delegations {
+                if vote.power == 0 {
+                    continue;
+                }
+                tally_votes.add_delegator_vote(
+                    delegator.clone(),
+                    validator,
+                    &vote.vote,
+                    vote.power,
+                );
+            }
+        }
+        tally_votes
+    }
 }
 
 /// Check if an accepted proposal is being executed
@@ -232,3 +528,195 @@ where
         None => Ok(false),
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::types::address;
+    use crate::types::address::testing::{
+        established_address_1, established_address_2, established_address_3,
+    };
+
+    /// Test that the votes of delegators override their validator's vote,
+    /// that both the quorum and the pass threshold must be reached and that
+    /// the nay with veto votes can veto a proposal.
+    #[test]
+    fn test_tally_votes() {
+        let validator_1 = established_address_1();
+        let validator_2 = established_address_2();
+        let delegator = established_address_3();
+        let quorum = Decimal::new(334, 3);
+        let pass_threshold = Decimal::new(667, 3);
+        let veto_threshold = Decimal::new(334, 3);
+
+        let mut votes = Votes::default();
+        votes.add_validator_vote(validator_1.clone(), &ProposalVote::Yay, 60);
+        votes.add_validator_vote(validator_2, &ProposalVote::Abstain, 20);
+        votes.add_delegator_vote(
+            delegator.clone(),
+            validator_1.clone(),
+            &ProposalVote::Nay,
+            10,
+        );
+        let result =
+            tally_votes(votes, 100, quorum, pass_threshold, veto_threshold);
+        assert_eq!(
+            result,
+            ProposalResult {
+                result: TallyResult::Passed,
+                total_voting_power: 100,
+                total_yay_power: 50,
+                total_nay_power: 10,
+                total_abstain_power: 20,
+                total_veto_power: 0,
+                quorum_reached: true,
+            }
+        );
+
+        // The yay votes are below the pass threshold
+        let mut votes = Votes::default();
+        votes.add_validator_vote(validator_1.clone(), &ProposalVote::Yay, 60);
+        votes.add_delegator_vote(
+            delegator.clone(),
+            validator_1.clone(),
+            &ProposalVote::Nay,
+            30,
+        );
+        let result =
+            tally_votes(votes, 100, quorum, pass_threshold, veto_threshold);
+        assert_eq!(result.result, TallyResult::Rejected);
+        assert_eq!(result.total_yay_power, 30);
+        assert_eq!(result.total_nay_power, 30);
+
+        // The abstain votes count towards the quorum only
+        let mut votes = Votes::default();
+        votes.add_validator_vote(
+            validator_1.clone(),
+            &ProposalVote::Abstain,
+            30,
+        );
+        votes.add_delegator_vote(
+            delegator,
+            established_address_2(),
+            &ProposalVote::Yay,
+            5,
+        );
+        let result =
+            tally_votes(votes, 100, quorum, pass_threshold, veto_threshold);
+        assert_eq!(result.result, TallyResult::Passed);
+        assert_eq!(result.total_voted_power(), 35);
+        let result = tally_votes(
+            Votes::default(),
+            100,
+            quorum,
+            pass_threshold,
+            veto_threshold,
+        );
+        assert_eq!(result.result, TallyResult::Rejected);
+        assert!(!result.quorum_reached);
+
+        // The nay with veto votes count as nay votes towards the pass
+        // threshold and they veto the proposal above the veto threshold
+        let mut votes = Votes::default();
+        votes.add_validator_vote(validator_1.clone(), &ProposalVote::Yay, 60);
+        votes.add_delegator_vote(
+            established_address_3(),
+            validator_1.clone(),
+            &ProposalVote::NayWithVeto,
+            20,
+        );
+        let result =
+            tally_votes(votes, 100, quorum, pass_threshold, veto_threshold);
+        assert_eq!(result.result, TallyResult::Rejected);
+        assert_eq!(result.total_yay_power, 40);
+        assert_eq!(result.total_veto_power, 20);
+        let mut votes = Votes::default();
+        votes.add_validator_vote(validator_1, &ProposalVote::Yay, 50);
+        votes.add_validator_vote(
+            established_address_2(),
+            &ProposalVote::NayWithVeto,
+            30,
+        );
+        let result =
+            tally_votes(votes, 100, quorum, pass_threshold, veto_threshold);
+        assert_eq!(result.result, TallyResult::Vetoed);
+        assert!(result.quorum_reached);
+    }
+
+    /// Test that a validator that passes through the votes of its delegators
+    /// votes like the majority of their voting power, but keeps its own vote
+    /// without a majority.
+    #[test]
+    fn test_compute_tally_vote_pass_through() {
+        let validator = established_address_1();
+        let delegator_1 = established_address_2();
+        let delegator_2 = established_address_3();
+        let delegator_3 = address::testing::gen_established_address();
+        let params = TallyParams::new(&GovParams::default(), false);
+        let weighted = |vote, power| WeightedVote { vote, power };
+
+        let mut votes = ProposalVotes {
+            epoch: Epoch::default(),
+            validators: BTreeMap::from([(
+                validator.clone(),
+                weighted(ProposalVote::Nay, 100),
+            )]),
+            delegators: BTreeMap::from([
+                (
+                    delegator_1.clone(),
+                    BTreeMap::from([(
+                        validator.clone(),
+                        weighted(ProposalVote::Yay, 20),
+                    )]),
+                ),
+                (
+                    delegator_2.clone(),
+                    BTreeMap::from([(
+                        validator.clone(),
+                        weighted(ProposalVote::Yay, 10),
+                    )]),
+                ),
+                (
+                    delegator_3,
+                    BTreeMap::from([(
+                        validator.clone(),
+                        weighted(ProposalVote::Nay, 25),
+                    )]),
+                ),
+            ]),
+            pass_through: BTreeMap::new(),
+        };
+
+        // Without pass-through, the rest of the stake votes like the
+        // validator
+        let result = compute_tally(votes.clone(), 100, &params);
+        assert_eq!(result.result, TallyResult::Rejected);
+        assert_eq!(result.total_yay_power, 30);
+        assert_eq!(result.total_nay_power, 70);
+
+        // With pass-through, it votes like the majority of the delegators
+        votes.pass_through.insert(validator.clone(), 100);
+        let result = compute_tally(votes.clone(), 100, &params);
+        assert_eq!(result.result, TallyResult::Passed);
+        assert_eq!(result.total_yay_power, 75);
+        assert_eq!(result.total_nay_power, 25);
+
+        // On a tie, the validator keeps its own vote
+        votes
+            .delegators
+            .get_mut(&delegator_2)
+            .unwrap()
+            .insert(validator.clone(), weighted(ProposalVote::Yay, 5));
+        let result = compute_tally(votes.clone(), 100, &params);
+        assert_eq!(result.total_yay_power, 25);
+        assert_eq!(result.total_nay_power, 75);
+
+        // Without any vote of its own, the validator only votes through its
+        // delegators
+        votes.validators.clear();
+        votes.delegators.remove(&delegator_1);
+        let result = compute_tally(votes, 100, &params);
+        assert_eq!(result.total_yay_power, 5);
+        assert_eq!(result.total_nay_power, 95);
+    }
+}
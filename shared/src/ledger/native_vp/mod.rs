@@ -3,6 +3,7 @@
 
 pub mod governance;
 pub mod parameters;
+pub mod pgf;
 pub mod slash_fund;
 
 use std::cell::RefCell;
@@ -0,0 +1,148 @@
+//! Public goods funding VP
+
+use std::collections::BTreeSet;
+
+use namada_core::ledger::pgf;
+/// PGF storage
+pub use namada_core::ledger::pgf::storage;
+use thiserror::Error;
+
+use crate::ledger::native_vp::{self, Ctx, NativeVp};
+use crate::ledger::storage::{self as ledger_storage, StorageHasher};
+use crate::ledger::storage_api::StorageRead;
+use crate::types::address::{Address, InternalAddress};
+use crate::types::governance::PgfCouncil;
+use crate::types::storage::Key;
+use crate::types::token;
+use crate::vm::WasmCacheAccess;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Native VP error: {0}")]
+    NativeVpError(#[from] native_vp::Error),
+}
+
+/// PGF functions result
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// PGF VP
+pub struct PgfVp<'a, DB, H, CA>
+where
+    DB: ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
+    H: StorageHasher,
+    CA: WasmCacheAccess,
+{
+    /// Context to interact with the host structures.
+    pub ctx: Ctx<'a, DB, H, CA>,
+}
+
+impl<'a, DB, H, CA> NativeVp for PgfVp<'a, DB, H, CA>
+where
+    DB: 'static + ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
+    H: 'static + StorageHasher,
+    CA: 'static + WasmCacheAccess,
+{
+    type Error = Error;
+
+    const ADDR: InternalAddress = InternalAddress::Pgf;
+
+    fn validate_tx(
+        &self,
+        _tx_data: &[u8],
+        keys_changed: &BTreeSet<Key>,
+        verifiers: &BTreeSet<Address>,
+    ) -> Result<bool> {
+        // The funding may only be changed by the current council
+        let council: Option<PgfCouncil> = pgf::read_council(&self.ctx.pre())?;
+        let is_council_verifier = council
+            .as_ref()
+            .map(|council| verifiers.contains(&council.address))
+            .unwrap_or(false);
+
+        let mut is_stream_changed = false;
+        for key in keys_changed {
+            let is_valid = match get_key_type(key) {
+                KeyType::STREAM => {
+                    is_stream_changed = true;
+                    is_council_verifier && self.is_valid_amount(key)?
+                }
+                KeyType::RETRO_PAYMENT => {
+                    is_council_verifier && self.is_valid_amount(key)?
+                }
+                // The council is only elected by governance proposals
+                KeyType::COUNCIL => false,
+                // The funding is minted, the PGF doesn't hold any tokens
+                KeyType::BALANCE => false,
+                KeyType::UNKNOWN_PGF => false,
+                KeyType::UNKNOWN => true,
+            };
+            if !is_valid {
+                return Ok(false);
+            }
+        }
+
+        match council {
+            Some(council) if is_stream_changed => {
+                self.is_within_spending_cap(&council)
+            }
+            _ => Ok(true),
+        }
+    }
+}
+
+impl<'a, DB, H, CA> PgfVp<'a, DB, H, CA>
+where
+    DB: 'static + ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
+    H: 'static + StorageHasher,
+    CA: 'static + WasmCacheAccess,
+{
+    /// A payment may be removed, otherwise its amount must be positive
+    fn is_valid_amount(&self, key: &Key) -> Result<bool> {
+        let amount: Option<token::Amount> = self.ctx.post().read(key)?;
+        Ok(amount
+            .map(|amount| amount != token::Amount::default())
+            .unwrap_or(true))
+    }
+
+    /// Check that the continuous funding streams can all be paid every epoch
+    fn is_within_spending_cap(&self, council: &PgfCouncil) -> Result<bool> {
+        let streams = pgf::read_streams(&self.ctx.post())?;
+        let total = streams.into_iter().try_fold(
+            token::Amount::default(),
+            |total, (_, amount)| {
+                u64::from(total)
+                    .checked_add(u64::from(amount))
+                    .map(token::Amount::from)
+            },
+        );
+        Ok(matches!(total, Some(total) if total <= council.spending_cap))
+    }
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[allow(non_camel_case_types)]
+enum KeyType {
+    STREAM,
+    RETRO_PAYMENT,
+    COUNCIL,
+    BALANCE,
+    UNKNOWN_PGF,
+    UNKNOWN,
+}
+
+fn get_key_type(key: &Key) -> KeyType {
+    if storage::is_stream_key(key).is_some() {
+        KeyType::STREAM
+    } else if storage::is_retro_payment_key(key).is_some() {
+        KeyType::RETRO_PAYMENT
+    } else if storage::is_council_key(key) {
+        KeyType::COUNCIL
+    } else if storage::is_pgf_key(key) {
+        KeyType::UNKNOWN_PGF
+    } else if token::is_any_token_balance_key(key) == Some(&pgf::ADDRESS) {
+        KeyType::BALANCE
+    } else {
+        KeyType::UNKNOWN
+    }
+}
@@ -6,10 +6,11 @@ pub use namada_proof_of_stake;
 pub use namada_proof_of_stake::parameters::PosParams;
 pub use namada_proof_of_stake::storage::*;
 pub use namada_proof_of_stake::types;
-use namada_proof_of_stake::PosBase;
+use namada_proof_of_stake::{impl_pos_read_only, PosBase, PosReadOnly};
 use rust_decimal::Decimal;
 pub use vp::PosVP;
 
+use crate::ledger::queries::proven::{ProvenStorage, ProvingStorage};
 use crate::ledger::storage::{self as ledger_storage, Storage, StorageHasher};
 use crate::types::address::{Address, InternalAddress};
 use crate::types::storage::Epoch;
@@ -45,3 +46,16 @@ pub fn init_genesis_storage<'a, DB, H>(
         .init_genesis(params, validators, current_epoch)
         .expect("Initialize PoS genesis storage")
 }
+
+// The storage of the queries with proofs, in which the handlers can read PoS
+// data
+impl_pos_read_only! {
+    impl<'a, DB, H> PosReadOnly for ProvingStorage<'a, DB, H>
+        where
+            DB: ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter> +'static,
+            H: StorageHasher +'static,
+}
+
+impl_pos_read_only! {
+    impl PosReadOnly for ProvenStorage
+}
@@ -14,14 +14,14 @@ use rust_decimal::Decimal;
 use thiserror::Error;
 
 use super::{
-    bond_key, is_bond_key, is_params_key, is_total_deltas_key, is_unbond_key,
-    is_validator_deltas_key, is_validator_set_key, params_key,
+    bond_key, decode_params, is_bond_key, is_params_key, is_total_deltas_key,
+    is_unbond_key, is_validator_deltas_key, is_validator_set_key, params_key,
     total_deltas_key, unbond_key, validator_commission_rate_key,
     validator_consensus_key_key, validator_deltas_key,
     validator_max_commission_rate_change_key, validator_set_key,
     validator_slashes_key, validator_state_key, BondId, Bonds, CommissionRates,
-    TotalDeltas, Unbonds, ValidatorConsensusKeys, ValidatorDeltas,
-    ValidatorSets,
+    EpochedPosParams, TotalDeltas, Unbonds, ValidatorConsensusKeys,
+    ValidatorDeltas, ValidatorSets,
 };
 use crate::ledger::native_vp::{
     self, governance, Ctx, CtxPostStorageRead, CtxPreStorageRead, NativeVp,
@@ -122,11 +122,29 @@ where
 
         for key in keys_changed {
             if is_params_key(key) {
-                return governance::utils::is_proposal_accepted(
+                // The parameters can only be changed by an accepted
+                // governance proposal
+                if !governance::utils::is_proposal_accepted(
                     self.ctx.storage,
                     tx_data,
-                )
-                .map_err(Error::NativeVpError);
+                )? {
+                    tracing::info!(
+                        "PoS parameters change without an accepted \
+                         proposal rejected"
+                    );
+                    return Ok(false);
+                }
+                let pre = self
+                    .ctx
+                    .pre()
+                    .read_bytes(key)?
+                    .and_then(|bytes| decode_params(bytes).ok());
+                let post = self
+                    .ctx
+                    .post()
+                    .read_bytes(key)?
+                    .and_then(|bytes| decode_params(bytes).ok());
+                changes.push(Params(Data { pre, post }));
             } else if is_validator_set_key(key) {
                 let pre = self.ctx.pre().read_bytes(key)?.and_then(|bytes| {
                     ValidatorSets::try_from_slice(&bytes[..]).ok()
@@ -290,7 +308,7 @@ where
             }
         }
 
-        let params = self.ctx.pre().read_pos_params()?;
+        let params = self.ctx.pre().read_epoched_pos_params()?;
         let errors = validate(&params, changes, current_epoch);
         Ok(if errors.is_empty() {
             true
@@ -10,6 +10,7 @@ use crate::ledger::gas::{self, BlockGasMeter, VpGasMeter};
 use crate::ledger::ibc::vp::{Ibc, IbcToken};
 use crate::ledger::native_vp::governance::GovernanceVp;
 use crate::ledger::native_vp::parameters::{self, ParametersVp};
+use crate::ledger::native_vp::pgf::PgfVp;
 use crate::ledger::native_vp::slash_fund::SlashFundVp;
 use crate::ledger::native_vp::{self, NativeVp};
 use crate::ledger::pos::{self, PosVP};
@@ -54,6 +55,8 @@ pub enum Error {
     GovernanceNativeVpError(crate::ledger::native_vp::governance::Error),
     #[error("SlashFund native VP error: {0}")]
     SlashFundNativeVpError(crate::ledger::native_vp::slash_fund::Error),
+    #[error("PGF native VP error: {0}")]
+    PgfNativeVpError(crate::ledger::native_vp::pgf::Error),
     #[error("Ethereum bridge native VP error: {0}")]
     EthBridgeNativeVpError(crate::ledger::eth_bridge::vp::Error),
     #[error("Access to an internal address {0} is forbidden")]
@@ -369,6 +372,14 @@ where
                             gas_meter = slash_fund.ctx.gas_meter.into_inner();
                             result
                         }
+                        InternalAddress::Pgf => {
+                            let pgf = PgfVp { ctx };
+                            let result = pgf
+                                .validate_tx(tx_data, &keys_changed, &verifiers)
+                                .map_err(Error::PgfNativeVpError);
+                            gas_meter = pgf.ctx.gas_meter.into_inner();
+                            result
+                        }
                         InternalAddress::IbcToken(_)
                         | InternalAddress::IbcEscrow
                         | InternalAddress::IbcBurn
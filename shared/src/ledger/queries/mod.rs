@@ -1,24 +1,32 @@
 //! Ledger read-only queries can be handled and dispatched via the [`RPC`]
 //! defined via `router!` macro.
 
+use borsh::{BorshDeserialize, BorshSerialize};
 // Re-export to show in rustdoc!
-pub use shell::Shell;
 use shell::SHELL;
+pub use shell::{
+    ConversionUpdate, ConversionUpdates, IndexedTransfer, Masp,
+    NoteCommitmentFrontier, Nullifier, Shell, TransfersPage,
+    MAX_NULLIFIERS_HEIGHTS, MAX_TRANSFERS_PER_PAGE,
+};
 #[cfg(any(test, feature = "async-client"))]
 pub use types::Client;
 pub use types::{
-    EncodedResponseQuery, RequestCtx, RequestQuery, ResponseQuery, Router,
+    BatchProofOp, BatchRequest, BatchResponse, EncodedResponseQuery,
+    RequestCtx, RequestQuery, ResponseQuery, Router,
 };
 use vp::VP;
 // Re-export to show in rustdoc!
-pub use vp::{Pos, Vp};
+pub use vp::{Account, Governance, Pos, Token, Vp};
 
 use super::storage::{DBIter, StorageHasher, DB};
-use super::storage_api;
+use super::storage_api::{self, ResultExt};
 use crate::types::storage::BlockHeight;
 
+pub mod proven;
 #[macro_use]
 mod router;
+pub mod schema;
 mod shell;
 mod types;
 mod vp;
@@ -30,6 +38,9 @@ router! {RPC,
 
     // Validity-predicate's specific storage queries
     ( "vp" ) = (sub VP),
+
+    // A batch of borsh-encoded `BatchRequest`s handled at the same height
+    ( "batch" ) -> Vec<BatchResponse> = (with_options batch),
 }
 
 /// Handle RPC query request in the ledger. On success, returns response with
@@ -45,6 +56,47 @@ where
     RPC.handle(ctx, request)
 }
 
+/// Handle a batch of requests at the height of the batch request. The
+/// responses are in the order of the requests and a failed request doesn't
+/// fail the whole batch.
+fn batch<D, H>(
+    ctx: RequestCtx<'_, D, H>,
+    request: &RequestQuery,
+) -> storage_api::Result<EncodedResponseQuery>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    require_no_proof(request)?;
+    let requests: Vec<BatchRequest> =
+        BorshDeserialize::try_from_slice(&request.data)
+            .into_storage_result()?;
+    let batch_path = RPC.batch_path();
+    let responses: Vec<BatchResponse> = requests
+        .into_iter()
+        .map(|BatchRequest { path, data, prove }| {
+            if path.trim_end_matches('/') == batch_path {
+                return BatchResponse::error("Batches cannot be nested");
+            }
+            let request = RequestQuery {
+                data,
+                path,
+                height: request.height,
+                prove,
+            };
+            match RPC.handle(ctx.clone(), &request) {
+                Ok(response) => response.into(),
+                Err(err) => BatchResponse::error(err),
+            }
+        })
+        .collect();
+    let data = responses.try_to_vec().into_storage_result()?;
+    Ok(EncodedResponseQuery {
+        data,
+        ..Default::default()
+    })
+}
+
 // Handler helpers:
 
 /// For queries that only support latest height, check that the given height is
@@ -247,3 +299,84 @@ mod testing {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use borsh::BorshDeserialize;
+
+    use super::testing::TestClient;
+    use super::*;
+    use crate::ledger::storage_api::StorageWrite;
+    use crate::types::storage::Epoch;
+    use crate::types::{address, token};
+
+    /// Test that the schema describes the routes of the sub-routers at their
+    /// full paths.
+    #[test]
+    fn test_rpc_schema() {
+        let routes = RPC.schema();
+        let proposals = routes
+            .iter()
+            .find(|route| route.handler == "proposals")
+            .unwrap();
+        assert_eq!(
+            proposals.path(),
+            "/vp/governance/proposals/{status?}/{offset}/{limit}"
+        );
+        assert_eq!(proposals.kind, schema::HandlerKind::WithProof);
+        assert_eq!(proposals.response, "Vec<ProposalInfo>");
+
+        let nullifiers = routes
+            .iter()
+            .find(|route| route.handler == "nullifiers")
+            .unwrap();
+        assert_eq!(nullifiers.path(), "/shell/masp/nullifiers/{from}/{to}");
+        assert_eq!(
+            nullifiers.response,
+            "BTreeMap<BlockHeight, Vec<Nullifier>>"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_batch_request() -> storage_api::Result<()> {
+        let mut client = TestClient::new(RPC);
+        let token_addr = address::testing::established_address_1();
+        let owner = address::testing::established_address_2();
+        let balance_key = token::balance_key(&token_addr, &owner);
+        let balance = token::Amount::from(1000);
+        StorageWrite::write(&mut client.storage, &balance_key, balance)?;
+
+        let requests = [
+            BatchRequest::new(RPC.shell().epoch_path()),
+            BatchRequest {
+                prove: true,
+                ..BatchRequest::new(
+                    RPC.shell().storage_value_path(&balance_key),
+                )
+            },
+            BatchRequest::new("/shell/unknown".to_owned()),
+            BatchRequest::new(RPC.batch_path()),
+        ];
+        let responses = client.batch_request(&requests, None).await.unwrap();
+        assert_eq!(responses.len(), requests.len());
+        let mut responses = responses.into_iter();
+
+        let epoch = responses.next().unwrap().unwrap();
+        assert_eq!(
+            Epoch::try_from_slice(&epoch.data).unwrap(),
+            client.storage.last_epoch
+        );
+        let value = responses.next().unwrap().unwrap();
+        assert_eq!(
+            token::Amount::try_from_slice(&value.data).unwrap(),
+            balance
+        );
+        assert!(value.proof.is_some());
+        // A failed request doesn't fail the other requests in the batch
+        assert!(responses.next().unwrap().is_err());
+        // Batches cannot be nested
+        assert!(responses.next().unwrap().is_err());
+
+        Ok(())
+    }
+}
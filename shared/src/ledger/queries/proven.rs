@@ -0,0 +1,370 @@
+//! Support for queries with proofs. The handlers of these queries read the
+//! storage only via [`trait@StorageRead`], so that the same handler logic can
+//! be run by the ledger with a [`ProvingStorage`], which records and proves
+//! every storage read, and by a client with a [`ProvenStorage`], which only
+//! has the values that have been proven.
+//!
+//! Note that the absence of a key can only be proven for the keys in the IBC
+//! sub-tree. The reads of the other keys that are not present are returned
+//! without a proof and are reported in [`Proven::unproven`].
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use thiserror::Error;
+
+use super::EncodedResponseQuery;
+use crate::ledger::storage::merkle_tree::{self, MerkleRoot};
+use crate::ledger::storage::{
+    self, current_epoch_key, types, DBIter, Sha256Hasher, Storage,
+    StorageHasher, DB,
+};
+use crate::ledger::storage_api::{self, ResultExt, StorageRead};
+use crate::tendermint::merkle::proof::Proof;
+use crate::types::address::Address;
+use crate::types::storage::{BlockHash, BlockHeight, Epoch, Key, TxIndex};
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Decoding error: {0}")]
+    Decoding(#[from] std::io::Error),
+    #[error("Invalid proof: {0}")]
+    InvalidProof(#[from] merkle_tree::Error),
+    #[error("Missing a proof of the value of the key {0}")]
+    MissingProof(Key),
+    #[error("The proofs were made from different Merkle trees")]
+    RootMismatch,
+    #[error("Failed to re-run the query handler: {0}")]
+    Handler(storage_api::Error),
+    #[error("The response doesn't match the proven storage values")]
+    ResultMismatch,
+}
+
+/// Result for functions that may fail
+type Result<T> = std::result::Result<T, Error>;
+
+/// A storage read made by the handler of a query with proofs
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct ProvenRead {
+    /// The key that was read
+    pub key: Key,
+    /// The value that was read, `None` if the key is not present
+    pub value: Option<Vec<u8>>,
+}
+
+/// The verified response of a query with proofs
+#[derive(Clone, Debug)]
+pub struct Proven<T> {
+    /// The result of the query handler re-run against the proven values
+    pub data: T,
+    /// The root of the Merkle tree that all the proofs have been verified
+    /// against. It has to be checked against a trusted app hash before the
+    /// data can be trusted. It's `None` only when the handler didn't read
+    /// any proven value.
+    pub root: Option<MerkleRoot>,
+    /// The keys that have been read as not present without a proof of their
+    /// absence. The data can only be fully trusted when this is empty.
+    pub unproven: Vec<Key>,
+}
+
+/// The storage used by the ledger to run the handler of a query with proofs.
+/// It records every storage read made by the handler, which can then be
+/// proven with [`ProvingStorage::into_response`].
+pub struct ProvingStorage<'a, D, H>
+where
+    D: DB + for<'iter> DBIter<'iter>,
+    H: StorageHasher,
+{
+    storage: &'a Storage<D, H>,
+    height: BlockHeight,
+    reads: RefCell<BTreeMap<Key, Option<Vec<u8>>>>,
+}
+
+impl<'a, D, H> ProvingStorage<'a, D, H>
+where
+    D: DB + for<'iter> DBIter<'iter>,
+    H: StorageHasher,
+{
+    /// Wrap the storage to record the reads of a query handler at the given
+    /// height. The height `0` means the last committed height.
+    pub fn new(
+        storage: &'a Storage<D, H>,
+        height: BlockHeight,
+    ) -> storage_api::Result<Self> {
+        let height = if height == BlockHeight(0) {
+            storage.last_height
+        } else if height > storage.last_height {
+            return Err(storage_api::Error::new(format!(
+                "The height {height} has not been committed yet"
+            )));
+        } else {
+            height
+        };
+        Ok(Self {
+            storage,
+            height,
+            reads: RefCell::default(),
+        })
+    }
+
+    /// Encode the data returned by the query handler together with all the
+    /// storage reads made by the handler and, if requested, prove the reads
+    /// at the queried height.
+    pub fn into_response<T>(
+        self,
+        data: &T,
+        prove: bool,
+    ) -> storage_api::Result<EncodedResponseQuery>
+    where
+        T: BorshSerialize,
+    {
+        if !prove {
+            let data = data.try_to_vec().into_storage_result()?;
+            return Ok(EncodedResponseQuery {
+                data,
+                info: Default::default(),
+                proof: None,
+            });
+        }
+
+        let height = self.height;
+        let reads: Vec<ProvenRead> = self
+            .reads
+            .into_inner()
+            .into_iter()
+            .map(|(key, value)| ProvenRead { key, value })
+            .collect();
+        let mut ops = vec![];
+        for ProvenRead { key, value } in &reads {
+            let proof = match value {
+                Some(value) => {
+                    self.storage.get_existence_proof(key, value, height)
+                }
+                None => match self.storage.get_non_existence_proof(key, height)
+                {
+                    // The absence can't be proven outside of the IBC sub-tree
+                    Err(storage::Error::MerkleTreeError(
+                        merkle_tree::Error::NonExistenceProof(_),
+                    )) => continue,
+                    result => result,
+                },
+            }
+            .into_storage_result()?;
+            ops.extend(proof.ops);
+        }
+        // Encoded the same as a `(T, Vec<ProvenRead>)` tuple
+        let mut data = data.try_to_vec().into_storage_result()?;
+        reads.serialize(&mut data).into_storage_result()?;
+        Ok(EncodedResponseQuery {
+            data,
+            info: Default::default(),
+            proof: Some(Proof { ops }),
+        })
+    }
+
+    fn record(&self, key: &Key, value: Option<Vec<u8>>) {
+        self.reads.borrow_mut().insert(key.clone(), value);
+    }
+}
+
+impl<'a, D, H> StorageRead for ProvingStorage<'a, D, H>
+where
+    D: DB + for<'iter> DBIter<'iter>,
+    H: StorageHasher,
+{
+    type PrefixIter<'iter> = std::iter::Empty<(String, Vec<u8>)> where Self: 'iter;
+
+    fn read_bytes(&self, key: &Key) -> storage_api::Result<Option<Vec<u8>>> {
+        let (value, _gas) = self
+            .storage
+            .read_with_height(key, self.height)
+            .into_storage_result()?;
+        self.record(key, value.clone());
+        Ok(value)
+    }
+
+    fn has_key(&self, key: &Key) -> storage_api::Result<bool> {
+        Ok(self.read_bytes(key)?.is_some())
+    }
+
+    fn iter_prefix<'iter>(
+        &'iter self,
+        _prefix: &Key,
+    ) -> storage_api::Result<Self::PrefixIter<'iter>> {
+        Err(unsupported("Prefix iteration"))
+    }
+
+    fn iter_next<'iter>(
+        &'iter self,
+        _iter: &mut Self::PrefixIter<'iter>,
+    ) -> storage_api::Result<Option<(String, Vec<u8>)>> {
+        Err(unsupported("Prefix iteration"))
+    }
+
+    fn get_chain_id(&self) -> storage_api::Result<String> {
+        Err(unsupported("The chain ID"))
+    }
+
+    fn get_block_height(&self) -> storage_api::Result<BlockHeight> {
+        Err(unsupported("The block height"))
+    }
+
+    fn get_block_hash(&self) -> storage_api::Result<BlockHash> {
+        Err(unsupported("The block hash"))
+    }
+
+    /// The epoch of the block at the queried height, which is proven by its
+    /// entry in the Merkle tree
+    fn get_block_epoch(&self) -> storage_api::Result<Epoch> {
+        let epoch = if self.height == self.storage.last_height {
+            self.storage.last_epoch
+        } else {
+            self.storage
+                .block
+                .pred_epochs
+                .get_epoch(self.height)
+                .ok_or_else(|| {
+                    storage_api::Error::new(format!(
+                        "The epoch of the height {} is not known",
+                        self.height
+                    ))
+                })?
+        };
+        self.record(&current_epoch_key(), Some(types::encode(&epoch)));
+        Ok(epoch)
+    }
+
+    fn get_tx_index(&self) -> storage_api::Result<TxIndex> {
+        Err(unsupported("The tx index"))
+    }
+
+    fn get_native_token(&self) -> storage_api::Result<Address> {
+        Err(unsupported("The native token"))
+    }
+}
+
+/// The storage used by a client to re-run the handler of a query with proofs
+/// against the proven values
+#[derive(Debug, Default)]
+pub struct ProvenStorage {
+    values: BTreeMap<Key, Option<Vec<u8>>>,
+}
+
+impl StorageRead for ProvenStorage {
+    type PrefixIter<'iter> = std::iter::Empty<(String, Vec<u8>)> where Self: 'iter;
+
+    fn read_bytes(&self, key: &Key) -> storage_api::Result<Option<Vec<u8>>> {
+        self.values.get(key).cloned().ok_or_else(|| {
+            storage_api::Error::new(format!(
+                "The key {key} hasn't been read by the query handler"
+            ))
+        })
+    }
+
+    fn has_key(&self, key: &Key) -> storage_api::Result<bool> {
+        Ok(self.read_bytes(key)?.is_some())
+    }
+
+    fn iter_prefix<'iter>(
+        &'iter self,
+        _prefix: &Key,
+    ) -> storage_api::Result<Self::PrefixIter<'iter>> {
+        Err(unsupported("Prefix iteration"))
+    }
+
+    fn iter_next<'iter>(
+        &'iter self,
+        _iter: &mut Self::PrefixIter<'iter>,
+    ) -> storage_api::Result<Option<(String, Vec<u8>)>> {
+        Err(unsupported("Prefix iteration"))
+    }
+
+    fn get_chain_id(&self) -> storage_api::Result<String> {
+        Err(unsupported("The chain ID"))
+    }
+
+    fn get_block_height(&self) -> storage_api::Result<BlockHeight> {
+        Err(unsupported("The block height"))
+    }
+
+    fn get_block_hash(&self) -> storage_api::Result<BlockHash> {
+        Err(unsupported("The block hash"))
+    }
+
+    fn get_block_epoch(&self) -> storage_api::Result<Epoch> {
+        self.read(&current_epoch_key())?.ok_or_else(|| {
+            storage_api::Error::new_const("The epoch hasn't been proven")
+        })
+    }
+
+    fn get_tx_index(&self) -> storage_api::Result<TxIndex> {
+        Err(unsupported("The tx index"))
+    }
+
+    fn get_native_token(&self) -> storage_api::Result<Address> {
+        Err(unsupported("The native token"))
+    }
+}
+
+/// Verify the proofs of the storage reads in the response of a query with
+/// proofs and re-run the query handler against the proven values. The result
+/// must match the data in the response.
+pub fn verify_response<T, F>(
+    response: EncodedResponseQuery,
+    handler: F,
+) -> Result<Proven<T>>
+where
+    T: BorshSerialize + BorshDeserialize,
+    F: FnOnce(&ProvenStorage) -> storage_api::Result<T>,
+{
+    let (data, reads): (T, Vec<ProvenRead>) =
+        BorshDeserialize::try_from_slice(&response.data)?;
+    let mut proofs: BTreeMap<Key, merkle_tree::Proof> = match &response.proof {
+        Some(proof) => merkle_tree::Proof::from_tm_proof(proof)?
+            .into_iter()
+            .map(|proof| (proof.key.clone(), proof))
+            .collect(),
+        None => BTreeMap::default(),
+    };
+
+    let mut root = None;
+    let mut unproven = vec![];
+    let mut storage = ProvenStorage::default();
+    for ProvenRead { key, value } in reads {
+        match proofs.remove(&key) {
+            Some(proof) => {
+                let proof_root =
+                    proof.verify::<Sha256Hasher>(value.as_deref())?;
+                match &root {
+                    Some(root) if root != &proof_root => {
+                        return Err(Error::RootMismatch);
+                    }
+                    Some(_) => {}
+                    None => root = Some(proof_root),
+                }
+            }
+            None if value.is_none() => unproven.push(key.clone()),
+            None => return Err(Error::MissingProof(key)),
+        }
+        storage.values.insert(key, value);
+    }
+
+    let result = handler(&storage).map_err(Error::Handler)?;
+    if result.try_to_vec()? != data.try_to_vec()? {
+        return Err(Error::ResultMismatch);
+    }
+    Ok(Proven {
+        data: result,
+        root,
+        unproven,
+    })
+}
+
+/// The error for a storage method that can't be used by a query with proofs
+fn unsupported(what: &'static str) -> storage_api::Error {
+    storage_api::Error::new(format!(
+        "{what} is not available to queries with proofs"
+    ))
+}
@@ -68,6 +68,34 @@ macro_rules! handle_match {
         return Ok(result);
     };
 
+    // Handler function that reads the storage via `StorageRead`, so that its
+    // reads can be proven (`with_proof`)
+    (
+        $ctx:ident, $request:ident, $start:ident, $end:ident,
+        (with_proof $handle:tt), ( $( $matched_args:ident, )* ),
+    ) => {
+        // check that we're at the end of the path - trailing slash is optional
+        if !($end == $request.path.len() ||
+            // ignore trailing slashes
+            $end == $request.path.len() - 1 && &$request.path[$end..] == "/") {
+                // we're not at the end, no match
+                // println!("Not fully matched");
+                break
+        }
+        // Check that the request is not sent with unsupported non-default
+        $crate::ledger::queries::shell::check_past_height_limit(&$ctx, $request)?;
+        $crate::ledger::queries::require_no_data($request)?;
+
+        // Record the storage reads at the requested height, so they can be
+        // proven if requested
+        let storage = $crate::ledger::queries::proven::ProvingStorage::new(
+            $ctx.storage,
+            $request.height,
+        )?;
+        let data = $handle(&storage, $( $matched_args ),* )?;
+        return storage.into_response(&data, $request.prove);
+    };
+
     // Handler function that doesn't use the request, just the path args, if any
     (
         $ctx:ident, $request:ident, $start:ident, $end:ident,
@@ -480,6 +508,74 @@ macro_rules! pattern_and_handler_to_method {
         }
     };
 
+    // terminal rule for $handle whose storage reads can be proven
+    // (`with_proof`)
+    (
+        ( $( $param:tt: $param_ty:ty ),* )
+        [ $( { $prefix:expr } ),* ]
+        $return_type:path,
+        (with_proof $handle:tt),
+        ()
+    ) => {
+        // paste! used to construct the `fn $handle_path`'s name.
+        paste::paste! {
+            #[allow(dead_code)]
+            #[doc = "Get a path to query `" $handle "`."]
+            pub fn [<$handle _path>](&self, $( $param: &$param_ty ),* ) -> String {
+                itertools::join(
+                    [ Some(std::borrow::Cow::from(&self.prefix)), $( $prefix ),* ]
+                    .into_iter()
+                    .filter_map(|x| x), "/")
+            }
+
+            #[allow(dead_code)]
+            #[allow(clippy::too_many_arguments)]
+            #[cfg(any(test, feature = "async-client"))]
+            #[doc = "Request a simple borsh-encoded value from `" $handle "`, \
+                without any additional request data, specified block height or \
+                proof."]
+            pub async fn $handle<CLIENT>(&self, client: &CLIENT,
+                $( $param: &$param_ty ),*
+            )
+                -> std::result::Result<
+                    $return_type,
+                    <CLIENT as $crate::ledger::queries::Client>::Error
+                >
+                where CLIENT: $crate::ledger::queries::Client + std::marker::Sync {
+                    let path = self.[<$handle _path>]( $( $param ),* );
+
+                    let data = client.simple_request(path).await?;
+
+                    let decoded: $return_type =
+                        borsh::BorshDeserialize::try_from_slice(&data[..])?;
+                    Ok(decoded)
+            }
+
+            #[allow(dead_code)]
+            #[allow(clippy::too_many_arguments)]
+            #[cfg(any(test, feature = "async-client"))]
+            #[doc = "Request a value from `" $handle "` at the given block \
+                height (the last committed height if `None`) with the proofs \
+                of the storage reads made by the handler. The proofs are \
+                verified and the handler is re-run against the proven values."]
+            pub async fn [<$handle _with_proof>]<CLIENT>(&self, client: &CLIENT,
+                height: Option<$crate::types::storage::BlockHeight>,
+                $( $param: &$param_ty ),*
+            )
+                -> std::result::Result<
+                    $crate::ledger::queries::proven::Proven<$return_type>,
+                    <CLIENT as $crate::ledger::queries::Client>::Error
+                >
+                where CLIENT: $crate::ledger::queries::Client + std::marker::Sync {
+                    let path = self.[<$handle _path>]( $( $param ),* );
+
+                    client.proven_request(path, height, |storage| {
+                        $handle(storage, $( $param.clone() ),* )
+                    }).await
+            }
+        }
+    };
+
     // terminal rule that $handle that doesn't use request
     (
         ( $( $param:tt: $param_ty:ty ),* )
@@ -619,6 +715,183 @@ macro_rules! pattern_and_handler_to_method {
     };
 }
 
+/// Turn patterns and their handlers into descriptions of the routes, which
+/// are pushed into the `$routes` vector.
+macro_rules! pattern_and_handler_to_schema {
+    // push the description of a route with all its segments matched
+    (
+        @route $self:ident, $routes:ident,
+        [ $( $segment:expr ),* ],
+        $return_type:path, $handle:ident, $kind:ident
+    ) => {
+        $routes.push($crate::ledger::queries::schema::RouteSchema {
+            segments: [
+                $crate::ledger::queries::schema::prefix_segments(&$self.prefix),
+                vec![ $( $segment ),* ],
+            ].concat(),
+            handler: stringify!($handle),
+            kind: $crate::ledger::queries::schema::HandlerKind::$kind,
+            response: $crate::ledger::queries::schema::type_name(
+                stringify!($return_type)
+            ),
+        });
+    };
+
+    // sub-router - its routes are described by its own `schema`, which
+    // includes its prefix
+    (
+        $self:ident, $routes:ident,
+        $segments:tt
+        $( $_return_type:path )?,
+        (sub $router:ident),
+        $pattern:tt
+    ) => {
+        paste::paste! {
+            $routes.extend($self.[<$router:camel:snake>]().schema());
+        }
+    };
+
+    // sub-pattern - describe each handler inside it
+    (
+        $self:ident, $routes:ident,
+        $segments:tt
+        $( $_return_type:path )?,
+        { $( $sub_pattern:tt $( -> $sub_return_ty:path )? = $handle:tt, )* },
+        ()
+    ) => {
+        $(
+            pattern_and_handler_to_schema!(
+                $self, $routes,
+                $segments
+                $( $sub_return_ty )?, $handle, $sub_pattern
+            );
+        )*
+    };
+
+    // terminal rule for $handle that uses request (`with_options`)
+    (
+        $self:ident, $routes:ident,
+        $segments:tt
+        $return_type:path,
+        (with_options $handle:ident),
+        ()
+    ) => {
+        pattern_and_handler_to_schema!(@route $self, $routes, $segments,
+            $return_type, $handle, WithOptions);
+    };
+
+    // terminal rule for $handle whose storage reads can be proven
+    // (`with_proof`)
+    (
+        $self:ident, $routes:ident,
+        $segments:tt
+        $return_type:path,
+        (with_proof $handle:ident),
+        ()
+    ) => {
+        pattern_and_handler_to_schema!(@route $self, $routes, $segments,
+            $return_type, $handle, WithProof);
+    };
+
+    // terminal rule for $handle that doesn't use request
+    (
+        $self:ident, $routes:ident,
+        $segments:tt
+        $return_type:path,
+        $handle:ident,
+        ()
+    ) => {
+        pattern_and_handler_to_schema!(@route $self, $routes, $segments,
+            $return_type, $handle, Plain);
+    };
+
+    // literal string arg
+    (
+        $self:ident, $routes:ident,
+        [ $( $segment:expr ),* ]
+        $( $return_type:path )?,
+        $handle:tt,
+        ( $pattern:literal $( / $tail:tt )* )
+    ) => {
+        pattern_and_handler_to_schema!(
+            $self, $routes,
+            [ $( $segment, )*
+                $crate::ledger::queries::schema::SegmentSchema::Literal(
+                    $pattern.to_owned()
+                )
+            ]
+            $( $return_type )?, $handle, ( $( $tail )/ * )
+        );
+    };
+
+    // untyped arg
+    (
+        $self:ident, $routes:ident,
+        [ $( $segment:expr ),* ]
+        $( $return_type:path )?,
+        $handle:tt,
+        ( [$name:ident] $( / $tail:tt )* )
+    ) => {
+        pattern_and_handler_to_schema!(
+            $self, $routes,
+            [ $( $segment, )*
+                $crate::ledger::queries::schema::SegmentSchema::Arg {
+                    name: stringify!($name),
+                    ty: "str".to_owned(),
+                    optional: false,
+                }
+            ]
+            $( $return_type )?, $handle, ( $( $tail )/ * )
+        );
+    };
+
+    // opt typed arg
+    (
+        $self:ident, $routes:ident,
+        [ $( $segment:expr ),* ]
+        $( $return_type:path )?,
+        $handle:tt,
+        ( [$name:ident: opt $type:ty] $( / $tail:tt )* )
+    ) => {
+        pattern_and_handler_to_schema!(
+            $self, $routes,
+            [ $( $segment, )*
+                $crate::ledger::queries::schema::SegmentSchema::Arg {
+                    name: stringify!($name),
+                    ty: $crate::ledger::queries::schema::type_name(
+                        stringify!($type)
+                    ),
+                    optional: true,
+                }
+            ]
+            $( $return_type )?, $handle, ( $( $tail )/ * )
+        );
+    };
+
+    // typed arg
+    (
+        $self:ident, $routes:ident,
+        [ $( $segment:expr ),* ]
+        $( $return_type:path )?,
+        $handle:tt,
+        ( [$name:ident: $type:ty] $( / $tail:tt )* )
+    ) => {
+        pattern_and_handler_to_schema!(
+            $self, $routes,
+            [ $( $segment, )*
+                $crate::ledger::queries::schema::SegmentSchema::Arg {
+                    name: stringify!($name),
+                    ty: $crate::ledger::queries::schema::type_name(
+                        stringify!($type)
+                    ),
+                    optional: false,
+                }
+            ]
+            $( $return_type )?, $handle, ( $( $tail )/ * )
+        );
+    };
+}
+
 /// TT muncher macro that generates a `struct $name` with methods for all its
 /// handlers.
 macro_rules! router_type {
@@ -739,6 +1012,11 @@ macro_rules! router_type {
 ///   // necessary), which can have some `info` string and a proof.
 ///   ( "pattern_d" ) -> ReturnType = (with_options handler),
 ///
+///   // The handler reads the storage only via `StorageRead`. When a proof is
+///   // requested, the response carries the proofs of all its storage reads
+///   // and the client can re-run the handler against the proven values.
+///   ( "pattern_e" ) -> ReturnType = (with_proof handler),
+///
 ///   ( "another" / "pattern" / "that" / "goes" / "deep" ) -> ReturnType = handler,
 ///
 ///   // Inlined sub-tree
@@ -774,6 +1052,22 @@ macro_rules! router_type {
 ///     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
 ///     H: 'static + StorageHasher + Sync;
 /// ```
+///
+/// A handler defined as `(with_proof $handler)` is generic over the storage,
+/// which is a [`crate::ledger::queries::proven::ProvingStorage`] in the
+/// ledger and a [`crate::ledger::queries::proven::ProvenStorage`] in the
+/// client, and the expected signature is:
+/// ```rust,ignore
+/// fn handler<S>(storage: &S, args ...) -> storage_api::Result<ReturnType>
+/// where
+///     S: StorageRead;
+/// ```
+///
+/// Unlike the other handlers, these can also be queried at past block heights.
+///
+/// The generated router type also has a `schema` method that describes all
+/// its paths with the types of their arguments and responses (see
+/// [`crate::ledger::queries::schema`]).
 #[macro_export]
 macro_rules! router {
     { $name:ident, $( $pattern:tt $( -> $return_type:path )? = $handle:tt , )* } => (
@@ -783,6 +1077,19 @@ macro_rules! router {
 
         router_type!{[<$name:camel>] {}, $( $pattern $( -> $return_type )? = $handle ),* }
 
+        impl [<$name:camel>] {
+            #[doc = "Describe all the paths of this router, including the \
+                paths of its sub-routers"]
+            pub fn schema(&self) -> Vec<$crate::ledger::queries::schema::RouteSchema> {
+                let mut routes = vec![];
+                $(
+                    pattern_and_handler_to_schema!(self, routes,
+                        [] $( $return_type )?, $handle, $pattern);
+                )*
+                routes
+            }
+        }
+
 		impl $crate::ledger::queries::Router for [<$name:camel>] {
             // TODO: for some patterns, there's unused assignment of `$end`
             #[allow(unused_assignments)]
@@ -840,8 +1147,8 @@ mod test_rpc_handlers {
         EncodedResponseQuery, RequestCtx, RequestQuery, ResponseQuery,
     };
     use crate::ledger::storage::{DBIter, StorageHasher, DB};
-    use crate::ledger::storage_api::{self, ResultExt};
-    use crate::types::storage::Epoch;
+    use crate::ledger::storage_api::{self, ResultExt, StorageRead};
+    use crate::types::storage::{self, Epoch};
     use crate::types::token;
 
     /// A little macro to generate boilerplate for RPC handler functions.
@@ -943,6 +1250,27 @@ mod test_rpc_handlers {
             ..ResponseQuery::default()
         })
     }
+
+    /// This handler is hand-written, because the test helper macro doesn't
+    /// support handlers `with_proof`. It adds the given amount to the amounts
+    /// at the keys in [`d_keys`], if present.
+    pub fn d<S>(storage: &S, amount: token::Amount) -> storage_api::Result<u64>
+    where
+        S: StorageRead,
+    {
+        d_keys().iter().try_fold(u64::from(amount), |acc, key| {
+            let value: Option<token::Amount> = storage.read(key)?;
+            Ok(acc + value.map(u64::from).unwrap_or_default())
+        })
+    }
+
+    /// The keys read by the [`d`] handler
+    pub fn d_keys() -> [storage::Key; 2] {
+        [
+            storage::Key::parse("d/present").unwrap(),
+            storage::Key::parse("d/absent").unwrap(),
+        ]
+    }
 }
 
 /// You can expand the `router!` macro invocation with e.g.:
@@ -977,6 +1305,7 @@ mod test_rpc {
             },
         },
         ( "c" ) -> String = (with_options c),
+        ( "d" / [amount: token::Amount] ) -> u64 = (with_proof d),
     }
 
     router! {TEST_SUB_RPC,
@@ -988,10 +1317,15 @@ mod test_rpc {
 
 #[cfg(test)]
 mod test {
+    use borsh::{BorshDeserialize, BorshSerialize};
+
     use super::test_rpc::TEST_RPC;
+    use super::test_rpc_handlers::{d, d_keys};
+    use crate::ledger::queries::proven::{verify_response, ProvenRead};
+    use crate::ledger::queries::schema::{HandlerKind, SegmentSchema};
     use crate::ledger::queries::testing::TestClient;
-    use crate::ledger::queries::{RequestCtx, RequestQuery, Router};
-    use crate::ledger::storage_api;
+    use crate::ledger::queries::{Client, RequestCtx, RequestQuery, Router};
+    use crate::ledger::storage_api::{self, StorageWrite};
     use crate::types::storage::Epoch;
     use crate::types::token;
 
@@ -1074,6 +1408,10 @@ mod test {
         let result = TEST_RPC.c(&client, None, None, false).await.unwrap();
         assert_eq!(result.data, format!("c"));
 
+        let amount = token::Amount::from(1);
+        let result = TEST_RPC.d(&client, &amount).await.unwrap();
+        assert_eq!(result, 1);
+
         let result = TEST_RPC.test_sub_rpc().x(&client).await.unwrap();
         assert_eq!(result, format!("x"));
 
@@ -1087,4 +1425,103 @@ mod test {
 
         Ok(())
     }
+
+    /// Test the description of the paths of `TEST_RPC` router.
+    #[test]
+    fn test_router_schema() {
+        let routes = TEST_RPC.schema();
+        assert_eq!(routes.len(), 15);
+        let route = |handler: &str| {
+            routes
+                .iter()
+                .find(|route| route.handler == handler)
+                .unwrap()
+        };
+
+        // The sub-router's routes include its prefix
+        let y = route("y");
+        assert_eq!(y.path(), "/sub/y/{untyped_arg}");
+        assert_eq!(
+            y.segments[2],
+            SegmentSchema::Arg {
+                name: "untyped_arg",
+                ty: "str".to_owned(),
+                optional: false,
+            }
+        );
+        assert_eq!(y.kind, HandlerKind::Plain);
+        assert_eq!(y.response, "String");
+
+        let b2i = route("b2i");
+        assert_eq!(b2i.path(), "/b/2/i/{balance}");
+        assert_eq!(
+            b2i.segments[3],
+            SegmentSchema::Arg {
+                name: "balance",
+                ty: "Amount".to_owned(),
+                optional: false,
+            }
+        );
+
+        let b3iiii = route("b3iiii");
+        assert_eq!(b3iiii.path(), "/b/3/{a1}/{a2}/iiii/{a3?}/xyz/{a4?}");
+
+        let c = route("c");
+        assert_eq!(c.path(), "/c");
+        assert_eq!(c.kind, HandlerKind::WithOptions);
+
+        let d = route("d");
+        assert_eq!(d.path(), "/d/{amount}");
+        assert_eq!(d.kind, HandlerKind::WithProof);
+        assert!(d.kind.supports_proof());
+        assert_eq!(d.response, "u64");
+    }
+
+    /// Test a request with proofs to a handler `with_proof`.
+    #[tokio::test]
+    async fn test_router_macro_with_proof() -> storage_api::Result<()> {
+        let mut client = TestClient::new(TEST_RPC);
+        let [present_key, absent_key] = d_keys();
+        StorageWrite::write(
+            &mut client.storage,
+            &present_key,
+            token::Amount::from(2),
+        )?;
+
+        let amount = token::Amount::from(1);
+        let result =
+            TEST_RPC.d_with_proof(&client, None, &amount).await.unwrap();
+        assert_eq!(result.data, 3);
+        assert_eq!(result.root, Some(client.storage.merkle_root()));
+        // The absence of a key outside of the IBC sub-tree can't be proven
+        assert_eq!(result.unproven, vec![absent_key]);
+
+        // A response with a tampered value must fail the verification
+        let path = TEST_RPC.d_path(&amount);
+        let mut response = client
+            .request(path.clone(), None, None, true)
+            .await
+            .unwrap();
+        let (data, mut reads): (u64, Vec<ProvenRead>) =
+            BorshDeserialize::try_from_slice(&response.data).unwrap();
+        let read = reads
+            .iter_mut()
+            .find(|read| read.key == present_key)
+            .unwrap();
+        read.value = Some(token::Amount::from(10).try_to_vec().unwrap());
+        response.data = (data, reads).try_to_vec().unwrap();
+        assert!(
+            verify_response(response, |storage| d(storage, amount)).is_err()
+        );
+
+        // A response without proofs must fail the verification
+        let mut response =
+            client.request(path, None, None, true).await.unwrap();
+        response.proof = None;
+        assert!(
+            verify_response(response, |storage| d(storage, amount)).is_err()
+        );
+
+        Ok(())
+    }
 }
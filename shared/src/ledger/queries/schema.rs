@@ -0,0 +1,157 @@
+//! A static description of the paths of a router generated by the `router!`
+//! macro, from which the clients in other languages can generate their
+//! bindings. Every router type has a `schema` method that describes all its
+//! paths, including the paths of its sub-routers.
+//!
+//! The types are described by their names as written in the router, without
+//! the module paths, which for the borsh-encoded responses match the
+//! declarations of their `BorshSchema`, if any. The path arguments are not
+//! borsh-encoded, they are formatted with `Display` and parsed with `FromStr`.
+
+/// A description of a path of a router and its handler
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RouteSchema {
+    /// The segments of the path, starting from the root router
+    pub segments: Vec<SegmentSchema>,
+    /// The name of the handler function
+    pub handler: &'static str,
+    /// The kind of the handler
+    pub kind: HandlerKind,
+    /// The name of the type of the borsh-encoded response data. For a
+    /// `storage_value`, the data is returned verbatim as read from storage.
+    pub response: String,
+}
+
+/// A description of a path segment
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SegmentSchema {
+    /// A segment that must match the given string
+    Literal(String),
+    /// A dynamic argument
+    Arg {
+        /// The name of the argument
+        name: &'static str,
+        /// The name of the type of the argument. An untyped argument is a
+        /// `str`.
+        ty: String,
+        /// An optional argument may be omitted from the path
+        optional: bool,
+    },
+}
+
+/// The kinds of the router's handlers, which determine what a request may
+/// contain and what the response contains
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HandlerKind {
+    /// A handler that only reads the path arguments at the last committed
+    /// block height. The response doesn't have any proof.
+    Plain,
+    /// A handler that receives the whole request, which may have some data
+    /// attached, a block height and ask for a proof. It's up to the handler
+    /// which of these it supports.
+    WithOptions,
+    /// A handler whose storage reads can be proven. When a proof is
+    /// requested, the response data is the borsh-encoded tuple of the
+    /// response and the `Vec<ProvenRead>` of the reads and the response's
+    /// proof contains the proofs of the reads. These can also be requested at
+    /// past block heights.
+    WithProof,
+}
+
+impl HandlerKind {
+    /// Get the name of the kind
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HandlerKind::Plain => "plain",
+            HandlerKind::WithOptions => "with_options",
+            HandlerKind::WithProof => "with_proof",
+        }
+    }
+
+    /// Can a request for this kind of handler ask for a proof?
+    pub fn supports_proof(&self) -> bool {
+        match self {
+            HandlerKind::Plain => false,
+            HandlerKind::WithOptions | HandlerKind::WithProof => true,
+        }
+    }
+}
+
+impl RouteSchema {
+    /// Format the path of the route with the arguments' names in braces and
+    /// the optional arguments suffixed with `?`, e.g.
+    /// `/vp/governance/proposals/{status?}/{offset}/{limit}`
+    pub fn path(&self) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                SegmentSchema::Literal(literal) => format!("/{literal}"),
+                SegmentSchema::Arg {
+                    name,
+                    optional: false,
+                    ..
+                } => format!("/{{{name}}}"),
+                SegmentSchema::Arg {
+                    name,
+                    optional: true,
+                    ..
+                } => format!("/{{{name}?}}"),
+            })
+            .collect()
+    }
+}
+
+/// Get the literal segments of a router's prefix path
+pub fn prefix_segments(prefix: &str) -> Vec<SegmentSchema> {
+    prefix
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| SegmentSchema::Literal(segment.to_owned()))
+        .collect()
+}
+
+/// Normalize the name of a type as stringified by the `router!` macro. The
+/// whitespace and module paths are removed, and the generic arguments are
+/// separated by `", "` like in the declarations of `BorshSchema`, e.g.
+/// `std::collections::BTreeMap < storage::BlockHeight, Vec<Nullifier> >`
+/// becomes `BTreeMap<BlockHeight, Vec<Nullifier>>`.
+pub fn type_name(stringified: &str) -> String {
+    let mut name = String::with_capacity(stringified.len());
+    let mut ident = String::new();
+    for c in stringified.chars().filter(|c| !c.is_whitespace()) {
+        match c {
+            c if c.is_alphanumeric() || c == '_' => ident.push(c),
+            // A module path separator, the preceding ident is a module
+            ':' => ident.clear(),
+            c => {
+                name.push_str(&ident);
+                ident.clear();
+                name.push(c);
+                if c == ',' {
+                    name.push(' ');
+                }
+            }
+        }
+    }
+    name.push_str(&ident);
+    name
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_type_name() {
+        assert_eq!(type_name("u64"), "u64");
+        assert_eq!(type_name("token :: Amount"), "Amount");
+        assert_eq!(
+            type_name(
+                "std::collections::BTreeMap < storage::BlockHeight, \
+                 Vec<Nullifier> >"
+            ),
+            "BTreeMap<BlockHeight, Vec<Nullifier>>"
+        );
+        assert_eq!(type_name("Option<common::PublicKey>"), "Option<PublicKey>");
+    }
+}
@@ -0,0 +1,387 @@
+//! Queries of the shielded pool (MASP) that let a wallet sync with a few large
+//! requests instead of reading the shielded transfers one by one. The
+//! accepted shielded transfers are indexed from `0` in the order in which they
+//! were accepted.
+//!
+//! All the handlers read the storage via `trait StorageRead`, so they can be
+//! requested at a past height and with the proofs of the transfers and of the
+//! conversion state against the app hash. The ledger stores the frontier of
+//! the note commitment tree after each transfer, while the nullifiers are
+//! derived from the proven transfers.
+
+use std::collections::BTreeMap;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use masp_primitives::asset_type::AssetType;
+use masp_primitives::merkle_tree::{CommitmentTree, MerklePath};
+use masp_primitives::sapling::Node;
+
+use crate::ledger::masp::{head_tx_key, read_note_commitment_frontier, tx_key};
+use crate::ledger::storage::ConversionState;
+use crate::ledger::storage_api::{self, StorageRead};
+use crate::types::address::{masp, Address};
+use crate::types::storage::{BlockHeight, Epoch, Key, KeySeg, TxIndex};
+use crate::types::token::{Transfer, CONVERSION_KEY_PREFIX};
+
+/// The maximum number of transfers in a single page of the transfers query
+pub const MAX_TRANSFERS_PER_PAGE: u64 = 100;
+
+/// The maximum number of block heights in a single range of the nullifiers
+/// query
+pub const MAX_NULLIFIERS_HEIGHTS: u64 = 1000;
+
+/// The nullifier revealed by the spend of a shielded note
+pub type Nullifier = [u8; 32];
+
+/// An accepted shielded transfer with its position in the chain
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize)]
+pub struct IndexedTransfer {
+    /// The index of the transfer among all the accepted shielded transfers
+    pub index: u64,
+    /// The epoch in which the transfer was accepted
+    pub epoch: Epoch,
+    /// The height of the block in which the transfer was accepted
+    pub height: BlockHeight,
+    /// The index of the transfer's transaction in its block
+    pub tx_index: TxIndex,
+    /// The transfer
+    pub transfer: Transfer,
+}
+
+/// A page of the accepted shielded transfers
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize)]
+pub struct TransfersPage {
+    /// The number of all the accepted shielded transfers, i.e. the index of
+    /// the next one
+    pub head: u64,
+    /// The transfers in the page in the order in which they were accepted
+    pub transfers: Vec<IndexedTransfer>,
+}
+
+/// The note commitment tree with the notes of a number of the first accepted
+/// shielded transfers
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct NoteCommitmentFrontier {
+    /// The number of the transfers whose notes are in the tree
+    pub transfers: u64,
+    /// The frontier of the tree, from which the following notes can be
+    /// appended and the root of the tree computed
+    pub tree: CommitmentTree<Node>,
+}
+
+/// A conversion from the conversion tree with its Merkle path
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct ConversionUpdate {
+    /// The asset type of the conversion
+    pub asset_type: AssetType,
+    /// The token of the asset type
+    pub token: Address,
+    /// The epoch of the asset type
+    pub epoch: Epoch,
+    /// The conversion of the asset type
+    pub conversion: masp_primitives::transaction::components::Amount,
+    /// The Merkle path of the conversion in the conversion tree
+    pub path: MerklePath<Node>,
+}
+
+/// The conversions from the conversion tree that have been updated since an
+/// epoch
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct ConversionUpdates {
+    /// The root of the conversion tree that the paths of the conversions
+    /// lead to
+    pub root: Node,
+    /// The root of the conversion tree in the previous epoch
+    pub prev_root: Node,
+    /// The updated conversions
+    pub conversions: Vec<ConversionUpdate>,
+}
+
+router! {MASP,
+    ( "transfers" / [from: u64] / [limit: u64] )
+    -> TransfersPage = (with_proof transfers),
+
+    ( "frontier" / [count: u64] )
+    -> NoteCommitmentFrontier = (with_proof note_commitment_frontier),
+
+    ( "nullifiers" / [from: BlockHeight] / [to: BlockHeight] )
+    -> BTreeMap<BlockHeight, Vec<Nullifier>> = (with_proof nullifiers),
+
+    ( "conversions" / [since: opt Epoch] )
+    -> ConversionUpdates = (with_proof conversions),
+}
+
+/// Get a page of the accepted shielded transfers starting from the index
+/// `from`. At most [`MAX_TRANSFERS_PER_PAGE`] transfers are returned
+/// regardless of the `limit`.
+fn transfers<S>(
+    storage: &S,
+    from: u64,
+    limit: u64,
+) -> storage_api::Result<TransfersPage>
+where
+    S: StorageRead,
+{
+    let head = read_head(storage)?;
+    let to = from
+        .saturating_add(limit.min(MAX_TRANSFERS_PER_PAGE))
+        .min(head);
+    let transfers = (from..to)
+        .map(|index| read_transfer(storage, index))
+        .collect::<storage_api::Result<_>>()?;
+    Ok(TransfersPage { head, transfers })
+}
+
+/// Get the note commitment tree with the notes of the first `count` accepted
+/// shielded transfers from its frontier stored by the ledger. A wallet can
+/// start scanning the transfers from the index `count` with this tree and
+/// check the roots it computes against the anchors of the following spends.
+fn note_commitment_frontier<S>(
+    storage: &S,
+    count: u64,
+) -> storage_api::Result<NoteCommitmentFrontier>
+where
+    S: StorageRead,
+{
+    let head = read_head(storage)?;
+    if count > head {
+        return Err(storage_api::Error::new(format!(
+            "Only {head} shielded transfers have been accepted"
+        )));
+    }
+    let tree = read_note_commitment_frontier(storage, count)?;
+    Ok(NoteCommitmentFrontier {
+        transfers: count,
+        tree,
+    })
+}
+
+/// Get the nullifiers revealed by the shielded transfers accepted in the
+/// blocks from the height `from` to the height `to`, inclusive, by the height.
+/// The heights without any nullifiers are omitted. At most
+/// [`MAX_NULLIFIERS_HEIGHTS`] heights are scanned regardless of the height
+/// `to`.
+fn nullifiers<S>(
+    storage: &S,
+    from: BlockHeight,
+    to: BlockHeight,
+) -> storage_api::Result<BTreeMap<BlockHeight, Vec<Nullifier>>>
+where
+    S: StorageRead,
+{
+    let to = to.min(BlockHeight(
+        from.0.saturating_add(MAX_NULLIFIERS_HEIGHTS - 1),
+    ));
+    let head = read_head(storage)?;
+    // The transfers are accepted in the order of the heights, so the first
+    // one at or after the `from` height can be found by bisection
+    let (mut low, mut high) = (0, head);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if read_transfer(storage, mid)?.height < from {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+
+    let mut nullifiers: BTreeMap<BlockHeight, Vec<Nullifier>> = BTreeMap::new();
+    for index in low..head {
+        let IndexedTransfer {
+            height, transfer, ..
+        } = read_transfer(storage, index)?;
+        if height > to {
+            break;
+        }
+        let spends = transfer
+            .shielded
+            .iter()
+            .flat_map(|shielded| &shielded.shielded_spends)
+            .map(|spend| spend.nullifier)
+            .collect::<Vec<_>>();
+        if !spends.is_empty() {
+            nullifiers.entry(height).or_default().extend(spends);
+        }
+    }
+    Ok(nullifiers)
+}
+
+/// Get the conversions of the current conversion tree whose asset types are
+/// from the given epoch or later, or all of them when `None`, with their
+/// Merkle paths. The purely decoding entries that aren't in the tree are
+/// omitted.
+fn conversions<S>(
+    storage: &S,
+    since: Option<Epoch>,
+) -> storage_api::Result<ConversionUpdates>
+where
+    S: StorageRead,
+{
+    let state: ConversionState =
+        storage.read(&conversion_state_key())?.unwrap_or_default();
+    let tree_size = state.tree.size();
+    let conversions = state
+        .assets
+        .iter()
+        .filter(|(_, (_, epoch, _, position))| {
+            *position < tree_size && since.map_or(true, |since| *epoch >= since)
+        })
+        .map(|(asset_type, (token, epoch, conversion, position))| {
+            ConversionUpdate {
+                asset_type: *asset_type,
+                token: token.clone(),
+                epoch: *epoch,
+                conversion: conversion.clone().into(),
+                path: state.tree.path(*position),
+            }
+        })
+        .collect();
+    Ok(ConversionUpdates {
+        root: state.tree.root(),
+        prev_root: state.prev_root,
+        conversions,
+    })
+}
+
+/// Read the number of all the accepted shielded transfers
+fn read_head<S>(storage: &S) -> storage_api::Result<u64>
+where
+    S: StorageRead,
+{
+    Ok(storage.read(&head_tx_key())?.unwrap_or_default())
+}
+
+/// Read the accepted shielded transfer with the given index, which must be
+/// lower than the head
+fn read_transfer<S>(
+    storage: &S,
+    index: u64,
+) -> storage_api::Result<IndexedTransfer>
+where
+    S: StorageRead,
+{
+    let (epoch, height, tx_index, transfer): (
+        Epoch,
+        BlockHeight,
+        TxIndex,
+        Transfer,
+    ) = storage.read(&tx_key(index))?.ok_or_else(|| {
+        storage_api::Error::new(format!(
+            "The shielded transfer {index} is missing"
+        ))
+    })?;
+    Ok(IndexedTransfer {
+        index,
+        epoch,
+        height,
+        tx_index,
+        transfer,
+    })
+}
+
+/// The key of the conversion state
+fn conversion_state_key() -> Key {
+    Key::from(masp().to_db_key())
+        .push(&CONVERSION_KEY_PREFIX.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ledger::masp::{
+        note_commitment_frontier_key, update_note_commitment_frontiers,
+    };
+    use crate::ledger::queries::testing::TestClient;
+    use crate::ledger::queries::RPC;
+    use crate::ledger::storage_api::StorageWrite;
+    use crate::types::address::{self, nam};
+    use crate::types::token;
+
+    #[test]
+    fn test_masp_queries_router_paths() {
+        let path = RPC.shell().masp().transfers_path(&5, &10);
+        assert_eq!("/shell/masp/transfers/5/10", path);
+
+        let path = RPC.shell().masp().conversions_path(&None);
+        assert_eq!("/shell/masp/conversions", path);
+    }
+
+    #[tokio::test]
+    async fn test_masp_queries_router_with_client() -> storage_api::Result<()> {
+        // Initialize the `TestClient`
+        let mut client = TestClient::new(RPC);
+
+        // Write some transparent transfers into the MASP's list of transfers
+        let head = 3_u64;
+        for index in 0..head {
+            let transfer = Transfer {
+                source: address::testing::established_address_1(),
+                target: address::testing::established_address_2(),
+                token: nam(),
+                sub_prefix: None,
+                amount: token::Amount::whole(index + 1),
+                key: None,
+                shielded: None,
+            };
+            StorageWrite::write(
+                &mut client.storage,
+                &tx_key(index),
+                (Epoch(0), BlockHeight(index + 1), TxIndex(0), transfer),
+            )?;
+        }
+        StorageWrite::write(&mut client.storage, &head_tx_key(), head)?;
+        // The ledger stores the frontiers after each of the transfers
+        update_note_commitment_frontiers(&mut client.storage)?;
+        for count in 1..=head {
+            assert!(StorageRead::has_key(
+                &client.storage,
+                &note_commitment_frontier_key(count)
+            )?);
+        }
+
+        // Request the transfers a page at a time
+        let page = RPC.shell().masp().transfers(&client, &0, &2).await.unwrap();
+        assert_eq!(page.head, head);
+        assert_eq!(page.transfers.len(), 2);
+        assert_eq!(page.transfers[1].index, 1);
+        assert_eq!(page.transfers[1].height, BlockHeight(2));
+        let page = RPC.shell().masp().transfers(&client, &2, &2).await.unwrap();
+        assert_eq!(page.transfers.len(), 1);
+        assert_eq!(page.transfers[0].index, 2);
+
+        // Without any shielded parts there are no notes or nullifiers
+        let frontier = RPC
+            .shell()
+            .masp()
+            .note_commitment_frontier(&client, &head)
+            .await
+            .unwrap();
+        assert_eq!(frontier.transfers, head);
+        assert_eq!(frontier.tree.size(), 0);
+        assert!(RPC
+            .shell()
+            .masp()
+            .note_commitment_frontier(&client, &(head + 1))
+            .await
+            .is_err());
+        let nullifiers = RPC
+            .shell()
+            .masp()
+            .nullifiers(&client, &BlockHeight(1), &BlockHeight(3))
+            .await
+            .unwrap();
+        assert!(nullifiers.is_empty());
+
+        // Without a conversion state there are no conversions
+        let conversions = RPC
+            .shell()
+            .masp()
+            .conversions(&client, &None)
+            .await
+            .unwrap();
+        assert!(conversions.conversions.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,823 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use masp_primitives::asset_type::AssetType;
+use masp_primitives::merkle_tree::MerklePath;
+use masp_primitives::sapling::Node;
+use namada_core::types::address::Address;
+use namada_core::types::hash::Hash;
+use namada_core::types::storage::BlockResults;
+
+use crate::ledger::events::log::query::QueryMatcher;
+use crate::ledger::events::{self, Event, EventType};
+use crate::ledger::queries::types::{RequestCtx, RequestQuery};
+use crate::ledger::queries::EncodedResponseQuery;
+use crate::ledger::storage::traits::StorageHasher;
+use crate::ledger::storage::{DBIter, EventIndex, DB};
+use crate::ledger::storage_api::{self, ResultExt, StorageRead};
+use crate::tendermint::merkle::proof::Proof;
+use crate::types::storage::{
+    self, BlockHeight, Epoch, PrefixValue, StorageChange,
+};
+#[cfg(any(test, feature = "async-client"))]
+use crate::types::transaction::TxResult;
+
+// Re-export to show in rustdoc!
+use masp::MASP;
+pub use masp::{
+    ConversionUpdate, ConversionUpdates, IndexedTransfer, Masp,
+    NoteCommitmentFrontier, Nullifier, TransfersPage, MAX_NULLIFIERS_HEIGHTS,
+    MAX_TRANSFERS_PER_PAGE,
+};
+mod masp;
+
+type Conversion = (
+    Address,
+    Epoch,
+    masp_primitives::transaction::components::Amount,
+    MerklePath<Node>,
+);
+
+router! {SHELL,
+    // Epoch of the last committed block
+    ( "epoch" ) -> Epoch = epoch,
+
+    // Epoch of the block at the queried height, proven by its entry in the
+    // Merkle tree when the query is sent with proofs
+    ( "block_epoch" ) -> Epoch = (with_proof block_epoch),
+
+    // Raw storage access - read value
+    ( "value" / [storage_key: storage::Key] )
+        -> Vec<u8> = (with_options storage_value),
+
+    // Dry run a transaction
+    ( "dry_run_tx" ) -> TxResult = (with_options dry_run_tx),
+
+    // Raw storage access - prefix iterator
+    ( "prefix" / [storage_key: storage::Key] )
+        -> Vec<PrefixValue> = (with_options storage_prefix),
+
+    // Raw storage access - is given storage key present?
+    ( "has_key" / [storage_key: storage::Key] )
+        -> bool = (with_options storage_has_key),
+
+    // Raw storage access - changes committed at the given height
+    ( "changes" / [height: BlockHeight] )
+        -> Vec<StorageChange> = storage_changes,
+
+    // Raw storage access - changes committed at the given height with keys
+    // matching the given prefix
+    ( "changes" / [height: BlockHeight] / [storage_key: storage::Key] )
+        -> Vec<StorageChange> = storage_prefix_changes,
+
+    // Block results access - read bit-vec
+    ( "results" ) -> Vec<BlockResults> = read_results,
+
+    // Conversion state access - read conversion
+    ( "conv" / [asset_type: AssetType] ) -> Conversion = read_conversion,
+
+    // Shielded pool sync - transfers, note commitments, nullifiers and
+    // conversions
+    ( "masp" ) = (sub MASP),
+
+    // was the transaction accepted?
+    ( "accepted" / [tx_hash: Hash] ) -> Option<Event> = accepted,
+
+    // was the transaction applied?
+    ( "applied" / [tx_hash: Hash] ) -> Option<Event> = applied,
+
+    // Events in the event log matching the given Tendermint query, from
+    // the most recent ones
+    ( "events" / [query: String] ) -> Vec<Event> = events,
+
+    // Events from the persistent event store emitted at the given height, in
+    // pages of `events::store::PAGE_SIZE` events
+    ( "stored_events" / "height" / [height: BlockHeight] / [page: u64] )
+        -> Vec<Event> = stored_events_by_height,
+
+    // Events from the persistent event store emitted by the given tx
+    ( "stored_events" / "tx" / [tx_hash: Hash] / [page: u64] )
+        -> Vec<Event> = stored_events_by_tx_hash,
+
+    // Events from the persistent event store of the given type
+    ( "stored_events" / "type" / [event_type: String] / [page: u64] )
+        -> Vec<Event> = stored_events_by_type,
+
+}
+
+// Handlers:
+
+#[cfg(all(feature = "wasm-runtime", feature = "ferveo-tpke"))]
+fn dry_run_tx<D, H>(
+    mut ctx: RequestCtx<'_, D, H>,
+    request: &RequestQuery,
+) -> storage_api::Result<EncodedResponseQuery>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    use crate::ledger::gas::BlockGasMeter;
+    use crate::ledger::protocol;
+    use crate::ledger::storage::write_log::WriteLog;
+    use crate::proto::Tx;
+    use crate::types::storage::TxIndex;
+    use crate::types::transaction::{DecryptedTx, TxType};
+
+    let mut gas_meter = BlockGasMeter::default();
+    let mut write_log = WriteLog::default();
+    let tx = Tx::try_from(&request.data[..]).into_storage_result()?;
+    let tx = TxType::Decrypted(DecryptedTx::Decrypted {
+        tx,
+        #[cfg(not(feature = "mainnet"))]
+        has_valid_pow: true,
+    });
+    let data = protocol::apply_tx(
+        tx,
+        request.data.len(),
+        TxIndex(0),
+        &mut gas_meter,
+        &mut write_log,
+        ctx.storage,
+        &mut ctx.vp_wasm_cache,
+        &mut ctx.tx_wasm_cache,
+    )
+    .into_storage_result()?;
+    let data = data.try_to_vec().into_storage_result()?;
+    Ok(EncodedResponseQuery {
+        data,
+        proof: None,
+        info: Default::default(),
+    })
+}
+
+/// Query to read block results from storage
+pub fn read_results<D, H>(
+    ctx: RequestCtx<'_, D, H>,
+) -> storage_api::Result<Vec<BlockResults>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let (iter, _gas) = ctx.storage.iter_results();
+    let mut results =
+        vec![BlockResults::default(); ctx.storage.block.height.0 as usize + 1];
+    iter.for_each(|(key, value, _gas)| {
+        let key = key
+            .parse::<usize>()
+            .expect("expected integer for block height");
+        let value = BlockResults::try_from_slice(&value)
+            .expect("expected BlockResults bytes");
+        results[key] = value;
+    });
+    Ok(results)
+}
+
+/// Query to read a conversion from storage
+fn read_conversion<D, H>(
+    ctx: RequestCtx<'_, D, H>,
+    asset_type: AssetType,
+) -> storage_api::Result<Conversion>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    // Conversion values are constructed on request
+    if let Some((addr, epoch, conv, pos)) =
+        ctx.storage.conversion_state.assets.get(&asset_type)
+    {
+        Ok((
+            addr.clone(),
+            *epoch,
+            Into::<masp_primitives::transaction::components::Amount>::into(
+                conv.clone(),
+            ),
+            ctx.storage.conversion_state.tree.path(*pos),
+        ))
+    } else {
+        Err(storage_api::Error::new(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("No conversion found for asset type: {}", asset_type),
+        )))
+    }
+}
+
+#[cfg(not(all(feature = "wasm-runtime", feature = "ferveo-tpke")))]
+fn dry_run_tx<D, H>(
+    _ctx: RequestCtx<'_, D, H>,
+    _request: &RequestQuery,
+) -> storage_api::Result<EncodedResponseQuery>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    unimplemented!("Dry running tx requires \"wasm-runtime\" feature.")
+}
+
+fn epoch<D, H>(ctx: RequestCtx<'_, D, H>) -> storage_api::Result<Epoch>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let data = ctx.storage.last_epoch;
+    Ok(data)
+}
+
+fn block_epoch<S>(storage: &S) -> storage_api::Result<Epoch>
+where
+    S: StorageRead,
+{
+    storage.get_block_epoch()
+}
+
+/// Returns data with `vec![]` when the storage key is not found. For all
+/// borsh-encoded types, it is safe to check `data.is_empty()` to see if the
+/// value was found, except for unit - see `fn query_storage_value` in
+/// `apps/src/lib/client/rpc.rs` for unit type handling via `storage_has_key`.
+fn storage_value<D, H>(
+    ctx: RequestCtx<'_, D, H>,
+    request: &RequestQuery,
+    storage_key: storage::Key,
+) -> storage_api::Result<EncodedResponseQuery>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    check_past_height_limit(&ctx, request)?;
+
+    match ctx
+        .storage
+        .read_with_height(&storage_key, request.height)
+        .into_storage_result()?
+    {
+        (Some(value), _gas) => {
+            let proof = if request.prove {
+                let proof = ctx
+                    .storage
+                    .get_existence_proof(&storage_key, &value, request.height)
+                    .into_storage_result()?;
+                Some(proof)
+            } else {
+                None
+            };
+            Ok(EncodedResponseQuery {
+                data: value,
+                proof,
+                info: Default::default(),
+            })
+        }
+        (None, _gas) => {
+            let proof = if request.prove {
+                let proof = ctx
+                    .storage
+                    .get_non_existence_proof(&storage_key, request.height)
+                    .into_storage_result()?;
+                Some(proof)
+            } else {
+                None
+            };
+            Ok(EncodedResponseQuery {
+                data: vec![],
+                proof,
+                info: format!("No value found for key: {}", storage_key),
+            })
+        }
+    }
+}
+
+fn storage_prefix<D, H>(
+    ctx: RequestCtx<'_, D, H>,
+    request: &RequestQuery,
+    storage_key: storage::Key,
+) -> storage_api::Result<EncodedResponseQuery>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    check_past_height_limit(&ctx, request)?;
+
+    let (pairs, _gas) = ctx
+        .storage
+        .read_prefix_with_height(&storage_key, request.height)
+        .into_storage_result()?;
+    let data: Vec<PrefixValue> = pairs
+        .into_iter()
+        .map(|(key, value)| PrefixValue { key, value })
+        .collect();
+    let proof = if request.prove {
+        let proofs = ctx
+            .storage
+            .get_existence_proofs(
+                data.iter()
+                    .map(|PrefixValue { key, value }| (key, value.as_slice())),
+                request.height,
+            )
+            .into_storage_result()?;
+        let ops = proofs.into_iter().flat_map(|proof| proof.ops).collect();
+        // ops is not empty in this case
+        let proof = Proof { ops };
+        Some(proof)
+    } else {
+        None
+    };
+    let data = data.try_to_vec().into_storage_result()?;
+    Ok(EncodedResponseQuery {
+        data,
+        proof,
+        ..Default::default()
+    })
+}
+
+fn storage_has_key<D, H>(
+    ctx: RequestCtx<'_, D, H>,
+    request: &RequestQuery,
+    storage_key: storage::Key,
+) -> storage_api::Result<EncodedResponseQuery>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    check_past_height_limit(&ctx, request)?;
+
+    let (value, _gas) = ctx
+        .storage
+        .read_with_height(&storage_key, request.height)
+        .into_storage_result()?;
+    let proof = if request.prove {
+        let proof = match &value {
+            Some(value) => ctx.storage.get_existence_proof(
+                &storage_key,
+                value,
+                request.height,
+            ),
+            None => ctx
+                .storage
+                .get_non_existence_proof(&storage_key, request.height),
+        }
+        .into_storage_result()?;
+        Some(proof)
+    } else {
+        None
+    };
+    let data = value.is_some().try_to_vec().into_storage_result()?;
+    Ok(EncodedResponseQuery {
+        data,
+        proof,
+        ..Default::default()
+    })
+}
+
+/// Check that the requested height is not further in the past than allowed
+/// by the `storage_read_past_height_limit`, if any.
+pub(super) fn check_past_height_limit<D, H>(
+    ctx: &RequestCtx<'_, D, H>,
+    request: &RequestQuery,
+) -> storage_api::Result<()>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    if let Some(past_height_limit) = ctx.storage_read_past_height_limit {
+        if request.height.0 + past_height_limit < ctx.storage.last_height.0 {
+            return Err(storage_api::Error::new(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "Cannot query more than {past_height_limit} blocks in the \
+                     past (configured via \
+                     `shell.storage_read_past_height_limit`)."
+                ),
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn storage_changes<D, H>(
+    ctx: RequestCtx<'_, D, H>,
+    height: BlockHeight,
+) -> storage_api::Result<Vec<StorageChange>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    read_changes(ctx, height, None)
+}
+
+fn storage_prefix_changes<D, H>(
+    ctx: RequestCtx<'_, D, H>,
+    height: BlockHeight,
+    storage_key: storage::Key,
+) -> storage_api::Result<Vec<StorageChange>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    read_changes(ctx, height, Some(&storage_key))
+}
+
+fn read_changes<D, H>(
+    ctx: RequestCtx<'_, D, H>,
+    height: BlockHeight,
+    prefix: Option<&storage::Key>,
+) -> storage_api::Result<Vec<StorageChange>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    if height > ctx.storage.last_height {
+        return Err(storage_api::Error::new(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "The block height {height} has not been committed yet, the \
+                 last committed height is {}.",
+                ctx.storage.last_height
+            ),
+        )));
+    }
+    let (changes, _gas) = ctx
+        .storage
+        .read_changes(height, prefix)
+        .into_storage_result()?;
+    Ok(changes)
+}
+
+fn accepted<D, H>(
+    ctx: RequestCtx<'_, D, H>,
+    tx_hash: Hash,
+) -> storage_api::Result<Option<Event>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let matcher = QueryMatcher::accepted(tx_hash.clone());
+    match ctx.event_log.iter_with_matcher(matcher).by_ref().next() {
+        Some(event) => Ok(Some(event.clone())),
+        None => read_stored_tx_event(&ctx, tx_hash, EventType::Accepted),
+    }
+}
+
+fn applied<D, H>(
+    ctx: RequestCtx<'_, D, H>,
+    tx_hash: Hash,
+) -> storage_api::Result<Option<Event>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let matcher = QueryMatcher::applied(tx_hash.clone());
+    match ctx.event_log.iter_with_matcher(matcher).by_ref().next() {
+        Some(event) => Ok(Some(event.clone())),
+        None => read_stored_tx_event(&ctx, tx_hash, EventType::Applied),
+    }
+}
+
+/// Find the most recent event of the given type emitted by the given tx in
+/// the persistent event store. This is used for the events that are no
+/// longer in the in-memory event log, e.g. after a restart of the node.
+fn read_stored_tx_event<D, H>(
+    ctx: &RequestCtx<'_, D, H>,
+    tx_hash: Hash,
+    event_type: EventType,
+) -> storage_api::Result<Option<Event>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let events =
+        events::store::read_tx_events(&ctx.storage.db, tx_hash.to_string())
+            .into_storage_result()?;
+    Ok(events
+        .into_iter()
+        .rev()
+        .find(|event| event.event_type == event_type))
+}
+
+fn events<D, H>(
+    ctx: RequestCtx<'_, D, H>,
+    query: String,
+) -> storage_api::Result<Vec<Event>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let matcher: QueryMatcher = query.parse().into_storage_result()?;
+    Ok(ctx.event_log.iter_with_matcher(matcher).cloned().collect())
+}
+
+fn stored_events_by_height<D, H>(
+    ctx: RequestCtx<'_, D, H>,
+    height: BlockHeight,
+    page: u64,
+) -> storage_api::Result<Vec<Event>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    events::store::read_events(
+        &ctx.storage.db,
+        &EventIndex::Height(height),
+        page,
+    )
+    .into_storage_result()
+}
+
+fn stored_events_by_tx_hash<D, H>(
+    ctx: RequestCtx<'_, D, H>,
+    tx_hash: Hash,
+    page: u64,
+) -> storage_api::Result<Vec<Event>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    events::store::read_events(
+        &ctx.storage.db,
+        &EventIndex::TxHash(tx_hash.to_string()),
+        page,
+    )
+    .into_storage_result()
+}
+
+fn stored_events_by_type<D, H>(
+    ctx: RequestCtx<'_, D, H>,
+    event_type: String,
+    page: u64,
+) -> storage_api::Result<Vec<Event>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    events::store::read_events(
+        &ctx.storage.db,
+        &EventIndex::EventType(event_type),
+        page,
+    )
+    .into_storage_result()
+}
+
+#[cfg(test)]
+mod test {
+    use borsh::BorshDeserialize;
+
+    use crate::ledger::events::{self, Event, EventLevel, EventType};
+    use crate::ledger::queries::testing::TestClient;
+    use crate::ledger::queries::RPC;
+    use crate::ledger::storage::{current_epoch_key, types};
+    use crate::ledger::storage_api::{self, StorageWrite};
+    use crate::proto::Tx;
+    use crate::types::address::{Address, InternalAddress};
+    use crate::types::hash::Hash;
+    use crate::types::storage::{BlockHeight, Key, KeySeg};
+    use crate::types::{address, token};
+
+    const TX_NO_OP_WASM: &str = "../wasm_for_tests/tx_no_op.wasm";
+
+    #[test]
+    fn test_shell_queries_router_paths() {
+        let path = RPC.shell().epoch_path();
+        assert_eq!("/shell/epoch", path);
+
+        let token_addr = address::testing::established_address_1();
+        let owner = address::testing::established_address_2();
+        let key = token::balance_key(&token_addr, &owner);
+        let path = RPC.shell().storage_value_path(&key);
+        assert_eq!(format!("/shell/value/{}", key), path);
+
+        let path = RPC.shell().dry_run_tx_path();
+        assert_eq!("/shell/dry_run_tx", path);
+
+        let path = RPC.shell().storage_prefix_path(&key);
+        assert_eq!(format!("/shell/prefix/{}", key), path);
+
+        let path = RPC.shell().storage_has_key_path(&key);
+        assert_eq!(format!("/shell/has_key/{}", key), path);
+
+        let height = BlockHeight(10);
+        let path = RPC.shell().storage_changes_path(&height);
+        assert_eq!(format!("/shell/changes/{}", height), path);
+
+        let path = RPC.shell().storage_prefix_changes_path(&height, &key);
+        assert_eq!(format!("/shell/changes/{}/{}", height, key), path);
+
+        let query = "applied.height>=10 AND applied.code='0'".to_string();
+        let path = RPC.shell().events_path(&query);
+        assert_eq!(format!("/shell/events/{}", query), path);
+
+        let path = RPC.shell().stored_events_by_height_path(&height, &2);
+        assert_eq!(format!("/shell/stored_events/height/{}/2", height), path);
+    }
+
+    #[tokio::test]
+    async fn test_shell_queries_router_with_client() -> storage_api::Result<()>
+    {
+        // Initialize the `TestClient`
+        let mut client = TestClient::new(RPC);
+
+        // Request last committed epoch
+        let read_epoch = RPC.shell().epoch(&client).await.unwrap();
+        let current_epoch = client.storage.last_epoch;
+        assert_eq!(current_epoch, read_epoch);
+
+        // Request the epoch of the last committed block with a proof of its
+        // entry in the Merkle tree
+        client
+            .storage
+            .block
+            .tree
+            .update(&current_epoch_key(), types::encode(&current_epoch))
+            .unwrap();
+        let proven_epoch = RPC
+            .shell()
+            .block_epoch_with_proof(&client, None)
+            .await
+            .unwrap();
+        assert_eq!(proven_epoch.data, current_epoch);
+        assert_eq!(proven_epoch.root, Some(client.storage.merkle_root()));
+        assert!(proven_epoch.unproven.is_empty());
+
+        // Request dry run tx
+        let tx_no_op = std::fs::read(TX_NO_OP_WASM).expect("cannot load wasm");
+        let tx = Tx::new(tx_no_op, None);
+        let tx_bytes = tx.to_bytes();
+        let result = RPC
+            .shell()
+            .dry_run_tx(&client, Some(tx_bytes), None, false)
+            .await
+            .unwrap();
+        assert!(result.data.is_accepted());
+
+        // Request storage value for a balance key ...
+        let token_addr = address::testing::established_address_1();
+        let owner = address::testing::established_address_2();
+        let balance_key = token::balance_key(&token_addr, &owner);
+        // ... there should be no value yet.
+        let read_balance = RPC
+            .shell()
+            .storage_value(&client, None, None, false, &balance_key)
+            .await
+            .unwrap();
+        assert!(read_balance.data.is_empty());
+
+        // Request storage prefix iterator
+        let balance_prefix = token::balance_prefix(&token_addr);
+        let read_balances = RPC
+            .shell()
+            .storage_prefix(&client, None, None, false, &balance_prefix)
+            .await
+            .unwrap();
+        assert!(read_balances.data.is_empty());
+
+        // Request storage has key
+        let has_balance_key = RPC
+            .shell()
+            .storage_has_key(&client, None, None, false, &balance_key)
+            .await
+            .unwrap();
+        assert!(!has_balance_key.data);
+
+        // Then write some balance ...
+        let balance = token::Amount::from(1000);
+        StorageWrite::write(&mut client.storage, &balance_key, balance)?;
+        // ... there should be the same value now
+        let read_balance = RPC
+            .shell()
+            .storage_value(&client, None, None, false, &balance_key)
+            .await
+            .unwrap();
+        assert_eq!(
+            balance,
+            token::Amount::try_from_slice(&read_balance.data).unwrap()
+        );
+
+        // Request storage prefix iterator
+        let balance_prefix = token::balance_prefix(&token_addr);
+        let read_balances = RPC
+            .shell()
+            .storage_prefix(&client, None, None, false, &balance_prefix)
+            .await
+            .unwrap();
+        assert_eq!(read_balances.data.len(), 1);
+
+        // Request storage has key
+        let has_balance_key = RPC
+            .shell()
+            .storage_has_key(&client, None, None, false, &balance_key)
+            .await
+            .unwrap();
+        assert!(has_balance_key.data);
+
+        // Request the changes at the last committed height. The mock DB
+        // doesn't write any diffs, so we only check that the query succeeds.
+        let last_height = client.storage.last_height;
+        RPC.shell()
+            .storage_changes(&client, &last_height)
+            .await
+            .unwrap();
+        RPC.shell()
+            .storage_prefix_changes(&client, &last_height, &balance_prefix)
+            .await
+            .unwrap();
+
+        // Request the storage queries with proofs
+        let read_balance = RPC
+            .shell()
+            .storage_value(&client, None, None, true, &balance_key)
+            .await
+            .unwrap();
+        assert!(read_balance.proof.is_some());
+        let read_balances = RPC
+            .shell()
+            .storage_prefix(&client, None, None, true, &balance_prefix)
+            .await
+            .unwrap();
+        assert_eq!(read_balances.data.len(), 1);
+        assert!(read_balances.proof.is_some());
+        let has_balance_key = RPC
+            .shell()
+            .storage_has_key(&client, None, None, true, &balance_key)
+            .await
+            .unwrap();
+        assert!(has_balance_key.data);
+        assert!(has_balance_key.proof.is_some());
+
+        // Request a non-existence proof for a key that's not present. These
+        // proofs are only supported for the keys in the IBC sub-tree.
+        let ibc_key =
+            Key::from(Address::Internal(InternalAddress::Ibc).to_db_key())
+                .push(&"absent".to_owned())
+                .unwrap();
+        let has_ibc_key = RPC
+            .shell()
+            .storage_has_key(&client, None, None, true, &ibc_key)
+            .await
+            .unwrap();
+        assert!(!has_ibc_key.data);
+        assert!(has_ibc_key.proof.is_some());
+
+        // Log some events and query them
+        let applied_event = |height: u64| Event {
+            event_type: EventType::Applied,
+            level: EventLevel::Tx,
+            attributes: [
+                ("height".to_string(), height.to_string()),
+                ("code".to_string(), "0".to_string()),
+            ]
+            .into_iter()
+            .collect(),
+        };
+        client.event_log.log_events((1..=3).map(applied_event));
+        let query = "tm.event='NewBlock' AND applied.height>=2".to_string();
+        let events = RPC.shell().events(&client, &query).await.unwrap();
+        // The most recent events come first
+        assert_eq!(events, vec![applied_event(3), applied_event(2)]);
+        let query = "accepted.height EXISTS".to_string();
+        let events = RPC.shell().events(&client, &query).await.unwrap();
+        assert!(events.is_empty());
+
+        // Write some events into the persistent event store and query them
+        let tx_hash = Hash::sha256(b"tx");
+        let accepted_event = Event {
+            event_type: EventType::Accepted,
+            level: EventLevel::Tx,
+            attributes: [("hash".to_string(), tx_hash.to_string())]
+                .into_iter()
+                .collect(),
+        };
+        let height = BlockHeight(1);
+        events::store::write_events(
+            &mut client.storage.db,
+            height,
+            &[accepted_event.clone(), applied_event(1)],
+        )
+        .unwrap();
+        let events = RPC
+            .shell()
+            .stored_events_by_height(&client, &height, &0)
+            .await
+            .unwrap();
+        assert_eq!(events, vec![accepted_event.clone(), applied_event(1)]);
+        let events = RPC
+            .shell()
+            .stored_events_by_tx_hash(&client, &tx_hash, &0)
+            .await
+            .unwrap();
+        assert_eq!(events, vec![accepted_event.clone()]);
+        let events = RPC
+            .shell()
+            .stored_events_by_type(&client, &"applied".to_string(), &0)
+            .await
+            .unwrap();
+        assert_eq!(events, vec![applied_event(1)]);
+        let events = RPC
+            .shell()
+            .stored_events_by_height(&client, &height, &1)
+            .await
+            .unwrap();
+        assert!(events.is_empty());
+
+        // The tx is not in the event log, so it's found in the event store
+        let event = RPC.shell().accepted(&client, &tx_hash).await.unwrap();
+        assert_eq!(event, Some(accepted_event));
+        let event = RPC.shell().applied(&client, &tx_hash).await.unwrap();
+        assert_eq!(event, None);
+
+        Ok(())
+    }
+}
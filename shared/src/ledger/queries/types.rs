@@ -1,7 +1,11 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[cfg(any(test, feature = "async-client"))]
+use super::proven::{self, Proven, ProvenStorage};
 use crate::ledger::events::log::EventLog;
 use crate::ledger::storage::{DBIter, Storage, StorageHasher, DB};
 use crate::ledger::storage_api;
-use crate::tendermint::merkle::proof::Proof;
+use crate::tendermint::merkle::proof::{Proof, ProofOp};
 use crate::types::storage::BlockHeight;
 #[cfg(feature = "wasm-runtime")]
 use crate::vm::wasm::{TxCache, VpCache};
@@ -10,7 +14,7 @@ use crate::vm::WasmCacheRoAccess;
 
 /// A request context provides read-only access to storage and WASM compilation
 /// caches to request handlers.
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct RequestCtx<'shell, D, H>
 where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
@@ -32,6 +36,25 @@ where
     pub storage_read_past_height_limit: Option<u64>,
 }
 
+// Derived `Clone` would require the storage types to be `Clone` too
+impl<'shell, D, H> Clone for RequestCtx<'shell, D, H>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    fn clone(&self) -> Self {
+        Self {
+            storage: self.storage,
+            event_log: self.event_log,
+            #[cfg(feature = "wasm-runtime")]
+            vp_wasm_cache: self.vp_wasm_cache.clone(),
+            #[cfg(feature = "wasm-runtime")]
+            tx_wasm_cache: self.tx_wasm_cache.clone(),
+            storage_read_past_height_limit: self.storage_read_past_height_limit,
+        }
+    }
+}
+
 /// A `Router` handles parsing read-only query requests and dispatching them to
 /// their handler functions. A valid query returns a borsh-encoded result.
 pub trait Router {
@@ -94,6 +117,52 @@ pub trait Client {
         height: Option<BlockHeight>,
         prove: bool,
     ) -> Result<EncodedResponseQuery, Self::Error>;
+
+    /// Send a batch of query requests, which are all handled at the given
+    /// block height, or the last committed height if `None`. The responses are
+    /// in the order of the requests and each request can fail on its own.
+    async fn batch_request(
+        &self,
+        requests: &[BatchRequest],
+        height: Option<BlockHeight>,
+    ) -> Result<Vec<Result<EncodedResponseQuery, Self::Error>>, Self::Error>
+    {
+        let data = requests.try_to_vec()?;
+        let response = self
+            .request(super::RPC.batch_path(), Some(data), height, false)
+            .await?;
+        let responses: Vec<BatchResponse> =
+            BorshDeserialize::try_from_slice(&response.data)?;
+        Ok(responses
+            .into_iter()
+            .map(|response| {
+                response.into_result().map_err(|err| {
+                    std::io::Error::new(std::io::ErrorKind::Other, err).into()
+                })
+            })
+            .collect())
+    }
+
+    /// Send a query request with proofs at the given path and block height.
+    /// The proofs of the storage reads made by the query's handler are
+    /// verified and then the given handler is re-run against the proven
+    /// values. The root of the proofs still has to be checked against a
+    /// trusted app hash.
+    async fn proven_request<T, F>(
+        &self,
+        path: String,
+        height: Option<BlockHeight>,
+        handler: F,
+    ) -> Result<Proven<T>, Self::Error>
+    where
+        T: BorshSerialize + BorshDeserialize,
+        F: FnOnce(&ProvenStorage) -> storage_api::Result<T>,
+    {
+        let response = self.request(path, None, height, true).await?;
+        proven::verify_response(response, handler).map_err(|err| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, err).into()
+        })
+    }
 }
 
 /// Temporary domain-type for `tendermint_proto::abci::RequestQuery`, copied
@@ -139,6 +208,124 @@ pub struct ResponseQuery<T> {
 /// [`ResponseQuery`] with borsh-encoded `data` field
 pub type EncodedResponseQuery = ResponseQuery<Vec<u8>>;
 
+/// A query request in a batch of requests, which are all handled at the height
+/// of the batch request
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct BatchRequest {
+    /// Path of the request
+    pub path: String,
+    /// Raw request data
+    pub data: Vec<u8>,
+    /// Whether to return a Merkle proof with the response, if possible
+    pub prove: bool,
+}
+
+impl BatchRequest {
+    /// A request at the given path without any request data or proof
+    pub fn new(path: String) -> Self {
+        Self {
+            path,
+            data: vec![],
+            prove: false,
+        }
+    }
+}
+
+/// The response to a query request in a batch of requests. Like the ABCI
+/// query responses, a failed request has a non-zero `code` and the error in
+/// the `info`.
+#[derive(Clone, Debug, Default, BorshSerialize, BorshDeserialize)]
+pub struct BatchResponse {
+    /// Response code, `0` on success
+    pub code: u32,
+    /// Borsh-encoded response data
+    pub data: Vec<u8>,
+    /// Non-deterministic log of the request execution
+    pub info: String,
+    /// The operations of the proof, if it has been requested
+    pub proof: Option<Vec<BatchProofOp>>,
+}
+
+/// An operation of a proof in a [`BatchResponse`], the same as
+/// [`crate::tendermint::merkle::proof::ProofOp`]
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub struct BatchProofOp {
+    /// Type of the proof operation
+    pub field_type: String,
+    /// Key of the proven value
+    pub key: Vec<u8>,
+    /// Encoded proof
+    pub data: Vec<u8>,
+}
+
+impl BatchResponse {
+    /// The response to a failed request
+    pub fn error(error: impl ToString) -> Self {
+        Self {
+            code: 1,
+            info: error.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Convert the response back into an [`EncodedResponseQuery`] if the
+    /// request succeeded, or return the error otherwise
+    pub fn into_result(self) -> Result<EncodedResponseQuery, String> {
+        if self.code != 0 {
+            return Err(self.info);
+        }
+        let proof = self.proof.map(|ops| Proof {
+            ops: ops
+                .into_iter()
+                .map(
+                    |BatchProofOp {
+                         field_type,
+                         key,
+                         data,
+                     }| ProofOp {
+                        field_type,
+                        key,
+                        data,
+                    },
+                )
+                .collect(),
+        });
+        Ok(EncodedResponseQuery {
+            data: self.data,
+            info: self.info,
+            proof,
+        })
+    }
+}
+
+impl From<EncodedResponseQuery> for BatchResponse {
+    fn from(response: EncodedResponseQuery) -> Self {
+        let proof = response.proof.map(|proof| {
+            proof
+                .ops
+                .into_iter()
+                .map(
+                    |ProofOp {
+                         field_type,
+                         key,
+                         data,
+                     }| BatchProofOp {
+                        field_type,
+                        key,
+                        data,
+                    },
+                )
+                .collect()
+        });
+        Self {
+            code: 0,
+            data: response.data,
+            info: response.info,
+            proof,
+        }
+    }
+}
+
 impl RequestQuery {
     /// Try to convert tendermint RequestQuery into our [`RequestQuery`]
     /// domain type. This tries to convert the block height into our
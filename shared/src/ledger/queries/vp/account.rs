@@ -0,0 +1,138 @@
+use crate::ledger::storage_api::{self, StorageRead};
+use crate::types::address::Address;
+use crate::types::hash::Hash;
+use crate::types::key::{self, common};
+use crate::types::storage::Key;
+
+// Account validity predicate queries
+router! {ACCOUNT,
+    ( "exists" / [addr: Address] )
+    -> bool = (with_proof account_exists),
+
+    ( "vp_hash" / [addr: Address] )
+    -> Option<Hash> = (with_proof vp_code_hash),
+
+    ( "public_key" / [addr: Address] )
+    -> Option<common::PublicKey> = (with_proof public_key),
+}
+
+// Handlers that implement the functions via `trait StorageRead`. The ones
+// that are generic over the storage can also be requested with proofs.
+
+/// Find if the account of the given address exists. An established account
+/// exists once it has a validity predicate, while the implicit and internal
+/// accounts always exist.
+fn account_exists<S>(storage: &S, addr: Address) -> storage_api::Result<bool>
+where
+    S: StorageRead,
+{
+    match addr {
+        Address::Established(_) => {
+            storage.has_key(&Key::validity_predicate(&addr))
+        }
+        Address::Implicit(_) | Address::Internal(_) => Ok(true),
+    }
+}
+
+/// Get the hash of the validity predicate code of the given account, if it
+/// has one
+fn vp_code_hash<S>(
+    storage: &S,
+    addr: Address,
+) -> storage_api::Result<Option<Hash>>
+where
+    S: StorageRead,
+{
+    let vp_code = storage.read_bytes(&Key::validity_predicate(&addr))?;
+    Ok(vp_code.map(Hash::sha256))
+}
+
+/// Get the public key revealed for the given account, if any
+fn public_key<S>(
+    storage: &S,
+    addr: Address,
+) -> storage_api::Result<Option<common::PublicKey>>
+where
+    S: StorageRead,
+{
+    storage.read(&key::pk_key(&addr))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ledger::queries::testing::TestClient;
+    use crate::ledger::queries::RPC;
+    use crate::ledger::storage_api::{self, StorageWrite};
+    use crate::types::address;
+    use crate::types::hash::Hash;
+    use crate::types::key::{self, common, RefTo};
+    use crate::types::storage::Key;
+
+    #[tokio::test]
+    async fn test_account_queries_router_with_client() -> storage_api::Result<()>
+    {
+        // Initialize the `TestClient`
+        let mut client = TestClient::new(RPC);
+        let addr = address::testing::established_address_1();
+
+        // The established account doesn't exist until it has a VP
+        let exists = RPC
+            .vp()
+            .account()
+            .account_exists(&client, &addr)
+            .await
+            .unwrap();
+        assert!(!exists);
+        let vp_hash = RPC
+            .vp()
+            .account()
+            .vp_code_hash(&client, &addr)
+            .await
+            .unwrap();
+        assert!(vp_hash.is_none());
+
+        // Write a VP and a public key for the account
+        let vp_code = b"vp code".to_vec();
+        StorageWrite::write_bytes(
+            &mut client.storage,
+            &Key::validity_predicate(&addr),
+            &vp_code,
+        )?;
+        let public_key: common::PublicKey = key::testing::keypair_1().ref_to();
+        StorageWrite::write(
+            &mut client.storage,
+            &key::pk_key(&addr),
+            public_key.clone(),
+        )?;
+
+        let exists = RPC
+            .vp()
+            .account()
+            .account_exists(&client, &addr)
+            .await
+            .unwrap();
+        assert!(exists);
+        let vp_hash = RPC
+            .vp()
+            .account()
+            .vp_code_hash(&client, &addr)
+            .await
+            .unwrap();
+        assert_eq!(vp_hash, Some(Hash::sha256(&vp_code)));
+        let read_public_key =
+            RPC.vp().account().public_key(&client, &addr).await.unwrap();
+        assert_eq!(read_public_key, Some(public_key));
+
+        // Implicit accounts always exist
+        let implicit = address::testing::gen_implicit_address();
+        let exists = RPC
+            .vp()
+            .account()
+            .account_exists(&client, &implicit)
+            .await
+            .unwrap();
+        assert!(exists);
+
+        Ok(())
+    }
+}
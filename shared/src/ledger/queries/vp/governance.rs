@@ -0,0 +1,695 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::ledger::governance::parameters::GovParams;
+use crate::ledger::governance::storage as gov_storage;
+use crate::ledger::native_vp::governance::utils;
+use crate::ledger::queries::types::RequestCtx;
+use crate::ledger::slash_fund;
+use crate::ledger::storage::{DBIter, StorageHasher, DB};
+use crate::ledger::storage_api::{self, ResultExt, StorageRead};
+use crate::types::governance::{
+    decode_content, ProposalInfo, ProposalResult, ProposalStatus, ProposalVote,
+    ProposalVotes, TreasuryRecord, TreasuryVesting, VoteRecord,
+};
+use crate::types::storage::{Epoch, Key};
+use crate::types::token;
+
+/// The maximum number of proposals in a single page of the proposals query
+pub const MAX_PROPOSALS_PER_PAGE: u64 = 100;
+
+/// The treasury of the governance, i.e. the slash fund
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct TreasuryInfo {
+    /// The balance of the native token in the treasury
+    pub balance: token::Amount,
+    /// The vested treasury spends that are still to be paid
+    pub vesting: Vec<TreasuryVesting>,
+    /// The changes of the treasury made by the governance from the oldest
+    pub history: Vec<TreasuryRecord>,
+}
+
+// Governance validity predicate queries
+router! {GOVERNANCE,
+    ( "proposal" / [id: u64] )
+    -> Option<ProposalInfo> = (with_proof proposal),
+
+    ( "proposals" / [status: opt ProposalStatus] / [offset: u64] / [limit: u64] )
+    -> Vec<ProposalInfo> = (with_proof proposals),
+
+    ( "votes" / [id: u64] )
+    -> Option<ProposalVotes> = proposal_votes,
+
+    ( "vote_history" / [id: u64] )
+    -> Vec<VoteRecord> = (with_proof vote_history),
+
+    ( "tally" / [id: u64] )
+    -> Option<ProposalResult> = proposal_tally,
+
+    ( "parameters" )
+    -> GovParams = (with_proof parameters),
+
+    ( "treasury" )
+    -> TreasuryInfo = (with_proof treasury),
+}
+
+// Handlers that implement the functions via `trait StorageRead`. The ones
+// that are generic over the storage can also be requested with proofs.
+
+/// Find a proposal by its ID with its status stored at the queried block.
+fn proposal<S>(
+    storage: &S,
+    id: u64,
+) -> storage_api::Result<Option<ProposalInfo>>
+where
+    S: StorageRead,
+{
+    let epoch = storage.get_block_epoch()?;
+    read_proposal(storage, id, epoch)
+}
+
+/// Get a page of the proposals ordered by their IDs. When a `status` is given,
+/// only the proposals with this stored status are included in the pages. At
+/// most [`MAX_PROPOSALS_PER_PAGE`] proposals are returned regardless of the
+/// `limit`.
+fn proposals<S>(
+    storage: &S,
+    status: Option<ProposalStatus>,
+    offset: u64,
+    limit: u64,
+) -> storage_api::Result<Vec<ProposalInfo>>
+where
+    S: StorageRead,
+{
+    let epoch = storage.get_block_epoch()?;
+    let counter: u64 = storage
+        .read(&gov_storage::get_counter_key())?
+        .unwrap_or_default();
+    let limit = limit.min(MAX_PROPOSALS_PER_PAGE) as usize;
+
+    let mut page = Vec::with_capacity(limit);
+    let mut skipped = 0;
+    for id in 0..counter {
+        if page.len() == limit {
+            break;
+        }
+        let proposal = match read_proposal(storage, id, epoch)? {
+            Some(proposal) => proposal,
+            None => continue,
+        };
+        if status.map_or(false, |status| status != proposal.status) {
+            continue;
+        }
+        if skipped < offset {
+            skipped += 1;
+            continue;
+        }
+        page.push(proposal);
+    }
+    Ok(page)
+}
+
+/// Get the votes cast on a proposal with the voting power of each voter at
+/// the epoch of the tally (see [`proposal_tally`]), including the votes of
+/// delegators broken down by the validators of their bonds.
+fn proposal_votes<D, H>(
+    ctx: RequestCtx<'_, D, H>,
+    id: u64,
+) -> storage_api::Result<Option<ProposalVotes>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    match tally_epoch(ctx.storage, id)? {
+        Some(epoch) => {
+            utils::read_proposal_votes(ctx.storage, epoch, id).map(Some)
+        }
+        None => Ok(None),
+    }
+}
+
+/// Get all the votes cast on a proposal ordered by the height of the block in
+/// which they were cast. A voter may have changed their vote, only their
+/// latest vote counts in the tally.
+fn vote_history<S>(storage: &S, id: u64) -> storage_api::Result<Vec<VoteRecord>>
+where
+    S: StorageRead,
+{
+    let prefix = gov_storage::get_proposal_vote_history_prefix_key(id);
+    let mut history = vec![];
+    for iter_result in
+        storage_api::iter_prefix::<ProposalVote>(storage, &prefix)?
+    {
+        let (key, vote) = iter_result?;
+        let voter = gov_storage::get_voter_address(&key);
+        let delegation = gov_storage::get_vote_delegation_address(&key);
+        let height = gov_storage::get_vote_history_height(&key);
+        if let (Some(voter), Some(delegation), Some(height)) =
+            (voter, delegation, height)
+        {
+            history.push(VoteRecord {
+                voter: voter.clone(),
+                delegation: delegation.clone(),
+                vote,
+                height,
+            });
+        }
+    }
+    history.sort_by_key(|record| record.height);
+    Ok(history)
+}
+
+/// Compute the tally of a proposal. Until the end of the voting period, this
+/// is the live tally with the voting power at the epoch of the last committed
+/// block. After that, it's the final tally at the voting end epoch.
+fn proposal_tally<D, H>(
+    ctx: RequestCtx<'_, D, H>,
+    id: u64,
+) -> storage_api::Result<Option<ProposalResult>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let epoch = match tally_epoch(ctx.storage, id)? {
+        Some(epoch) => epoch,
+        None => return Ok(None),
+    };
+    let votes = utils::read_proposal_votes(ctx.storage, epoch, id)?;
+    let is_expedited = utils::is_expedited(ctx.storage, id)?;
+    utils::compute_proposal_result(ctx.storage, epoch, votes, is_expedited)
+        .map(Some)
+}
+
+/// Read the governance parameters
+fn parameters<S>(storage: &S) -> storage_api::Result<GovParams>
+where
+    S: StorageRead,
+{
+    let min_proposal_fund: token::Amount =
+        read_parameter(storage, gov_storage::get_min_proposal_fund_key())?;
+    let min_expedited_proposal_fund: token::Amount = read_parameter(
+        storage,
+        gov_storage::get_min_expedited_proposal_fund_key(),
+    )?;
+    Ok(GovParams {
+        min_proposal_fund: u64::from(min_proposal_fund),
+        max_proposal_code_size: read_parameter(
+            storage,
+            gov_storage::get_max_proposal_code_size_key(),
+        )?,
+        min_proposal_period: read_parameter(
+            storage,
+            gov_storage::get_min_proposal_period_key(),
+        )?,
+        max_proposal_period: read_parameter(
+            storage,
+            gov_storage::get_max_proposal_period_key(),
+        )?,
+        max_proposal_content_size: read_parameter(
+            storage,
+            gov_storage::get_max_proposal_content_key(),
+        )?,
+        min_proposal_grace_epochs: read_parameter(
+            storage,
+            gov_storage::get_min_proposal_grace_epoch_key(),
+        )?,
+        quorum: read_parameter(storage, gov_storage::get_quorum_key())?,
+        pass_threshold: read_parameter(
+            storage,
+            gov_storage::get_pass_threshold_key(),
+        )?,
+        veto_threshold: read_parameter(
+            storage,
+            gov_storage::get_veto_threshold_key(),
+        )?,
+        burn_missed_quorum_deposit: read_parameter(
+            storage,
+            gov_storage::get_burn_missed_quorum_deposit_key(),
+        )?,
+        min_expedited_proposal_fund: u64::from(min_expedited_proposal_fund),
+        min_expedited_proposal_period: read_parameter(
+            storage,
+            gov_storage::get_min_expedited_proposal_period_key(),
+        )?,
+        expedited_pass_threshold: read_parameter(
+            storage,
+            gov_storage::get_expedited_pass_threshold_key(),
+        )?,
+    })
+}
+
+/// Read the treasury with its balance of the native token, the vested spends
+/// that are still to be paid and its history
+fn treasury<S>(storage: &S) -> storage_api::Result<TreasuryInfo>
+where
+    S: StorageRead,
+{
+    let native_token = storage.get_native_token()?;
+    Ok(TreasuryInfo {
+        balance: slash_fund::read_balance(storage, &native_token)?,
+        vesting: slash_fund::read_vesting(storage)?,
+        history: slash_fund::read_history(storage)?,
+    })
+}
+
+/// Read a proposal by its ID with its stored status, if the proposal exists.
+/// A proposal without a stored status gets the status of its voting period
+/// at the given epoch.
+fn read_proposal<S>(
+    storage: &S,
+    id: u64,
+    epoch: Epoch,
+) -> storage_api::Result<Option<ProposalInfo>>
+where
+    S: StorageRead,
+{
+    let author = match storage.read(&gov_storage::get_author_key(id))? {
+        Some(author) => author,
+        None => return Ok(None),
+    };
+    let content_key = gov_storage::get_content_key(id);
+    let content = match storage.read_bytes(&content_key)? {
+        Some(bytes) => decode_content(bytes).into_storage_result()?,
+        None => return Err(missing_field(id, &content_key)),
+    };
+    let voting_start_epoch: Epoch =
+        read_field(storage, id, gov_storage::get_voting_start_epoch_key(id))?;
+    let voting_end_epoch: Epoch =
+        read_field(storage, id, gov_storage::get_voting_end_epoch_key(id))?;
+    let grace_epoch =
+        read_field(storage, id, gov_storage::get_grace_epoch_key(id))?;
+    let r#type =
+        read_field(storage, id, gov_storage::get_proposal_type_key(id))?;
+    let expedited = utils::is_expedited(storage, id)?;
+    let status = match storage.read(&gov_storage::get_status_key(id))? {
+        Some(status) => status,
+        // A proposal stored before the statuses were introduced
+        None => ProposalStatus::at_epoch(
+            epoch,
+            voting_start_epoch,
+            voting_end_epoch,
+        ),
+    };
+    Ok(Some(ProposalInfo {
+        id,
+        author,
+        content,
+        voting_start_epoch,
+        voting_end_epoch,
+        grace_epoch,
+        r#type,
+        expedited,
+        status,
+    }))
+}
+
+/// Get the epoch at which the votes of a proposal are counted, i.e. the epoch
+/// of the last committed block, but no later than the voting end epoch
+fn tally_epoch<S>(storage: &S, id: u64) -> storage_api::Result<Option<Epoch>>
+where
+    S: StorageRead,
+{
+    let voting_end_epoch: Option<Epoch> =
+        storage.read(&gov_storage::get_voting_end_epoch_key(id))?;
+    match voting_end_epoch {
+        Some(voting_end_epoch) => {
+            let epoch = storage.get_block_epoch()?;
+            Ok(Some(epoch.min(voting_end_epoch)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Read a field of an existing proposal, which must be present
+fn read_field<S, T>(storage: &S, id: u64, key: Key) -> storage_api::Result<T>
+where
+    S: StorageRead,
+    T: borsh::BorshDeserialize,
+{
+    storage.read(&key)?.ok_or_else(|| missing_field(id, &key))
+}
+
+/// The error of a proposal that is missing a field
+fn missing_field(id: u64, key: &Key) -> storage_api::Error {
+    storage_api::Error::new(format!(
+        "The proposal {id} is missing the field {key}"
+    ))
+}
+
+/// Read a governance parameter, which must be present
+fn read_parameter<S, T>(storage: &S, key: Key) -> storage_api::Result<T>
+where
+    S: StorageRead,
+    T: borsh::BorshDeserialize,
+{
+    storage.read(&key)?.ok_or_else(|| {
+        storage_api::Error::new(format!(
+            "The governance parameter {key} is missing"
+        ))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::MAX_PROPOSALS_PER_PAGE;
+    use crate::ledger::governance::parameters::GovParams;
+    use crate::ledger::governance::storage as gov_storage;
+    use crate::ledger::queries::testing::TestClient;
+    use crate::ledger::queries::RPC;
+    use crate::ledger::slash_fund;
+    use crate::ledger::storage::testing::TestStorage;
+    use crate::ledger::storage::{current_epoch_key, types};
+    use crate::ledger::storage_api::{self, StorageWrite};
+    use crate::types::address;
+    use crate::types::governance::{
+        ProposalContent, ProposalInfo, ProposalStatus, ProposalType,
+        ProposalVote, TreasuryRecord,
+    };
+    use crate::types::storage::{BlockHeight, Epoch};
+    use crate::types::token;
+
+    #[test]
+    fn test_governance_queries_router_paths() {
+        let path = RPC.vp().governance().proposal_path(&1);
+        assert_eq!("/vp/governance/proposal/1", path);
+
+        let path = RPC.vp().governance().proposals_path(&None, &0, &10);
+        assert_eq!("/vp/governance/proposals/0/10", path);
+
+        let path = RPC.vp().governance().proposals_path(
+            &Some(ProposalStatus::ExecutionFailed),
+            &10,
+            &MAX_PROPOSALS_PER_PAGE,
+        );
+        assert_eq!("/vp/governance/proposals/execution-failed/10/100", path);
+
+        let path = RPC.vp().governance().proposal_votes_path(&1);
+        assert_eq!("/vp/governance/votes/1", path);
+
+        let path = RPC.vp().governance().vote_history_path(&1);
+        assert_eq!("/vp/governance/vote_history/1", path);
+
+        let path = RPC.vp().governance().proposal_tally_path(&1);
+        assert_eq!("/vp/governance/tally/1", path);
+
+        let path = RPC.vp().governance().parameters_path();
+        assert_eq!("/vp/governance/parameters", path);
+
+        let path = RPC.vp().governance().treasury_path();
+        assert_eq!("/vp/governance/treasury", path);
+    }
+
+    #[tokio::test]
+    async fn test_governance_queries_router_with_client(
+    ) -> storage_api::Result<()> {
+        // Initialize the `TestClient`
+        let mut client = TestClient::new(RPC);
+        let params = GovParams::default();
+        params.init_storage(&mut client.storage);
+        let current_epoch = client.storage.last_epoch;
+        client
+            .storage
+            .block
+            .tree
+            .update(&current_epoch_key(), types::encode(&current_epoch))
+            .unwrap();
+
+        // Request the governance parameters
+        let read_params =
+            RPC.vp().governance().parameters(&client).await.unwrap();
+        assert_eq!(
+            read_params.min_proposal_fund,
+            u64::from(token::Amount::whole(params.min_proposal_fund))
+        );
+        assert_eq!(read_params.max_proposal_period, params.max_proposal_period);
+
+        // There are no proposals yet
+        let read_proposal =
+            RPC.vp().governance().proposal(&client, &0).await.unwrap();
+        assert!(read_proposal.is_none());
+
+        // Write a pending and an on-going proposal
+        let author = address::testing::established_address_1();
+        let pending = write_proposal(
+            &mut client.storage,
+            0,
+            &author,
+            current_epoch + 1_u64,
+            current_epoch + 4_u64,
+            ProposalStatus::Pending,
+        )?;
+        let on_going = write_proposal(
+            &mut client.storage,
+            1,
+            &author,
+            current_epoch,
+            current_epoch + 3_u64,
+            ProposalStatus::Voting,
+        )?;
+        StorageWrite::write(
+            &mut client.storage,
+            &gov_storage::get_counter_key(),
+            2_u64,
+        )?;
+
+        // Request a proposal by its ID
+        let read_proposal =
+            RPC.vp().governance().proposal(&client, &1).await.unwrap();
+        assert_eq!(read_proposal, Some(on_going.clone()));
+
+        // Request the proposal with the proofs of its fields
+        let proven = RPC
+            .vp()
+            .governance()
+            .proposal_with_proof(&client, None, &1)
+            .await
+            .unwrap();
+        assert_eq!(proven.data, Some(on_going.clone()));
+
+        // Request the pages of proposals
+        let page = RPC
+            .vp()
+            .governance()
+            .proposals(&client, &None, &0, &10)
+            .await
+            .unwrap();
+        assert_eq!(page, vec![pending.clone(), on_going.clone()]);
+        let page = RPC
+            .vp()
+            .governance()
+            .proposals(&client, &None, &1, &10)
+            .await
+            .unwrap();
+        assert_eq!(page, vec![on_going.clone()]);
+        let page = RPC
+            .vp()
+            .governance()
+            .proposals(&client, &None, &0, &1)
+            .await
+            .unwrap();
+        assert_eq!(page, vec![pending.clone()]);
+
+        // Request the proposals filtered by their status
+        let page = RPC
+            .vp()
+            .governance()
+            .proposals(&client, &Some(ProposalStatus::Voting), &0, &10)
+            .await
+            .unwrap();
+        assert_eq!(page, vec![on_going.clone()]);
+        let page = RPC
+            .vp()
+            .governance()
+            .proposals(&client, &Some(ProposalStatus::Executed), &0, &10)
+            .await
+            .unwrap();
+        assert!(page.is_empty());
+
+        // The stored status is used, rather than the voting period
+        StorageWrite::write(
+            &mut client.storage,
+            &gov_storage::get_status_key(1),
+            ProposalStatus::Rejected,
+        )?;
+        let page = RPC
+            .vp()
+            .governance()
+            .proposals(&client, &Some(ProposalStatus::Rejected), &0, &10)
+            .await
+            .unwrap();
+        assert_eq!(
+            page,
+            vec![ProposalInfo {
+                status: ProposalStatus::Rejected,
+                ..on_going
+            }]
+        );
+
+        // Request the vote history of a proposal, in which a voter changed
+        // their vote
+        let voter = address::testing::established_address_2();
+        let validator = address::testing::established_address_3();
+        for (height, vote) in
+            [(9, ProposalVote::Yay), (10, ProposalVote::NayWithVeto)]
+        {
+            StorageWrite::write(
+                &mut client.storage,
+                &gov_storage::get_vote_history_key(
+                    1,
+                    voter.clone(),
+                    validator.clone(),
+                    BlockHeight(height),
+                ),
+                vote,
+            )?;
+        }
+        let history = RPC
+            .vp()
+            .governance()
+            .vote_history(&client, &1)
+            .await
+            .unwrap();
+        let votes: Vec<_> = history
+            .into_iter()
+            .map(|record| (record.voter, record.height, record.vote))
+            .collect();
+        assert_eq!(
+            votes,
+            vec![
+                (voter.clone(), BlockHeight(9), ProposalVote::Yay),
+                (voter, BlockHeight(10), ProposalVote::NayWithVeto),
+            ]
+        );
+        let history = RPC
+            .vp()
+            .governance()
+            .vote_history(&client, &0)
+            .await
+            .unwrap();
+        assert!(history.is_empty());
+
+        // Request the treasury, which only has the forfeited funds
+        let treasury = RPC.vp().governance().treasury(&client).await.unwrap();
+        assert_eq!(treasury.balance, token::Amount::default());
+        assert!(treasury.history.is_empty());
+        let amount = token::Amount::whole(500);
+        StorageWrite::write(
+            &mut client.storage,
+            &token::balance_key(&address::nam(), &slash_fund::ADDRESS),
+            amount,
+        )?;
+        slash_fund::record_deposit(
+            &mut client.storage,
+            0,
+            current_epoch,
+            amount,
+        )?;
+        let treasury = RPC.vp().governance().treasury(&client).await.unwrap();
+        assert_eq!(treasury.balance, amount);
+        assert!(treasury.vesting.is_empty());
+        assert_eq!(
+            treasury.history,
+            vec![TreasuryRecord::Deposit {
+                proposal_id: 0,
+                epoch: current_epoch,
+                amount,
+            }]
+        );
+
+        Ok(())
+    }
+
+    /// Test that the content of a proposal stored as a map, before the content
+    /// could be a hash, is read as the full content
+    #[tokio::test]
+    async fn test_read_proposal_with_legacy_content() -> storage_api::Result<()>
+    {
+        let mut client = TestClient::new(RPC);
+        let author = address::testing::established_address_1();
+        let epoch = client.storage.last_epoch;
+        let mut proposal = write_proposal(
+            &mut client.storage,
+            0,
+            &author,
+            epoch,
+            epoch + 3_u64,
+            ProposalStatus::Voting,
+        )?;
+        let content = BTreeMap::from([(
+            "title".to_string(),
+            "Legacy proposal".to_string(),
+        )]);
+        StorageWrite::write(
+            &mut client.storage,
+            &gov_storage::get_content_key(0),
+            content.clone(),
+        )?;
+        proposal.content = ProposalContent::Full(content);
+
+        let read_proposal =
+            RPC.vp().governance().proposal(&client, &0).await.unwrap();
+        assert_eq!(read_proposal, Some(proposal));
+
+        Ok(())
+    }
+
+    /// Write the fields of a proposal to storage and return the proposal as
+    /// it should be read
+    fn write_proposal(
+        storage: &mut TestStorage,
+        id: u64,
+        author: &address::Address,
+        voting_start_epoch: Epoch,
+        voting_end_epoch: Epoch,
+        status: ProposalStatus,
+    ) -> storage_api::Result<ProposalInfo> {
+        let content = ProposalContent::Full(BTreeMap::from([(
+            "title".to_string(),
+            format!("Proposal {id}"),
+        )]));
+        let grace_epoch = voting_end_epoch + 6_u64;
+        StorageWrite::write(
+            storage,
+            &gov_storage::get_author_key(id),
+            author.clone(),
+        )?;
+        StorageWrite::write(
+            storage,
+            &gov_storage::get_content_key(id),
+            content.clone(),
+        )?;
+        StorageWrite::write(
+            storage,
+            &gov_storage::get_voting_start_epoch_key(id),
+            voting_start_epoch,
+        )?;
+        StorageWrite::write(
+            storage,
+            &gov_storage::get_voting_end_epoch_key(id),
+            voting_end_epoch,
+        )?;
+        StorageWrite::write(
+            storage,
+            &gov_storage::get_grace_epoch_key(id),
+            grace_epoch,
+        )?;
+        StorageWrite::write(
+            storage,
+            &gov_storage::get_proposal_type_key(id),
+            ProposalType::TextOnly,
+        )?;
+        StorageWrite::write(storage, &gov_storage::get_status_key(id), status)?;
+        Ok(ProposalInfo {
+            id,
+            author: author.clone(),
+            content,
+            voting_start_epoch,
+            voting_end_epoch,
+            grace_epoch,
+            r#type: ProposalType::TextOnly,
+            expedited: false,
+            status,
+        })
+    }
+}
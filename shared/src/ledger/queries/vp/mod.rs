@@ -1,11 +1,27 @@
 // Re-export to show in rustdoc!
+pub use account::Account;
+use account::ACCOUNT;
+use governance::GOVERNANCE;
+pub use governance::{Governance, TreasuryInfo};
+pub use pgf::Pgf;
+use pgf::PGF;
 pub use pos::Pos;
 use pos::POS;
+pub use token::Token;
+use token::TOKEN;
+mod account;
+mod governance;
+mod pgf;
 mod pos;
+mod token;
 
 // Validity predicate queries
 router! {VP,
     ( "pos" ) = (sub POS),
+    ( "governance" ) = (sub GOVERNANCE),
+    ( "pgf" ) = (sub PGF),
+    ( "token" ) = (sub TOKEN),
+    ( "account" ) = (sub ACCOUNT),
 }
 
 #[cfg(any(test, feature = "async-client"))]
@@ -34,8 +50,9 @@ pub mod client_only_methods {
             let faucet_account_key = namada_core::ledger::parameters::storage::get_faucet_account_key();
             if RPC
                 .shell()
-                .storage_has_key(client, &faucet_account_key)
+                .storage_has_key(client, None, None, false, &faucet_account_key)
                 .await?
+                .data
             {
                 let faucet_account = Address::try_from_slice(
                     &RPC.shell()
@@ -115,8 +132,9 @@ pub mod client_only_methods {
             .expect("Faucet PoW difficulty couldn't be read");
             let counter = if RPC
                 .shell()
-                .storage_has_key(client, counter_key)
+                .storage_has_key(client, None, None, false, counter_key)
                 .await?
+                .data
             {
                 testnet_pow::Counter::try_from_slice(
                     &RPC.shell()
@@ -0,0 +1,110 @@
+use crate::ledger::pgf;
+use crate::ledger::storage_api::{self, StorageRead};
+use crate::types::address::Address;
+use crate::types::governance::PgfCouncil;
+use crate::types::token;
+
+// Public goods funding validity predicate queries
+router! {PGF,
+    ( "council" ) -> Option<PgfCouncil> = (with_proof council),
+
+    ( "streams" ) -> Vec<(Address, token::Amount)> = (with_proof streams),
+
+    ( "retro_payments" )
+    -> Vec<(Address, token::Amount)> = (with_proof retro_payments),
+}
+
+/// Read the PGF council, if one has been elected
+fn council<S>(storage: &S) -> storage_api::Result<Option<PgfCouncil>>
+where
+    S: StorageRead,
+{
+    pgf::read_council(storage)
+}
+
+/// Read the continuous funding streams by their recipient with the amount
+/// paid every epoch
+fn streams<S>(storage: &S) -> storage_api::Result<Vec<(Address, token::Amount)>>
+where
+    S: StorageRead,
+{
+    pgf::read_streams(storage)
+}
+
+/// Read the retroactive payments approved by the council that haven't been
+/// paid yet by their recipient
+fn retro_payments<S>(
+    storage: &S,
+) -> storage_api::Result<Vec<(Address, token::Amount)>>
+where
+    S: StorageRead,
+{
+    pgf::read_retro_payments(storage)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ledger::pgf::storage as pgf_storage;
+    use crate::ledger::queries::testing::TestClient;
+    use crate::ledger::queries::RPC;
+    use crate::ledger::storage_api::{self, StorageWrite};
+    use crate::types::address;
+    use crate::types::governance::PgfCouncil;
+    use crate::types::token;
+
+    #[test]
+    fn test_pgf_queries_router_paths() {
+        let path = RPC.vp().pgf().council_path();
+        assert_eq!("/vp/pgf/council", path);
+
+        let path = RPC.vp().pgf().streams_path();
+        assert_eq!("/vp/pgf/streams", path);
+
+        let path = RPC.vp().pgf().retro_payments_path();
+        assert_eq!("/vp/pgf/retro_payments", path);
+    }
+
+    #[tokio::test]
+    async fn test_pgf_queries_router_with_client() -> storage_api::Result<()> {
+        // Initialize the `TestClient`
+        let mut client = TestClient::new(RPC);
+
+        // There's no council yet
+        let council = RPC.vp().pgf().council(&client).await.unwrap();
+        assert!(council.is_none());
+
+        let council = PgfCouncil {
+            address: address::testing::established_address_1(),
+            spending_cap: token::Amount::whole(100),
+        };
+        StorageWrite::write(
+            &mut client.storage,
+            &pgf_storage::get_council_key(),
+            council.clone(),
+        )?;
+        let recipient = address::testing::established_address_2();
+        StorageWrite::write(
+            &mut client.storage,
+            &pgf_storage::get_stream_key(&recipient),
+            token::Amount::whole(10),
+        )?;
+        StorageWrite::write(
+            &mut client.storage,
+            &pgf_storage::get_retro_payment_key(&recipient),
+            token::Amount::whole(20),
+        )?;
+
+        let read_council = RPC.vp().pgf().council(&client).await.unwrap();
+        assert_eq!(read_council, Some(council));
+        let streams = RPC.vp().pgf().streams(&client).await.unwrap();
+        assert_eq!(
+            streams,
+            vec![(recipient.clone(), token::Amount::whole(10))]
+        );
+        let retro_payments =
+            RPC.vp().pgf().retro_payments(&client).await.unwrap();
+        assert_eq!(retro_payments, vec![(recipient, token::Amount::whole(20))]);
+
+        Ok(())
+    }
+}
@@ -5,7 +5,7 @@ use namada_proof_of_stake::PosReadOnly;
 use crate::ledger::pos::{self, BondId};
 use crate::ledger::queries::types::RequestCtx;
 use crate::ledger::storage::{DBIter, StorageHasher, DB};
-use crate::ledger::storage_api;
+use crate::ledger::storage_api::{self, StorageRead};
 use crate::types::address::Address;
 use crate::types::storage::Epoch;
 use crate::types::token;
@@ -13,102 +13,110 @@ use crate::types::token;
 // PoS validity predicate queries
 router! {POS,
     ( "validator" ) = {
-        ( "is_validator" / [addr: Address] ) -> bool = is_validator,
+        ( "is_validator" / [addr: Address] )
+        -> bool = (with_proof is_validator),
 
         ( "addresses" / [epoch: opt Epoch] )
-        -> HashSet<Address> = validator_addresses,
+        -> HashSet<Address> = (with_proof validator_addresses),
 
         ( "stake" / [validator: Address] / [epoch: opt Epoch] )
-        -> token::Amount = validator_stake,
+        -> token::Amount = (with_proof validator_stake),
     },
 
     ( "total_stake" / [epoch: opt Epoch] )
-    -> token::Amount = total_stake,
+    -> token::Amount = (with_proof total_stake),
 
     ( "delegations" / [owner: Address] )
     -> HashSet<Address> = delegations,
 
     ( "bond_amount" / [owner: Address] / [validator: Address] / [epoch: opt Epoch] )
-    -> token::Amount = bond_amount,
+    -> token::Amount = (with_proof bond_amount),
 }
 
-// Handlers that implement the functions via `trait StorageRead`:
+// Handlers that implement the functions via `trait StorageRead`. The ones
+// that are generic over the storage can also be requested with proofs.
 
 /// Find if the given address belongs to a validator account.
-fn is_validator<D, H>(
-    ctx: RequestCtx<'_, D, H>,
-    addr: Address,
-) -> storage_api::Result<bool>
+fn is_validator<S>(storage: &S, addr: Address) -> storage_api::Result<bool>
 where
-    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
-    H: 'static + StorageHasher + Sync,
+    S: StorageRead + PosReadOnly,
 {
-    ctx.storage.is_validator(&addr)
+    storage.is_validator(&addr)
 }
 
 /// Get all the validator known addresses. These validators may be in any state,
 /// e.g. active, inactive or jailed.
-fn validator_addresses<D, H>(
-    ctx: RequestCtx<'_, D, H>,
+fn validator_addresses<S>(
+    storage: &S,
     epoch: Option<Epoch>,
 ) -> storage_api::Result<HashSet<Address>>
 where
-    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
-    H: 'static + StorageHasher + Sync,
+    S: StorageRead + PosReadOnly,
 {
-    let epoch = epoch.unwrap_or(ctx.storage.last_epoch);
-    ctx.storage.validator_addresses(epoch)
+    let epoch = epoch_or_last(storage, epoch)?;
+    storage.validator_addresses(epoch)
 }
 
 /// Get the total stake of a validator at the given epoch or current when
 /// `None`. The total stake is a sum of validator's self-bonds and delegations
 /// to their address.
-fn validator_stake<D, H>(
-    ctx: RequestCtx<'_, D, H>,
+fn validator_stake<S>(
+    storage: &S,
     validator: Address,
     epoch: Option<Epoch>,
 ) -> storage_api::Result<token::Amount>
 where
-    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
-    H: 'static + StorageHasher + Sync,
+    S: StorageRead + PosReadOnly,
 {
-    let epoch = epoch.unwrap_or(ctx.storage.last_epoch);
-    ctx.storage.validator_stake(&validator, epoch)
+    let epoch = epoch_or_last(storage, epoch)?;
+    storage.validator_stake(&validator, epoch)
 }
 
 /// Get the total stake in PoS system at the given epoch or current when `None`.
-fn total_stake<D, H>(
-    ctx: RequestCtx<'_, D, H>,
+fn total_stake<S>(
+    storage: &S,
     epoch: Option<Epoch>,
 ) -> storage_api::Result<token::Amount>
 where
-    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
-    H: 'static + StorageHasher + Sync,
+    S: StorageRead + PosReadOnly,
 {
-    let epoch = epoch.unwrap_or(ctx.storage.last_epoch);
-    ctx.storage.total_stake(epoch)
+    let epoch = epoch_or_last(storage, epoch)?;
+    storage.total_stake(epoch)
 }
 
 /// Get the total bond amount for the given bond ID (this may be delegation or
 /// self-bond when `owner == validator`) at the given epoch, or the current
 /// epoch when `None`.
-fn bond_amount<D, H>(
-    ctx: RequestCtx<'_, D, H>,
+fn bond_amount<S>(
+    storage: &S,
     owner: Address,
     validator: Address,
     epoch: Option<Epoch>,
 ) -> storage_api::Result<token::Amount>
 where
-    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
-    H: 'static + StorageHasher + Sync,
+    S: StorageRead + PosReadOnly,
 {
-    let epoch = epoch.unwrap_or(ctx.storage.last_epoch);
+    let epoch = epoch_or_last(storage, epoch)?;
 
     let bond_id = BondId {
         source: owner,
         validator,
     };
-    ctx.storage.bond_amount(&bond_id, epoch)
+    storage.bond_amount(&bond_id, epoch)
+}
+
+/// Get the given epoch or the epoch of the last committed block when `None`.
+fn epoch_or_last<S>(
+    storage: &S,
+    epoch: Option<Epoch>,
+) -> storage_api::Result<Epoch>
+where
+    S: StorageRead,
+{
+    match epoch {
+        Some(epoch) => Ok(epoch),
+        None => storage.get_block_epoch(),
+    }
 }
 
 /// Find all the validator addresses to whom the given `owner` address has
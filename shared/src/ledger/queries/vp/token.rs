@@ -0,0 +1,254 @@
+use std::collections::BTreeSet;
+
+use borsh::BorshDeserialize;
+
+use crate::ledger::queries::types::RequestCtx;
+use crate::ledger::storage::{DBIter, StorageHasher, DB};
+use crate::ledger::storage_api::{self, ResultExt, StorageRead};
+use crate::types::address::{self, Address};
+use crate::types::storage::Key;
+use crate::types::token::{self, TokenBalance};
+
+// Token validity predicate queries
+router! {TOKEN,
+    ( "balance" / [token: Address] / [owner: Address] )
+    -> token::Amount = (with_proof balance),
+
+    ( "balances" / [owner: Address] )
+    -> Vec<TokenBalance> = (with_proof balances),
+
+    ( "balances" / [owner: Address] / [sub_prefix: Key] )
+    -> Vec<TokenBalance> = (with_proof multitoken_balances),
+
+    ( "supply" / [token: Address] )
+    -> token::Amount = (with_proof total_supply),
+
+    ( "supply" / [token: Address] / [sub_prefix: Key] )
+    -> token::Amount = multitoken_total_supply,
+}
+
+// Handlers that implement the functions via `trait StorageRead`. The ones
+// that are generic over the storage can also be requested with proofs.
+
+/// Get the balance of the given owner in the given token. The balance is zero
+/// when the owner has never held the token.
+fn balance<S>(
+    storage: &S,
+    token: Address,
+    owner: Address,
+) -> storage_api::Result<token::Amount>
+where
+    S: StorageRead,
+{
+    storage_api::token::read_balance(storage, &token, &owner)
+}
+
+/// Get the balances of the given owner in the known tokens without a
+/// multitoken sub-prefix. Only the balance keys of the owner are read, so the
+/// balances of the other owners are never iterated. The tokens that the owner
+/// has never held are skipped and the balances are ordered by the tokens.
+fn balances<S>(
+    storage: &S,
+    owner: Address,
+) -> storage_api::Result<Vec<TokenBalance>>
+where
+    S: StorageRead,
+{
+    owner_balances(storage, &owner, None)
+}
+
+/// Get the balances of the given owner in the known tokens under the given
+/// multitoken sub-prefix, read the same way as [`balances`].
+fn multitoken_balances<S>(
+    storage: &S,
+    owner: Address,
+    sub_prefix: Key,
+) -> storage_api::Result<Vec<TokenBalance>>
+where
+    S: StorageRead,
+{
+    owner_balances(storage, &owner, Some(sub_prefix))
+}
+
+fn owner_balances<S>(
+    storage: &S,
+    owner: &Address,
+    sub_prefix: Option<Key>,
+) -> storage_api::Result<Vec<TokenBalance>>
+where
+    S: StorageRead,
+{
+    let tokens: BTreeSet<Address> = address::tokens().into_keys().collect();
+    let mut balances = vec![];
+    for token in tokens {
+        let key = match &sub_prefix {
+            Some(sub_prefix) => token::multitoken_balance_key(
+                &token::multitoken_balance_prefix(&token, sub_prefix),
+                owner,
+            ),
+            None => token::balance_key(&token, owner),
+        };
+        if let Some(amount) = storage.read(&key)? {
+            balances.push(TokenBalance {
+                token,
+                sub_prefix: sub_prefix.clone(),
+                amount,
+            });
+        }
+    }
+    Ok(balances)
+}
+
+/// Get the total supply of the given token, i.e. the sum of all the balances
+/// in the token without a multitoken sub-prefix. The supply is read from its
+/// storage key, which is maintained when the token is minted or burned.
+fn total_supply<S>(
+    storage: &S,
+    token: Address,
+) -> storage_api::Result<token::Amount>
+where
+    S: StorageRead,
+{
+    storage_api::token::read_total_supply(storage, &token)
+}
+
+/// Get the total supply of the given token under the given multitoken
+/// sub-prefix, i.e. the sum of all the balances with this sub-prefix
+fn multitoken_total_supply<D, H>(
+    ctx: RequestCtx<'_, D, H>,
+    token: Address,
+    sub_prefix: Key,
+) -> storage_api::Result<token::Amount>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+{
+    let prefix = token::multitoken_balance_prefix(&token, &sub_prefix);
+    let mut supply = token::Amount::default();
+    for iter_result in storage_api::iter_prefix_bytes(ctx.storage, &prefix)? {
+        let (key, bytes) = iter_result?;
+        match token::is_multitoken_balance_key(&token, &key) {
+            Some((key_sub_prefix, _)) if key_sub_prefix == sub_prefix => {
+                supply += token::Amount::try_from_slice(&bytes)
+                    .into_storage_result()?;
+            }
+            _ => {}
+        }
+    }
+    Ok(supply)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ledger::queries::testing::TestClient;
+    use crate::ledger::queries::RPC;
+    use crate::ledger::storage_api::{self, StorageWrite};
+    use crate::types::address::{self, nam};
+    use crate::types::storage::Key;
+    use crate::types::token::{self, TokenBalance};
+
+    #[tokio::test]
+    async fn test_token_queries_router_with_client() -> storage_api::Result<()>
+    {
+        // Initialize the `TestClient`
+        let mut client = TestClient::new(RPC);
+        let token = nam();
+        let owner = address::testing::established_address_1();
+        let other_owner = address::testing::established_address_2();
+        let sub_prefix = Key::parse("ibc/channel-0").unwrap();
+        let multitoken_prefix =
+            token::multitoken_balance_prefix(&token, &sub_prefix);
+
+        // Write some balances
+        let balance = token::Amount::whole(100);
+        let other_balance = token::Amount::whole(20);
+        let multitoken_balance = token::Amount::whole(3);
+        StorageWrite::write(
+            &mut client.storage,
+            &token::balance_key(&token, &owner),
+            balance,
+        )?;
+        StorageWrite::write(
+            &mut client.storage,
+            &token::balance_key(&token, &other_owner),
+            other_balance,
+        )?;
+        StorageWrite::write(
+            &mut client.storage,
+            &token::multitoken_balance_key(&multitoken_prefix, &owner),
+            multitoken_balance,
+        )?;
+
+        // Request a single balance
+        let read_balance = RPC
+            .vp()
+            .token()
+            .balance(&client, &token, &owner)
+            .await
+            .unwrap();
+        assert_eq!(read_balance, balance);
+
+        // Request all the balances of the owner without and with the
+        // sub-prefix
+        let balances =
+            RPC.vp().token().balances(&client, &owner).await.unwrap();
+        assert_eq!(
+            balances,
+            vec![TokenBalance {
+                token: token.clone(),
+                sub_prefix: None,
+                amount: balance,
+            }]
+        );
+        let balances = RPC
+            .vp()
+            .token()
+            .multitoken_balances(&client, &owner, &sub_prefix)
+            .await
+            .unwrap();
+        assert_eq!(
+            balances,
+            vec![TokenBalance {
+                token: token.clone(),
+                sub_prefix: Some(sub_prefix.clone()),
+                amount: multitoken_balance,
+            }]
+        );
+
+        // Request the supply of the token with and without the sub-prefix.
+        // The supply is initialized from the balances as at genesis and then
+        // maintained by minting.
+        storage_api::token::init_total_supply(&mut client.storage, &token)?;
+        let supply = RPC
+            .vp()
+            .token()
+            .total_supply(&client, &token)
+            .await
+            .unwrap();
+        assert_eq!(supply, balance + other_balance);
+        let minted = token::Amount::whole(7);
+        storage_api::token::mint(
+            &mut client.storage,
+            &address::testing::established_address_3(),
+            &token,
+            &other_owner,
+            minted,
+        )?;
+        let supply = RPC
+            .vp()
+            .token()
+            .total_supply(&client, &token)
+            .await
+            .unwrap();
+        assert_eq!(supply, balance + other_balance + minted);
+        let supply = RPC
+            .vp()
+            .token()
+            .multitoken_total_supply(&client, &token, &sub_prefix)
+            .await
+            .unwrap();
+        assert_eq!(supply, multitoken_balance);
+
+        Ok(())
+    }
+}
@@ -3005,6 +3005,11 @@ fn test_genesis_validators() -> Result<()> {
             .rpc_address
             .set_port(first_port + 1);
         config.ledger.shell.ledger_address.set_port(first_port + 2);
+        if let Some(address) =
+            config.ledger.shell.events_subscription_address.as_mut()
+        {
+            address.set_port(first_port + 3);
+        }
         config
     };
 
@@ -3188,6 +3193,11 @@ fn double_signing_gets_slashed() -> Result<()> {
             .rpc_address
             .set_port(first_port + 1);
         config.ledger.shell.ledger_address.set_port(first_port + 2);
+        if let Some(address) =
+            config.ledger.shell.events_subscription_address.as_mut()
+        {
+            address.set_port(first_port + 3);
+        }
         config
     };
 
@@ -574,9 +574,8 @@ pub mod testing {
     use namada::proof_of_stake::parameters::testing::arb_rate;
     use namada::proof_of_stake::storage::{BondId, Bonds, Unbonds};
     use namada::proof_of_stake::types::{
-        Bond, Unbond, ValidatorState, WeightedValidator,
+        Bond, EpochedPosParams, Unbond, ValidatorState, WeightedValidator,
     };
-    use namada::proof_of_stake::PosParams;
     use namada::types::key::common::PublicKey;
     use namada::types::key::RefTo;
     use namada::types::storage::Epoch;
@@ -845,7 +844,7 @@ pub mod testing {
         pub fn apply(self, is_current_tx_valid: bool) {
             // Read the PoS parameters
             use namada_tx_prelude::PosRead;
-            let params = tx::ctx().read_pos_params().unwrap();
+            let params = tx::ctx().read_epoched_pos_params().unwrap();
 
             let current_epoch = tx_host_env::with(|env| {
                 // Reset the gas meter on each change, so that we never run
@@ -1044,7 +1043,7 @@ pub mod testing {
 
     pub fn apply_pos_storage_change(
         change: PosStorageChange,
-        params: &PosParams,
+        params: &EpochedPosParams,
         current_epoch: Epoch,
         // valid changes can make assumptions that are not applicable to
         // invalid changes
@@ -1064,6 +1063,7 @@ pub mod testing {
                 delta,
                 offset,
             } => {
+                let offset_value = offset.value_at(params, current_epoch);
                 let bond_id = BondId {
                     source: owner,
                     validator,
@@ -1078,14 +1078,13 @@ pub mod testing {
                     };
                     value
                         .pos_deltas
-                        .insert(current_epoch + offset.value(params), amount);
+                        .insert(current_epoch + offset_value, amount);
                     match bonds {
                         Some(mut bonds) => {
                             // Resize the data if needed (the offset may be
                             // greater than the default from an invalid PoS
                             // action)
-                            let required_len =
-                                offset.value(params) as usize + 1;
+                            let required_len = offset_value as usize + 1;
                             if bonds.data.len() < required_len {
                                 bonds.data.resize_with(
                                     required_len,
@@ -1133,6 +1132,7 @@ pub mod testing {
                 delta,
             } => {
                 let offset = DynEpochOffset::UnbondingLen;
+                let offset_value = offset.value_at(params, current_epoch);
                 let bond_id = BondId {
                     source: owner,
                     validator,
@@ -1147,7 +1147,7 @@ pub mod testing {
                 // Look for bonds from the epoch at unbonding offset to the last
                 // update, until we unbond the full amount
                 let mut bond_epoch =
-                    u64::from(bonds.last_update()) + params.unbonding_len;
+                    u64::from(bonds.last_update()) + offset_value;
                 'outer: while to_unbond != token::Amount::default()
                     && bond_epoch >= bonds.last_update().into()
                 {
@@ -1157,7 +1157,7 @@ pub mod testing {
                                 value.deltas.insert(
                                     (
                                         *start_epoch,
-                                        (current_epoch + offset.value(params)),
+                                        (current_epoch + offset_value),
                                     ),
                                     to_unbond,
                                 );
@@ -1168,7 +1168,7 @@ pub mod testing {
                                 value.deltas.insert(
                                     (
                                         *start_epoch,
-                                        (current_epoch + offset.value(params)),
+                                        (current_epoch + offset_value),
                                     ),
                                     *delta,
                                 );
@@ -1360,7 +1360,7 @@ pub mod testing {
         token_delta: i128,
         offset: DynEpochOffset,
         current_epoch: Epoch,
-        params: &PosParams,
+        params: &EpochedPosParams,
     ) {
         use namada_tx_prelude::{PosRead, PosWrite};
 
@@ -1550,7 +1550,7 @@ pub mod testing {
         pub fn apply(self) {
             // Read the PoS parameters
             use namada_tx_prelude::PosRead;
-            let params = tx::ctx().read_pos_params().unwrap();
+            let params = tx::ctx().read_epoched_pos_params().unwrap();
 
             for (epoch, changes) in self.changes {
                 for change in changes {
@@ -1562,7 +1562,7 @@ pub mod testing {
 
     /// Find if there are any vacant active validator slots
     pub fn has_vacant_active_validator_slots(
-        params: &PosParams,
+        params: &EpochedPosParams,
         current_epoch: Epoch,
     ) -> bool {
         use namada_tx_prelude::PosRead;
@@ -1571,7 +1571,10 @@ pub mod testing {
         let validator_set = validator_sets
             .get_at_offset(current_epoch, DynEpochOffset::PipelineLen, params)
             .unwrap();
-        params.max_validator_slots
-            > validator_set.active.len().try_into().unwrap()
+        let max_validator_slots = params
+            .get_at_offset(current_epoch, DynEpochOffset::PipelineLen, params)
+            .unwrap()
+            .max_validator_slots;
+        max_validator_slots > validator_set.active.len().try_into().unwrap()
     }
 }
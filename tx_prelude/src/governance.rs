@@ -1,9 +1,10 @@
 //! Governance
 
 use namada_core::ledger::governance::{storage, ADDRESS as governance_address};
+use namada_core::types::governance::{ProposalStatus, ProposalType};
 use namada_core::types::token::Amount;
 use namada_core::types::transaction::governance::{
-    InitProposalData, VoteProposalData,
+    InitProposalData, VotePassThroughData, VoteProposalData,
 };
 
 use super::*;
@@ -34,14 +35,31 @@ pub fn init_proposal(ctx: &mut Ctx, data: InitProposalData) -> TxResult {
     let grace_epoch_key = storage::get_grace_epoch_key(proposal_id);
     ctx.write(&grace_epoch_key, data.grace_epoch)?;
 
-    if let Some(proposal_code) = data.proposal_code {
-        let proposal_code_key = storage::get_proposal_code_key(proposal_id);
-        ctx.write_bytes(&proposal_code_key, proposal_code)?;
+    let proposal_type_key = storage::get_proposal_type_key(proposal_id);
+    match data.r#type {
+        ProposalType::Default(Some(proposal_code)) => {
+            // The code is written under its own key, apart from the type
+            ctx.write(&proposal_type_key, ProposalType::Default(None))?;
+            let proposal_code_key =
+                storage::get_proposal_code_key(proposal_id);
+            ctx.write_bytes(&proposal_code_key, proposal_code)?;
+        }
+        r#type => ctx.write(&proposal_type_key, r#type)?,
     }
 
+    let status_key = storage::get_status_key(proposal_id);
+    ctx.write(&status_key, ProposalStatus::Pending)?;
+
     ctx.write(&counter_key, proposal_id + 1)?;
 
-    let min_proposal_funds_key = storage::get_min_proposal_fund_key();
+    let min_proposal_funds_key = if data.expedited {
+        // The key is only written for expedited proposals
+        let expedited_key = storage::get_expedited_key(proposal_id);
+        ctx.write(&expedited_key, true)?;
+        storage::get_min_expedited_proposal_fund_key()
+    } else {
+        storage::get_min_proposal_fund_key()
+    };
     let min_proposal_funds: Amount =
         ctx.read(&min_proposal_funds_key)?.unwrap();
 
@@ -67,13 +85,36 @@ pub fn init_proposal(ctx: &mut Ctx, data: InitProposalData) -> TxResult {
 
 /// A proposal vote transaction.
 pub fn vote_proposal(ctx: &mut Ctx, data: VoteProposalData) -> TxResult {
+    let height = ctx.get_block_height()?;
     for delegation in data.delegations {
         let vote_key = storage::get_vote_proposal_key(
             data.id,
             data.voter.clone(),
-            delegation,
+            delegation.clone(),
         );
         ctx.write(&vote_key, data.vote.clone())?;
+
+        let vote_history_key = storage::get_vote_history_key(
+            data.id,
+            data.voter.clone(),
+            delegation,
+            height,
+        );
+        ctx.write(&vote_history_key, data.vote.clone())?;
     }
     Ok(())
 }
+
+/// A transaction to opt a validator in or out of passing through the
+/// majority vote of its delegators on proposals.
+pub fn set_vote_pass_through(
+    ctx: &mut Ctx,
+    data: VotePassThroughData,
+) -> TxResult {
+    let pass_through_key = storage::get_vote_pass_through_key(&data.validator);
+    if data.pass_through {
+        ctx.write(&pass_through_key, true)
+    } else {
+        ctx.delete(&pass_through_key)
+    }
+}
@@ -9,6 +9,7 @@
 pub mod governance;
 pub mod ibc;
 pub mod key;
+pub mod pgf;
 pub mod proof_of_stake;
 pub mod token;
 
@@ -18,6 +19,7 @@ use std::marker::PhantomData;
 pub use borsh::{BorshDeserialize, BorshSerialize};
 pub use namada_core::ledger::governance::storage as gov_storage;
 pub use namada_core::ledger::parameters::storage as parameters_storage;
+pub use namada_core::ledger::pgf::storage as pgf_storage;
 pub use namada_core::ledger::slash_fund::storage as slash_fund_storage;
 pub use namada_core::ledger::storage::types::encode;
 pub use namada_core::ledger::storage_api::{
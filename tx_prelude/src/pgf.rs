@@ -0,0 +1,30 @@
+//! Public goods funding
+
+use namada_core::ledger::pgf::storage;
+use namada_core::types::governance::PgfCouncil;
+use namada_core::types::transaction::pgf::PgfFundingAction;
+
+use super::*;
+
+/// A transaction of the PGF council to change the funding. The council must
+/// sign it.
+pub fn update_funding(ctx: &mut Ctx, action: PgfFundingAction) -> TxResult {
+    let council: PgfCouncil = ctx
+        .read(&storage::get_council_key())?
+        .ok_or_else(|| Error::new_const("No PGF council has been elected"))?;
+    ctx.insert_verifier(&council.address)?;
+
+    let (key, amount) = match action {
+        PgfFundingAction::SetStream { recipient, amount } => {
+            (storage::get_stream_key(&recipient), amount)
+        }
+        PgfFundingAction::RetroPayment { recipient, amount } => {
+            (storage::get_retro_payment_key(&recipient), amount)
+        }
+    };
+    if amount == token::Amount::default() {
+        ctx.delete(&key)
+    } else {
+        ctx.write(&key, amount)
+    }
+}
@@ -12,7 +12,9 @@ use namada_proof_of_stake::storage::{
     validator_slashes_key, validator_state_key, BondId, Bonds, TotalDeltas,
     Unbonds, ValidatorConsensusKeys, ValidatorDeltas, ValidatorSets,
 };
-use namada_proof_of_stake::types::{CommissionRates, ValidatorStates};
+use namada_proof_of_stake::types::{
+    CommissionRates, EpochedPosParams, ValidatorStates,
+};
 pub use namada_proof_of_stake::{
     epoched, parameters, types, PosActions as PosWrite, PosReadOnly as PosRead,
 };
@@ -134,7 +136,7 @@ namada_proof_of_stake::impl_pos_read_only! {
 impl namada_proof_of_stake::PosActions for Ctx {
     fn write_pos_params(
         &mut self,
-        params: &PosParams,
+        params: &EpochedPosParams,
     ) -> storage_api::Result<()> {
         self.write(&params_key(), params)
     }
@@ -10,6 +10,8 @@ pub mod tx_init_account;
 pub mod tx_init_proposal;
 #[cfg(feature = "tx_init_validator")]
 pub mod tx_init_validator;
+#[cfg(feature = "tx_pgf_funding")]
+pub mod tx_pgf_funding;
 #[cfg(feature = "tx_reveal_pk")]
 pub mod tx_reveal_pk;
 #[cfg(feature = "tx_transfer")]
@@ -18,6 +20,8 @@ pub mod tx_transfer;
 pub mod tx_unbond;
 #[cfg(feature = "tx_update_vp")]
 pub mod tx_update_vp;
+#[cfg(feature = "tx_vote_pass_through")]
+pub mod tx_vote_pass_through;
 #[cfg(feature = "tx_vote_proposal")]
 pub mod tx_vote_proposal;
 #[cfg(feature = "tx_withdraw")]
@@ -0,0 +1,16 @@
+//! A tx for the PGF council to change the public goods funding
+
+use namada_tx_prelude::*;
+
+#[transaction]
+fn apply_tx(ctx: &mut Ctx, tx_data: Vec<u8>) -> TxResult {
+    let signed = SignedTxData::try_from_slice(&tx_data[..])
+        .wrap_err("failed to decode SignedTxData")?;
+    let data = signed.data.ok_or_err_msg("Missing data")?;
+    let action = transaction::pgf::PgfFundingAction::try_from_slice(&data[..])
+        .wrap_err("failed to decode PgfFundingAction")?;
+
+    debug_log!("apply_tx called to change the public goods funding");
+
+    pgf::update_funding(ctx, action)
+}
@@ -0,0 +1,18 @@
+//! A tx for a validator to opt in or out of passing through the majority
+//! vote of its delegators
+
+use namada_tx_prelude::*;
+
+#[transaction]
+fn apply_tx(ctx: &mut Ctx, tx_data: Vec<u8>) -> TxResult {
+    let signed = SignedTxData::try_from_slice(&tx_data[..])
+        .wrap_err("failed to decode SignedTxData")?;
+    let data = signed.data.ok_or_err_msg("Missing data")?;
+    let tx_data =
+        transaction::governance::VotePassThroughData::try_from_slice(&data[..])
+            .wrap_err("failed to decode VotePassThroughData")?;
+
+    debug_log!("apply_tx called to set the vote pass-through of a validator");
+
+    governance::set_vote_pass_through(ctx, tx_data)
+}
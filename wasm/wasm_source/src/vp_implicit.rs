@@ -21,6 +21,7 @@ enum KeyType<'a> {
     Token(&'a Address),
     PoS,
     GovernanceVote(&'a Address),
+    Pgf,
     Unknown,
 }
 
@@ -43,6 +44,8 @@ impl<'a> From<&'a storage::Key> for KeyType<'a> {
             } else {
                 Self::Unknown
             }
+        } else if pgf_storage::is_pgf_key(key) {
+            Self::Pgf
         } else {
             Self::Unknown
         }
@@ -174,6 +177,16 @@ fn validate_tx(
                     true
                 }
             }
+            KeyType::Pgf => {
+                // The changes of the funding made by this address as the PGF
+                // council must be signed
+                let council: Option<governance::PgfCouncil> =
+                    ctx.read_pre(&pgf_storage::get_council_key())?;
+                match council {
+                    Some(council) if council.address == addr => *valid_sig,
+                    _ => true,
+                }
+            }
             KeyType::Unknown => {
                 if key.segments.get(0) == Some(&addr.to_db_key()) {
                     // Unknown changes to this address space require a valid
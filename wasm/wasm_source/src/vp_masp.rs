@@ -43,6 +43,12 @@ fn validate_tx(
         verifiers,
     );
 
+    // The note commitment frontiers are only stored by the ledger
+    if keys_changed.iter().any(token::is_masp_frontier_key) {
+        debug_log!("The note commitment frontiers cannot be written by a tx");
+        return reject();
+    }
+
     let signed = SignedTxData::try_from_slice(&tx_data[..]).unwrap();
     // Also get the data as bytes for the VM.
     let data = signed.data.as_ref().unwrap().clone();
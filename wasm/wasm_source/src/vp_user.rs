@@ -19,6 +19,7 @@ enum KeyType<'a> {
     Vp(&'a Address),
     Masp,
     GovernanceVote(&'a Address),
+    Pgf,
     Unknown,
 }
 
@@ -39,6 +40,11 @@ impl<'a> From<&'a storage::Key> for KeyType<'a> {
             } else {
                 Self::Unknown
             }
+        } else if let Some(address) = gov_storage::is_vote_pass_through_key(key)
+        {
+            Self::GovernanceVote(address)
+        } else if pgf_storage::is_pgf_key(key) {
+            Self::Pgf
         } else if let Some(address) = key.is_validity_predicate() {
             Self::Vp(address)
         } else if token::is_masp_key(key) {
@@ -149,6 +155,16 @@ fn validate_tx(
                     true
                 }
             }
+            KeyType::Pgf => {
+                // The changes of the funding made by this address as the PGF
+                // council must be signed
+                let council: Option<governance::PgfCouncil> =
+                    ctx.read_pre(&pgf_storage::get_council_key())?;
+                match council {
+                    Some(council) if council.address == addr => *valid_sig,
+                    _ => true,
+                }
+            }
             KeyType::Vp(owner) => {
                 let has_post: bool = ctx.has_key_post(key)?;
                 if owner == &addr {
@@ -543,6 +559,79 @@ mod tests {
         );
     }
 
+    /// Test that a change of the public goods funding by the PGF council
+    /// requires a valid signature.
+    #[test]
+    fn test_pgf_funding_signature() {
+        let vp_owner = address::testing::established_address_1();
+        let recipient = address::testing::established_address_2();
+        let keypair = key::testing::keypair_1();
+        let public_key = keypair.ref_to();
+
+        for signed in [false, true] {
+            // Initialize a tx environment
+            let mut tx_env = TestTxEnv::default();
+
+            // Spawn the accounts to be able to modify their storage
+            tx_env.spawn_accounts([&vp_owner, &recipient]);
+
+            tx_env.write_public_key(&vp_owner, &public_key);
+
+            // Elect the VP owner as the council
+            let council = governance::PgfCouncil {
+                address: vp_owner.clone(),
+                spending_cap: token::Amount::whole(100),
+            };
+            tx_env
+                .storage
+                .write(
+                    &pgf_storage::get_council_key(),
+                    council.try_to_vec().unwrap(),
+                )
+                .unwrap();
+
+            // Initialize VP environment from a transaction
+            vp_host_env::init_from_tx(vp_owner.clone(), tx_env, |_address| {
+                // Set a funding stream in a transaction
+                tx_host_env::pgf::update_funding(
+                    tx::ctx(),
+                    transaction::pgf::PgfFundingAction::SetStream {
+                        recipient: recipient.clone(),
+                        amount: token::Amount::whole(10),
+                    },
+                )
+                .unwrap();
+            });
+
+            let mut vp_env = vp_host_env::take();
+            let tx_data: Vec<u8> = if signed {
+                let tx = vp_env.tx.clone();
+                let signed_tx = tx.sign(&keypair);
+                let tx_data: Vec<u8> =
+                    signed_tx.data.as_ref().cloned().unwrap();
+                vp_env.tx = signed_tx;
+                tx_data
+            } else {
+                vec![]
+            };
+            let keys_changed: BTreeSet<storage::Key> =
+                vp_env.all_touched_storage_keys();
+            let verifiers: BTreeSet<Address> = vp_env.get_verifiers();
+            vp_host_env::set(vp_env);
+            assert_eq!(
+                validate_tx(
+                    &CTX,
+                    tx_data,
+                    vp_owner.clone(),
+                    keys_changed,
+                    verifiers
+                )
+                .unwrap(),
+                signed
+            );
+        }
+    }
+
     /// Test that a validity predicate update with a valid signature is
     /// accepted.
     #[test]
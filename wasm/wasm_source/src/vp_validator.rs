@@ -21,6 +21,7 @@ enum KeyType<'a> {
     PoS,
     Vp(&'a Address),
     GovernanceVote(&'a Address),
+    Pgf,
     Unknown,
 }
 
@@ -41,6 +42,11 @@ impl<'a> From<&'a storage::Key> for KeyType<'a> {
             } else {
                 Self::Unknown
             }
+        } else if let Some(address) = gov_storage::is_vote_pass_through_key(key)
+        {
+            Self::GovernanceVote(address)
+        } else if pgf_storage::is_pgf_key(key) {
+            Self::Pgf
         } else if let Some(address) = key.is_validity_predicate() {
             Self::Vp(address)
         } else {
@@ -158,6 +164,16 @@ fn validate_tx(
                     true
                 }
             }
+            KeyType::Pgf => {
+                // The changes of the funding made by this address as the PGF
+                // council must be signed
+                let council: Option<governance::PgfCouncil> =
+                    ctx.read_pre(&pgf_storage::get_council_key())?;
+                match council {
+                    Some(council) if council.address == addr => *valid_sig,
+                    _ => true,
+                }
+            }
             KeyType::Vp(owner) => {
                 let has_post: bool = ctx.has_key_post(key)?;
                 if owner == &addr {